@@ -0,0 +1,91 @@
+//! Compares the memory held by the types of a large batch of custom events when they are
+//! deserialized into `String`s and into interned `EventType`s.
+//!
+//! ```text
+//! cargo bench --features interning --bench interning
+//! ```
+
+extern crate ruma_events;
+extern crate serde_json;
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+
+use ruma_events::EventType;
+use ruma_events::collections::all::Event;
+use serde_json::from_str;
+
+const EVENTS: usize = 100_000;
+
+const EVENT_TYPES: &[&str] = &[
+    "im.vector.modular.widgets",
+    "io.element.voice_broadcast_info",
+    "org.matrix.msc3401.call.member",
+    "org.matrix.msc3672.beacon",
+];
+
+/// Counts the bytes currently allocated through the system allocator.
+struct CountingAllocator;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATED.fetch_add(layout.size(), Ordering::SeqCst);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        ALLOCATED.fetch_sub(layout.size(), Ordering::SeqCst);
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+/// Runs `f` and returns its result with the number of bytes it left allocated.
+fn retained<T, F>(f: F) -> (T, usize) where F: FnOnce() -> T {
+    let before = ALLOCATED.load(Ordering::SeqCst);
+    let result = f();
+
+    (result, ALLOCATED.load(Ordering::SeqCst).saturating_sub(before))
+}
+
+fn main() {
+    let types = (0..EVENTS)
+        .map(|index| format!(r#""{}""#, EVENT_TYPES[index % EVENT_TYPES.len()]))
+        .collect::<Vec<String>>();
+    let events = (0..EVENTS)
+        .map(|index| format!(
+            r#"{{"content":{{}},"type":"{}"}}"#,
+            EVENT_TYPES[index % EVENT_TYPES.len()],
+        ))
+        .collect::<Vec<String>>();
+
+    let (strings, string_bytes) = retained(|| {
+        types.iter().map(|json| from_str::<String>(json).unwrap()).collect::<Vec<_>>()
+    });
+
+    let (event_types, event_type_bytes) = retained(|| {
+        types.iter().map(|json| from_str::<EventType>(json).unwrap()).collect::<Vec<_>>()
+    });
+
+    let start = Instant::now();
+    let (parsed, event_bytes) = retained(|| {
+        events.iter().map(|json| from_str::<Event>(json).unwrap()).collect::<Vec<_>>()
+    });
+    let event_time = start.elapsed();
+
+    assert_eq!(strings.len(), event_types.len());
+
+    println!("types as String:    {} bytes", string_bytes);
+    println!("types as EventType: {} bytes", event_type_bytes);
+    println!(
+        "custom events:      {} bytes for {} events in {:?}",
+        event_bytes,
+        parsed.len(),
+        event_time,
+    );
+}
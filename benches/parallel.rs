@@ -0,0 +1,53 @@
+//! Compares deserializing a large batch of events serially and in parallel.
+//!
+//! ```text
+//! cargo bench --features rayon --bench parallel
+//! ```
+
+extern crate ruma_events;
+extern crate serde_json;
+
+use std::time::Instant;
+
+use ruma_events::collections::all::Event;
+use ruma_events::collections::{DeserializeConfig, deserialize_events_parallel};
+use serde_json::value::RawValue;
+use serde_json::from_str;
+
+const EVENTS: usize = 100_000;
+
+fn main() {
+    let events = (0..EVENTS)
+        .map(|index| format!(
+            r#"{{
+                "content": {{"body": "Message {}", "msgtype": "m.text"}},
+                "event_id": "$event{}:example.com",
+                "origin_server_ts": {},
+                "room_id": "!n8f893n9:example.com",
+                "sender": "@carl:example.com",
+                "type": "m.room.message"
+            }}"#,
+            index, index, index,
+        ))
+        .collect::<Vec<String>>()
+        .join(",");
+    let json = format!("[{}]", events);
+    let batch = from_str::<Vec<&RawValue>>(&json).unwrap();
+
+    let start = Instant::now();
+    let serial = batch
+        .iter()
+        .map(|event| from_str::<Event>(event.get()))
+        .collect::<Vec<_>>();
+    let serial_time = start.elapsed();
+
+    let start = Instant::now();
+    let parallel = deserialize_events_parallel(&batch, &DeserializeConfig::default());
+    let parallel_time = start.elapsed();
+
+    assert_eq!(serial.len(), parallel.len());
+    assert!(parallel.iter().all(Result::is_ok));
+
+    println!("serial:   {} events in {:?}", serial.len(), serial_time);
+    println!("parallel: {} events in {:?}", parallel.len(), parallel_time);
+}
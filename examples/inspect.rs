@@ -0,0 +1,73 @@
+//! Reads event JSON from standard input and prints the typed event it deserializes into.
+//!
+//! The input can be a single event or an array of events. Each event is deserialized with the
+//! strict type mismatch policy first, so events whose structure contradicts their `type` are
+//! reported, and then with the lenient policy that `Deserialize` uses.
+//!
+//! ```text
+//! cargo run --example inspect --features inspect < event.json
+//! ```
+
+extern crate ruma_events;
+extern crate serde_json;
+
+use std::io::{self, Read};
+use std::process;
+
+use ruma_events::collections::TypeMismatchPolicy;
+use ruma_events::collections::all::Event;
+use serde_json::Value;
+
+fn main() {
+    let mut input = String::new();
+
+    if let Err(error) = io::stdin().read_to_string(&mut input) {
+        eprintln!("failed to read standard input: {}", error);
+        process::exit(1);
+    }
+
+    let value = match serde_json::from_str::<Value>(&input) {
+        Ok(value) => value,
+        Err(error) => {
+            eprintln!("input is not valid JSON: {}", error);
+            process::exit(1);
+        }
+    };
+
+    let events = match value {
+        Value::Array(events) => events,
+        event => vec![event],
+    };
+
+    let mut failures = 0;
+
+    for (index, event) in events.into_iter().enumerate() {
+        if !inspect(index, event) {
+            failures += 1;
+        }
+    }
+
+    if failures > 0 {
+        process::exit(1);
+    }
+}
+
+/// Prints the typed event `value` deserializes into, returning whether deserialization succeeded.
+fn inspect(index: usize, value: Value) -> bool {
+    println!("event {} ({}):", index, value.get("type").unwrap_or(&Value::Null));
+
+    if let Err(error) = Event::from_value_with_policy(value.clone(), TypeMismatchPolicy::Strict) {
+        println!("  strict deserialization failed: {}", error);
+    }
+
+    match Event::from_value_with_policy(value, TypeMismatchPolicy::Lenient) {
+        Ok(event) => {
+            println!("{:#?}", event);
+            true
+        }
+        Err(error) => {
+            println!("  deserialization failed: {}", error);
+            false
+        }
+    }
+}
@@ -1,6 +1,6 @@
 //! Types for the *m.call.answer* event.
 
-use super::SessionDescription;
+use super::{SessionDescription, VersionId};
 
 room_event! {
     /// This event is sent by the callee when they wish to answer the call.
@@ -14,6 +14,16 @@ pub struct AnswerEventContent {
     pub answer: SessionDescription,
     /// The ID of the call this event relates to.
     pub call_id: String,
+    /// The unique ID of the client that sent this event, introduced in version 1.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub party_id: Option<String>,
     /// The version of the VoIP specification this messages adheres to.
-    pub version: u64,
+    pub version: VersionId,
+    /// Fields that are not covered by the Matrix specification.
+    #[cfg(feature = "preserve-extra-fields")]
+    #[serde(flatten)]
+    pub extra: ::std::collections::BTreeMap<String, ::serde_json::Value>,
 }
+
+impl_event_content!(AnswerEventContent, CallAnswer);
+impl_room_event_content!(message_like, AnswerEventContent);
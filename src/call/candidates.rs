@@ -1,5 +1,7 @@
 //! Types for the *m.call.candidates* event.
 
+use super::VersionId;
+
 room_event! {
     /// This event is sent by callers after sending an invite and by the callee after answering.
     /// Its purpose is to give the other party additional ICE candidates to try using to
@@ -14,10 +16,20 @@ pub struct CandidatesEventContent {
     pub call_id: String,
     /// A list of candidates.
     pub candidates: Vec<Candidate>,
+    /// The unique ID of the client that sent this event, introduced in version 1.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub party_id: Option<String>,
     /// The version of the VoIP specification this messages adheres to.
-    pub version: u64,
+    pub version: VersionId,
+    /// Fields that are not covered by the Matrix specification.
+    #[cfg(feature = "preserve-extra-fields")]
+    #[serde(flatten)]
+    pub extra: ::std::collections::BTreeMap<String, ::serde_json::Value>,
 }
 
+impl_event_content!(CandidatesEventContent, CallCandidates);
+impl_room_event_content!(message_like, CandidatesEventContent);
+
 /// An ICE (Interactive Connectivity Establishment) candidate.
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct Candidate {
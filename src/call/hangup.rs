@@ -1,5 +1,7 @@
 //! Types for the *m.call.hangup* event.
 
+use super::VersionId;
+
 room_event! {
     /// Sent by either party to signal their termination of the call. This can be sent either once
     /// the call has has been established or before to abort the call.
@@ -11,6 +13,16 @@ room_event! {
 pub struct HangupEventContent {
     /// The ID of the call this event relates to.
     pub call_id: String,
+    /// The unique ID of the client that sent this event, introduced in version 1.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub party_id: Option<String>,
     /// The version of the VoIP specification this messages adheres to.
-    pub version: u64,
+    pub version: VersionId,
+    /// Fields that are not covered by the Matrix specification.
+    #[cfg(feature = "preserve-extra-fields")]
+    #[serde(flatten)]
+    pub extra: ::std::collections::BTreeMap<String, ::serde_json::Value>,
 }
+
+impl_event_content!(HangupEventContent, CallHangup);
+impl_room_event_content!(message_like, HangupEventContent);
@@ -1,6 +1,11 @@
 //! Types for the *m.call.invite* event.
 
-use super::SessionDescription;
+use ruma_identifiers::UserId;
+
+use clock::{Clock, SystemClock};
+use int::UInt;
+
+use super::{SessionDescription, VersionId};
 
 room_event! {
     /// This event is sent by the caller when they wish to establish a call.
@@ -12,12 +17,38 @@ room_event! {
 pub struct InviteEventContent {
     /// A unique identifer for the call.
     pub call_id: String,
+    /// The ID of the user being called. If absent, the call is intended for any member of the
+    /// room other than the sender. Introduced in version 1.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub invitee: Option<UserId>,
     /// The time in milliseconds that the invite is valid for. Once the invite age exceeds this
     /// value, clients should discard it. They should also no longer show the call as awaiting an
     /// answer in the UI.
-    pub lifetime: u64,
+    pub lifetime: UInt,
     /// The session description object.
     pub offer: SessionDescription,
+    /// The unique ID of the client that sent this event, introduced in version 1.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub party_id: Option<String>,
     /// The version of the VoIP specification this messages adheres to.
-    pub version: u64,
+    pub version: VersionId,
+    /// Fields that are not covered by the Matrix specification.
+    #[cfg(feature = "preserve-extra-fields")]
+    #[serde(flatten)]
+    pub extra: ::std::collections::BTreeMap<String, ::serde_json::Value>,
+}
+
+impl_event_content!(InviteEventContent, CallInvite);
+impl_room_event_content!(message_like, InviteEventContent);
+
+impl InviteEvent {
+    /// Whether the invite has outlived its `lifetime` by now, counted from `origin_server_ts`.
+    pub fn is_expired(&self) -> bool {
+        self.is_expired_at(SystemClock)
+    }
+
+    /// Like `is_expired`, but reads the current time from `clock`.
+    pub fn is_expired_at<C: Clock>(&self, clock: C) -> bool {
+        clock.now().saturating_sub(self.origin_server_ts.into()) > self.content.lifetime.into()
+    }
 }
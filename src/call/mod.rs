@@ -2,10 +2,18 @@
 //!
 //! This module also contains types shared by events in its child namespaces.
 
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde::de::{Error as SerdeError, Visitor};
+
 pub mod answer;
 pub mod candidates;
 pub mod hangup;
 pub mod invite;
+pub mod negotiate;
+pub mod reject;
+pub mod select_answer;
 
 /// A VoIP session description.
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -33,3 +41,175 @@ impl_enum! {
         Offer => "offer",
     }
 }
+
+/// The version of the VoIP specification a call event adheres to.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum VersionId {
+    /// The original version of VoIP calls, serialized as the integer `0`.
+    V0,
+    /// Version 1 of VoIP calls, serialized as the string `"1"`.
+    V1,
+    /// A version that is not part of the specification, such as a namespaced experimental version.
+    Custom(String),
+}
+
+impl VersionId {
+    /// The version both parties of a call use, given the version of the *m.call.invite* event and
+    /// the version of the *m.call.answer* event, or `None` if the versions are incompatible.
+    ///
+    /// A callee that only supports an older version answers with that version, and the call
+    /// continues using it. Custom versions are only compatible with themselves.
+    pub fn negotiate(invite: &VersionId, answer: &VersionId) -> Option<VersionId> {
+        match (invite, answer) {
+            (VersionId::V0, VersionId::V0) | (VersionId::V1, VersionId::V0) => Some(VersionId::V0),
+            (VersionId::V1, VersionId::V1) => Some(VersionId::V1),
+            (VersionId::Custom(invite), VersionId::Custom(answer)) if invite == answer => {
+                Some(VersionId::Custom(invite.clone()))
+            }
+            _ => None,
+        }
+    }
+
+    /// Whether this version supports the features introduced in version 1, such as `party_id`.
+    pub fn supports_v1(&self) -> bool {
+        *self == VersionId::V1
+    }
+}
+
+impl Display for VersionId {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match *self {
+            VersionId::V0 => write!(f, "0"),
+            VersionId::V1 => write!(f, "1"),
+            VersionId::Custom(ref version) => write!(f, "{}", version),
+        }
+    }
+}
+
+impl<'a> From<&'a str> for VersionId {
+    fn from(s: &'a str) -> VersionId {
+        match s {
+            "0" => VersionId::V0,
+            "1" => VersionId::V1,
+            version => VersionId::Custom(version.to_string()),
+        }
+    }
+}
+
+impl Serialize for VersionId {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
+        match *self {
+            VersionId::V0 => serializer.serialize_u64(0),
+            _ => serializer.serialize_str(&self.to_string()),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for VersionId {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where D: Deserializer<'de> {
+        struct VersionIdVisitor;
+
+        impl<'de> Visitor<'de> for VersionIdVisitor {
+            type Value = VersionId;
+
+            fn expecting(&self, formatter: &mut Formatter) -> FmtResult {
+                write!(formatter, "a VoIP version as an integer or a string")
+            }
+
+            fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E> where E: SerdeError {
+                Ok(VersionId::from(v.to_string().as_str()))
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<Self::Value, E> where E: SerdeError {
+                Ok(VersionId::from(v))
+            }
+        }
+
+        deserializer.deserialize_any(VersionIdVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{from_str, to_string};
+
+    use clock::FixedClock;
+    use super::VersionId;
+    use super::invite::{InviteEvent, InviteEventContent};
+    use super::select_answer::SelectAnswerEventContent;
+
+    #[test]
+    fn version_serialization() {
+        assert_eq!(to_string(&VersionId::V0).unwrap(), "0");
+        assert_eq!(to_string(&VersionId::V1).unwrap(), r#""1""#);
+        assert_eq!(from_str::<VersionId>("0").unwrap(), VersionId::V0);
+        assert_eq!(from_str::<VersionId>(r#""1""#).unwrap(), VersionId::V1);
+        assert_eq!(
+            from_str::<VersionId>(r#""org.example.v2""#).unwrap(),
+            VersionId::Custom("org.example.v2".to_string())
+        );
+    }
+
+    #[test]
+    fn version_negotiation() {
+        assert_eq!(VersionId::negotiate(&VersionId::V1, &VersionId::V0), Some(VersionId::V0));
+        assert_eq!(VersionId::negotiate(&VersionId::V1, &VersionId::V1), Some(VersionId::V1));
+        assert_eq!(VersionId::negotiate(&VersionId::V0, &VersionId::V1), None);
+        assert_eq!(
+            VersionId::negotiate(&VersionId::V1, &VersionId::Custom("org.example.v2".to_string())),
+            None
+        );
+    }
+
+    #[test]
+    fn v1_fields() {
+        let invite = from_str::<InviteEventContent>(r#"{
+            "call_id": "12345",
+            "invitee": "@bob:example.com",
+            "lifetime": 60000,
+            "offer": {"sdp": "v=0", "session_type": "offer"},
+            "party_id": "ABCDEF",
+            "version": "1"
+        }"#).unwrap();
+
+        assert_eq!(invite.invitee.unwrap().to_string(), "@bob:example.com");
+        assert_eq!(invite.party_id.as_deref(), Some("ABCDEF"));
+        assert!(invite.version.supports_v1());
+
+        let v0 = from_str::<InviteEventContent>(r#"{
+            "call_id": "12345",
+            "lifetime": 60000,
+            "offer": {"sdp": "v=0", "session_type": "offer"},
+            "version": 0
+        }"#).unwrap();
+
+        assert_eq!(v0.party_id, None);
+        assert_eq!(v0.version, VersionId::V0);
+
+        let select = from_str::<SelectAnswerEventContent>(
+            r#"{"call_id":"12345","party_id":"ABCDEF","selected_party_id":"GHIJKL","version":"1"}"#
+        ).unwrap();
+
+        assert_eq!(select.selected_party_id, "GHIJKL");
+    }
+
+    #[test]
+    fn invite_expiry() {
+        let invite = from_str::<InviteEvent>(r#"{
+            "content": {
+                "call_id": "12345",
+                "lifetime": 60000,
+                "offer": {"sdp": "v=0", "session_type": "offer"},
+                "version": 0
+            },
+            "event_id": "$h29iv0s8:example.com",
+            "origin_server_ts": 1000,
+            "room_id": "!n8f893n9:example.com",
+            "sender": "@alice:example.com",
+            "type": "m.call.invite"
+        }"#).unwrap();
+
+        assert!(!invite.is_expired_at(FixedClock(61_000)));
+        assert!(invite.is_expired_at(FixedClock(61_001)));
+    }
+}
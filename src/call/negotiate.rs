@@ -0,0 +1,46 @@
+//! Types for the *m.call.negotiate* event.
+
+use clock::{Clock, SystemClock};
+use int::UInt;
+
+use super::{SessionDescription, VersionId};
+
+room_event! {
+    /// This event is sent by either party once a call is established to renegotiate the session,
+    /// e.g. to add a video stream.
+    pub struct NegotiateEvent(NegotiateEventContent) {}
+}
+
+/// The payload of a `NegotiateEvent`.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct NegotiateEventContent {
+    /// The ID of the call this event relates to.
+    pub call_id: String,
+    /// The session description of the renegotiation, an offer or an answer.
+    pub description: SessionDescription,
+    /// The time in milliseconds that the negotiation is valid for.
+    pub lifetime: UInt,
+    /// The unique ID of the client that sent this event.
+    pub party_id: String,
+    /// The version of the VoIP specification this message adheres to. At least version 1.
+    pub version: VersionId,
+    /// Fields that are not covered by the Matrix specification.
+    #[cfg(feature = "preserve-extra-fields")]
+    #[serde(flatten)]
+    pub extra: ::std::collections::BTreeMap<String, ::serde_json::Value>,
+}
+
+impl_event_content!(NegotiateEventContent, CallNegotiate);
+impl_room_event_content!(message_like, NegotiateEventContent);
+
+impl NegotiateEvent {
+    /// Whether the negotiation has outlived its `lifetime` by now, counted from `origin_server_ts`.
+    pub fn is_expired(&self) -> bool {
+        self.is_expired_at(SystemClock)
+    }
+
+    /// Like `is_expired`, but reads the current time from `clock`.
+    pub fn is_expired_at<C: Clock>(&self, clock: C) -> bool {
+        clock.now().saturating_sub(self.origin_server_ts.into()) > self.content.lifetime.into()
+    }
+}
@@ -0,0 +1,27 @@
+//! Types for the *m.call.reject* event.
+
+use super::VersionId;
+
+room_event! {
+    /// This event is sent by the callee to reject an invite, so that the caller and the other
+    /// devices of the callee stop ringing.
+    pub struct RejectEvent(RejectEventContent) {}
+}
+
+/// The payload of a `RejectEvent`.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct RejectEventContent {
+    /// The ID of the call this event relates to.
+    pub call_id: String,
+    /// The unique ID of the client that sent this event.
+    pub party_id: String,
+    /// The version of the VoIP specification this message adheres to. At least version 1.
+    pub version: VersionId,
+    /// Fields that are not covered by the Matrix specification.
+    #[cfg(feature = "preserve-extra-fields")]
+    #[serde(flatten)]
+    pub extra: ::std::collections::BTreeMap<String, ::serde_json::Value>,
+}
+
+impl_event_content!(RejectEventContent, CallReject);
+impl_room_event_content!(message_like, RejectEventContent);
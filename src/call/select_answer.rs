@@ -0,0 +1,29 @@
+//! Types for the *m.call.select_answer* event.
+
+use super::VersionId;
+
+room_event! {
+    /// This event is sent by the caller when it has chosen an answer, so that other devices of
+    /// the callee that answered know they were not chosen.
+    pub struct SelectAnswerEvent(SelectAnswerEventContent) {}
+}
+
+/// The payload of a `SelectAnswerEvent`.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct SelectAnswerEventContent {
+    /// The ID of the call this event relates to.
+    pub call_id: String,
+    /// The unique ID of the client that sent this event.
+    pub party_id: String,
+    /// The `party_id` of the answer that was selected.
+    pub selected_party_id: String,
+    /// The version of the VoIP specification this message adheres to. At least version 1.
+    pub version: VersionId,
+    /// Fields that are not covered by the Matrix specification.
+    #[cfg(feature = "preserve-extra-fields")]
+    #[serde(flatten)]
+    pub extra: ::std::collections::BTreeMap<String, ::serde_json::Value>,
+}
+
+impl_event_content!(SelectAnswerEventContent, CallSelectAnswer);
+impl_room_event_content!(message_like, SelectAnswerEventContent);
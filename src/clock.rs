@@ -0,0 +1,44 @@
+//! Sources of the current time for checking whether events have expired.
+//!
+//! Some events are only valid for a limited time, such as call invites and messages in rooms
+//! with a retention policy. Their `is_expired` helpers read the current time from the system
+//! clock, and their `is_expired_at` counterparts take any `Clock`, so that tests can use a
+//! fixed time and targets without a system clock can provide their own.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A source of the current time.
+pub trait Clock {
+    /// The current time in milliseconds since the Unix epoch, like an event's
+    /// `origin_server_ts`.
+    fn now(&self) -> u64;
+}
+
+/// The system clock.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_millis() as u64)
+            .unwrap_or(0)
+    }
+}
+
+/// A clock that is stopped at the given time in milliseconds since the Unix epoch.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct FixedClock(pub u64);
+
+impl Clock for FixedClock {
+    fn now(&self) -> u64 {
+        self.0
+    }
+}
+
+impl<C: Clock + ?Sized> Clock for &C {
+    fn now(&self) -> u64 {
+        (**self).now()
+    }
+}
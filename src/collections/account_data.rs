@@ -0,0 +1,309 @@
+//! Enums for heterogeneous collections of account data events, such as the `account_data`
+//! sections of a sync response.
+//!
+//! Global account data and the account data of a room hold different event types, so each has
+//! its own enum.
+
+use ruma_identifiers::RoomId;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde::de::Error;
+use serde_json::{Error as JsonError, Value, from_value};
+
+use {CustomEvent, EventType};
+use direct::DirectEvent;
+use fully_read::FullyReadEvent;
+use ignored_user_list::IgnoredUserListEvent;
+#[cfg(feature = "events-encryption")]
+use key::backup::MegolmBackupEvent;
+use push_rules::PushRulesEvent;
+use tag::TagEvent;
+
+use super::{DeserializeConfig, deserialize_limited_value};
+
+/// An event in the global account data of a user.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub enum AnyGlobalAccountDataEvent {
+    /// m.direct
+    Direct(DirectEvent),
+    /// m.ignored_user_list
+    IgnoredUserList(IgnoredUserListEvent),
+    /// m.megolm_backup.v1
+    #[cfg(feature = "events-encryption")]
+    MegolmBackupV1(MegolmBackupEvent),
+    /// m.push_rules
+    PushRules(PushRulesEvent),
+    /// Any global account data event that is not part of the specification.
+    Custom(CustomEvent),
+}
+
+/// An event in the account data of a user for a specific room.
+#[derive(Clone, Debug)]
+pub enum AnyRoomAccountDataEvent {
+    /// m.fully_read
+    FullyRead(FullyReadEvent),
+    /// m.tag
+    Tag(TagEvent),
+    /// Any room account data event that is not part of the specification.
+    Custom(CustomEvent),
+}
+
+impl Serialize for AnyGlobalAccountDataEvent {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
+        match *self {
+            AnyGlobalAccountDataEvent::Direct(ref event) => event.serialize(serializer),
+            AnyGlobalAccountDataEvent::IgnoredUserList(ref event) => event.serialize(serializer),
+            #[cfg(feature = "events-encryption")]
+            AnyGlobalAccountDataEvent::MegolmBackupV1(ref event) => event.serialize(serializer),
+            AnyGlobalAccountDataEvent::PushRules(ref event) => event.serialize(serializer),
+            AnyGlobalAccountDataEvent::Custom(ref event) => event.serialize(serializer),
+        }
+    }
+}
+
+impl AnyGlobalAccountDataEvent {
+    /// Deserializes an event from a JSON value with the given per-call options.
+    pub fn from_value_with_config(value: Value, config: &DeserializeConfig)
+    -> Result<Self, JsonError> {
+        let event_type_value = match value.get("type") {
+            Some(value) => value,
+            None => return Err(JsonError::missing_field("type")),
+        };
+
+        let event_type = match EventType::deserialize(event_type_value) {
+            Ok(event_type) => event_type,
+            Err(error) => return Err(JsonError::custom(error.to_string())),
+        };
+
+        config.observe_unknown_type(&event_type);
+
+        AnyGlobalAccountDataEvent::from_typed_value(value, event_type)
+    }
+
+    /// Deserializes an event of the given type from a JSON value.
+    fn from_typed_value(value: Value, event_type: EventType) -> Result<Self, JsonError> {
+        match event_type {
+            EventType::Direct => {
+                let event = match from_value::<DirectEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(AnyGlobalAccountDataEvent::Direct(event))
+            }
+            EventType::IgnoredUserList => {
+                let event = match from_value::<IgnoredUserListEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(AnyGlobalAccountDataEvent::IgnoredUserList(event))
+            }
+            #[cfg(feature = "events-encryption")]
+            EventType::MegolmBackupV1 => {
+                let event = match from_value::<MegolmBackupEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(AnyGlobalAccountDataEvent::MegolmBackupV1(event))
+            }
+            EventType::PushRules => {
+                let event = match from_value::<PushRulesEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(AnyGlobalAccountDataEvent::PushRules(event))
+            }
+            EventType::Custom(_) => {
+                let event = match from_value::<CustomEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(AnyGlobalAccountDataEvent::Custom(event))
+            }
+            _ => {
+                Err(JsonError::custom("not a global account data event".to_string()))
+            }
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for AnyGlobalAccountDataEvent {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where D: Deserializer<'de> {
+        let value = deserialize_limited_value(deserializer)?;
+
+        AnyGlobalAccountDataEvent::from_value_with_config(value, &DeserializeConfig::default())
+            .map_err(|error| D::Error::custom(error.to_string()))
+    }
+}
+
+impl Serialize for AnyRoomAccountDataEvent {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
+        match *self {
+            AnyRoomAccountDataEvent::FullyRead(ref event) => event.serialize(serializer),
+            AnyRoomAccountDataEvent::Tag(ref event) => event.serialize(serializer),
+            AnyRoomAccountDataEvent::Custom(ref event) => event.serialize(serializer),
+        }
+    }
+}
+
+impl AnyRoomAccountDataEvent {
+    /// Attaches the ID of the room the event belongs to, such as the room whose account data in
+    /// a sync response contains the event.
+    ///
+    /// Custom events are returned unchanged, since they have no `room_id` field.
+    pub fn into_full_event(self, room_id: RoomId) -> Self {
+        match self {
+            AnyRoomAccountDataEvent::FullyRead(event) => {
+                AnyRoomAccountDataEvent::FullyRead(event.into_full_event(room_id))
+            }
+            AnyRoomAccountDataEvent::Tag(event) => {
+                AnyRoomAccountDataEvent::Tag(event.into_full_event(room_id))
+            }
+            AnyRoomAccountDataEvent::Custom(event) => AnyRoomAccountDataEvent::Custom(event),
+        }
+    }
+
+    /// Deserializes an event from a JSON value with the given per-call options.
+    pub fn from_value_with_config(value: Value, config: &DeserializeConfig)
+    -> Result<Self, JsonError> {
+        let event_type_value = match value.get("type") {
+            Some(value) => value,
+            None => return Err(JsonError::missing_field("type")),
+        };
+
+        let event_type = match EventType::deserialize(event_type_value) {
+            Ok(event_type) => event_type,
+            Err(error) => return Err(JsonError::custom(error.to_string())),
+        };
+
+        config.observe_unknown_type(&event_type);
+
+        AnyRoomAccountDataEvent::from_typed_value(value, event_type)
+    }
+
+    /// Deserializes an event of the given type from a JSON value.
+    fn from_typed_value(value: Value, event_type: EventType) -> Result<Self, JsonError> {
+        match event_type {
+            EventType::FullyRead => {
+                let event = match from_value::<FullyReadEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(AnyRoomAccountDataEvent::FullyRead(event))
+            }
+            EventType::Tag => {
+                let event = match from_value::<TagEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(AnyRoomAccountDataEvent::Tag(event))
+            }
+            EventType::Custom(_) => {
+                let event = match from_value::<CustomEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(AnyRoomAccountDataEvent::Custom(event))
+            }
+            _ => {
+                Err(JsonError::custom("not a room account data event".to_string()))
+            }
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for AnyRoomAccountDataEvent {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where D: Deserializer<'de> {
+        let value = deserialize_limited_value(deserializer)?;
+
+        AnyRoomAccountDataEvent::from_value_with_config(value, &DeserializeConfig::default())
+            .map_err(|error| D::Error::custom(error.to_string()))
+    }
+}
+
+macro_rules! impl_from_t_for_account_data_event {
+    ($ty:ty, $variant:ident, $collection:ident) => {
+        impl From<$ty> for $collection {
+            fn from(event: $ty) -> Self {
+                $collection::$variant(event)
+            }
+        }
+    };
+}
+
+impl_from_t_for_account_data_event!(DirectEvent, Direct, AnyGlobalAccountDataEvent);
+impl_from_t_for_account_data_event!(
+    IgnoredUserListEvent,
+    IgnoredUserList,
+    AnyGlobalAccountDataEvent
+);
+#[cfg(feature = "events-encryption")]
+impl_from_t_for_account_data_event!(MegolmBackupEvent, MegolmBackupV1, AnyGlobalAccountDataEvent);
+impl_from_t_for_account_data_event!(PushRulesEvent, PushRules, AnyGlobalAccountDataEvent);
+impl_from_t_for_account_data_event!(FullyReadEvent, FullyRead, AnyRoomAccountDataEvent);
+impl_from_t_for_account_data_event!(TagEvent, Tag, AnyRoomAccountDataEvent);
+
+#[cfg(test)]
+mod tests {
+    use ruma_identifiers::RoomId;
+    use serde_json::{from_str, to_value};
+
+    use super::{AnyGlobalAccountDataEvent, AnyRoomAccountDataEvent};
+
+    #[test]
+    fn deserialize_account_data() {
+        let global = from_str::<Vec<AnyGlobalAccountDataEvent>>(r#"[
+            {
+                "content": {"ignored_users": {"@spam:example.com": {}}},
+                "type": "m.ignored_user_list"
+            },
+            {"content": {"@bob:example.com": ["!a:example.com"]}, "type": "m.direct"},
+            {"content": {"theme": "dark"}, "type": "org.example.settings"}
+        ]"#).unwrap();
+
+        match global[0] {
+            AnyGlobalAccountDataEvent::IgnoredUserList(ref event) => {
+                assert!(event.content.is_ignored(&from_str(r#""@spam:example.com""#).unwrap()));
+            }
+            _ => unreachable!(),
+        }
+        match global[1] {
+            AnyGlobalAccountDataEvent::Direct(_) => {}
+            _ => unreachable!(),
+        }
+        match global[2] {
+            AnyGlobalAccountDataEvent::Custom(ref event) => {
+                assert_eq!(event.content["theme"], "dark");
+            }
+            _ => unreachable!(),
+        }
+
+        let room = from_str::<Vec<AnyRoomAccountDataEvent>>(r#"[
+            {"content": {"event_id": "$someplace:example.org"}, "type": "m.fully_read"},
+            {"content": {"tags": {"u.work": {"order": 0.9}}}, "type": "m.tag"}
+        ]"#).unwrap();
+
+        match room[0] {
+            AnyRoomAccountDataEvent::FullyRead(ref event) => assert_eq!(event.room_id, None),
+            _ => unreachable!(),
+        }
+        assert_eq!(to_value(&room[1]).unwrap()["type"], "m.tag");
+
+        let room_id = from_str::<RoomId>(r#""!a:example.com""#).unwrap();
+        for event in room {
+            let json = to_value(event.into_full_event(room_id.clone())).unwrap();
+            assert_eq!(json["room_id"], "!a:example.com");
+        }
+
+        assert!(from_str::<AnyRoomAccountDataEvent>(
+            r#"{"content": {"ignored_users": {}}, "type": "m.ignored_user_list"}"#
+        ).is_err());
+    }
+}
@@ -2,46 +2,186 @@
 //! the trait of the same name.
 
 use {CustomEvent, CustomRoomEvent, CustomStateEvent, EventType};
+#[cfg(feature = "events-call")]
 use call::answer::AnswerEvent;
+#[cfg(feature = "events-call")]
 use call::candidates::CandidatesEvent;
+#[cfg(feature = "events-call")]
 use call::hangup::HangupEvent;
+#[cfg(feature = "events-call")]
 use call::invite::InviteEvent;
+#[cfg(feature = "events-call")]
+use call::negotiate::NegotiateEvent;
+#[cfg(feature = "events-call")]
+use call::reject::RejectEvent;
+#[cfg(feature = "events-call")]
+use call::select_answer::SelectAnswerEvent;
+use direct::DirectEvent;
+#[cfg(feature = "events-encryption")]
+use forwarded_room_key::ForwardedRoomKeyEvent;
+use fully_read::FullyReadEvent;
+use ignored_user_list::IgnoredUserListEvent;
+#[cfg(feature = "events-encryption")]
+use key::backup::MegolmBackupEvent;
+#[cfg(feature = "events-encryption")]
+use key::verification::accept::AcceptEvent;
+#[cfg(feature = "events-encryption")]
+use key::verification::cancel::CancelEvent;
+#[cfg(feature = "events-encryption")]
+use key::verification::done::DoneEvent;
+#[cfg(feature = "events-encryption")]
+use key::verification::key::KeyEvent;
+#[cfg(feature = "events-encryption")]
+use key::verification::mac::MacEvent;
+#[cfg(feature = "events-encryption")]
+use key::verification::request::RequestEvent;
+#[cfg(feature = "events-encryption")]
+use key::verification::start::StartEvent;
+#[cfg(feature = "events-policy")]
+use policy::rule::room::RoomRuleEvent;
+#[cfg(feature = "events-policy")]
+use policy::rule::server::ServerRuleEvent;
+#[cfg(feature = "events-policy")]
+use policy::rule::user::UserRuleEvent;
+#[cfg(feature = "events-poll")]
+use poll::end::PollEndEvent;
+#[cfg(feature = "events-poll")]
+use poll::response::PollResponseEvent;
+#[cfg(feature = "events-poll")]
+use poll::start::PollStartEvent;
 use presence::PresenceEvent;
+use push_rules::PushRulesEvent;
+use reaction::ReactionEvent;
 use receipt::ReceiptEvent;
 use room::aliases::AliasesEvent;
 use room::avatar::AvatarEvent;
 use room::canonical_alias::CanonicalAliasEvent;
 use room::create::CreateEvent;
+#[cfg(feature = "events-encryption")]
+use room::encrypted::EncryptedEvent;
+use room::encryption::EncryptionEvent;
 use room::guest_access::GuestAccessEvent;
 use room::history_visibility::HistoryVisibilityEvent;
 use room::join_rules::JoinRulesEvent;
 use room::member::MemberEvent;
 use room::message::MessageEvent;
 use room::name::NameEvent;
+use room::pinned_events::PinnedEventsEvent;
 use room::power_levels::PowerLevelsEvent;
 use room::redaction::RedactionEvent;
+use room::server_acl::ServerAclEvent;
 use room::third_party_invite::ThirdPartyInviteEvent;
+use room::tombstone::TombstoneEvent;
 use room::topic::TopicEvent;
+#[cfg(feature = "events-encryption")]
+use room_key::RoomKeyEvent;
+#[cfg(feature = "events-encryption")]
+use room_key_request::RoomKeyRequestEvent;
+#[cfg(feature = "events-encryption")]
+use secret::request::SecretRequestEvent;
+#[cfg(feature = "events-encryption")]
+use secret::send::SecretSendEvent;
+use space::child::ChildEvent;
+use space::parent::ParentEvent;
+use sticker::StickerEvent;
 use tag::TagEvent;
 use typing::TypingEvent;
 
+use std::collections::HashMap;
+use std::io::Write;
+
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use serde::de::Error;
-use serde_json::{Value, from_value};
+use serde::ser::SerializeSeq;
+use serde_json::{Error as JsonError, Value, from_value};
+
+use super::{
+    DeserializeConfig, StructureMismatch, TypeMismatchPolicy, deserialize_limited_value,
+    structure_mismatch,
+};
 
 /// A basic event, room event, or state event.
 #[derive(Clone, Debug)]
+#[non_exhaustive]
 pub enum Event {
     /// m.call.answer
+    #[cfg(feature = "events-call")]
     CallAnswer(AnswerEvent),
     /// m.call.candidates
+    #[cfg(feature = "events-call")]
     CallCandidates(CandidatesEvent),
     /// m.call.hangup
+    #[cfg(feature = "events-call")]
     CallHangup(HangupEvent),
     /// m.call.invite
+    #[cfg(feature = "events-call")]
     CallInvite(InviteEvent),
+    /// m.call.negotiate
+    #[cfg(feature = "events-call")]
+    CallNegotiate(NegotiateEvent),
+    /// m.call.reject
+    #[cfg(feature = "events-call")]
+    CallReject(RejectEvent),
+    /// m.call.select_answer
+    #[cfg(feature = "events-call")]
+    CallSelectAnswer(SelectAnswerEvent),
+    /// m.direct
+    Direct(DirectEvent),
+    /// m.forwarded_room_key
+    #[cfg(feature = "events-encryption")]
+    ForwardedRoomKey(ForwardedRoomKeyEvent),
+    /// m.fully_read
+    FullyRead(FullyReadEvent),
+    /// m.ignored_user_list
+    IgnoredUserList(IgnoredUserListEvent),
+    /// m.key.verification.accept
+    #[cfg(feature = "events-encryption")]
+    KeyVerificationAccept(AcceptEvent),
+    /// m.key.verification.cancel
+    #[cfg(feature = "events-encryption")]
+    KeyVerificationCancel(CancelEvent),
+    /// m.key.verification.done
+    #[cfg(feature = "events-encryption")]
+    KeyVerificationDone(DoneEvent),
+    /// m.key.verification.key
+    #[cfg(feature = "events-encryption")]
+    KeyVerificationKey(KeyEvent),
+    /// m.key.verification.mac
+    #[cfg(feature = "events-encryption")]
+    KeyVerificationMac(MacEvent),
+    /// m.key.verification.request
+    #[cfg(feature = "events-encryption")]
+    KeyVerificationRequest(RequestEvent),
+    /// m.key.verification.start
+    #[cfg(feature = "events-encryption")]
+    KeyVerificationStart(StartEvent),
+    /// m.megolm_backup.v1
+    #[cfg(feature = "events-encryption")]
+    MegolmBackupV1(MegolmBackupEvent),
+    /// m.policy.rule.room
+    #[cfg(feature = "events-policy")]
+    PolicyRuleRoom(RoomRuleEvent),
+    /// m.policy.rule.server
+    #[cfg(feature = "events-policy")]
+    PolicyRuleServer(ServerRuleEvent),
+    /// m.policy.rule.user
+    #[cfg(feature = "events-policy")]
+    PolicyRuleUser(UserRuleEvent),
+    /// m.poll.end
+    #[cfg(feature = "events-poll")]
+    PollEnd(PollEndEvent),
+    /// m.poll.response
+    #[cfg(feature = "events-poll")]
+    PollResponse(PollResponseEvent),
+    /// m.poll.start
+    #[cfg(feature = "events-poll")]
+    PollStart(PollStartEvent),
     /// m.presence
     Presence(PresenceEvent),
+    /// m.push_rules
+    PushRules(PushRulesEvent),
+    /// m.reaction
+    Reaction(ReactionEvent),
     /// m.receipt
     Receipt(ReceiptEvent),
     /// m.room.aliases
@@ -52,26 +192,55 @@ pub enum Event {
     RoomCanonicalAlias(CanonicalAliasEvent),
     /// m.room.create
     RoomCreate(CreateEvent),
+    /// m.room.encrypted
+    #[cfg(feature = "events-encryption")]
+    RoomEncrypted(EncryptedEvent),
+    /// m.room.encryption
+    RoomEncryption(EncryptionEvent),
     /// m.room.guest_access
     RoomGuestAccess(GuestAccessEvent),
     /// m.room.history_visibility
     RoomHistoryVisibility(HistoryVisibilityEvent),
     /// m.room.join_rules
     RoomJoinRules(JoinRulesEvent),
+    /// m.room_key
+    #[cfg(feature = "events-encryption")]
+    RoomKey(RoomKeyEvent),
+    /// m.room_key_request
+    #[cfg(feature = "events-encryption")]
+    RoomKeyRequest(RoomKeyRequestEvent),
     /// m.room.member
     RoomMember(MemberEvent),
     /// m.room.message
     RoomMessage(MessageEvent),
     /// m.room.name
     RoomName(NameEvent),
+    /// m.room.pinned_events
+    RoomPinnedEvents(PinnedEventsEvent),
     /// m.room.power_levels
     RoomPowerLevels(PowerLevelsEvent),
     /// m.room.redaction
     RoomRedaction(RedactionEvent),
+    /// m.room.server_acl
+    RoomServerAcl(ServerAclEvent),
     /// m.room.third_party_invite
     RoomThirdPartyInvite(ThirdPartyInviteEvent),
+    /// m.room.tombstone
+    RoomTombstone(TombstoneEvent),
     /// m.room.topic
     RoomTopic(TopicEvent),
+    /// m.secret.request
+    #[cfg(feature = "events-encryption")]
+    SecretRequest(SecretRequestEvent),
+    /// m.secret.send
+    #[cfg(feature = "events-encryption")]
+    SecretSend(SecretSendEvent),
+    /// m.space.child
+    SpaceChild(ChildEvent),
+    /// m.space.parent
+    SpaceParent(ParentEvent),
+    /// m.sticker
+    Sticker(StickerEvent),
     /// m.tag
     Tag(TagEvent),
     /// m.typing
@@ -86,15 +255,49 @@ pub enum Event {
 
 /// A room event or state event.
 #[derive(Clone, Debug)]
+#[non_exhaustive]
 pub enum RoomEvent {
     /// m.call.answer
+    #[cfg(feature = "events-call")]
     CallAnswer(AnswerEvent),
     /// m.call.candidates
+    #[cfg(feature = "events-call")]
     CallCandidates(CandidatesEvent),
     /// m.call.hangup
+    #[cfg(feature = "events-call")]
     CallHangup(HangupEvent),
     /// m.call.invite
+    #[cfg(feature = "events-call")]
     CallInvite(InviteEvent),
+    /// m.call.negotiate
+    #[cfg(feature = "events-call")]
+    CallNegotiate(NegotiateEvent),
+    /// m.call.reject
+    #[cfg(feature = "events-call")]
+    CallReject(RejectEvent),
+    /// m.call.select_answer
+    #[cfg(feature = "events-call")]
+    CallSelectAnswer(SelectAnswerEvent),
+    /// m.policy.rule.room
+    #[cfg(feature = "events-policy")]
+    PolicyRuleRoom(RoomRuleEvent),
+    /// m.policy.rule.server
+    #[cfg(feature = "events-policy")]
+    PolicyRuleServer(ServerRuleEvent),
+    /// m.policy.rule.user
+    #[cfg(feature = "events-policy")]
+    PolicyRuleUser(UserRuleEvent),
+    /// m.poll.end
+    #[cfg(feature = "events-poll")]
+    PollEnd(PollEndEvent),
+    /// m.poll.response
+    #[cfg(feature = "events-poll")]
+    PollResponse(PollResponseEvent),
+    /// m.poll.start
+    #[cfg(feature = "events-poll")]
+    PollStart(PollStartEvent),
+    /// m.reaction
+    Reaction(ReactionEvent),
     /// m.room.aliases
     RoomAliases(AliasesEvent),
     /// m.room.avatar
@@ -103,6 +306,11 @@ pub enum RoomEvent {
     RoomCanonicalAlias(CanonicalAliasEvent),
     /// m.room.create
     RoomCreate(CreateEvent),
+    /// m.room.encrypted
+    #[cfg(feature = "events-encryption")]
+    RoomEncrypted(EncryptedEvent),
+    /// m.room.encryption
+    RoomEncryption(EncryptionEvent),
     /// m.room.guest_access
     RoomGuestAccess(GuestAccessEvent),
     /// m.room.history_visibility
@@ -115,14 +323,26 @@ pub enum RoomEvent {
     RoomMessage(MessageEvent),
     /// m.room.name
     RoomName(NameEvent),
+    /// m.room.pinned_events
+    RoomPinnedEvents(PinnedEventsEvent),
     /// m.room.power_levels
     RoomPowerLevels(PowerLevelsEvent),
     /// m.room.redaction
     RoomRedaction(RedactionEvent),
+    /// m.room.server_acl
+    RoomServerAcl(ServerAclEvent),
     /// m.room.third_party_invite
     RoomThirdPartyInvite(ThirdPartyInviteEvent),
+    /// m.room.tombstone
+    RoomTombstone(TombstoneEvent),
     /// m.room.topic
     RoomTopic(TopicEvent),
+    /// m.space.child
+    SpaceChild(ChildEvent),
+    /// m.space.parent
+    SpaceParent(ParentEvent),
+    /// m.sticker
+    Sticker(StickerEvent),
     /// Any room event that is not part of the specification.
     CustomRoom(CustomRoomEvent),
     /// Any state event that is not part of the specification.
@@ -131,7 +351,17 @@ pub enum RoomEvent {
 
 /// A state event.
 #[derive(Clone, Debug)]
+#[non_exhaustive]
 pub enum StateEvent {
+    /// m.policy.rule.room
+    #[cfg(feature = "events-policy")]
+    PolicyRuleRoom(RoomRuleEvent),
+    /// m.policy.rule.server
+    #[cfg(feature = "events-policy")]
+    PolicyRuleServer(ServerRuleEvent),
+    /// m.policy.rule.user
+    #[cfg(feature = "events-policy")]
+    PolicyRuleUser(UserRuleEvent),
     /// m.room.aliases
     RoomAliases(AliasesEvent),
     /// m.room.avatar
@@ -140,6 +370,8 @@ pub enum StateEvent {
     RoomCanonicalAlias(CanonicalAliasEvent),
     /// m.room.create
     RoomCreate(CreateEvent),
+    /// m.room.encryption
+    RoomEncryption(EncryptionEvent),
     /// m.room.guest_access
     RoomGuestAccess(GuestAccessEvent),
     /// m.room.history_visibility
@@ -150,12 +382,22 @@ pub enum StateEvent {
     RoomMember(MemberEvent),
     /// m.room.name
     RoomName(NameEvent),
+    /// m.room.pinned_events
+    RoomPinnedEvents(PinnedEventsEvent),
     /// m.room.power_levels
     RoomPowerLevels(PowerLevelsEvent),
+    /// m.room.server_acl
+    RoomServerAcl(ServerAclEvent),
     /// m.room.third_party_invite
     RoomThirdPartyInvite(ThirdPartyInviteEvent),
+    /// m.room.tombstone
+    RoomTombstone(TombstoneEvent),
     /// m.room.topic
     RoomTopic(TopicEvent),
+    /// m.space.child
+    SpaceChild(ChildEvent),
+    /// m.space.parent
+    SpaceParent(ParentEvent),
     /// Any state event that is not part of the specification.
     CustomState(CustomStateEvent),
 }
@@ -163,26 +405,88 @@ pub enum StateEvent {
 impl Serialize for Event {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
         match *self {
+            #[cfg(feature = "events-call")]
             Event::CallAnswer(ref event) => event.serialize(serializer),
+            #[cfg(feature = "events-call")]
             Event::CallCandidates(ref event) => event.serialize(serializer),
+            #[cfg(feature = "events-call")]
             Event::CallHangup(ref event) => event.serialize(serializer),
+            #[cfg(feature = "events-call")]
             Event::CallInvite(ref event) => event.serialize(serializer),
+            #[cfg(feature = "events-call")]
+            Event::CallNegotiate(ref event) => event.serialize(serializer),
+            #[cfg(feature = "events-call")]
+            Event::CallReject(ref event) => event.serialize(serializer),
+            #[cfg(feature = "events-call")]
+            Event::CallSelectAnswer(ref event) => event.serialize(serializer),
+            Event::Direct(ref event) => event.serialize(serializer),
+            #[cfg(feature = "events-encryption")]
+            Event::ForwardedRoomKey(ref event) => event.serialize(serializer),
+            Event::FullyRead(ref event) => event.serialize(serializer),
+            Event::IgnoredUserList(ref event) => event.serialize(serializer),
+            #[cfg(feature = "events-encryption")]
+            Event::KeyVerificationAccept(ref event) => event.serialize(serializer),
+            #[cfg(feature = "events-encryption")]
+            Event::KeyVerificationCancel(ref event) => event.serialize(serializer),
+            #[cfg(feature = "events-encryption")]
+            Event::KeyVerificationDone(ref event) => event.serialize(serializer),
+            #[cfg(feature = "events-encryption")]
+            Event::KeyVerificationKey(ref event) => event.serialize(serializer),
+            #[cfg(feature = "events-encryption")]
+            Event::KeyVerificationMac(ref event) => event.serialize(serializer),
+            #[cfg(feature = "events-encryption")]
+            Event::KeyVerificationRequest(ref event) => event.serialize(serializer),
+            #[cfg(feature = "events-encryption")]
+            Event::KeyVerificationStart(ref event) => event.serialize(serializer),
+            #[cfg(feature = "events-encryption")]
+            Event::MegolmBackupV1(ref event) => event.serialize(serializer),
+            #[cfg(feature = "events-policy")]
+            Event::PolicyRuleRoom(ref event) => event.serialize(serializer),
+            #[cfg(feature = "events-policy")]
+            Event::PolicyRuleServer(ref event) => event.serialize(serializer),
+            #[cfg(feature = "events-policy")]
+            Event::PolicyRuleUser(ref event) => event.serialize(serializer),
+            #[cfg(feature = "events-poll")]
+            Event::PollEnd(ref event) => event.serialize(serializer),
+            #[cfg(feature = "events-poll")]
+            Event::PollResponse(ref event) => event.serialize(serializer),
+            #[cfg(feature = "events-poll")]
+            Event::PollStart(ref event) => event.serialize(serializer),
             Event::Presence(ref event) => event.serialize(serializer),
+            Event::PushRules(ref event) => event.serialize(serializer),
+            Event::Reaction(ref event) => event.serialize(serializer),
             Event::Receipt(ref event) => event.serialize(serializer),
             Event::RoomAliases(ref event) => event.serialize(serializer),
             Event::RoomAvatar(ref event) => event.serialize(serializer),
             Event::RoomCanonicalAlias(ref event) => event.serialize(serializer),
             Event::RoomCreate(ref event) => event.serialize(serializer),
+            #[cfg(feature = "events-encryption")]
+            Event::RoomEncrypted(ref event) => event.serialize(serializer),
+            Event::RoomEncryption(ref event) => event.serialize(serializer),
             Event::RoomGuestAccess(ref event) => event.serialize(serializer),
             Event::RoomHistoryVisibility(ref event) => event.serialize(serializer),
             Event::RoomJoinRules(ref event) => event.serialize(serializer),
+            #[cfg(feature = "events-encryption")]
+            Event::RoomKey(ref event) => event.serialize(serializer),
+            #[cfg(feature = "events-encryption")]
+            Event::RoomKeyRequest(ref event) => event.serialize(serializer),
             Event::RoomMember(ref event) => event.serialize(serializer),
             Event::RoomMessage(ref event) => event.serialize(serializer),
             Event::RoomName(ref event) => event.serialize(serializer),
+            Event::RoomPinnedEvents(ref event) => event.serialize(serializer),
             Event::RoomPowerLevels(ref event) => event.serialize(serializer),
             Event::RoomRedaction(ref event) => event.serialize(serializer),
+            Event::RoomServerAcl(ref event) => event.serialize(serializer),
             Event::RoomThirdPartyInvite(ref event) => event.serialize(serializer),
+            Event::RoomTombstone(ref event) => event.serialize(serializer),
             Event::RoomTopic(ref event) => event.serialize(serializer),
+            #[cfg(feature = "events-encryption")]
+            Event::SecretRequest(ref event) => event.serialize(serializer),
+            #[cfg(feature = "events-encryption")]
+            Event::SecretSend(ref event) => event.serialize(serializer),
+            Event::SpaceChild(ref event) => event.serialize(serializer),
+            Event::SpaceParent(ref event) => event.serialize(serializer),
+            Event::Sticker(ref event) => event.serialize(serializer),
             Event::Tag(ref event) => event.serialize(serializer),
             Event::Typing(ref event) => event.serialize(serializer),
             Event::Custom(ref event) => event.serialize(serializer),
@@ -192,65 +496,319 @@ impl Serialize for Event {
     }
 }
 
-impl<'de> Deserialize<'de> for Event {
-    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where D: Deserializer<'de> {
-        let value: Value = Deserialize::deserialize(deserializer)?;
-
+impl Event {
+    /// Deserializes an event from a JSON value with the given per-call options, such as the
+    /// policy for events whose structure contradicts their `type`.
+    ///
+    /// With `TypeMismatchPolicy::Lenient`, an event whose structure contradicts its `type` is
+    /// returned as a custom variant together with the mismatch.
+    pub fn from_value_with_config(value: Value, config: &DeserializeConfig)
+    -> Result<(Self, Option<StructureMismatch>), JsonError> {
         let event_type_value = match value.get("type") {
-            Some(value) => value.clone(),
-            None => return Err(D::Error::missing_field("type")),
+            Some(value) => value,
+            None => return Err(JsonError::missing_field("type")),
         };
 
-        let event_type = match from_value::<EventType>(event_type_value.clone()) {
+        let event_type = match EventType::deserialize(event_type_value) {
             Ok(event_type) => event_type,
-            Err(error) => return Err(D::Error::custom(error.to_string())),
+            Err(error) => return Err(JsonError::custom(error.to_string())),
         };
 
+        if let Some(mismatch) = structure_mismatch(&event_type, &value) {
+            match config.policy {
+                TypeMismatchPolicy::Ignore => {}
+                TypeMismatchPolicy::Strict => {
+                    return Err(JsonError::custom(mismatch.to_string()));
+                }
+                TypeMismatchPolicy::Lenient => {
+                    config.observe_structure_mismatch(&event_type);
+
+                    if value.get("state_key").is_some() {
+                        let event = match from_value::<CustomStateEvent>(value) {
+                            Ok(event) => event,
+                            Err(error) => return Err(JsonError::custom(error.to_string())),
+                        };
+
+                        return Ok((Event::CustomState(event), Some(mismatch)));
+                    }
+
+                    let event = match from_value::<CustomRoomEvent>(value) {
+                        Ok(event) => event,
+                        Err(error) => return Err(JsonError::custom(error.to_string())),
+                    };
+
+                    return Ok((Event::CustomRoom(event), Some(mismatch)));
+                }
+            }
+        }
+
+        config.observe_unknown_type(&event_type);
+
+        Event::from_typed_value(value, event_type).map(|event| (event, None))
+    }
+
+    /// Deserializes an event from a JSON value like `from_value_with_config`, with the
+    /// given policy and the default for every other option.
+    pub fn from_value_with_policy(value: Value, policy: TypeMismatchPolicy)
+    -> Result<(Self, Option<StructureMismatch>), JsonError> {
+        let config = DeserializeConfig { policy, ..DeserializeConfig::default() };
+
+        Event::from_value_with_config(value, &config)
+    }
+
+    /// Deserializes an event of the given type from a JSON value.
+    fn from_typed_value(value: Value, event_type: EventType) -> Result<Self, JsonError> {
         match event_type {
+            #[cfg(feature = "events-call")]
             EventType::CallAnswer => {
                 let event = match from_value::<AnswerEvent>(value) {
                     Ok(event) => event,
-                    Err(error) => return Err(D::Error::custom(error.to_string())),
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
                 };
 
                 Ok(Event::CallAnswer(event))
             }
+            #[cfg(feature = "events-call")]
             EventType::CallCandidates => {
                 let event = match from_value::<CandidatesEvent>(value) {
                     Ok(event) => event,
-                    Err(error) => return Err(D::Error::custom(error.to_string())),
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
                 };
 
                 Ok(Event::CallCandidates(event))
             }
+            #[cfg(feature = "events-call")]
             EventType::CallHangup => {
                 let event = match from_value::<HangupEvent>(value) {
                     Ok(event) => event,
-                    Err(error) => return Err(D::Error::custom(error.to_string())),
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
                 };
 
                 Ok(Event::CallHangup(event))
             }
+            #[cfg(feature = "events-call")]
             EventType::CallInvite => {
                 let event = match from_value::<InviteEvent>(value) {
                     Ok(event) => event,
-                    Err(error) => return Err(D::Error::custom(error.to_string())),
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
                 };
 
                 Ok(Event::CallInvite(event))
             }
+            #[cfg(feature = "events-call")]
+            EventType::CallNegotiate => {
+                let event = match from_value::<NegotiateEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(Event::CallNegotiate(event))
+            }
+            #[cfg(feature = "events-call")]
+            EventType::CallReject => {
+                let event = match from_value::<RejectEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(Event::CallReject(event))
+            }
+            #[cfg(feature = "events-call")]
+            EventType::CallSelectAnswer => {
+                let event = match from_value::<SelectAnswerEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(Event::CallSelectAnswer(event))
+            }
+            EventType::Direct => {
+                let event = match from_value::<DirectEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(Event::Direct(event))
+            }
+            #[cfg(feature = "events-encryption")]
+            EventType::ForwardedRoomKey => {
+                let event = match from_value::<ForwardedRoomKeyEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(Event::ForwardedRoomKey(event))
+            }
+            EventType::FullyRead => {
+                let event = match from_value::<FullyReadEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(Event::FullyRead(event))
+            }
+            EventType::IgnoredUserList => {
+                let event = match from_value::<IgnoredUserListEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(Event::IgnoredUserList(event))
+            }
+            #[cfg(feature = "events-encryption")]
+            EventType::KeyVerificationAccept => {
+                let event = match from_value::<AcceptEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(Event::KeyVerificationAccept(event))
+            }
+            #[cfg(feature = "events-encryption")]
+            EventType::KeyVerificationCancel => {
+                let event = match from_value::<CancelEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(Event::KeyVerificationCancel(event))
+            }
+            #[cfg(feature = "events-encryption")]
+            EventType::KeyVerificationDone => {
+                let event = match from_value::<DoneEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(Event::KeyVerificationDone(event))
+            }
+            #[cfg(feature = "events-encryption")]
+            EventType::KeyVerificationKey => {
+                let event = match from_value::<KeyEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(Event::KeyVerificationKey(event))
+            }
+            #[cfg(feature = "events-encryption")]
+            EventType::KeyVerificationMac => {
+                let event = match from_value::<MacEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(Event::KeyVerificationMac(event))
+            }
+            #[cfg(feature = "events-encryption")]
+            EventType::KeyVerificationRequest => {
+                let event = match from_value::<RequestEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(Event::KeyVerificationRequest(event))
+            }
+            #[cfg(feature = "events-encryption")]
+            EventType::KeyVerificationStart => {
+                let event = match from_value::<StartEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(Event::KeyVerificationStart(event))
+            }
+            #[cfg(feature = "events-encryption")]
+            EventType::MegolmBackupV1 => {
+                let event = match from_value::<MegolmBackupEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(Event::MegolmBackupV1(event))
+            }
+            #[cfg(feature = "events-policy")]
+            EventType::PolicyRuleRoom => {
+                let event = match from_value::<RoomRuleEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(Event::PolicyRuleRoom(event))
+            }
+            #[cfg(feature = "events-policy")]
+            EventType::PolicyRuleServer => {
+                let event = match from_value::<ServerRuleEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(Event::PolicyRuleServer(event))
+            }
+            #[cfg(feature = "events-policy")]
+            EventType::PolicyRuleUser => {
+                let event = match from_value::<UserRuleEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(Event::PolicyRuleUser(event))
+            }
+            #[cfg(feature = "events-poll")]
+            EventType::PollEnd => {
+                let event = match from_value::<PollEndEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(Event::PollEnd(event))
+            }
+            #[cfg(feature = "events-poll")]
+            EventType::PollResponse => {
+                let event = match from_value::<PollResponseEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(Event::PollResponse(event))
+            }
+            #[cfg(feature = "events-poll")]
+            EventType::PollStart => {
+                let event = match from_value::<PollStartEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(Event::PollStart(event))
+            }
             EventType::Presence => {
                 let event = match from_value::<PresenceEvent>(value) {
                     Ok(event) => event,
-                    Err(error) => return Err(D::Error::custom(error.to_string())),
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
                 };
 
                 Ok(Event::Presence(event))
             }
+            EventType::PushRules => {
+                let event = match from_value::<PushRulesEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(Event::PushRules(event))
+            }
+            EventType::Reaction => {
+                let event = match from_value::<ReactionEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(Event::Reaction(event))
+            }
             EventType::Receipt => {
                 let event = match from_value::<ReceiptEvent>(value) {
                     Ok(event) => event,
-                    Err(error) => return Err(D::Error::custom(error.to_string())),
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
                 };
 
                 Ok(Event::Receipt(event))
@@ -258,7 +816,7 @@ impl<'de> Deserialize<'de> for Event {
             EventType::RoomAliases => {
                 let event = match from_value::<AliasesEvent>(value) {
                     Ok(event) => event,
-                    Err(error) => return Err(D::Error::custom(error.to_string())),
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
                 };
 
                 Ok(Event::RoomAliases(event))
@@ -266,7 +824,7 @@ impl<'de> Deserialize<'de> for Event {
             EventType::RoomAvatar => {
                 let event = match from_value::<AvatarEvent>(value) {
                     Ok(event) => event,
-                    Err(error) => return Err(D::Error::custom(error.to_string())),
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
                 };
 
                 Ok(Event::RoomAvatar(event))
@@ -274,7 +832,7 @@ impl<'de> Deserialize<'de> for Event {
             EventType::RoomCanonicalAlias => {
                 let event = match from_value::<CanonicalAliasEvent>(value) {
                     Ok(event) => event,
-                    Err(error) => return Err(D::Error::custom(error.to_string())),
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
                 };
 
                 Ok(Event::RoomCanonicalAlias(event))
@@ -282,15 +840,32 @@ impl<'de> Deserialize<'de> for Event {
             EventType::RoomCreate => {
                 let event = match from_value::<CreateEvent>(value) {
                     Ok(event) => event,
-                    Err(error) => return Err(D::Error::custom(error.to_string())),
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
                 };
 
                 Ok(Event::RoomCreate(event))
             }
+            #[cfg(feature = "events-encryption")]
+            EventType::RoomEncrypted => {
+                let event = match from_value::<EncryptedEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(Event::RoomEncrypted(event))
+            }
+            EventType::RoomEncryption => {
+                let event = match from_value::<EncryptionEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(Event::RoomEncryption(event))
+            }
             EventType::RoomGuestAccess => {
                 let event = match from_value::<GuestAccessEvent>(value) {
                     Ok(event) => event,
-                    Err(error) => return Err(D::Error::custom(error.to_string())),
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
                 };
 
                 Ok(Event::RoomGuestAccess(event))
@@ -298,7 +873,7 @@ impl<'de> Deserialize<'de> for Event {
             EventType::RoomHistoryVisibility => {
                 let event = match from_value::<HistoryVisibilityEvent>(value) {
                     Ok(event) => event,
-                    Err(error) => return Err(D::Error::custom(error.to_string())),
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
                 };
 
                 Ok(Event::RoomHistoryVisibility(event))
@@ -306,15 +881,33 @@ impl<'de> Deserialize<'de> for Event {
             EventType::RoomJoinRules => {
                 let event = match from_value::<JoinRulesEvent>(value) {
                     Ok(event) => event,
-                    Err(error) => return Err(D::Error::custom(error.to_string())),
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
                 };
 
                 Ok(Event::RoomJoinRules(event))
             }
+            #[cfg(feature = "events-encryption")]
+            EventType::RoomKey => {
+                let event = match from_value::<RoomKeyEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(Event::RoomKey(event))
+            }
+            #[cfg(feature = "events-encryption")]
+            EventType::RoomKeyRequest => {
+                let event = match from_value::<RoomKeyRequestEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(Event::RoomKeyRequest(event))
+            }
             EventType::RoomMember => {
                 let event = match from_value::<MemberEvent>(value) {
                     Ok(event) => event,
-                    Err(error) => return Err(D::Error::custom(error.to_string())),
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
                 };
 
                 Ok(Event::RoomMember(event))
@@ -322,7 +915,7 @@ impl<'de> Deserialize<'de> for Event {
             EventType::RoomMessage => {
                 let event = match from_value::<MessageEvent>(value) {
                     Ok(event) => event,
-                    Err(error) => return Err(D::Error::custom(error.to_string())),
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
                 };
 
                 Ok(Event::RoomMessage(event))
@@ -330,15 +923,23 @@ impl<'de> Deserialize<'de> for Event {
             EventType::RoomName => {
                 let event = match from_value::<NameEvent>(value) {
                     Ok(event) => event,
-                    Err(error) => return Err(D::Error::custom(error.to_string())),
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
                 };
 
                 Ok(Event::RoomName(event))
             }
+            EventType::RoomPinnedEvents => {
+                let event = match from_value::<PinnedEventsEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(Event::RoomPinnedEvents(event))
+            }
             EventType::RoomPowerLevels => {
                 let event = match from_value::<PowerLevelsEvent>(value) {
                     Ok(event) => event,
-                    Err(error) => return Err(D::Error::custom(error.to_string())),
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
                 };
 
                 Ok(Event::RoomPowerLevels(event))
@@ -346,31 +947,89 @@ impl<'de> Deserialize<'de> for Event {
             EventType::RoomRedaction => {
                 let event = match from_value::<RedactionEvent>(value) {
                     Ok(event) => event,
-                    Err(error) => return Err(D::Error::custom(error.to_string())),
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
                 };
 
                 Ok(Event::RoomRedaction(event))
             }
+            EventType::RoomServerAcl => {
+                let event = match from_value::<ServerAclEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(Event::RoomServerAcl(event))
+            }
             EventType::RoomThirdPartyInvite => {
                 let event = match from_value::<ThirdPartyInviteEvent>(value) {
                     Ok(event) => event,
-                    Err(error) => return Err(D::Error::custom(error.to_string())),
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
                 };
 
                 Ok(Event::RoomThirdPartyInvite(event))
             }
+            EventType::RoomTombstone => {
+                let event = match from_value::<TombstoneEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(Event::RoomTombstone(event))
+            }
             EventType::RoomTopic => {
                 let event = match from_value::<TopicEvent>(value) {
                     Ok(event) => event,
-                    Err(error) => return Err(D::Error::custom(error.to_string())),
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
                 };
 
                 Ok(Event::RoomTopic(event))
             }
+            #[cfg(feature = "events-encryption")]
+            EventType::SecretRequest => {
+                let event = match from_value::<SecretRequestEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(Event::SecretRequest(event))
+            }
+            #[cfg(feature = "events-encryption")]
+            EventType::SecretSend => {
+                let event = match from_value::<SecretSendEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(Event::SecretSend(event))
+            }
+            EventType::SpaceChild => {
+                let event = match from_value::<ChildEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(Event::SpaceChild(event))
+            }
+            EventType::SpaceParent => {
+                let event = match from_value::<ParentEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(Event::SpaceParent(event))
+            }
+            EventType::Sticker => {
+                let event = match from_value::<StickerEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(Event::Sticker(event))
+            }
             EventType::Tag => {
                 let event = match from_value::<TagEvent>(value) {
                     Ok(event) => event,
-                    Err(error) => return Err(D::Error::custom(error.to_string())),
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
                 };
 
                 Ok(Event::Tag(event))
@@ -378,7 +1037,7 @@ impl<'de> Deserialize<'de> for Event {
             EventType::Typing => {
                 let event = match from_value::<TypingEvent>(value) {
                     Ok(event) => event,
-                    Err(error) => return Err(D::Error::custom(error.to_string())),
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
                 };
 
                 Ok(Event::Typing(event))
@@ -387,7 +1046,7 @@ impl<'de> Deserialize<'de> for Event {
                 if value.get("state_key").is_some() {
                     let event = match from_value::<CustomStateEvent>(value) {
                         Ok(event) => event,
-                        Err(error) => return Err(D::Error::custom(error.to_string())),
+                        Err(error) => return Err(JsonError::custom(error.to_string())),
                     };
 
                     Ok(Event::CustomState(event))
@@ -395,14 +1054,14 @@ impl<'de> Deserialize<'de> for Event {
                     value.get("sender").is_some() {
                     let event = match from_value::<CustomRoomEvent>(value) {
                         Ok(event) => event,
-                        Err(error) => return Err(D::Error::custom(error.to_string())),
+                        Err(error) => return Err(JsonError::custom(error.to_string())),
                     };
 
                     Ok(Event::CustomRoom(event))
                 } else {
                     let event = match from_value::<CustomEvent>(value) {
                         Ok(event) => event,
-                        Err(error) => return Err(D::Error::custom(error.to_string())),
+                        Err(error) => return Err(JsonError::custom(error.to_string())),
                     };
 
                     Ok(Event::Custom(event))
@@ -412,84 +1071,268 @@ impl<'de> Deserialize<'de> for Event {
     }
 }
 
+impl<'de> Deserialize<'de> for Event {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where D: Deserializer<'de> {
+        let value = deserialize_limited_value(deserializer)?;
+
+        Event::from_value_with_config(value, &DeserializeConfig::default())
+            .map(|(event, _)| event)
+            .map_err(|error| D::Error::custom(error.to_string()))
+    }
+}
+
 impl Serialize for RoomEvent {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
         match *self {
+            #[cfg(feature = "events-call")]
             RoomEvent::CallAnswer(ref event) => event.serialize(serializer),
+            #[cfg(feature = "events-call")]
             RoomEvent::CallCandidates(ref event) => event.serialize(serializer),
+            #[cfg(feature = "events-call")]
             RoomEvent::CallHangup(ref event) => event.serialize(serializer),
+            #[cfg(feature = "events-call")]
             RoomEvent::CallInvite(ref event) => event.serialize(serializer),
+            #[cfg(feature = "events-call")]
+            RoomEvent::CallNegotiate(ref event) => event.serialize(serializer),
+            #[cfg(feature = "events-call")]
+            RoomEvent::CallReject(ref event) => event.serialize(serializer),
+            #[cfg(feature = "events-call")]
+            RoomEvent::CallSelectAnswer(ref event) => event.serialize(serializer),
+            #[cfg(feature = "events-policy")]
+            RoomEvent::PolicyRuleRoom(ref event) => event.serialize(serializer),
+            #[cfg(feature = "events-policy")]
+            RoomEvent::PolicyRuleServer(ref event) => event.serialize(serializer),
+            #[cfg(feature = "events-policy")]
+            RoomEvent::PolicyRuleUser(ref event) => event.serialize(serializer),
+            #[cfg(feature = "events-poll")]
+            RoomEvent::PollEnd(ref event) => event.serialize(serializer),
+            #[cfg(feature = "events-poll")]
+            RoomEvent::PollResponse(ref event) => event.serialize(serializer),
+            #[cfg(feature = "events-poll")]
+            RoomEvent::PollStart(ref event) => event.serialize(serializer),
+            RoomEvent::Reaction(ref event) => event.serialize(serializer),
             RoomEvent::RoomAliases(ref event) => event.serialize(serializer),
             RoomEvent::RoomAvatar(ref event) => event.serialize(serializer),
             RoomEvent::RoomCanonicalAlias(ref event) => event.serialize(serializer),
             RoomEvent::RoomCreate(ref event) => event.serialize(serializer),
+            #[cfg(feature = "events-encryption")]
+            RoomEvent::RoomEncrypted(ref event) => event.serialize(serializer),
+            RoomEvent::RoomEncryption(ref event) => event.serialize(serializer),
             RoomEvent::RoomGuestAccess(ref event) => event.serialize(serializer),
             RoomEvent::RoomHistoryVisibility(ref event) => event.serialize(serializer),
             RoomEvent::RoomJoinRules(ref event) => event.serialize(serializer),
             RoomEvent::RoomMember(ref event) => event.serialize(serializer),
             RoomEvent::RoomMessage(ref event) => event.serialize(serializer),
             RoomEvent::RoomName(ref event) => event.serialize(serializer),
+            RoomEvent::RoomPinnedEvents(ref event) => event.serialize(serializer),
             RoomEvent::RoomPowerLevels(ref event) => event.serialize(serializer),
             RoomEvent::RoomRedaction(ref event) => event.serialize(serializer),
+            RoomEvent::RoomServerAcl(ref event) => event.serialize(serializer),
             RoomEvent::RoomThirdPartyInvite(ref event) => event.serialize(serializer),
+            RoomEvent::RoomTombstone(ref event) => event.serialize(serializer),
             RoomEvent::RoomTopic(ref event) => event.serialize(serializer),
+            RoomEvent::SpaceChild(ref event) => event.serialize(serializer),
+            RoomEvent::SpaceParent(ref event) => event.serialize(serializer),
+            RoomEvent::Sticker(ref event) => event.serialize(serializer),
             RoomEvent::CustomRoom(ref event) => event.serialize(serializer),
             RoomEvent::CustomState(ref event) => event.serialize(serializer),
         }
     }
 }
 
-impl<'de> Deserialize<'de> for RoomEvent {
-    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where D: Deserializer<'de> {
-        let value: Value = Deserialize::deserialize(deserializer)?;
-
+impl RoomEvent {
+    /// Deserializes a room event from a JSON value with the given per-call options, such as the
+    /// policy for events whose structure contradicts their `type`.
+    ///
+    /// With `TypeMismatchPolicy::Lenient`, an event whose structure contradicts its `type` is
+    /// returned as a custom variant together with the mismatch.
+    pub fn from_value_with_config(value: Value, config: &DeserializeConfig)
+    -> Result<(Self, Option<StructureMismatch>), JsonError> {
         let event_type_value = match value.get("type") {
-            Some(value) => value.clone(),
-            None => return Err(D::Error::missing_field("type")),
+            Some(value) => value,
+            None => return Err(JsonError::missing_field("type")),
         };
 
-        let event_type = match from_value::<EventType>(event_type_value.clone()) {
+        let event_type = match EventType::deserialize(event_type_value) {
             Ok(event_type) => event_type,
-            Err(error) => return Err(D::Error::custom(error.to_string())),
+            Err(error) => return Err(JsonError::custom(error.to_string())),
         };
 
+        if let Some(mismatch) = structure_mismatch(&event_type, &value) {
+            match config.policy {
+                TypeMismatchPolicy::Ignore => {}
+                TypeMismatchPolicy::Strict => {
+                    return Err(JsonError::custom(mismatch.to_string()));
+                }
+                TypeMismatchPolicy::Lenient => {
+                    config.observe_structure_mismatch(&event_type);
+
+                    if value.get("state_key").is_some() {
+                        let event = match from_value::<CustomStateEvent>(value) {
+                            Ok(event) => event,
+                            Err(error) => return Err(JsonError::custom(error.to_string())),
+                        };
+
+                        return Ok((RoomEvent::CustomState(event), Some(mismatch)));
+                    }
+
+                    let event = match from_value::<CustomRoomEvent>(value) {
+                        Ok(event) => event,
+                        Err(error) => return Err(JsonError::custom(error.to_string())),
+                    };
+
+                    return Ok((RoomEvent::CustomRoom(event), Some(mismatch)));
+                }
+            }
+        }
+
+        config.observe_unknown_type(&event_type);
+
+        RoomEvent::from_typed_value(value, event_type).map(|event| (event, None))
+    }
+
+    /// Deserializes a room event from a JSON value like `from_value_with_config`, with the
+    /// given policy and the default for every other option.
+    pub fn from_value_with_policy(value: Value, policy: TypeMismatchPolicy)
+    -> Result<(Self, Option<StructureMismatch>), JsonError> {
+        let config = DeserializeConfig { policy, ..DeserializeConfig::default() };
+
+        RoomEvent::from_value_with_config(value, &config)
+    }
+
+    /// Deserializes an event of the given type from a JSON value.
+    #[allow(clippy::needless_return)]
+    fn from_typed_value(value: Value, event_type: EventType) -> Result<Self, JsonError> {
         match event_type {
+            #[cfg(feature = "events-call")]
             EventType::CallAnswer => {
                 let event = match from_value::<AnswerEvent>(value) {
                     Ok(event) => event,
-                    Err(error) => return Err(D::Error::custom(error.to_string())),
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
                 };
 
                 Ok(RoomEvent::CallAnswer(event))
             }
+            #[cfg(feature = "events-call")]
             EventType::CallCandidates => {
                 let event = match from_value::<CandidatesEvent>(value) {
                     Ok(event) => event,
-                    Err(error) => return Err(D::Error::custom(error.to_string())),
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
                 };
 
                 Ok(RoomEvent::CallCandidates(event))
             }
+            #[cfg(feature = "events-call")]
             EventType::CallHangup => {
                 let event = match from_value::<HangupEvent>(value) {
                     Ok(event) => event,
-                    Err(error) => return Err(D::Error::custom(error.to_string())),
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
                 };
 
                 Ok(RoomEvent::CallHangup(event))
             }
+            #[cfg(feature = "events-call")]
             EventType::CallInvite => {
                 let event = match from_value::<InviteEvent>(value) {
                     Ok(event) => event,
-                    Err(error) => return Err(D::Error::custom(error.to_string())),
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
                 };
 
                 Ok(RoomEvent::CallInvite(event))
             }
+            #[cfg(feature = "events-call")]
+            EventType::CallNegotiate => {
+                let event = match from_value::<NegotiateEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(RoomEvent::CallNegotiate(event))
+            }
+            #[cfg(feature = "events-call")]
+            EventType::CallReject => {
+                let event = match from_value::<RejectEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(RoomEvent::CallReject(event))
+            }
+            #[cfg(feature = "events-call")]
+            EventType::CallSelectAnswer => {
+                let event = match from_value::<SelectAnswerEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(RoomEvent::CallSelectAnswer(event))
+            }
+            #[cfg(feature = "events-policy")]
+            EventType::PolicyRuleRoom => {
+                let event = match from_value::<RoomRuleEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(RoomEvent::PolicyRuleRoom(event))
+            }
+            #[cfg(feature = "events-policy")]
+            EventType::PolicyRuleServer => {
+                let event = match from_value::<ServerRuleEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(RoomEvent::PolicyRuleServer(event))
+            }
+            #[cfg(feature = "events-policy")]
+            EventType::PolicyRuleUser => {
+                let event = match from_value::<UserRuleEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(RoomEvent::PolicyRuleUser(event))
+            }
+            #[cfg(feature = "events-poll")]
+            EventType::PollEnd => {
+                let event = match from_value::<PollEndEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(RoomEvent::PollEnd(event))
+            }
+            #[cfg(feature = "events-poll")]
+            EventType::PollResponse => {
+                let event = match from_value::<PollResponseEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(RoomEvent::PollResponse(event))
+            }
+            #[cfg(feature = "events-poll")]
+            EventType::PollStart => {
+                let event = match from_value::<PollStartEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(RoomEvent::PollStart(event))
+            }
+            EventType::Reaction => {
+                let event = match from_value::<ReactionEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(RoomEvent::Reaction(event))
+            }
             EventType::RoomAliases => {
                 let event = match from_value::<AliasesEvent>(value) {
                     Ok(event) => event,
-                    Err(error) => return Err(D::Error::custom(error.to_string())),
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
                 };
 
                 Ok(RoomEvent::RoomAliases(event))
@@ -497,7 +1340,7 @@ impl<'de> Deserialize<'de> for RoomEvent {
             EventType::RoomAvatar => {
                 let event = match from_value::<AvatarEvent>(value) {
                     Ok(event) => event,
-                    Err(error) => return Err(D::Error::custom(error.to_string())),
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
                 };
 
                 Ok(RoomEvent::RoomAvatar(event))
@@ -505,7 +1348,7 @@ impl<'de> Deserialize<'de> for RoomEvent {
             EventType::RoomCanonicalAlias => {
                 let event = match from_value::<CanonicalAliasEvent>(value) {
                     Ok(event) => event,
-                    Err(error) => return Err(D::Error::custom(error.to_string())),
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
                 };
 
                 Ok(RoomEvent::RoomCanonicalAlias(event))
@@ -513,15 +1356,32 @@ impl<'de> Deserialize<'de> for RoomEvent {
             EventType::RoomCreate => {
                 let event = match from_value::<CreateEvent>(value) {
                     Ok(event) => event,
-                    Err(error) => return Err(D::Error::custom(error.to_string())),
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
                 };
 
                 Ok(RoomEvent::RoomCreate(event))
             }
+            #[cfg(feature = "events-encryption")]
+            EventType::RoomEncrypted => {
+                let event = match from_value::<EncryptedEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(RoomEvent::RoomEncrypted(event))
+            }
+            EventType::RoomEncryption => {
+                let event = match from_value::<EncryptionEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(RoomEvent::RoomEncryption(event))
+            }
             EventType::RoomGuestAccess => {
                 let event = match from_value::<GuestAccessEvent>(value) {
                     Ok(event) => event,
-                    Err(error) => return Err(D::Error::custom(error.to_string())),
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
                 };
 
                 Ok(RoomEvent::RoomGuestAccess(event))
@@ -529,7 +1389,7 @@ impl<'de> Deserialize<'de> for RoomEvent {
             EventType::RoomHistoryVisibility => {
                 let event = match from_value::<HistoryVisibilityEvent>(value) {
                     Ok(event) => event,
-                    Err(error) => return Err(D::Error::custom(error.to_string())),
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
                 };
 
                 Ok(RoomEvent::RoomHistoryVisibility(event))
@@ -537,7 +1397,7 @@ impl<'de> Deserialize<'de> for RoomEvent {
             EventType::RoomJoinRules => {
                 let event = match from_value::<JoinRulesEvent>(value) {
                     Ok(event) => event,
-                    Err(error) => return Err(D::Error::custom(error.to_string())),
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
                 };
 
                 Ok(RoomEvent::RoomJoinRules(event))
@@ -545,7 +1405,7 @@ impl<'de> Deserialize<'de> for RoomEvent {
             EventType::RoomMember => {
                 let event = match from_value::<MemberEvent>(value) {
                     Ok(event) => event,
-                    Err(error) => return Err(D::Error::custom(error.to_string())),
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
                 };
 
                 Ok(RoomEvent::RoomMember(event))
@@ -553,7 +1413,7 @@ impl<'de> Deserialize<'de> for RoomEvent {
             EventType::RoomMessage => {
                 let event = match from_value::<MessageEvent>(value) {
                     Ok(event) => event,
-                    Err(error) => return Err(D::Error::custom(error.to_string())),
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
                 };
 
                 Ok(RoomEvent::RoomMessage(event))
@@ -561,15 +1421,23 @@ impl<'de> Deserialize<'de> for RoomEvent {
             EventType::RoomName => {
                 let event = match from_value::<NameEvent>(value) {
                     Ok(event) => event,
-                    Err(error) => return Err(D::Error::custom(error.to_string())),
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
                 };
 
                 Ok(RoomEvent::RoomName(event))
             }
+            EventType::RoomPinnedEvents => {
+                let event = match from_value::<PinnedEventsEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(RoomEvent::RoomPinnedEvents(event))
+            }
             EventType::RoomPowerLevels => {
                 let event = match from_value::<PowerLevelsEvent>(value) {
                     Ok(event) => event,
-                    Err(error) => return Err(D::Error::custom(error.to_string())),
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
                 };
 
                 Ok(RoomEvent::RoomPowerLevels(event))
@@ -577,90 +1445,231 @@ impl<'de> Deserialize<'de> for RoomEvent {
             EventType::RoomRedaction => {
                 let event = match from_value::<RedactionEvent>(value) {
                     Ok(event) => event,
-                    Err(error) => return Err(D::Error::custom(error.to_string())),
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
                 };
 
                 Ok(RoomEvent::RoomRedaction(event))
             }
+            EventType::RoomServerAcl => {
+                let event = match from_value::<ServerAclEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(RoomEvent::RoomServerAcl(event))
+            }
             EventType::RoomThirdPartyInvite => {
                 let event = match from_value::<ThirdPartyInviteEvent>(value) {
                     Ok(event) => event,
-                    Err(error) => return Err(D::Error::custom(error.to_string())),
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
                 };
 
                 Ok(RoomEvent::RoomThirdPartyInvite(event))
             }
+            EventType::RoomTombstone => {
+                let event = match from_value::<TombstoneEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(RoomEvent::RoomTombstone(event))
+            }
             EventType::RoomTopic => {
                 let event = match from_value::<TopicEvent>(value) {
                     Ok(event) => event,
-                    Err(error) => return Err(D::Error::custom(error.to_string())),
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
                 };
 
                 Ok(RoomEvent::RoomTopic(event))
             }
+            EventType::SpaceChild => {
+                let event = match from_value::<ChildEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(RoomEvent::SpaceChild(event))
+            }
+            EventType::SpaceParent => {
+                let event = match from_value::<ParentEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(RoomEvent::SpaceParent(event))
+            }
+            EventType::Sticker => {
+                let event = match from_value::<StickerEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(RoomEvent::Sticker(event))
+            }
             EventType::Custom(_) => {
                 if value.get("state_key").is_some() {
                     let event = match from_value::<CustomStateEvent>(value) {
                         Ok(event) => event,
-                        Err(error) => return Err(D::Error::custom(error.to_string())),
+                        Err(error) => return Err(JsonError::custom(error.to_string())),
                     };
 
                     Ok(RoomEvent::CustomState(event))
                 } else {
                     let event = match from_value::<CustomRoomEvent>(value) {
                         Ok(event) => event,
-                        Err(error) => return Err(D::Error::custom(error.to_string())),
+                        Err(error) => return Err(JsonError::custom(error.to_string())),
                     };
 
                     Ok(RoomEvent::CustomRoom(event))
                 }
             }
-            EventType::Presence | EventType::Receipt | EventType::Tag | EventType::Typing => {
-                return Err(D::Error::custom("not a room event".to_string()));
+            EventType::Direct | EventType::FullyRead | EventType::IgnoredUserList |
+            EventType::Presence | EventType::PushRules | EventType::Receipt | EventType::Tag |
+            EventType::Typing => {
+                return Err(JsonError::custom("not a room event".to_string()));
+            }
+            #[cfg(feature = "events-encryption")]
+            EventType::ForwardedRoomKey | EventType::KeyVerificationAccept |
+            EventType::KeyVerificationCancel | EventType::KeyVerificationDone |
+            EventType::KeyVerificationKey | EventType::KeyVerificationMac |
+            EventType::KeyVerificationRequest | EventType::KeyVerificationStart |
+            EventType::MegolmBackupV1 | EventType::RoomKey | EventType::RoomKeyRequest |
+            EventType::SecretRequest | EventType::SecretSend => {
+                return Err(JsonError::custom("not a room event".to_string()));
             }
         }
     }
 }
 
+impl<'de> Deserialize<'de> for RoomEvent {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where D: Deserializer<'de> {
+        let value = deserialize_limited_value(deserializer)?;
+
+        RoomEvent::from_value_with_config(value, &DeserializeConfig::default())
+            .map(|(event, _)| event)
+            .map_err(|error| D::Error::custom(error.to_string()))
+    }
+}
+
 impl Serialize for StateEvent {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
         match *self {
+            #[cfg(feature = "events-policy")]
+            StateEvent::PolicyRuleRoom(ref event) => event.serialize(serializer),
+            #[cfg(feature = "events-policy")]
+            StateEvent::PolicyRuleServer(ref event) => event.serialize(serializer),
+            #[cfg(feature = "events-policy")]
+            StateEvent::PolicyRuleUser(ref event) => event.serialize(serializer),
             StateEvent::RoomAliases(ref event) => event.serialize(serializer),
             StateEvent::RoomAvatar(ref event) => event.serialize(serializer),
             StateEvent::RoomCanonicalAlias(ref event) => event.serialize(serializer),
             StateEvent::RoomCreate(ref event) => event.serialize(serializer),
+            StateEvent::RoomEncryption(ref event) => event.serialize(serializer),
             StateEvent::RoomGuestAccess(ref event) => event.serialize(serializer),
             StateEvent::RoomHistoryVisibility(ref event) => event.serialize(serializer),
             StateEvent::RoomJoinRules(ref event) => event.serialize(serializer),
             StateEvent::RoomMember(ref event) => event.serialize(serializer),
             StateEvent::RoomName(ref event) => event.serialize(serializer),
+            StateEvent::RoomPinnedEvents(ref event) => event.serialize(serializer),
             StateEvent::RoomPowerLevels(ref event) => event.serialize(serializer),
+            StateEvent::RoomServerAcl(ref event) => event.serialize(serializer),
             StateEvent::RoomThirdPartyInvite(ref event) => event.serialize(serializer),
+            StateEvent::RoomTombstone(ref event) => event.serialize(serializer),
             StateEvent::RoomTopic(ref event) => event.serialize(serializer),
+            StateEvent::SpaceChild(ref event) => event.serialize(serializer),
+            StateEvent::SpaceParent(ref event) => event.serialize(serializer),
             StateEvent::CustomState(ref event) => event.serialize(serializer),
         }
     }
 }
 
-impl<'de> Deserialize<'de> for StateEvent {
-    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where D: Deserializer<'de> {
-        let value: Value = Deserialize::deserialize(deserializer)?;
-
+impl StateEvent {
+    /// Deserializes a state event from a JSON value with the given per-call options, such as the
+    /// policy for events whose structure contradicts their `type`.
+    ///
+    /// With `TypeMismatchPolicy::Lenient`, an event with a `state_key` whose type is not a state
+    /// event type is returned as a custom state event together with the mismatch. A state event
+    /// without a `state_key` is rejected like with `TypeMismatchPolicy::Strict`.
+    pub fn from_value_with_config(value: Value, config: &DeserializeConfig)
+    -> Result<(Self, Option<StructureMismatch>), JsonError> {
         let event_type_value = match value.get("type") {
-            Some(value) => value.clone(),
-            None => return Err(D::Error::missing_field("type")),
+            Some(value) => value,
+            None => return Err(JsonError::missing_field("type")),
         };
 
-        let event_type = match from_value::<EventType>(event_type_value.clone()) {
+        let event_type = match EventType::deserialize(event_type_value) {
             Ok(event_type) => event_type,
-            Err(error) => return Err(D::Error::custom(error.to_string())),
+            Err(error) => return Err(JsonError::custom(error.to_string())),
         };
 
+        if let Some(mismatch) = structure_mismatch(&event_type, &value) {
+            match config.policy {
+                TypeMismatchPolicy::Ignore => {}
+                TypeMismatchPolicy::Lenient if value.get("state_key").is_some() => {
+                    config.observe_structure_mismatch(&event_type);
+
+                    let event = match from_value::<CustomStateEvent>(value) {
+                        Ok(event) => event,
+                        Err(error) => return Err(JsonError::custom(error.to_string())),
+                    };
+
+                    return Ok((StateEvent::CustomState(event), Some(mismatch)));
+                }
+                TypeMismatchPolicy::Lenient | TypeMismatchPolicy::Strict => {
+                    return Err(JsonError::custom(mismatch.to_string()));
+                }
+            }
+        }
+
+        config.observe_unknown_type(&event_type);
+
+        StateEvent::from_typed_value(value, event_type).map(|event| (event, None))
+    }
+
+    /// Deserializes a state event from a JSON value like `from_value_with_config`, with the
+    /// given policy and the default for every other option.
+    pub fn from_value_with_policy(value: Value, policy: TypeMismatchPolicy)
+    -> Result<(Self, Option<StructureMismatch>), JsonError> {
+        let config = DeserializeConfig { policy, ..DeserializeConfig::default() };
+
+        StateEvent::from_value_with_config(value, &config)
+    }
+
+    /// Deserializes an event of the given type from a JSON value.
+    #[allow(clippy::needless_return)]
+    fn from_typed_value(value: Value, event_type: EventType) -> Result<Self, JsonError> {
         match event_type {
+            #[cfg(feature = "events-policy")]
+            EventType::PolicyRuleRoom => {
+                let event = match from_value::<RoomRuleEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(StateEvent::PolicyRuleRoom(event))
+            }
+            #[cfg(feature = "events-policy")]
+            EventType::PolicyRuleServer => {
+                let event = match from_value::<ServerRuleEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(StateEvent::PolicyRuleServer(event))
+            }
+            #[cfg(feature = "events-policy")]
+            EventType::PolicyRuleUser => {
+                let event = match from_value::<UserRuleEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(StateEvent::PolicyRuleUser(event))
+            }
             EventType::RoomAliases => {
                 let event = match from_value::<AliasesEvent>(value) {
                     Ok(event) => event,
-                    Err(error) => return Err(D::Error::custom(error.to_string())),
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
                 };
 
                 Ok(StateEvent::RoomAliases(event))
@@ -668,7 +1677,7 @@ impl<'de> Deserialize<'de> for StateEvent {
             EventType::RoomAvatar => {
                 let event = match from_value::<AvatarEvent>(value) {
                     Ok(event) => event,
-                    Err(error) => return Err(D::Error::custom(error.to_string())),
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
                 };
 
                 Ok(StateEvent::RoomAvatar(event))
@@ -676,7 +1685,7 @@ impl<'de> Deserialize<'de> for StateEvent {
             EventType::RoomCanonicalAlias => {
                 let event = match from_value::<CanonicalAliasEvent>(value) {
                     Ok(event) => event,
-                    Err(error) => return Err(D::Error::custom(error.to_string())),
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
                 };
 
                 Ok(StateEvent::RoomCanonicalAlias(event))
@@ -684,15 +1693,23 @@ impl<'de> Deserialize<'de> for StateEvent {
             EventType::RoomCreate => {
                 let event = match from_value::<CreateEvent>(value) {
                     Ok(event) => event,
-                    Err(error) => return Err(D::Error::custom(error.to_string())),
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
                 };
 
                 Ok(StateEvent::RoomCreate(event))
             }
+            EventType::RoomEncryption => {
+                let event = match from_value::<EncryptionEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(StateEvent::RoomEncryption(event))
+            }
             EventType::RoomGuestAccess => {
                 let event = match from_value::<GuestAccessEvent>(value) {
                     Ok(event) => event,
-                    Err(error) => return Err(D::Error::custom(error.to_string())),
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
                 };
 
                 Ok(StateEvent::RoomGuestAccess(event))
@@ -700,7 +1717,7 @@ impl<'de> Deserialize<'de> for StateEvent {
             EventType::RoomHistoryVisibility => {
                 let event = match from_value::<HistoryVisibilityEvent>(value) {
                     Ok(event) => event,
-                    Err(error) => return Err(D::Error::custom(error.to_string())),
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
                 };
 
                 Ok(StateEvent::RoomHistoryVisibility(event))
@@ -708,7 +1725,7 @@ impl<'de> Deserialize<'de> for StateEvent {
             EventType::RoomJoinRules => {
                 let event = match from_value::<JoinRulesEvent>(value) {
                     Ok(event) => event,
-                    Err(error) => return Err(D::Error::custom(error.to_string())),
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
                 };
 
                 Ok(StateEvent::RoomJoinRules(event))
@@ -716,7 +1733,7 @@ impl<'de> Deserialize<'de> for StateEvent {
             EventType::RoomMember => {
                 let event = match from_value::<MemberEvent>(value) {
                     Ok(event) => event,
-                    Err(error) => return Err(D::Error::custom(error.to_string())),
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
                 };
 
                 Ok(StateEvent::RoomMember(event))
@@ -724,140 +1741,401 @@ impl<'de> Deserialize<'de> for StateEvent {
             EventType::RoomName => {
                 let event = match from_value::<NameEvent>(value) {
                     Ok(event) => event,
-                    Err(error) => return Err(D::Error::custom(error.to_string())),
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
                 };
 
                 Ok(StateEvent::RoomName(event))
             }
+            EventType::RoomPinnedEvents => {
+                let event = match from_value::<PinnedEventsEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(StateEvent::RoomPinnedEvents(event))
+            }
             EventType::RoomPowerLevels => {
                 let event = match from_value::<PowerLevelsEvent>(value) {
                     Ok(event) => event,
-                    Err(error) => return Err(D::Error::custom(error.to_string())),
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
                 };
 
                 Ok(StateEvent::RoomPowerLevels(event))
             }
+            EventType::RoomServerAcl => {
+                let event = match from_value::<ServerAclEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(StateEvent::RoomServerAcl(event))
+            }
             EventType::RoomThirdPartyInvite => {
                 let event = match from_value::<ThirdPartyInviteEvent>(value) {
                     Ok(event) => event,
-                    Err(error) => return Err(D::Error::custom(error.to_string())),
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
                 };
 
                 Ok(StateEvent::RoomThirdPartyInvite(event))
             }
+            EventType::RoomTombstone => {
+                let event = match from_value::<TombstoneEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(StateEvent::RoomTombstone(event))
+            }
             EventType::RoomTopic => {
                 let event = match from_value::<TopicEvent>(value) {
                     Ok(event) => event,
-                    Err(error) => return Err(D::Error::custom(error.to_string())),
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
                 };
 
                 Ok(StateEvent::RoomTopic(event))
             }
+            EventType::SpaceChild => {
+                let event = match from_value::<ChildEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(StateEvent::SpaceChild(event))
+            }
+            EventType::SpaceParent => {
+                let event = match from_value::<ParentEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(StateEvent::SpaceParent(event))
+            }
             EventType::Custom(_) => {
                 let event = match from_value::<CustomStateEvent>(value) {
                     Ok(event) => event,
-                    Err(error) => return Err(D::Error::custom(error.to_string())),
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
                 };
 
                 Ok(StateEvent::CustomState(event))
             }
+            EventType::Direct | EventType::FullyRead | EventType::IgnoredUserList |
+            EventType::Presence | EventType::PushRules | EventType::Reaction | EventType::Receipt |
+            EventType::RoomMessage | EventType::RoomRedaction | EventType::Sticker |
+            EventType::Tag | EventType::Typing => {
+                return Err(JsonError::custom("not a state event".to_string()));
+            }
+            #[cfg(feature = "events-encryption")]
+            EventType::ForwardedRoomKey | EventType::KeyVerificationAccept |
+            EventType::KeyVerificationCancel | EventType::KeyVerificationDone |
+            EventType::KeyVerificationKey | EventType::KeyVerificationMac |
+            EventType::KeyVerificationRequest | EventType::KeyVerificationStart |
+            EventType::MegolmBackupV1 | EventType::RoomEncrypted | EventType::RoomKey |
+            EventType::RoomKeyRequest | EventType::SecretRequest | EventType::SecretSend => {
+                return Err(JsonError::custom("not a state event".to_string()));
+            }
+            #[cfg(feature = "events-call")]
             EventType::CallAnswer | EventType::CallCandidates | EventType::CallHangup |
-            EventType::CallInvite | EventType::Presence | EventType::Receipt |
-            EventType::RoomMessage | EventType::RoomRedaction | EventType::Tag |
-            EventType::Typing => {
-                return Err(D::Error::custom("not a state event".to_string()));
+            EventType::CallInvite | EventType::CallNegotiate | EventType::CallReject |
+            EventType::CallSelectAnswer => {
+                Err(JsonError::custom("not a state event".to_string()))
+            }
+            #[cfg(feature = "events-poll")]
+            EventType::PollEnd | EventType::PollResponse | EventType::PollStart => {
+                Err(JsonError::custom("not a state event".to_string()))
             }
         }
     }
 }
 
-macro_rules! impl_from_t_for_event {
-    ($ty:ty, $variant:ident) => {
-        impl From<$ty> for Event {
-            fn from(event: $ty) -> Self {
-                Event::$variant(event)
+impl<'de> Deserialize<'de> for StateEvent {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where D: Deserializer<'de> {
+        let value = deserialize_limited_value(deserializer)?;
+
+        StateEvent::from_value_with_config(value, &DeserializeConfig::default())
+            .map(|(event, _)| event)
+            .map_err(|error| D::Error::custom(error.to_string()))
+    }
+}
+
+impl Event {
+    /// Converts this event into a state event, if it is one.
+    pub fn into_state(self) -> Option<StateEvent> {
+        match self {
+            #[cfg(feature = "events-policy")]
+            Event::PolicyRuleRoom(event) => Some(StateEvent::PolicyRuleRoom(event)),
+            #[cfg(feature = "events-policy")]
+            Event::PolicyRuleServer(event) => Some(StateEvent::PolicyRuleServer(event)),
+            #[cfg(feature = "events-policy")]
+            Event::PolicyRuleUser(event) => Some(StateEvent::PolicyRuleUser(event)),
+            Event::RoomAliases(event) => Some(StateEvent::RoomAliases(event)),
+            Event::RoomAvatar(event) => Some(StateEvent::RoomAvatar(event)),
+            Event::RoomCanonicalAlias(event) => Some(StateEvent::RoomCanonicalAlias(event)),
+            Event::RoomCreate(event) => Some(StateEvent::RoomCreate(event)),
+            Event::RoomEncryption(event) => Some(StateEvent::RoomEncryption(event)),
+            Event::RoomGuestAccess(event) => Some(StateEvent::RoomGuestAccess(event)),
+            Event::RoomHistoryVisibility(event) => Some(StateEvent::RoomHistoryVisibility(event)),
+            Event::RoomJoinRules(event) => Some(StateEvent::RoomJoinRules(event)),
+            Event::RoomMember(event) => Some(StateEvent::RoomMember(event)),
+            Event::RoomName(event) => Some(StateEvent::RoomName(event)),
+            Event::RoomPinnedEvents(event) => Some(StateEvent::RoomPinnedEvents(event)),
+            Event::RoomPowerLevels(event) => Some(StateEvent::RoomPowerLevels(event)),
+            Event::RoomServerAcl(event) => Some(StateEvent::RoomServerAcl(event)),
+            Event::RoomThirdPartyInvite(event) => Some(StateEvent::RoomThirdPartyInvite(event)),
+            Event::RoomTombstone(event) => Some(StateEvent::RoomTombstone(event)),
+            Event::RoomTopic(event) => Some(StateEvent::RoomTopic(event)),
+            Event::SpaceChild(event) => Some(StateEvent::SpaceChild(event)),
+            Event::SpaceParent(event) => Some(StateEvent::SpaceParent(event)),
+            Event::CustomState(event) => Some(StateEvent::CustomState(event)),
+            _ => None,
+        }
+    }
+
+    /// Copies this event into a state event, if it is one.
+    ///
+    /// This allows extracting state events with `events.iter().filter_map(Event::as_state)`.
+    pub fn as_state(&self) -> Option<StateEvent> {
+        match *self {
+            #[cfg(feature = "events-policy")]
+            Event::PolicyRuleRoom(ref event) => Some(StateEvent::PolicyRuleRoom(event.clone())),
+            #[cfg(feature = "events-policy")]
+            Event::PolicyRuleServer(ref event) => Some(StateEvent::PolicyRuleServer(event.clone())),
+            #[cfg(feature = "events-policy")]
+            Event::PolicyRuleUser(ref event) => Some(StateEvent::PolicyRuleUser(event.clone())),
+            Event::RoomAliases(ref event) => Some(StateEvent::RoomAliases(event.clone())),
+            Event::RoomAvatar(ref event) => Some(StateEvent::RoomAvatar(event.clone())),
+            Event::RoomCanonicalAlias(ref event) => {
+                Some(StateEvent::RoomCanonicalAlias(event.clone()))
             }
+            Event::RoomCreate(ref event) => Some(StateEvent::RoomCreate(event.clone())),
+            Event::RoomEncryption(ref event) => Some(StateEvent::RoomEncryption(event.clone())),
+            Event::RoomGuestAccess(ref event) => Some(StateEvent::RoomGuestAccess(event.clone())),
+            Event::RoomHistoryVisibility(ref event) => {
+                Some(StateEvent::RoomHistoryVisibility(event.clone()))
+            }
+            Event::RoomJoinRules(ref event) => Some(StateEvent::RoomJoinRules(event.clone())),
+            Event::RoomMember(ref event) => Some(StateEvent::RoomMember(event.clone())),
+            Event::RoomName(ref event) => Some(StateEvent::RoomName(event.clone())),
+            Event::RoomPinnedEvents(ref event) => Some(StateEvent::RoomPinnedEvents(event.clone())),
+            Event::RoomPowerLevels(ref event) => Some(StateEvent::RoomPowerLevels(event.clone())),
+            Event::RoomServerAcl(ref event) => Some(StateEvent::RoomServerAcl(event.clone())),
+            Event::RoomThirdPartyInvite(ref event) => {
+                Some(StateEvent::RoomThirdPartyInvite(event.clone()))
+            }
+            Event::RoomTombstone(ref event) => Some(StateEvent::RoomTombstone(event.clone())),
+            Event::RoomTopic(ref event) => Some(StateEvent::RoomTopic(event.clone())),
+            Event::SpaceChild(ref event) => Some(StateEvent::SpaceChild(event.clone())),
+            Event::SpaceParent(ref event) => Some(StateEvent::SpaceParent(event.clone())),
+            Event::CustomState(ref event) => Some(StateEvent::CustomState(event.clone())),
+            _ => None,
         }
-    };
+    }
 }
 
-impl_from_t_for_event!(AnswerEvent, CallAnswer);
-impl_from_t_for_event!(CandidatesEvent, CallCandidates);
-impl_from_t_for_event!(HangupEvent, CallHangup);
-impl_from_t_for_event!(InviteEvent, CallInvite);
-impl_from_t_for_event!(PresenceEvent, Presence);
-impl_from_t_for_event!(ReceiptEvent, Receipt);
-impl_from_t_for_event!(AliasesEvent, RoomAliases);
-impl_from_t_for_event!(AvatarEvent, RoomAvatar);
-impl_from_t_for_event!(CanonicalAliasEvent, RoomCanonicalAlias);
-impl_from_t_for_event!(CreateEvent, RoomCreate);
-impl_from_t_for_event!(GuestAccessEvent, RoomGuestAccess);
-impl_from_t_for_event!(HistoryVisibilityEvent, RoomHistoryVisibility);
-impl_from_t_for_event!(JoinRulesEvent, RoomJoinRules);
-impl_from_t_for_event!(MemberEvent, RoomMember);
-impl_from_t_for_event!(MessageEvent, RoomMessage);
-impl_from_t_for_event!(NameEvent, RoomName);
-impl_from_t_for_event!(PowerLevelsEvent, RoomPowerLevels);
-impl_from_t_for_event!(RedactionEvent, RoomRedaction);
-impl_from_t_for_event!(ThirdPartyInviteEvent, RoomThirdPartyInvite);
-impl_from_t_for_event!(TopicEvent, RoomTopic);
-impl_from_t_for_event!(TagEvent, Tag);
-impl_from_t_for_event!(TypingEvent, Typing);
-impl_from_t_for_event!(CustomEvent, Custom);
-impl_from_t_for_event!(CustomRoomEvent, CustomRoom);
-impl_from_t_for_event!(CustomStateEvent, CustomState);
-
-macro_rules! impl_from_t_for_room_event {
-    ($ty:ty, $variant:ident) => {
-        impl From<$ty> for RoomEvent {
-            fn from(event: $ty) -> Self {
-                RoomEvent::$variant(event)
-            }
+impl StateEvent {
+    /// The type of the event.
+    pub fn event_type(&self) -> &EventType {
+        match *self {
+            #[cfg(feature = "events-policy")]
+            StateEvent::PolicyRuleRoom(ref event) => &event.event_type,
+            #[cfg(feature = "events-policy")]
+            StateEvent::PolicyRuleServer(ref event) => &event.event_type,
+            #[cfg(feature = "events-policy")]
+            StateEvent::PolicyRuleUser(ref event) => &event.event_type,
+            StateEvent::RoomAliases(ref event) => &event.event_type,
+            StateEvent::RoomAvatar(ref event) => &event.event_type,
+            StateEvent::RoomCanonicalAlias(ref event) => &event.event_type,
+            StateEvent::RoomCreate(ref event) => &event.event_type,
+            StateEvent::RoomEncryption(ref event) => &event.event_type,
+            StateEvent::RoomGuestAccess(ref event) => &event.event_type,
+            StateEvent::RoomHistoryVisibility(ref event) => &event.event_type,
+            StateEvent::RoomJoinRules(ref event) => &event.event_type,
+            StateEvent::RoomMember(ref event) => &event.event_type,
+            StateEvent::RoomName(ref event) => &event.event_type,
+            StateEvent::RoomPinnedEvents(ref event) => &event.event_type,
+            StateEvent::RoomPowerLevels(ref event) => &event.event_type,
+            StateEvent::RoomServerAcl(ref event) => &event.event_type,
+            StateEvent::RoomThirdPartyInvite(ref event) => &event.event_type,
+            StateEvent::RoomTombstone(ref event) => &event.event_type,
+            StateEvent::RoomTopic(ref event) => &event.event_type,
+            StateEvent::SpaceChild(ref event) => &event.event_type,
+            StateEvent::SpaceParent(ref event) => &event.event_type,
+            StateEvent::CustomState(ref event) => &event.event_type,
+        }
+    }
+
+    /// A key that determines which piece of room state the event represents.
+    pub fn state_key(&self) -> &str {
+        match *self {
+            #[cfg(feature = "events-policy")]
+            StateEvent::PolicyRuleRoom(ref event) => &event.state_key,
+            #[cfg(feature = "events-policy")]
+            StateEvent::PolicyRuleServer(ref event) => &event.state_key,
+            #[cfg(feature = "events-policy")]
+            StateEvent::PolicyRuleUser(ref event) => &event.state_key,
+            StateEvent::RoomAliases(ref event) => &event.state_key,
+            StateEvent::RoomAvatar(ref event) => &event.state_key,
+            StateEvent::RoomCanonicalAlias(ref event) => &event.state_key,
+            StateEvent::RoomCreate(ref event) => &event.state_key,
+            StateEvent::RoomEncryption(ref event) => &event.state_key,
+            StateEvent::RoomGuestAccess(ref event) => &event.state_key,
+            StateEvent::RoomHistoryVisibility(ref event) => &event.state_key,
+            StateEvent::RoomJoinRules(ref event) => &event.state_key,
+            StateEvent::RoomMember(ref event) => &event.state_key,
+            StateEvent::RoomName(ref event) => &event.state_key,
+            StateEvent::RoomPinnedEvents(ref event) => &event.state_key,
+            StateEvent::RoomPowerLevels(ref event) => &event.state_key,
+            StateEvent::RoomServerAcl(ref event) => &event.state_key,
+            StateEvent::RoomThirdPartyInvite(ref event) => &event.state_key,
+            StateEvent::RoomTombstone(ref event) => &event.state_key,
+            StateEvent::RoomTopic(ref event) => &event.state_key,
+            StateEvent::SpaceChild(ref event) => &event.state_key,
+            StateEvent::SpaceParent(ref event) => &event.state_key,
+            StateEvent::CustomState(ref event) => &event.state_key,
         }
-    };
+    }
+}
+
+/// Extraction of the current room state from a sequence of events.
+///
+/// This is implemented for anything that can be iterated over with items that may be state
+/// events, such as a `Vec<Event>`, a `&[Event]`, or an iterator adapter over either.
+pub trait StateEventsExt {
+    /// The latest state event for each `(event_type, state_key)` pair, in one pass over the
+    /// events. Later events replace earlier ones, and events that are not state events are
+    /// skipped.
+    fn latest_state(self) -> HashMap<(EventType, String), StateEvent>;
+}
+
+impl<I> StateEventsExt for I where I: IntoIterator, I::Item: IntoStateEvent {
+    fn latest_state(self) -> HashMap<(EventType, String), StateEvent> {
+        collect_latest_state(self.into_iter().filter_map(IntoStateEvent::into_state_event))
+    }
+}
+
+/// An event that may be a state event, as accepted by `StateEventsExt`.
+pub trait IntoStateEvent {
+    /// Converts the event into a state event, copying it if it is borrowed, or returns `None` if
+    /// it is not a state event.
+    fn into_state_event(self) -> Option<StateEvent>;
+}
+
+impl IntoStateEvent for Event {
+    fn into_state_event(self) -> Option<StateEvent> {
+        self.into_state()
+    }
+}
+
+impl IntoStateEvent for &Event {
+    fn into_state_event(self) -> Option<StateEvent> {
+        self.as_state()
+    }
+}
+
+impl IntoStateEvent for StateEvent {
+    fn into_state_event(self) -> Option<StateEvent> {
+        Some(self)
+    }
+}
+
+impl IntoStateEvent for &StateEvent {
+    fn into_state_event(self) -> Option<StateEvent> {
+        Some(self.clone())
+    }
+}
+
+fn collect_latest_state<I>(events: I) -> HashMap<(EventType, String), StateEvent>
+where I: Iterator<Item=StateEvent> {
+    let mut state = HashMap::new();
+
+    for event in events {
+        state.insert((event.event_type().clone(), event.state_key().to_string()), event);
+    }
+
+    state
+}
+
+/// Serializes events into `writer` as a JSON array, one event at a time.
+///
+/// Unlike collecting the events into a `Vec` first, this does not need all of the events in memory
+/// at once, which makes it suitable for large responses such as those of `/messages`.
+pub fn serialize_events<'a, I, W>(events: I, writer: W) -> Result<(), JsonError>
+where I: IntoIterator<Item=&'a Event>, W: Write {
+    let mut serializer = ::serde_json::Serializer::new(writer);
+    let mut seq = serializer.serialize_seq(None)?;
+
+    for event in events {
+        seq.serialize_element(event)?;
+    }
+
+    seq.end()
 }
 
-impl_from_t_for_room_event!(AnswerEvent, CallAnswer);
-impl_from_t_for_room_event!(CandidatesEvent, CallCandidates);
-impl_from_t_for_room_event!(HangupEvent, CallHangup);
-impl_from_t_for_room_event!(InviteEvent, CallInvite);
-impl_from_t_for_room_event!(AliasesEvent, RoomAliases);
-impl_from_t_for_room_event!(AvatarEvent, RoomAvatar);
-impl_from_t_for_room_event!(CanonicalAliasEvent, RoomCanonicalAlias);
-impl_from_t_for_room_event!(CreateEvent, RoomCreate);
-impl_from_t_for_room_event!(GuestAccessEvent, RoomGuestAccess);
-impl_from_t_for_room_event!(HistoryVisibilityEvent, RoomHistoryVisibility);
-impl_from_t_for_room_event!(JoinRulesEvent, RoomJoinRules);
-impl_from_t_for_room_event!(MemberEvent, RoomMember);
-impl_from_t_for_room_event!(MessageEvent, RoomMessage);
-impl_from_t_for_room_event!(NameEvent, RoomName);
-impl_from_t_for_room_event!(PowerLevelsEvent, RoomPowerLevels);
-impl_from_t_for_room_event!(RedactionEvent, RoomRedaction);
-impl_from_t_for_room_event!(ThirdPartyInviteEvent, RoomThirdPartyInvite);
-impl_from_t_for_room_event!(TopicEvent, RoomTopic);
-impl_from_t_for_room_event!(CustomRoomEvent, CustomRoom);
-impl_from_t_for_room_event!(CustomStateEvent, CustomState);
-
-macro_rules! impl_from_t_for_state_event {
-    ($ty:ty, $variant:ident) => {
-        impl From<$ty> for StateEvent {
-            fn from(event: $ty) -> Self {
-                StateEvent::$variant(event)
+#[cfg(test)]
+mod tests {
+    use serde_json::{Value, from_str, from_slice};
+
+    use EventType;
+    use super::{Event, StateEvent, StateEventsExt, serialize_events};
+
+    #[test]
+    fn events_serialize_into_json_array() {
+        let events = vec![
+            from_str::<Event>(r#"{"content":{"tags":{}},"type":"m.tag"}"#).unwrap(),
+            from_str::<Event>(r#"{"content":{"foo":"bar"},"type":"io.ruma.test"}"#).unwrap(),
+        ];
+        let mut buffer = Vec::new();
+
+        serialize_events(&events, &mut buffer).unwrap();
+
+        assert_eq!(
+            from_slice::<Value>(&buffer).unwrap(),
+            from_str::<Value>(r#"[
+                {"content":{"tags":{}},"type":"m.tag"},
+                {"content":{"foo":"bar"},"type":"io.ruma.test"}
+            ]"#).unwrap()
+        );
+    }
+
+    #[test]
+    fn latest_state_is_extracted() {
+        let events = from_str::<Vec<Event>>(r#"[
+            {
+                "content": {"name": "Old"},
+                "event_id": "$h29iv0s8:example.com",
+                "origin_server_ts": 1,
+                "room_id": "!n8f893n9:example.com",
+                "sender": "@carl:example.com",
+                "state_key": "",
+                "type": "m.room.name"
+            },
+            {"content": {"tags": {}}, "type": "m.tag"},
+            {
+                "content": {"name": "New"},
+                "event_id": "$fd8s9afsd:example.com",
+                "origin_server_ts": 2,
+                "room_id": "!n8f893n9:example.com",
+                "sender": "@carl:example.com",
+                "state_key": "",
+                "type": "m.room.name"
             }
+        ]"#).unwrap();
+
+        assert_eq!(events.iter().filter_map(Event::as_state).count(), 2);
+
+        let state = events.iter().latest_state();
+
+        assert_eq!(state.len(), 1);
+        match state[&(EventType::RoomName, "".to_string())] {
+            StateEvent::RoomName(ref event) => assert_eq!(event.content.name, "New"),
+            _ => unreachable!(),
+        }
+
+        match events.iter().take(2).latest_state()[&(EventType::RoomName, "".to_string())] {
+            StateEvent::RoomName(ref event) => assert_eq!(event.content.name, "Old"),
+            _ => unreachable!(),
         }
-    };
-}
 
-impl_from_t_for_state_event!(AliasesEvent, RoomAliases);
-impl_from_t_for_state_event!(AvatarEvent, RoomAvatar);
-impl_from_t_for_state_event!(CanonicalAliasEvent, RoomCanonicalAlias);
-impl_from_t_for_state_event!(CreateEvent, RoomCreate);
-impl_from_t_for_state_event!(GuestAccessEvent, RoomGuestAccess);
-impl_from_t_for_state_event!(HistoryVisibilityEvent, RoomHistoryVisibility);
-impl_from_t_for_state_event!(JoinRulesEvent, RoomJoinRules);
-impl_from_t_for_state_event!(MemberEvent, RoomMember);
-impl_from_t_for_state_event!(NameEvent, RoomName);
-impl_from_t_for_state_event!(PowerLevelsEvent, RoomPowerLevels);
-impl_from_t_for_state_event!(ThirdPartyInviteEvent, RoomThirdPartyInvite);
-impl_from_t_for_state_event!(TopicEvent, RoomTopic);
-impl_from_t_for_state_event!(CustomStateEvent, CustomState);
+        assert_eq!(events[..].latest_state().len(), 1);
+        assert_eq!(events.into_iter().filter_map(Event::into_state).latest_state().len(), 1);
+    }
+}
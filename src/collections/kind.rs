@@ -0,0 +1,265 @@
+//! Marker traits for the kinds of events, which determine the collections an event type can be
+//! converted into.
+//!
+//! Every event type of the specification is declared here once, with its kind. The declaration
+//! implements the marker trait of the kind, which only compiles if the event type implements the
+//! matching event trait, and generates the `From` conversions into exactly the collections that
+//! can hold the kind. Converting e.g. a `PresenceEvent` into a `RoomEvent` is a compile error.
+//!
+//! A presence event converts into the collections of basic events:
+//!
+//! ```
+//! # extern crate ruma_events;
+//! use ruma_events::collections::{all, only};
+//! use ruma_events::presence::PresenceEvent;
+//!
+//! fn collect(event: PresenceEvent) -> (all::Event, only::Event) {
+//!     (all::Event::from(event.clone()), only::Event::from(event))
+//! }
+//! # fn main() {}
+//! ```
+//!
+//! but not into a collection of room events:
+//!
+//! ```compile_fail
+//! # extern crate ruma_events;
+//! use ruma_events::collections::all;
+//! use ruma_events::presence::PresenceEvent;
+//!
+//! fn collect(event: PresenceEvent) -> all::RoomEvent {
+//!     all::RoomEvent::from(event)
+//! }
+//! # fn main() {}
+//! ```
+//!
+//! Likewise, a message event does not convert into a collection of state events:
+//!
+//! ```compile_fail
+//! # extern crate ruma_events;
+//! use ruma_events::collections::all;
+//! use ruma_events::room::message::MessageEvent;
+//!
+//! fn collect(event: MessageEvent) -> all::StateEvent {
+//!     all::StateEvent::from(event)
+//! }
+//! # fn main() {}
+//! ```
+
+use {CustomEvent, CustomRoomEvent, CustomStateEvent, Event, RoomEvent, StateEvent};
+#[cfg(feature = "events-call")]
+use call::answer::AnswerEvent;
+#[cfg(feature = "events-call")]
+use call::candidates::CandidatesEvent;
+#[cfg(feature = "events-call")]
+use call::hangup::HangupEvent;
+#[cfg(feature = "events-call")]
+use call::invite::InviteEvent;
+#[cfg(feature = "events-call")]
+use call::negotiate::NegotiateEvent;
+#[cfg(feature = "events-call")]
+use call::reject::RejectEvent;
+#[cfg(feature = "events-call")]
+use call::select_answer::SelectAnswerEvent;
+use direct::DirectEvent;
+#[cfg(feature = "events-encryption")]
+use forwarded_room_key::ForwardedRoomKeyEvent;
+use fully_read::FullyReadEvent;
+use ignored_user_list::IgnoredUserListEvent;
+#[cfg(feature = "events-encryption")]
+use key::backup::MegolmBackupEvent;
+#[cfg(feature = "events-encryption")]
+use key::verification::accept::AcceptEvent;
+#[cfg(feature = "events-encryption")]
+use key::verification::cancel::CancelEvent;
+#[cfg(feature = "events-encryption")]
+use key::verification::done::DoneEvent;
+#[cfg(feature = "events-encryption")]
+use key::verification::key::KeyEvent;
+#[cfg(feature = "events-encryption")]
+use key::verification::mac::MacEvent;
+#[cfg(feature = "events-encryption")]
+use key::verification::request::RequestEvent;
+#[cfg(feature = "events-encryption")]
+use key::verification::start::StartEvent;
+#[cfg(feature = "events-policy")]
+use policy::rule::room::RoomRuleEvent;
+#[cfg(feature = "events-policy")]
+use policy::rule::server::ServerRuleEvent;
+#[cfg(feature = "events-policy")]
+use policy::rule::user::UserRuleEvent;
+#[cfg(feature = "events-poll")]
+use poll::end::PollEndEvent;
+#[cfg(feature = "events-poll")]
+use poll::response::PollResponseEvent;
+#[cfg(feature = "events-poll")]
+use poll::start::PollStartEvent;
+use presence::PresenceEvent;
+use push_rules::PushRulesEvent;
+use reaction::ReactionEvent;
+use receipt::ReceiptEvent;
+use room::aliases::AliasesEvent;
+use room::avatar::AvatarEvent;
+use room::canonical_alias::CanonicalAliasEvent;
+use room::create::CreateEvent;
+#[cfg(feature = "events-encryption")]
+use room::encrypted::EncryptedEvent;
+use room::encryption::EncryptionEvent;
+use room::guest_access::GuestAccessEvent;
+use room::history_visibility::HistoryVisibilityEvent;
+use room::join_rules::JoinRulesEvent;
+use room::member::MemberEvent;
+use room::message::MessageEvent;
+use room::name::NameEvent;
+use room::pinned_events::PinnedEventsEvent;
+use room::power_levels::PowerLevelsEvent;
+use room::redaction::RedactionEvent;
+use room::server_acl::ServerAclEvent;
+use room::third_party_invite::ThirdPartyInviteEvent;
+use room::tombstone::TombstoneEvent;
+use room::topic::TopicEvent;
+#[cfg(feature = "events-encryption")]
+use room_key::RoomKeyEvent;
+#[cfg(feature = "events-encryption")]
+use room_key_request::RoomKeyRequestEvent;
+#[cfg(feature = "events-encryption")]
+use secret::request::SecretRequestEvent;
+#[cfg(feature = "events-encryption")]
+use secret::send::SecretSendEvent;
+use space::child::ChildEvent;
+use space::parent::ParentEvent;
+use sticker::StickerEvent;
+use tag::TagEvent;
+use typing::TypingEvent;
+
+use super::{all, only};
+
+/// A basic event, which can be converted into `all::Event` and `only::Event`.
+pub trait BasicEventKind: Event {}
+
+/// A room event that is not a state event, which can be converted into `all::Event`,
+/// `all::RoomEvent`, and `only::RoomEvent`.
+pub trait MessageLikeEventKind: RoomEvent {}
+
+/// A state event, which can be converted into `all::Event`, `all::RoomEvent`, and
+/// `all::StateEvent`.
+pub trait StateEventKind: StateEvent {}
+
+macro_rules! impl_from {
+    ($ty:ty, $variant:ident, $($collection:ident)::+) => {
+        impl From<$ty> for $($collection)::+ {
+            fn from(event: $ty) -> Self {
+                $($collection)::+::$variant(event)
+            }
+        }
+    };
+}
+
+macro_rules! impl_event_kind {
+    (basic, $ty:ty, $variant:ident) => {
+        impl BasicEventKind for $ty {}
+        impl_from!($ty, $variant, all::Event);
+        impl_from!($ty, $variant, only::Event);
+    };
+    (message_like, $ty:ty, $variant:ident) => {
+        impl MessageLikeEventKind for $ty {}
+        impl_from!($ty, $variant, all::Event);
+        impl_from!($ty, $variant, all::RoomEvent);
+        impl_from!($ty, $variant, only::RoomEvent);
+    };
+    (state, $ty:ty, $variant:ident) => {
+        impl StateEventKind for $ty {}
+        impl_from!($ty, $variant, all::Event);
+        impl_from!($ty, $variant, all::RoomEvent);
+        impl_from!($ty, $variant, all::StateEvent);
+    };
+}
+
+impl_event_kind!(basic, DirectEvent, Direct);
+#[cfg(feature = "events-encryption")]
+impl_event_kind!(basic, ForwardedRoomKeyEvent, ForwardedRoomKey);
+impl_event_kind!(basic, FullyReadEvent, FullyRead);
+impl_event_kind!(basic, IgnoredUserListEvent, IgnoredUserList);
+#[cfg(feature = "events-encryption")]
+impl_event_kind!(basic, AcceptEvent, KeyVerificationAccept);
+#[cfg(feature = "events-encryption")]
+impl_event_kind!(basic, CancelEvent, KeyVerificationCancel);
+#[cfg(feature = "events-encryption")]
+impl_event_kind!(basic, DoneEvent, KeyVerificationDone);
+#[cfg(feature = "events-encryption")]
+impl_event_kind!(basic, KeyEvent, KeyVerificationKey);
+#[cfg(feature = "events-encryption")]
+impl_event_kind!(basic, MacEvent, KeyVerificationMac);
+#[cfg(feature = "events-encryption")]
+impl_event_kind!(basic, RequestEvent, KeyVerificationRequest);
+#[cfg(feature = "events-encryption")]
+impl_event_kind!(basic, StartEvent, KeyVerificationStart);
+#[cfg(feature = "events-encryption")]
+impl_event_kind!(basic, MegolmBackupEvent, MegolmBackupV1);
+impl_event_kind!(basic, PresenceEvent, Presence);
+impl_event_kind!(basic, PushRulesEvent, PushRules);
+impl_event_kind!(basic, ReceiptEvent, Receipt);
+#[cfg(feature = "events-encryption")]
+impl_event_kind!(basic, RoomKeyEvent, RoomKey);
+#[cfg(feature = "events-encryption")]
+impl_event_kind!(basic, RoomKeyRequestEvent, RoomKeyRequest);
+#[cfg(feature = "events-encryption")]
+impl_event_kind!(basic, SecretRequestEvent, SecretRequest);
+#[cfg(feature = "events-encryption")]
+impl_event_kind!(basic, SecretSendEvent, SecretSend);
+impl_event_kind!(basic, TagEvent, Tag);
+impl_event_kind!(basic, TypingEvent, Typing);
+impl_event_kind!(basic, CustomEvent, Custom);
+
+#[cfg(feature = "events-call")]
+impl_event_kind!(message_like, AnswerEvent, CallAnswer);
+#[cfg(feature = "events-call")]
+impl_event_kind!(message_like, CandidatesEvent, CallCandidates);
+#[cfg(feature = "events-call")]
+impl_event_kind!(message_like, HangupEvent, CallHangup);
+#[cfg(feature = "events-call")]
+impl_event_kind!(message_like, InviteEvent, CallInvite);
+#[cfg(feature = "events-call")]
+impl_event_kind!(message_like, NegotiateEvent, CallNegotiate);
+#[cfg(feature = "events-call")]
+impl_event_kind!(message_like, RejectEvent, CallReject);
+#[cfg(feature = "events-call")]
+impl_event_kind!(message_like, SelectAnswerEvent, CallSelectAnswer);
+#[cfg(feature = "events-poll")]
+impl_event_kind!(message_like, PollEndEvent, PollEnd);
+#[cfg(feature = "events-poll")]
+impl_event_kind!(message_like, PollResponseEvent, PollResponse);
+#[cfg(feature = "events-poll")]
+impl_event_kind!(message_like, PollStartEvent, PollStart);
+impl_event_kind!(message_like, ReactionEvent, Reaction);
+#[cfg(feature = "events-encryption")]
+impl_event_kind!(message_like, EncryptedEvent, RoomEncrypted);
+impl_event_kind!(message_like, MessageEvent, RoomMessage);
+impl_event_kind!(message_like, RedactionEvent, RoomRedaction);
+impl_event_kind!(message_like, StickerEvent, Sticker);
+impl_event_kind!(message_like, CustomRoomEvent, CustomRoom);
+
+#[cfg(feature = "events-policy")]
+impl_event_kind!(state, RoomRuleEvent, PolicyRuleRoom);
+#[cfg(feature = "events-policy")]
+impl_event_kind!(state, ServerRuleEvent, PolicyRuleServer);
+#[cfg(feature = "events-policy")]
+impl_event_kind!(state, UserRuleEvent, PolicyRuleUser);
+impl_event_kind!(state, AliasesEvent, RoomAliases);
+impl_event_kind!(state, AvatarEvent, RoomAvatar);
+impl_event_kind!(state, CanonicalAliasEvent, RoomCanonicalAlias);
+impl_event_kind!(state, CreateEvent, RoomCreate);
+impl_event_kind!(state, EncryptionEvent, RoomEncryption);
+impl_event_kind!(state, GuestAccessEvent, RoomGuestAccess);
+impl_event_kind!(state, HistoryVisibilityEvent, RoomHistoryVisibility);
+impl_event_kind!(state, JoinRulesEvent, RoomJoinRules);
+impl_event_kind!(state, MemberEvent, RoomMember);
+impl_event_kind!(state, NameEvent, RoomName);
+impl_event_kind!(state, PinnedEventsEvent, RoomPinnedEvents);
+impl_event_kind!(state, PowerLevelsEvent, RoomPowerLevels);
+impl_event_kind!(state, ServerAclEvent, RoomServerAcl);
+impl_event_kind!(state, ThirdPartyInviteEvent, RoomThirdPartyInvite);
+impl_event_kind!(state, TombstoneEvent, RoomTombstone);
+impl_event_kind!(state, TopicEvent, RoomTopic);
+impl_event_kind!(state, ChildEvent, SpaceChild);
+impl_event_kind!(state, ParentEvent, SpaceParent);
+impl_event_kind!(state, CustomStateEvent, CustomState);
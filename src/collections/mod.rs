@@ -0,0 +1,624 @@
+//! Enums for heterogeneous collections of events.
+//!
+//! This module also contains types shared by the collections in its child modules.
+
+use std::cell::Cell;
+use std::fmt::{Debug, Display, Formatter, Result as FmtResult};
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+use serde::de::{
+    DeserializeOwned, DeserializeSeed, Deserializer, Error, MapAccess, SeqAccess, Visitor,
+};
+use serde_json::{Error as JsonError, Map, Number, Value, from_str, from_value};
+#[cfg(feature = "rayon")]
+use serde_json::{Deserializer as JsonDeserializer, value::RawValue};
+
+use EventType;
+
+pub mod account_data;
+pub mod all;
+pub mod kind;
+pub mod only;
+pub mod to_device;
+
+/// How the collection deserializers treat an event whose structure contradicts its `type`, such as
+/// an *m.room.message* event with a `state_key`.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum TypeMismatchPolicy {
+    /// Deserialize the event according to its `type` alone, disregarding its structure.
+    #[default]
+    Ignore,
+
+    /// Deserialize the event into the custom variant that matches its structure, keeping the
+    /// declared event type, or fail if the collection has no such variant.
+    Lenient,
+
+    /// Fail to deserialize the event.
+    Strict,
+}
+
+/// How the structure of an event contradicts its `type`.
+///
+/// Deserializing with `TypeMismatchPolicy::Lenient` reports this alongside the event, so callers
+/// can log or count events that fell back to a custom variant.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum StructureMismatch {
+    /// The event has a `state_key`, but its type is not a state event type.
+    UnexpectedStateKey(EventType),
+
+    /// The event has no `state_key`, but its type is a state event type.
+    MissingStateKey(EventType),
+}
+
+impl Display for StructureMismatch {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match *self {
+            StructureMismatch::UnexpectedStateKey(ref event_type) => {
+                write!(f, "{} is not a state event but has a state_key", event_type)
+            }
+            StructureMismatch::MissingStateKey(ref event_type) => {
+                write!(f, "{} is a state event but has no state_key", event_type)
+            }
+        }
+    }
+}
+
+/// An observer that is notified whenever the collection deserializers encounter an event type that
+/// is not part of the specification, or fall back to a custom variant because the structure of an
+/// event contradicts its type, e.g. to collect metrics on unrecognized event types.
+///
+/// Any `Fn(&str)` closure that is `Send` and `Sync` is an observer.
+pub trait UnknownTypeObserver: Send + Sync {
+    /// Called with the type of every event that is deserialized into a custom variant.
+    fn observe(&self, event_type: &str);
+}
+
+impl<F> UnknownTypeObserver for F where F: Fn(&str) + Send + Sync {
+    fn observe(&self, event_type: &str) {
+        self(event_type)
+    }
+}
+
+/// Options for a single call to the `from_value_with_config` function of a collection.
+///
+/// Deserializing a collection through `Deserialize` uses the default options.
+#[derive(Clone, Copy, Default)]
+pub struct DeserializeConfig<'a> {
+    /// How to treat an event whose structure contradicts its `type`.
+    pub policy: TypeMismatchPolicy,
+
+    /// The observer to notify of unknown event types, if any.
+    pub observer: Option<&'a dyn UnknownTypeObserver>,
+}
+
+impl<'a> Debug for DeserializeConfig<'a> {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        f.debug_struct("DeserializeConfig")
+            .field("policy", &self.policy)
+            .field("observer", &self.observer.map(|_| "UnknownTypeObserver"))
+            .finish()
+    }
+}
+
+impl<'a> DeserializeConfig<'a> {
+    /// Notifies the observer, if any, when `event_type` is not part of the specification.
+    fn observe_unknown_type(&self, event_type: &EventType) {
+        if let (Some(observer), EventType::Custom(event_type)) = (self.observer, event_type) {
+            observer.observe(event_type);
+        }
+    }
+
+    /// Notifies the observer, if any, that an event of type `event_type` was deserialized into a
+    /// custom variant because its structure contradicts its type.
+    fn observe_structure_mismatch(&self, event_type: &EventType) {
+        if let Some(observer) = self.observer {
+            observer.observe(&event_type.to_string());
+        }
+    }
+}
+
+/// The result of deserializing one event of a batch, so that a single invalid event does not
+/// fail the whole batch.
+pub type EventResult<T> = Result<T, JsonError>;
+
+/// Deserializes a large number of events across all threads of the global rayon thread pool,
+/// e.g. for the events of a federation backfill.
+///
+/// The results are in the same order as `events`, and identical to deserializing each event on
+/// its own with `all::Event::from_value_with_config`, within the default `InputLimits`. The
+/// observer of `config`, if any, is called from all threads.
+#[cfg(feature = "rayon")]
+pub fn deserialize_events_parallel(events: &[&RawValue], config: &DeserializeConfig)
+-> Vec<EventResult<all::Event>> {
+    events.par_iter().map(|event| deserialize_event(event.get(), config)).collect()
+}
+
+/// Deserializes a single event of a batch from JSON, within the default `InputLimits`.
+#[cfg(feature = "rayon")]
+fn deserialize_event(json: &str, config: &DeserializeConfig) -> EventResult<all::Event> {
+    let mut deserializer = JsonDeserializer::from_str(json);
+    let value = deserialize_limited_value(&mut deserializer)?;
+    deserializer.end()?;
+
+    all::Event::from_value_with_config(value, config).map(|(event, _)| event)
+}
+
+/// Limits on the JSON the collection deserializers accept, protecting against pathological input
+/// such as deeply nested arrays from untrusted sources.
+///
+/// The limits are enforced while the input is read, so an event that exceeds them is rejected
+/// before it is held in memory in full. The default limits apply, unless other limits are passed
+/// to `from_str_with_limits` or `from_value_with_limits` for a single call.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct InputLimits {
+    /// The maximum nesting depth of arrays and objects. The top-level event object has depth 1.
+    pub max_depth: usize,
+
+    /// The maximum size of an event: every value counts 1, plus the length in bytes of every
+    /// string and object key.
+    pub max_size: usize,
+}
+
+impl Default for InputLimits {
+    /// A depth of 32 and a size of 65536, the maximum size of an event in the specification.
+    fn default() -> Self {
+        DEFAULT_INPUT_LIMITS
+    }
+}
+
+const DEFAULT_INPUT_LIMITS: InputLimits = InputLimits {
+    max_depth: 32,
+    max_size: 65_536,
+};
+
+thread_local! {
+    /// The limits of the `from_str_with_limits` or `from_value_with_limits` call in progress on
+    /// this thread, so that the nested deserializers of an event enforce them as well.
+    static INPUT_LIMITS: Cell<InputLimits> = const { Cell::new(DEFAULT_INPUT_LIMITS) };
+}
+
+/// Deserializes a `T` from a string of JSON, enforcing the given limits instead of the default
+/// `InputLimits` in the collection deserializers and every other deserializer of this crate that
+/// limits its input.
+pub fn from_str_with_limits<T>(json: &str, limits: InputLimits) -> Result<T, JsonError>
+where T: DeserializeOwned {
+    with_input_limits(limits, || from_str(json))
+}
+
+/// Deserializes a `T` from a JSON value, enforcing the given limits instead of the default
+/// `InputLimits` in the collection deserializers and every other deserializer of this crate that
+/// limits its input.
+pub fn from_value_with_limits<T>(value: Value, limits: InputLimits) -> Result<T, JsonError>
+where T: DeserializeOwned {
+    with_input_limits(limits, || from_value(value))
+}
+
+/// Calls `f` with `limits` in effect on this thread, restoring the previous limits afterwards,
+/// even if `f` panics.
+fn with_input_limits<F, R>(limits: InputLimits, f: F) -> R where F: FnOnce() -> R {
+    struct Restore(InputLimits);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            INPUT_LIMITS.with(|current| current.set(self.0));
+        }
+    }
+
+    let _restore = Restore(INPUT_LIMITS.with(|current| current.replace(limits)));
+
+    f()
+}
+
+/// Deserializes a `Value` while enforcing the `InputLimits` in effect on this thread.
+pub(crate) fn deserialize_limited_value<'de, D>(deserializer: D) -> Result<Value, D::Error>
+where D: Deserializer<'de> {
+    let limits = INPUT_LIMITS.with(Cell::get);
+    let budget = Cell::new(limits.max_size);
+
+    LimitedValue { budget: &budget, depth: 0, limits }.deserialize(deserializer)
+}
+
+/// A `Value` seed that counts nesting depth and size against `InputLimits`.
+#[derive(Clone, Copy)]
+struct LimitedValue<'a> {
+    budget: &'a Cell<usize>,
+    depth: usize,
+    limits: InputLimits,
+}
+
+impl<'a> LimitedValue<'a> {
+    fn charge<E>(&self, size: usize) -> Result<(), E> where E: Error {
+        match self.budget.get().checked_sub(size) {
+            Some(remaining) => {
+                self.budget.set(remaining);
+                Ok(())
+            }
+            None => Err(E::custom(format!(
+                "event exceeds the maximum size of {}", self.limits.max_size
+            ))),
+        }
+    }
+
+    fn nested<E>(&self) -> Result<LimitedValue<'a>, E> where E: Error {
+        if self.depth >= self.limits.max_depth {
+            return Err(E::custom(format!(
+                "event exceeds the maximum depth of {}", self.limits.max_depth
+            )));
+        }
+
+        Ok(LimitedValue { budget: self.budget, depth: self.depth + 1, limits: self.limits })
+    }
+}
+
+impl<'a, 'de> DeserializeSeed<'de> for LimitedValue<'a> {
+    type Value = Value;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Value, D::Error> where D: Deserializer<'de> {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'a, 'de> Visitor<'de> for LimitedValue<'a> {
+    type Value = Value;
+
+    fn expecting(&self, formatter: &mut Formatter) -> FmtResult {
+        formatter.write_str("any valid JSON value")
+    }
+
+    fn visit_bool<E>(self, value: bool) -> Result<Value, E> where E: Error {
+        self.charge(1)?;
+        Ok(Value::Bool(value))
+    }
+
+    fn visit_i64<E>(self, value: i64) -> Result<Value, E> where E: Error {
+        self.charge(1)?;
+        Ok(Value::Number(value.into()))
+    }
+
+    fn visit_u64<E>(self, value: u64) -> Result<Value, E> where E: Error {
+        self.charge(1)?;
+        Ok(Value::Number(value.into()))
+    }
+
+    fn visit_f64<E>(self, value: f64) -> Result<Value, E> where E: Error {
+        self.charge(1)?;
+        Ok(Number::from_f64(value).map_or(Value::Null, Value::Number))
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<Value, E> where E: Error {
+        self.charge(1 + value.len())?;
+        Ok(Value::String(value.to_string()))
+    }
+
+    fn visit_string<E>(self, value: String) -> Result<Value, E> where E: Error {
+        self.charge(1 + value.len())?;
+        Ok(Value::String(value))
+    }
+
+    fn visit_unit<E>(self) -> Result<Value, E> where E: Error {
+        self.charge(1)?;
+        Ok(Value::Null)
+    }
+
+    fn visit_none<E>(self) -> Result<Value, E> where E: Error {
+        self.visit_unit()
+    }
+
+    fn visit_some<D>(self, deserializer: D) -> Result<Value, D::Error>
+    where D: Deserializer<'de> {
+        deserializer.deserialize_any(self)
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Value, A::Error> where A: SeqAccess<'de> {
+        self.charge(1)?;
+        let nested = self.nested()?;
+        let mut values = Vec::new();
+
+        while let Some(value) = seq.next_element_seed(nested)? {
+            values.push(value);
+        }
+
+        Ok(Value::Array(values))
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Value, A::Error> where A: MapAccess<'de> {
+        self.charge(1)?;
+        let nested = self.nested()?;
+        let mut values = Map::new();
+
+        while let Some(key) = map.next_key::<String>()? {
+            self.charge(key.len())?;
+            let value = map.next_value_seed(nested)?;
+            values.insert(key, value);
+        }
+
+        Ok(Value::Object(values))
+    }
+}
+
+/// Describes how the structure of an event contradicts its `type`, if it does.
+fn structure_mismatch(event_type: &EventType, value: &Value) -> Option<StructureMismatch> {
+    let has_state_key = value.get("state_key").is_some();
+
+    match *event_type {
+        EventType::Reaction | EventType::RoomMessage | EventType::RoomRedaction |
+        EventType::Sticker if has_state_key => {
+            Some(StructureMismatch::UnexpectedStateKey(event_type.clone()))
+        }
+        EventType::Direct | EventType::FullyRead | EventType::IgnoredUserList |
+        EventType::Presence | EventType::PushRules | EventType::Receipt | EventType::Tag |
+        EventType::Typing if has_state_key => {
+            Some(StructureMismatch::UnexpectedStateKey(event_type.clone()))
+        }
+        #[cfg(feature = "events-encryption")]
+        EventType::ForwardedRoomKey | EventType::KeyVerificationAccept |
+        EventType::KeyVerificationCancel | EventType::KeyVerificationDone |
+        EventType::KeyVerificationKey | EventType::KeyVerificationMac |
+        EventType::KeyVerificationRequest | EventType::KeyVerificationStart |
+        EventType::MegolmBackupV1 | EventType::RoomKey | EventType::RoomKeyRequest |
+        EventType::SecretRequest | EventType::SecretSend
+        if has_state_key => {
+            Some(StructureMismatch::UnexpectedStateKey(event_type.clone()))
+        }
+        #[cfg(feature = "events-encryption")]
+        EventType::RoomEncrypted if has_state_key => {
+            Some(StructureMismatch::UnexpectedStateKey(event_type.clone()))
+        }
+        #[cfg(feature = "events-call")]
+        EventType::CallAnswer | EventType::CallCandidates | EventType::CallHangup |
+        EventType::CallInvite | EventType::CallNegotiate | EventType::CallReject |
+        EventType::CallSelectAnswer
+        if has_state_key => {
+            Some(StructureMismatch::UnexpectedStateKey(event_type.clone()))
+        }
+        #[cfg(feature = "events-poll")]
+        EventType::PollEnd | EventType::PollResponse | EventType::PollStart if has_state_key => {
+            Some(StructureMismatch::UnexpectedStateKey(event_type.clone()))
+        }
+        EventType::RoomAliases | EventType::RoomAvatar | EventType::RoomCanonicalAlias |
+        EventType::RoomCreate | EventType::RoomEncryption | EventType::RoomGuestAccess |
+        EventType::RoomHistoryVisibility | EventType::RoomJoinRules | EventType::RoomMember |
+        EventType::RoomName | EventType::RoomPinnedEvents | EventType::RoomPowerLevels |
+        EventType::RoomServerAcl | EventType::RoomThirdPartyInvite | EventType::RoomTombstone |
+        EventType::RoomTopic | EventType::SpaceChild | EventType::SpaceParent
+        if !has_state_key => {
+            Some(StructureMismatch::MissingStateKey(event_type.clone()))
+        }
+        #[cfg(feature = "events-policy")]
+        EventType::PolicyRuleRoom | EventType::PolicyRuleServer | EventType::PolicyRuleUser
+        if !has_state_key => {
+            Some(StructureMismatch::MissingStateKey(event_type.clone()))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use serde_json::{Value, from_str};
+
+    use EventType;
+    use super::{
+        DeserializeConfig, InputLimits, StructureMismatch, TypeMismatchPolicy, from_str_with_limits,
+        from_value_with_limits,
+    };
+    use super::all::{Event, StateEvent};
+    use super::only;
+
+    fn message_with_state_key() -> Value {
+        from_str(r#"{
+            "content": {"body": "test", "msgtype": "m.text"},
+            "event_id": "$h29iv0s8:example.com",
+            "origin_server_ts": 1,
+            "room_id": "!n8f893n9:example.com",
+            "sender": "@carl:example.com",
+            "state_key": "",
+            "type": "m.room.message"
+        }"#).unwrap()
+    }
+
+    #[test]
+    fn default_policy_ignores_type_mismatch() {
+        match from_str::<Event>(&message_with_state_key().to_string()).unwrap() {
+            Event::RoomMessage(_) => {}
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn strict_policy_rejects_type_mismatch() {
+        assert!(
+            Event::from_value_with_policy(message_with_state_key(), TypeMismatchPolicy::Strict)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn lenient_policy_falls_back_to_custom_variant() {
+        let (event, mismatch) =
+            Event::from_value_with_policy(message_with_state_key(), TypeMismatchPolicy::Lenient)
+                .unwrap();
+
+        match event {
+            Event::CustomState(event) => assert_eq!(event.event_type, EventType::RoomMessage),
+            _ => unreachable!(),
+        }
+        assert_eq!(mismatch, Some(StructureMismatch::UnexpectedStateKey(EventType::RoomMessage)));
+    }
+
+    #[test]
+    fn matching_structure_has_no_mismatch() {
+        let (_, mismatch) = Event::from_value_with_policy(
+            from_str(r#"{"content":{"tags":{}},"type":"m.tag"}"#).unwrap(),
+            TypeMismatchPolicy::Lenient,
+        ).unwrap();
+
+        assert_eq!(mismatch, None);
+    }
+
+    #[test]
+    fn policy_applies_to_basic_events() {
+        let tag = || from_str(r#"{"content":{"tags":{}},"state_key":"","type":"m.tag"}"#).unwrap();
+
+        match only::Event::from_value_with_policy(tag(), TypeMismatchPolicy::Ignore).unwrap() {
+            (only::Event::Tag(_), None) => {}
+            _ => unreachable!(),
+        }
+        assert!(only::Event::from_value_with_policy(tag(), TypeMismatchPolicy::Strict).is_err());
+        assert!(only::Event::from_value_with_policy(tag(), TypeMismatchPolicy::Lenient).is_err());
+    }
+
+    #[test]
+    fn policy_applies_to_room_events() {
+        let message = message_with_state_key;
+        let name = || from_str(r#"{
+            "content": {"name": "Room"},
+            "event_id": "$h29iv0s8:example.com",
+            "origin_server_ts": 1,
+            "room_id": "!n8f893n9:example.com",
+            "sender": "@carl:example.com",
+            "type": "m.room.name"
+        }"#).unwrap();
+
+        match only::RoomEvent::from_value_with_policy(message(), TypeMismatchPolicy::Ignore) {
+            Ok((only::RoomEvent::RoomMessage(_), None)) => {}
+            _ => unreachable!(),
+        }
+        assert!(
+            only::RoomEvent::from_value_with_policy(message(), TypeMismatchPolicy::Strict).is_err()
+        );
+        assert!(
+            only::RoomEvent::from_value_with_policy(message(), TypeMismatchPolicy::Lenient).is_err()
+        );
+
+        match only::RoomEvent::from_value_with_policy(name(), TypeMismatchPolicy::Lenient) {
+            Ok((only::RoomEvent::CustomRoom(event), Some(mismatch))) => {
+                assert_eq!(event.event_type, EventType::RoomName);
+                assert_eq!(mismatch, StructureMismatch::MissingStateKey(EventType::RoomName));
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn policy_applies_to_state_events() {
+        let message = message_with_state_key;
+
+        assert!(StateEvent::from_value_with_policy(message(), TypeMismatchPolicy::Ignore).is_err());
+        assert!(StateEvent::from_value_with_policy(message(), TypeMismatchPolicy::Strict).is_err());
+
+        match StateEvent::from_value_with_policy(message(), TypeMismatchPolicy::Lenient) {
+            Ok((StateEvent::CustomState(event), Some(mismatch))) => {
+                assert_eq!(event.event_type, EventType::RoomMessage);
+                assert_eq!(mismatch, StructureMismatch::UnexpectedStateKey(EventType::RoomMessage));
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn unknown_types_are_observed() {
+        let count = AtomicUsize::new(0);
+        let observer = |event_type: &str| {
+            assert!(event_type == "io.ruma.observed" || event_type == "m.sticker");
+            count.fetch_add(1, Ordering::SeqCst);
+        };
+        let config = DeserializeConfig {
+            observer: Some(&observer),
+            policy: TypeMismatchPolicy::Lenient,
+        };
+
+        let event = |json: &str| Event::from_value_with_config(from_str(json).unwrap(), &config);
+
+        event(r#"{"content":{},"type":"io.ruma.observed"}"#).unwrap();
+        event(r#"{"content":{"tags":{}},"type":"m.tag"}"#).unwrap();
+        event(r#"{
+            "content": {},
+            "event_id": "$h29iv0s8:example.com",
+            "origin_server_ts": 1,
+            "room_id": "!n8f893n9:example.com",
+            "sender": "@carl:example.com",
+            "state_key": "",
+            "type": "m.sticker"
+        }"#).unwrap();
+        from_str::<Event>(r#"{"content":{},"type":"io.ruma.unobserved"}"#).unwrap();
+
+        assert_eq!(count.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn input_limits_reject_pathological_events() {
+        let nested = format!(
+            r#"{{"content":{{"value":{}{}}},"type":"io.ruma.nested"}}"#,
+            "[".repeat(40),
+            "]".repeat(40)
+        );
+        assert!(from_str::<Event>(&nested).is_err());
+
+        let large = format!(
+            r#"{{"content":{{"value":"{}"}},"type":"io.ruma.large"}}"#,
+            "a".repeat(70_000)
+        );
+        assert!(from_str::<Event>(&large).is_err());
+
+        let ordinary = format!(
+            r#"{{"content":{{"value":{}"{}"{}}},"type":"io.ruma.ordinary"}}"#,
+            "[".repeat(10),
+            "a".repeat(1_000),
+            "]".repeat(10)
+        );
+        assert!(from_str::<Event>(&ordinary).is_ok());
+    }
+
+    #[test]
+    fn input_limits_are_per_call() {
+        let nested = format!(
+            r#"{{"content":{{"value":{}{}}},"type":"io.ruma.nested"}}"#,
+            "[".repeat(40),
+            "]".repeat(40)
+        );
+        let generous = InputLimits { max_depth: 64, max_size: 100_000 };
+        assert!(from_str_with_limits::<Event>(&nested, generous).is_ok());
+        assert!(from_value_with_limits::<Event>(from_str(&nested).unwrap(), generous).is_ok());
+        assert!(from_str::<Event>(&nested).is_err());
+
+        let message = |body: &str| format!(concat!(
+            r#"{{"content":{{"body":"{}","msgtype":"m.text"}},"#,
+            r#""event_id":"$h29iv0s8:example.com","origin_server_ts":1,"#,
+            r#""room_id":"!n8f893n9:example.com","sender":"@carl:example.com","#,
+            r#""type":"m.room.message"}}"#
+        ), body);
+        let long_message = message(&"a".repeat(70_000));
+        assert!(from_str_with_limits::<Event>(&long_message, generous).is_ok());
+        assert!(from_str::<Event>(&long_message).is_err());
+
+        let strict = InputLimits { max_depth: 32, max_size: 100 };
+        assert!(from_str_with_limits::<Event>(&message("a"), strict).is_err());
+        assert!(from_str::<Event>(&message("a")).is_ok());
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn parallel_deserialization_keeps_order() {
+        use serde_json::value::RawValue;
+
+        use super::all::Event;
+        use super::deserialize_events_parallel;
+
+        let batch = from_str::<Vec<&RawValue>>(r#"[
+            {"content": {"tags": {}}, "type": "m.tag"},
+            {"content": {"name": "Ruma"}, "type": "m.room.name"},
+            {"content": {"user_ids": []}, "room_id": "!n8f893n9:example.com", "type": "m.typing"}
+        ]"#).unwrap();
+
+        let events = deserialize_events_parallel(&batch, &DeserializeConfig::default());
+
+        assert_eq!(events.len(), 3);
+        assert!(matches!(events[0], Ok(Event::Tag(_))));
+        assert!(events[1].is_err());
+        assert!(matches!(events[2], Ok(Event::Typing(_))));
+    }
+}
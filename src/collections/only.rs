@@ -2,30 +2,133 @@
 //! most" the trait of the same name.
 
 use {CustomEvent, CustomRoomEvent, EventType};
+#[cfg(feature = "events-call")]
 use call::answer::AnswerEvent;
+#[cfg(feature = "events-call")]
 use call::candidates::CandidatesEvent;
+#[cfg(feature = "events-call")]
 use call::hangup::HangupEvent;
+#[cfg(feature = "events-call")]
 use call::invite::InviteEvent;
+#[cfg(feature = "events-call")]
+use call::negotiate::NegotiateEvent;
+#[cfg(feature = "events-call")]
+use call::reject::RejectEvent;
+#[cfg(feature = "events-call")]
+use call::select_answer::SelectAnswerEvent;
+use direct::DirectEvent;
+#[cfg(feature = "events-encryption")]
+use forwarded_room_key::ForwardedRoomKeyEvent;
+use fully_read::FullyReadEvent;
+use ignored_user_list::IgnoredUserListEvent;
+#[cfg(feature = "events-encryption")]
+use key::backup::MegolmBackupEvent;
+#[cfg(feature = "events-encryption")]
+use key::verification::accept::AcceptEvent;
+#[cfg(feature = "events-encryption")]
+use key::verification::cancel::CancelEvent;
+#[cfg(feature = "events-encryption")]
+use key::verification::done::DoneEvent;
+#[cfg(feature = "events-encryption")]
+use key::verification::key::KeyEvent;
+#[cfg(feature = "events-encryption")]
+use key::verification::mac::MacEvent;
+#[cfg(feature = "events-encryption")]
+use key::verification::request::RequestEvent;
+#[cfg(feature = "events-encryption")]
+use key::verification::start::StartEvent;
+#[cfg(feature = "events-poll")]
+use poll::end::PollEndEvent;
+#[cfg(feature = "events-poll")]
+use poll::response::PollResponseEvent;
+#[cfg(feature = "events-poll")]
+use poll::start::PollStartEvent;
 use presence::PresenceEvent;
+use push_rules::PushRulesEvent;
+use reaction::ReactionEvent;
 use receipt::ReceiptEvent;
+#[cfg(feature = "events-encryption")]
+use room::encrypted::EncryptedEvent;
 use room::message::MessageEvent;
 use room::redaction::RedactionEvent;
+#[cfg(feature = "events-encryption")]
+use room_key::RoomKeyEvent;
+#[cfg(feature = "events-encryption")]
+use room_key_request::RoomKeyRequestEvent;
+#[cfg(feature = "events-encryption")]
+use secret::request::SecretRequestEvent;
+#[cfg(feature = "events-encryption")]
+use secret::send::SecretSendEvent;
+use sticker::StickerEvent;
 use tag::TagEvent;
 use typing::TypingEvent;
 
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use serde::de::Error;
-use serde_json::{Value, from_value};
+use serde_json::{Error as JsonError, Value, from_value};
+
+use super::{
+    DeserializeConfig, StructureMismatch, TypeMismatchPolicy, deserialize_limited_value,
+    structure_mismatch,
+};
 
 pub use super::all::StateEvent;
 
 /// A basic event.
 #[derive(Clone, Debug)]
+#[non_exhaustive]
 pub enum Event {
+    /// m.direct
+    Direct(DirectEvent),
+    /// m.forwarded_room_key
+    #[cfg(feature = "events-encryption")]
+    ForwardedRoomKey(ForwardedRoomKeyEvent),
+    /// m.fully_read
+    FullyRead(FullyReadEvent),
+    /// m.ignored_user_list
+    IgnoredUserList(IgnoredUserListEvent),
+    /// m.key.verification.accept
+    #[cfg(feature = "events-encryption")]
+    KeyVerificationAccept(AcceptEvent),
+    /// m.key.verification.cancel
+    #[cfg(feature = "events-encryption")]
+    KeyVerificationCancel(CancelEvent),
+    /// m.key.verification.done
+    #[cfg(feature = "events-encryption")]
+    KeyVerificationDone(DoneEvent),
+    /// m.key.verification.key
+    #[cfg(feature = "events-encryption")]
+    KeyVerificationKey(KeyEvent),
+    /// m.key.verification.mac
+    #[cfg(feature = "events-encryption")]
+    KeyVerificationMac(MacEvent),
+    /// m.key.verification.request
+    #[cfg(feature = "events-encryption")]
+    KeyVerificationRequest(RequestEvent),
+    /// m.key.verification.start
+    #[cfg(feature = "events-encryption")]
+    KeyVerificationStart(StartEvent),
+    /// m.megolm_backup.v1
+    #[cfg(feature = "events-encryption")]
+    MegolmBackupV1(MegolmBackupEvent),
     /// m.presence
     Presence(PresenceEvent),
+    /// m.push_rules
+    PushRules(PushRulesEvent),
     /// m.receipt
     Receipt(ReceiptEvent),
+    /// m.room_key
+    #[cfg(feature = "events-encryption")]
+    RoomKey(RoomKeyEvent),
+    /// m.room_key_request
+    #[cfg(feature = "events-encryption")]
+    RoomKeyRequest(RoomKeyRequestEvent),
+    /// m.secret.request
+    #[cfg(feature = "events-encryption")]
+    SecretRequest(SecretRequestEvent),
+    /// m.secret.send
+    #[cfg(feature = "events-encryption")]
+    SecretSend(SecretSendEvent),
     /// m.tag
     Tag(TagEvent),
     /// m.typing
@@ -36,19 +139,49 @@ pub enum Event {
 
 /// A room event.
 #[derive(Clone, Debug)]
+#[non_exhaustive]
 pub enum RoomEvent {
     /// m.call.answer
+    #[cfg(feature = "events-call")]
     CallAnswer(AnswerEvent),
     /// m.call.candidates
+    #[cfg(feature = "events-call")]
     CallCandidates(CandidatesEvent),
     /// m.call.hangup
+    #[cfg(feature = "events-call")]
     CallHangup(HangupEvent),
     /// m.call.invite
+    #[cfg(feature = "events-call")]
     CallInvite(InviteEvent),
+    /// m.call.negotiate
+    #[cfg(feature = "events-call")]
+    CallNegotiate(NegotiateEvent),
+    /// m.call.reject
+    #[cfg(feature = "events-call")]
+    CallReject(RejectEvent),
+    /// m.call.select_answer
+    #[cfg(feature = "events-call")]
+    CallSelectAnswer(SelectAnswerEvent),
+    /// m.poll.end
+    #[cfg(feature = "events-poll")]
+    PollEnd(PollEndEvent),
+    /// m.poll.response
+    #[cfg(feature = "events-poll")]
+    PollResponse(PollResponseEvent),
+    /// m.poll.start
+    #[cfg(feature = "events-poll")]
+    PollStart(PollStartEvent),
+    /// m.reaction
+    Reaction(ReactionEvent),
+    /// m.room.encrypted
+    #[cfg(feature = "events-encryption")]
+    RoomEncrypted(EncryptedEvent),
     /// m.room.message
     RoomMessage(MessageEvent),
     /// m.room.redaction
     RoomRedaction(RedactionEvent),
+    /// m.sticker
+    Sticker(StickerEvent),
     /// Any room event that is not part of the specification.
     CustomRoom(CustomRoomEvent),
 }
@@ -56,8 +189,38 @@ pub enum RoomEvent {
 impl Serialize for Event {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
         match *self {
+            Event::Direct(ref event) => event.serialize(serializer),
+            #[cfg(feature = "events-encryption")]
+            Event::ForwardedRoomKey(ref event) => event.serialize(serializer),
+            Event::FullyRead(ref event) => event.serialize(serializer),
+            Event::IgnoredUserList(ref event) => event.serialize(serializer),
+            #[cfg(feature = "events-encryption")]
+            Event::KeyVerificationAccept(ref event) => event.serialize(serializer),
+            #[cfg(feature = "events-encryption")]
+            Event::KeyVerificationCancel(ref event) => event.serialize(serializer),
+            #[cfg(feature = "events-encryption")]
+            Event::KeyVerificationDone(ref event) => event.serialize(serializer),
+            #[cfg(feature = "events-encryption")]
+            Event::KeyVerificationKey(ref event) => event.serialize(serializer),
+            #[cfg(feature = "events-encryption")]
+            Event::KeyVerificationMac(ref event) => event.serialize(serializer),
+            #[cfg(feature = "events-encryption")]
+            Event::KeyVerificationRequest(ref event) => event.serialize(serializer),
+            #[cfg(feature = "events-encryption")]
+            Event::KeyVerificationStart(ref event) => event.serialize(serializer),
+            #[cfg(feature = "events-encryption")]
+            Event::MegolmBackupV1(ref event) => event.serialize(serializer),
             Event::Presence(ref event) => event.serialize(serializer),
+            Event::PushRules(ref event) => event.serialize(serializer),
             Event::Receipt(ref event) => event.serialize(serializer),
+            #[cfg(feature = "events-encryption")]
+            Event::RoomKey(ref event) => event.serialize(serializer),
+            #[cfg(feature = "events-encryption")]
+            Event::RoomKeyRequest(ref event) => event.serialize(serializer),
+            #[cfg(feature = "events-encryption")]
+            Event::SecretRequest(ref event) => event.serialize(serializer),
+            #[cfg(feature = "events-encryption")]
+            Event::SecretSend(ref event) => event.serialize(serializer),
             Event::Tag(ref event) => event.serialize(serializer),
             Event::Typing(ref event) => event.serialize(serializer),
             Event::Custom(ref event) => event.serialize(serializer),
@@ -65,41 +228,220 @@ impl Serialize for Event {
     }
 }
 
-impl<'de> Deserialize<'de> for Event {
-    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where D: Deserializer<'de> {
-        let value: Value = Deserialize::deserialize(deserializer)?;
-
+impl Event {
+    /// Deserializes a basic event from a JSON value with the given per-call options, such as the
+    /// policy for events whose structure contradicts their `type`.
+    ///
+    /// There is no custom variant for events with a `state_key`, so `TypeMismatchPolicy::Lenient`
+    /// rejects a basic event with a `state_key` like `TypeMismatchPolicy::Strict` does.
+    pub fn from_value_with_config(value: Value, config: &DeserializeConfig)
+    -> Result<(Self, Option<StructureMismatch>), JsonError> {
         let event_type_value = match value.get("type") {
-            Some(value) => value.clone(),
-            None => return Err(D::Error::missing_field("type")),
+            Some(value) => value,
+            None => return Err(JsonError::missing_field("type")),
         };
 
-        let event_type = match from_value::<EventType>(event_type_value.clone()) {
+        let event_type = match EventType::deserialize(event_type_value) {
             Ok(event_type) => event_type,
-            Err(error) => return Err(D::Error::custom(error.to_string())),
+            Err(error) => return Err(JsonError::custom(error.to_string())),
         };
 
+        if let Some(mismatch) = structure_mismatch(&event_type, &value) {
+            match config.policy {
+                TypeMismatchPolicy::Ignore => {}
+                TypeMismatchPolicy::Lenient | TypeMismatchPolicy::Strict => {
+                    return Err(JsonError::custom(mismatch.to_string()));
+                }
+            }
+        }
+
+        config.observe_unknown_type(&event_type);
+
+        Event::from_typed_value(value, event_type).map(|event| (event, None))
+    }
+
+    /// Deserializes a basic event from a JSON value like `from_value_with_config`, with the
+    /// given policy and the default for every other option.
+    pub fn from_value_with_policy(value: Value, policy: TypeMismatchPolicy)
+    -> Result<(Self, Option<StructureMismatch>), JsonError> {
+        let config = DeserializeConfig { policy, ..DeserializeConfig::default() };
+
+        Event::from_value_with_config(value, &config)
+    }
+
+    /// Deserializes an event of the given type from a JSON value.
+    #[allow(clippy::needless_return)]
+    fn from_typed_value(value: Value, event_type: EventType) -> Result<Self, JsonError> {
         match event_type {
+            EventType::Direct => {
+                let event = match from_value::<DirectEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(Event::Direct(event))
+            }
+            #[cfg(feature = "events-encryption")]
+            EventType::ForwardedRoomKey => {
+                let event = match from_value::<ForwardedRoomKeyEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(Event::ForwardedRoomKey(event))
+            }
+            EventType::FullyRead => {
+                let event = match from_value::<FullyReadEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(Event::FullyRead(event))
+            }
+            EventType::IgnoredUserList => {
+                let event = match from_value::<IgnoredUserListEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(Event::IgnoredUserList(event))
+            }
+            #[cfg(feature = "events-encryption")]
+            EventType::KeyVerificationAccept => {
+                let event = match from_value::<AcceptEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(Event::KeyVerificationAccept(event))
+            }
+            #[cfg(feature = "events-encryption")]
+            EventType::KeyVerificationCancel => {
+                let event = match from_value::<CancelEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(Event::KeyVerificationCancel(event))
+            }
+            #[cfg(feature = "events-encryption")]
+            EventType::KeyVerificationDone => {
+                let event = match from_value::<DoneEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(Event::KeyVerificationDone(event))
+            }
+            #[cfg(feature = "events-encryption")]
+            EventType::KeyVerificationKey => {
+                let event = match from_value::<KeyEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(Event::KeyVerificationKey(event))
+            }
+            #[cfg(feature = "events-encryption")]
+            EventType::KeyVerificationMac => {
+                let event = match from_value::<MacEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(Event::KeyVerificationMac(event))
+            }
+            #[cfg(feature = "events-encryption")]
+            EventType::KeyVerificationRequest => {
+                let event = match from_value::<RequestEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(Event::KeyVerificationRequest(event))
+            }
+            #[cfg(feature = "events-encryption")]
+            EventType::KeyVerificationStart => {
+                let event = match from_value::<StartEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(Event::KeyVerificationStart(event))
+            }
+            #[cfg(feature = "events-encryption")]
+            EventType::MegolmBackupV1 => {
+                let event = match from_value::<MegolmBackupEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(Event::MegolmBackupV1(event))
+            }
             EventType::Presence => {
                 let event = match from_value::<PresenceEvent>(value) {
                     Ok(event) => event,
-                    Err(error) => return Err(D::Error::custom(error.to_string())),
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
                 };
 
                 Ok(Event::Presence(event))
             }
+            EventType::PushRules => {
+                let event = match from_value::<PushRulesEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(Event::PushRules(event))
+            }
             EventType::Receipt => {
                 let event = match from_value::<ReceiptEvent>(value) {
                     Ok(event) => event,
-                    Err(error) => return Err(D::Error::custom(error.to_string())),
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
                 };
 
                 Ok(Event::Receipt(event))
             }
+            #[cfg(feature = "events-encryption")]
+            EventType::RoomKey => {
+                let event = match from_value::<RoomKeyEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(Event::RoomKey(event))
+            }
+            #[cfg(feature = "events-encryption")]
+            EventType::RoomKeyRequest => {
+                let event = match from_value::<RoomKeyRequestEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(Event::RoomKeyRequest(event))
+            }
+            #[cfg(feature = "events-encryption")]
+            EventType::SecretRequest => {
+                let event = match from_value::<SecretRequestEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(Event::SecretRequest(event))
+            }
+            #[cfg(feature = "events-encryption")]
+            EventType::SecretSend => {
+                let event = match from_value::<SecretSendEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(Event::SecretSend(event))
+            }
             EventType::Tag => {
                 let event = match from_value::<TagEvent>(value) {
                     Ok(event) => event,
-                    Err(error) => return Err(D::Error::custom(error.to_string())),
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
                 };
 
                 Ok(Event::Tag(event))
@@ -107,7 +449,7 @@ impl<'de> Deserialize<'de> for Event {
             EventType::Typing => {
                 let event = match from_value::<TypingEvent>(value) {
                     Ok(event) => event,
-                    Err(error) => return Err(D::Error::custom(error.to_string())),
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
                 };
 
                 Ok(Event::Typing(event))
@@ -115,88 +457,255 @@ impl<'de> Deserialize<'de> for Event {
             EventType::Custom(_) => {
                 let event = match from_value::<CustomEvent>(value) {
                     Ok(event) => event,
-                    Err(error) => return Err(D::Error::custom(error.to_string())),
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
                 };
 
                 Ok(Event::Custom(event))
             }
+            EventType::Reaction | EventType::RoomAliases | EventType::RoomAvatar |
+            EventType::RoomCanonicalAlias | EventType::RoomCreate | EventType::RoomEncryption |
+            EventType::RoomGuestAccess | EventType::RoomHistoryVisibility |
+            EventType::RoomJoinRules | EventType::RoomMember | EventType::RoomMessage |
+            EventType::RoomName | EventType::RoomPinnedEvents | EventType::RoomPowerLevels |
+            EventType::RoomRedaction | EventType::RoomServerAcl | EventType::RoomThirdPartyInvite |
+            EventType::RoomTombstone | EventType::RoomTopic | EventType::SpaceChild |
+            EventType::SpaceParent | EventType::Sticker => {
+                return Err(JsonError::custom("not exclusively a basic event".to_string()));
+            }
+            #[cfg(feature = "events-encryption")]
+            EventType::RoomEncrypted => {
+                return Err(JsonError::custom("not exclusively a basic event".to_string()));
+            }
+            #[cfg(feature = "events-call")]
             EventType::CallAnswer | EventType::CallCandidates | EventType::CallHangup |
-            EventType::CallInvite | EventType::RoomAliases | EventType::RoomAvatar |
-            EventType::RoomCanonicalAlias | EventType::RoomCreate | EventType::RoomGuestAccess |
-            EventType::RoomHistoryVisibility | EventType::RoomJoinRules | EventType::RoomMember |
-            EventType::RoomMessage | EventType::RoomName | EventType::RoomPowerLevels |
-            EventType::RoomRedaction | EventType::RoomThirdPartyInvite | EventType::RoomTopic => {
-                return Err(D::Error::custom("not exclusively a basic event".to_string()));
+            EventType::CallInvite | EventType::CallNegotiate | EventType::CallReject |
+            EventType::CallSelectAnswer => {
+                Err(JsonError::custom("not exclusively a basic event".to_string()))
+            }
+            #[cfg(feature = "events-poll")]
+            EventType::PollEnd | EventType::PollResponse | EventType::PollStart => {
+                Err(JsonError::custom("not exclusively a basic event".to_string()))
+            }
+            #[cfg(feature = "events-policy")]
+            EventType::PolicyRuleRoom | EventType::PolicyRuleServer | EventType::PolicyRuleUser => {
+                Err(JsonError::custom("not exclusively a basic event".to_string()))
             }
         }
     }
 }
 
+impl<'de> Deserialize<'de> for Event {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where D: Deserializer<'de> {
+        let value = deserialize_limited_value(deserializer)?;
+
+        Event::from_value_with_config(value, &DeserializeConfig::default())
+            .map(|(event, _)| event)
+            .map_err(|error| D::Error::custom(error.to_string()))
+    }
+}
+
 impl Serialize for RoomEvent {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
         match *self {
+            #[cfg(feature = "events-call")]
             RoomEvent::CallAnswer(ref event) => event.serialize(serializer),
+            #[cfg(feature = "events-call")]
             RoomEvent::CallCandidates(ref event) => event.serialize(serializer),
+            #[cfg(feature = "events-call")]
             RoomEvent::CallHangup(ref event) => event.serialize(serializer),
+            #[cfg(feature = "events-call")]
             RoomEvent::CallInvite(ref event) => event.serialize(serializer),
+            #[cfg(feature = "events-call")]
+            RoomEvent::CallNegotiate(ref event) => event.serialize(serializer),
+            #[cfg(feature = "events-call")]
+            RoomEvent::CallReject(ref event) => event.serialize(serializer),
+            #[cfg(feature = "events-call")]
+            RoomEvent::CallSelectAnswer(ref event) => event.serialize(serializer),
+            #[cfg(feature = "events-poll")]
+            RoomEvent::PollEnd(ref event) => event.serialize(serializer),
+            #[cfg(feature = "events-poll")]
+            RoomEvent::PollResponse(ref event) => event.serialize(serializer),
+            #[cfg(feature = "events-poll")]
+            RoomEvent::PollStart(ref event) => event.serialize(serializer),
+            RoomEvent::Reaction(ref event) => event.serialize(serializer),
+            #[cfg(feature = "events-encryption")]
+            RoomEvent::RoomEncrypted(ref event) => event.serialize(serializer),
             RoomEvent::RoomMessage(ref event) => event.serialize(serializer),
             RoomEvent::RoomRedaction(ref event) => event.serialize(serializer),
+            RoomEvent::Sticker(ref event) => event.serialize(serializer),
             RoomEvent::CustomRoom(ref event) => event.serialize(serializer),
         }
     }
 }
 
-impl<'de> Deserialize<'de> for RoomEvent {
-    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where D: Deserializer<'de> {
-        let value: Value = Deserialize::deserialize(deserializer)?;
-
+impl RoomEvent {
+    /// Deserializes a room event from a JSON value with the given per-call options, such as the
+    /// policy for events whose structure contradicts their `type`.
+    ///
+    /// With `TypeMismatchPolicy::Lenient`, a state event without a `state_key` is returned as a
+    /// custom room event together with the mismatch. There is no custom variant for events with a
+    /// `state_key`, so a room event with a `state_key` is rejected like with
+    /// `TypeMismatchPolicy::Strict`.
+    pub fn from_value_with_config(value: Value, config: &DeserializeConfig)
+    -> Result<(Self, Option<StructureMismatch>), JsonError> {
         let event_type_value = match value.get("type") {
-            Some(value) => value.clone(),
-            None => return Err(D::Error::missing_field("type")),
+            Some(value) => value,
+            None => return Err(JsonError::missing_field("type")),
         };
 
-        let event_type = match from_value::<EventType>(event_type_value.clone()) {
+        let event_type = match EventType::deserialize(event_type_value) {
             Ok(event_type) => event_type,
-            Err(error) => return Err(D::Error::custom(error.to_string())),
+            Err(error) => return Err(JsonError::custom(error.to_string())),
         };
 
+        if let Some(mismatch) = structure_mismatch(&event_type, &value) {
+            match config.policy {
+                TypeMismatchPolicy::Ignore => {}
+                TypeMismatchPolicy::Lenient if value.get("state_key").is_none() => {
+                    config.observe_structure_mismatch(&event_type);
+
+                    let event = match from_value::<CustomRoomEvent>(value) {
+                        Ok(event) => event,
+                        Err(error) => return Err(JsonError::custom(error.to_string())),
+                    };
+
+                    return Ok((RoomEvent::CustomRoom(event), Some(mismatch)));
+                }
+                TypeMismatchPolicy::Lenient | TypeMismatchPolicy::Strict => {
+                    return Err(JsonError::custom(mismatch.to_string()));
+                }
+            }
+        }
+
+        config.observe_unknown_type(&event_type);
+
+        RoomEvent::from_typed_value(value, event_type).map(|event| (event, None))
+    }
+
+    /// Deserializes a room event from a JSON value like `from_value_with_config`, with the
+    /// given policy and the default for every other option.
+    pub fn from_value_with_policy(value: Value, policy: TypeMismatchPolicy)
+    -> Result<(Self, Option<StructureMismatch>), JsonError> {
+        let config = DeserializeConfig { policy, ..DeserializeConfig::default() };
+
+        RoomEvent::from_value_with_config(value, &config)
+    }
+
+    /// Deserializes an event of the given type from a JSON value.
+    #[allow(clippy::needless_return)]
+    fn from_typed_value(value: Value, event_type: EventType) -> Result<Self, JsonError> {
         match event_type {
+            #[cfg(feature = "events-call")]
             EventType::CallAnswer => {
                 let event = match from_value::<AnswerEvent>(value) {
                     Ok(event) => event,
-                    Err(error) => return Err(D::Error::custom(error.to_string())),
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
                 };
 
                 Ok(RoomEvent::CallAnswer(event))
             }
+            #[cfg(feature = "events-call")]
             EventType::CallCandidates => {
                 let event = match from_value::<CandidatesEvent>(value) {
                     Ok(event) => event,
-                    Err(error) => return Err(D::Error::custom(error.to_string())),
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
                 };
 
                 Ok(RoomEvent::CallCandidates(event))
             }
+            #[cfg(feature = "events-call")]
             EventType::CallHangup => {
                 let event = match from_value::<HangupEvent>(value) {
                     Ok(event) => event,
-                    Err(error) => return Err(D::Error::custom(error.to_string())),
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
                 };
 
                 Ok(RoomEvent::CallHangup(event))
             }
+            #[cfg(feature = "events-call")]
             EventType::CallInvite => {
                 let event = match from_value::<InviteEvent>(value) {
                     Ok(event) => event,
-                    Err(error) => return Err(D::Error::custom(error.to_string())),
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
                 };
 
                 Ok(RoomEvent::CallInvite(event))
             }
+            #[cfg(feature = "events-call")]
+            EventType::CallNegotiate => {
+                let event = match from_value::<NegotiateEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(RoomEvent::CallNegotiate(event))
+            }
+            #[cfg(feature = "events-call")]
+            EventType::CallReject => {
+                let event = match from_value::<RejectEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(RoomEvent::CallReject(event))
+            }
+            #[cfg(feature = "events-call")]
+            EventType::CallSelectAnswer => {
+                let event = match from_value::<SelectAnswerEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(RoomEvent::CallSelectAnswer(event))
+            }
+            #[cfg(feature = "events-poll")]
+            EventType::PollEnd => {
+                let event = match from_value::<PollEndEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(RoomEvent::PollEnd(event))
+            }
+            #[cfg(feature = "events-poll")]
+            EventType::PollResponse => {
+                let event = match from_value::<PollResponseEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(RoomEvent::PollResponse(event))
+            }
+            #[cfg(feature = "events-poll")]
+            EventType::PollStart => {
+                let event = match from_value::<PollStartEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(RoomEvent::PollStart(event))
+            }
+            EventType::Reaction => {
+                let event = match from_value::<ReactionEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(RoomEvent::Reaction(event))
+            }
+            #[cfg(feature = "events-encryption")]
+            EventType::RoomEncrypted => {
+                let event = match from_value::<EncryptedEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(RoomEvent::RoomEncrypted(event))
+            }
             EventType::RoomMessage => {
                 let event = match from_value::<MessageEvent>(value) {
                     Ok(event) => event,
-                    Err(error) => return Err(D::Error::custom(error.to_string())),
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
                 };
 
                 Ok(RoomEvent::RoomMessage(event))
@@ -204,60 +713,61 @@ impl<'de> Deserialize<'de> for RoomEvent {
             EventType::RoomRedaction => {
                 let event = match from_value::<RedactionEvent>(value) {
                     Ok(event) => event,
-                    Err(error) => return Err(D::Error::custom(error.to_string())),
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
                 };
 
                 Ok(RoomEvent::RoomRedaction(event))
             }
+            EventType::Sticker => {
+                let event = match from_value::<StickerEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(RoomEvent::Sticker(event))
+            }
             EventType::Custom(_) => {
                 let event = match from_value::<CustomRoomEvent>(value) {
                     Ok(event) => event,
-                    Err(error) => return Err(D::Error::custom(error.to_string())),
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
                 };
 
                 Ok(RoomEvent::CustomRoom(event))
             }
-            EventType::Presence | EventType::Receipt | EventType::RoomAliases |
-            EventType::RoomAvatar | EventType::RoomCanonicalAlias | EventType::RoomCreate |
-            EventType::RoomGuestAccess | EventType::RoomHistoryVisibility |
-            EventType::RoomJoinRules | EventType::RoomMember | EventType::RoomName |
-            EventType::RoomPowerLevels |EventType::RoomThirdPartyInvite | EventType::RoomTopic |
-            EventType::Tag | EventType::Typing => {
-                return Err(D::Error::custom("not exclusively a room event".to_string()));
+            EventType::Direct | EventType::FullyRead | EventType::IgnoredUserList |
+            EventType::Presence | EventType::PushRules | EventType::Receipt |
+            EventType::RoomAliases | EventType::RoomAvatar | EventType::RoomCanonicalAlias |
+            EventType::RoomCreate | EventType::RoomEncryption | EventType::RoomGuestAccess |
+            EventType::RoomHistoryVisibility | EventType::RoomJoinRules | EventType::RoomMember |
+            EventType::RoomName | EventType::RoomPinnedEvents | EventType::RoomPowerLevels |
+            EventType::RoomServerAcl | EventType::RoomThirdPartyInvite | EventType::RoomTombstone |
+            EventType::RoomTopic | EventType::SpaceChild | EventType::SpaceParent | EventType::Tag |
+            EventType::Typing => {
+                return Err(JsonError::custom("not exclusively a room event".to_string()));
             }
-        }
-    }
-}
-macro_rules! impl_from_t_for_event {
-    ($ty:ty, $variant:ident) => {
-        impl From<$ty> for Event {
-            fn from(event: $ty) -> Self {
-                Event::$variant(event)
+            #[cfg(feature = "events-encryption")]
+            EventType::ForwardedRoomKey | EventType::KeyVerificationAccept |
+            EventType::KeyVerificationCancel | EventType::KeyVerificationDone |
+            EventType::KeyVerificationKey | EventType::KeyVerificationMac |
+            EventType::KeyVerificationRequest | EventType::KeyVerificationStart |
+            EventType::MegolmBackupV1 | EventType::RoomKey | EventType::RoomKeyRequest |
+            EventType::SecretRequest | EventType::SecretSend => {
+                return Err(JsonError::custom("not exclusively a room event".to_string()));
+            }
+            #[cfg(feature = "events-policy")]
+            EventType::PolicyRuleRoom | EventType::PolicyRuleServer | EventType::PolicyRuleUser => {
+                Err(JsonError::custom("not exclusively a room event".to_string()))
             }
         }
-    };
+    }
 }
 
-impl_from_t_for_event!(PresenceEvent, Presence);
-impl_from_t_for_event!(ReceiptEvent, Receipt);
-impl_from_t_for_event!(TagEvent, Tag);
-impl_from_t_for_event!(TypingEvent, Typing);
-impl_from_t_for_event!(CustomEvent, Custom);
+impl<'de> Deserialize<'de> for RoomEvent {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where D: Deserializer<'de> {
+        let value = deserialize_limited_value(deserializer)?;
 
-macro_rules! impl_from_t_for_room_event {
-    ($ty:ty, $variant:ident) => {
-        impl From<$ty> for RoomEvent {
-            fn from(event: $ty) -> Self {
-                RoomEvent::$variant(event)
-            }
-        }
-    };
+        RoomEvent::from_value_with_config(value, &DeserializeConfig::default())
+            .map(|(event, _)| event)
+            .map_err(|error| D::Error::custom(error.to_string()))
+    }
 }
-
-impl_from_t_for_room_event!(AnswerEvent, CallAnswer);
-impl_from_t_for_room_event!(CandidatesEvent, CallCandidates);
-impl_from_t_for_room_event!(HangupEvent, CallHangup);
-impl_from_t_for_room_event!(InviteEvent, CallInvite);
-impl_from_t_for_room_event!(MessageEvent, RoomMessage);
-impl_from_t_for_room_event!(RedactionEvent, RoomRedaction);
-impl_from_t_for_room_event!(CustomRoomEvent, CustomRoom);
@@ -0,0 +1,567 @@
+//! Enums for heterogeneous collections of to-device events, such as the `to_device` section of a
+//! sync response.
+//!
+//! To-device events only have `content`, `sender` and `type` fields.
+
+use ruma_identifiers::UserId;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde::de::Error;
+use serde_json::{Error as JsonError, Value, from_value};
+
+use EventType;
+#[cfg(feature = "events-encryption")]
+use forwarded_room_key::ForwardedRoomKeyEvent;
+#[cfg(feature = "events-encryption")]
+use key::verification::accept::AcceptEvent;
+#[cfg(feature = "events-encryption")]
+use key::verification::cancel::CancelEvent;
+#[cfg(feature = "events-encryption")]
+use key::verification::done::DoneEvent;
+#[cfg(feature = "events-encryption")]
+use key::verification::key::KeyEvent;
+#[cfg(feature = "events-encryption")]
+use key::verification::mac::MacEvent;
+#[cfg(feature = "events-encryption")]
+use key::verification::request::RequestEvent;
+#[cfg(feature = "events-encryption")]
+use key::verification::start::StartEvent;
+#[cfg(feature = "events-encryption")]
+use room::encrypted::EncryptedToDeviceEvent;
+#[cfg(feature = "events-encryption")]
+use room_key::RoomKeyEvent;
+#[cfg(feature = "events-encryption")]
+use room_key_request::RoomKeyRequestEvent;
+#[cfg(feature = "events-encryption")]
+use secret::request::SecretRequestEvent;
+#[cfg(feature = "events-encryption")]
+use secret::send::SecretSendEvent;
+
+use super::{DeserializeConfig, deserialize_limited_value};
+
+event! {
+    /// A custom to-device event not covered by the Matrix specification.
+    ///
+    /// The `Debug` output redacts well-known secrets, such as `access_token`, in the content.
+    masked pub struct CustomToDeviceEvent(Value) {
+        /// The user who sent the event.
+        pub sender: UserId
+    }
+}
+
+/// A to-device event.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub enum ToDeviceEvent {
+    /// m.forwarded_room_key
+    #[cfg(feature = "events-encryption")]
+    ForwardedRoomKey(ForwardedRoomKeyEvent),
+    /// m.key.verification.accept
+    #[cfg(feature = "events-encryption")]
+    KeyVerificationAccept(AcceptEvent),
+    /// m.key.verification.cancel
+    #[cfg(feature = "events-encryption")]
+    KeyVerificationCancel(CancelEvent),
+    /// m.key.verification.done
+    #[cfg(feature = "events-encryption")]
+    KeyVerificationDone(DoneEvent),
+    /// m.key.verification.key
+    #[cfg(feature = "events-encryption")]
+    KeyVerificationKey(KeyEvent),
+    /// m.key.verification.mac
+    #[cfg(feature = "events-encryption")]
+    KeyVerificationMac(MacEvent),
+    /// m.key.verification.request
+    #[cfg(feature = "events-encryption")]
+    KeyVerificationRequest(RequestEvent),
+    /// m.key.verification.start
+    #[cfg(feature = "events-encryption")]
+    KeyVerificationStart(StartEvent),
+    /// m.room.encrypted
+    #[cfg(feature = "events-encryption")]
+    RoomEncrypted(EncryptedToDeviceEvent),
+    /// m.room_key
+    #[cfg(feature = "events-encryption")]
+    RoomKey(RoomKeyEvent),
+    /// m.room_key_request
+    #[cfg(feature = "events-encryption")]
+    RoomKeyRequest(RoomKeyRequestEvent),
+    /// m.secret.request
+    #[cfg(feature = "events-encryption")]
+    SecretRequest(SecretRequestEvent),
+    /// m.secret.send
+    #[cfg(feature = "events-encryption")]
+    SecretSend(SecretSendEvent),
+    /// Any to-device event that is not part of the specification.
+    Custom(CustomToDeviceEvent),
+}
+
+impl ToDeviceEvent {
+    /// The user who sent the event.
+    pub fn sender(&self) -> &UserId {
+        match *self {
+            #[cfg(feature = "events-encryption")]
+            ToDeviceEvent::ForwardedRoomKey(ref event) => &event.sender,
+            #[cfg(feature = "events-encryption")]
+            ToDeviceEvent::KeyVerificationAccept(ref event) => &event.sender,
+            #[cfg(feature = "events-encryption")]
+            ToDeviceEvent::KeyVerificationCancel(ref event) => &event.sender,
+            #[cfg(feature = "events-encryption")]
+            ToDeviceEvent::KeyVerificationDone(ref event) => &event.sender,
+            #[cfg(feature = "events-encryption")]
+            ToDeviceEvent::KeyVerificationKey(ref event) => &event.sender,
+            #[cfg(feature = "events-encryption")]
+            ToDeviceEvent::KeyVerificationMac(ref event) => &event.sender,
+            #[cfg(feature = "events-encryption")]
+            ToDeviceEvent::KeyVerificationRequest(ref event) => &event.sender,
+            #[cfg(feature = "events-encryption")]
+            ToDeviceEvent::KeyVerificationStart(ref event) => &event.sender,
+            #[cfg(feature = "events-encryption")]
+            ToDeviceEvent::RoomEncrypted(ref event) => &event.sender,
+            #[cfg(feature = "events-encryption")]
+            ToDeviceEvent::RoomKey(ref event) => &event.sender,
+            #[cfg(feature = "events-encryption")]
+            ToDeviceEvent::RoomKeyRequest(ref event) => &event.sender,
+            #[cfg(feature = "events-encryption")]
+            ToDeviceEvent::SecretRequest(ref event) => &event.sender,
+            #[cfg(feature = "events-encryption")]
+            ToDeviceEvent::SecretSend(ref event) => &event.sender,
+            ToDeviceEvent::Custom(ref event) => &event.sender,
+        }
+    }
+}
+
+impl Serialize for ToDeviceEvent {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
+        match *self {
+            #[cfg(feature = "events-encryption")]
+            ToDeviceEvent::ForwardedRoomKey(ref event) => event.serialize(serializer),
+            #[cfg(feature = "events-encryption")]
+            ToDeviceEvent::KeyVerificationAccept(ref event) => event.serialize(serializer),
+            #[cfg(feature = "events-encryption")]
+            ToDeviceEvent::KeyVerificationCancel(ref event) => event.serialize(serializer),
+            #[cfg(feature = "events-encryption")]
+            ToDeviceEvent::KeyVerificationDone(ref event) => event.serialize(serializer),
+            #[cfg(feature = "events-encryption")]
+            ToDeviceEvent::KeyVerificationKey(ref event) => event.serialize(serializer),
+            #[cfg(feature = "events-encryption")]
+            ToDeviceEvent::KeyVerificationMac(ref event) => event.serialize(serializer),
+            #[cfg(feature = "events-encryption")]
+            ToDeviceEvent::KeyVerificationRequest(ref event) => event.serialize(serializer),
+            #[cfg(feature = "events-encryption")]
+            ToDeviceEvent::KeyVerificationStart(ref event) => event.serialize(serializer),
+            #[cfg(feature = "events-encryption")]
+            ToDeviceEvent::RoomEncrypted(ref event) => event.serialize(serializer),
+            #[cfg(feature = "events-encryption")]
+            ToDeviceEvent::RoomKey(ref event) => event.serialize(serializer),
+            #[cfg(feature = "events-encryption")]
+            ToDeviceEvent::RoomKeyRequest(ref event) => event.serialize(serializer),
+            #[cfg(feature = "events-encryption")]
+            ToDeviceEvent::SecretRequest(ref event) => event.serialize(serializer),
+            #[cfg(feature = "events-encryption")]
+            ToDeviceEvent::SecretSend(ref event) => event.serialize(serializer),
+            ToDeviceEvent::Custom(ref event) => event.serialize(serializer),
+        }
+    }
+}
+
+impl ToDeviceEvent {
+    /// Deserializes an event from a JSON value with the given per-call options.
+    pub fn from_value_with_config(value: Value, config: &DeserializeConfig)
+    -> Result<Self, JsonError> {
+        let event_type_value = match value.get("type") {
+            Some(value) => value,
+            None => return Err(JsonError::missing_field("type")),
+        };
+
+        let event_type = match EventType::deserialize(event_type_value) {
+            Ok(event_type) => event_type,
+            Err(error) => return Err(JsonError::custom(error.to_string())),
+        };
+
+        config.observe_unknown_type(&event_type);
+
+        ToDeviceEvent::from_typed_value(value, event_type)
+    }
+
+    /// Deserializes an event of the given type from a JSON value.
+    fn from_typed_value(value: Value, event_type: EventType) -> Result<Self, JsonError> {
+        match event_type {
+            #[cfg(feature = "events-encryption")]
+            EventType::ForwardedRoomKey => {
+                let event = match from_value::<ForwardedRoomKeyEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(ToDeviceEvent::ForwardedRoomKey(event))
+            }
+            #[cfg(feature = "events-encryption")]
+            EventType::KeyVerificationAccept => {
+                let event = match from_value::<AcceptEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(ToDeviceEvent::KeyVerificationAccept(event))
+            }
+            #[cfg(feature = "events-encryption")]
+            EventType::KeyVerificationCancel => {
+                let event = match from_value::<CancelEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(ToDeviceEvent::KeyVerificationCancel(event))
+            }
+            #[cfg(feature = "events-encryption")]
+            EventType::KeyVerificationDone => {
+                let event = match from_value::<DoneEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(ToDeviceEvent::KeyVerificationDone(event))
+            }
+            #[cfg(feature = "events-encryption")]
+            EventType::KeyVerificationKey => {
+                let event = match from_value::<KeyEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(ToDeviceEvent::KeyVerificationKey(event))
+            }
+            #[cfg(feature = "events-encryption")]
+            EventType::KeyVerificationMac => {
+                let event = match from_value::<MacEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(ToDeviceEvent::KeyVerificationMac(event))
+            }
+            #[cfg(feature = "events-encryption")]
+            EventType::KeyVerificationRequest => {
+                let event = match from_value::<RequestEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(ToDeviceEvent::KeyVerificationRequest(event))
+            }
+            #[cfg(feature = "events-encryption")]
+            EventType::KeyVerificationStart => {
+                let event = match from_value::<StartEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(ToDeviceEvent::KeyVerificationStart(event))
+            }
+            #[cfg(feature = "events-encryption")]
+            EventType::RoomEncrypted => {
+                let event = match from_value::<EncryptedToDeviceEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(ToDeviceEvent::RoomEncrypted(event))
+            }
+            #[cfg(feature = "events-encryption")]
+            EventType::RoomKey => {
+                let event = match from_value::<RoomKeyEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(ToDeviceEvent::RoomKey(event))
+            }
+            #[cfg(feature = "events-encryption")]
+            EventType::RoomKeyRequest => {
+                let event = match from_value::<RoomKeyRequestEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(ToDeviceEvent::RoomKeyRequest(event))
+            }
+            #[cfg(feature = "events-encryption")]
+            EventType::SecretRequest => {
+                let event = match from_value::<SecretRequestEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(ToDeviceEvent::SecretRequest(event))
+            }
+            #[cfg(feature = "events-encryption")]
+            EventType::SecretSend => {
+                let event = match from_value::<SecretSendEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(ToDeviceEvent::SecretSend(event))
+            }
+            EventType::Custom(_) => {
+                let event = match from_value::<CustomToDeviceEvent>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(JsonError::custom(error.to_string())),
+                };
+
+                Ok(ToDeviceEvent::Custom(event))
+            }
+            _ => {
+                Err(JsonError::custom("not a to-device event".to_string()))
+            }
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for ToDeviceEvent {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where D: Deserializer<'de> {
+        let value = deserialize_limited_value(deserializer)?;
+
+        ToDeviceEvent::from_value_with_config(value, &DeserializeConfig::default())
+            .map_err(|error| D::Error::custom(error.to_string()))
+    }
+}
+
+macro_rules! impl_from_t_for_to_device_event {
+    ($ty:ty, $variant:ident) => {
+        impl From<$ty> for ToDeviceEvent {
+            fn from(event: $ty) -> Self {
+                ToDeviceEvent::$variant(event)
+            }
+        }
+    };
+}
+
+#[cfg(feature = "events-encryption")]
+impl_from_t_for_to_device_event!(ForwardedRoomKeyEvent, ForwardedRoomKey);
+#[cfg(feature = "events-encryption")]
+impl_from_t_for_to_device_event!(AcceptEvent, KeyVerificationAccept);
+#[cfg(feature = "events-encryption")]
+impl_from_t_for_to_device_event!(CancelEvent, KeyVerificationCancel);
+#[cfg(feature = "events-encryption")]
+impl_from_t_for_to_device_event!(DoneEvent, KeyVerificationDone);
+#[cfg(feature = "events-encryption")]
+impl_from_t_for_to_device_event!(KeyEvent, KeyVerificationKey);
+#[cfg(feature = "events-encryption")]
+impl_from_t_for_to_device_event!(MacEvent, KeyVerificationMac);
+#[cfg(feature = "events-encryption")]
+impl_from_t_for_to_device_event!(RequestEvent, KeyVerificationRequest);
+#[cfg(feature = "events-encryption")]
+impl_from_t_for_to_device_event!(StartEvent, KeyVerificationStart);
+#[cfg(feature = "events-encryption")]
+impl_from_t_for_to_device_event!(EncryptedToDeviceEvent, RoomEncrypted);
+#[cfg(feature = "events-encryption")]
+impl_from_t_for_to_device_event!(RoomKeyEvent, RoomKey);
+#[cfg(feature = "events-encryption")]
+impl_from_t_for_to_device_event!(RoomKeyRequestEvent, RoomKeyRequest);
+#[cfg(feature = "events-encryption")]
+impl_from_t_for_to_device_event!(SecretRequestEvent, SecretRequest);
+#[cfg(feature = "events-encryption")]
+impl_from_t_for_to_device_event!(SecretSendEvent, SecretSend);
+impl_from_t_for_to_device_event!(CustomToDeviceEvent, Custom);
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{Value, from_str, to_value};
+
+    #[cfg(feature = "events-encryption")]
+    use key::verification::CancelCode;
+    #[cfg(feature = "events-encryption")]
+    use room::encrypted::EncryptedEventContent;
+    #[cfg(feature = "events-encryption")]
+    use room::encryption::Algorithm;
+    #[cfg(feature = "events-encryption")]
+    use secret::request::RequestAction;
+    use super::ToDeviceEvent;
+
+    #[cfg(feature = "events-encryption")]
+    #[test]
+    fn deserialize_to_device_events() {
+        let events = from_str::<Vec<ToDeviceEvent>>(r#"[
+            {
+                "content": {
+                    "algorithm": "m.megolm.v1.aes-sha2",
+                    "room_id": "!Cuyf34gef24t:localhost",
+                    "session_id": "X3lUlvLELLYxeTx4yOVu6UDpasGEVO0Jbu+QFnm0cKQ",
+                    "session_key": "AgAAAADxKHa9uFxcXzwYoNueL5Xqi69IkD4sni8LlfJL7qNBEY..."
+                },
+                "sender": "@alice:example.com",
+                "type": "m.room_key"
+            },
+            {
+                "content": {
+                    "algorithm": "m.megolm.v1.aes-sha2",
+                    "forwarding_curve25519_key_chain": ["hPQNcabIABgGnx3/ACv/jmMmiQHo"],
+                    "room_id": "!Cuyf34gef24t:localhost",
+                    "sender_claimed_ed25519_key": "aj40p+aw64yPIdsxoog8jhPu9i7l7NcFRecuOQblE3Y",
+                    "sender_key": "RF3s+E7RkTQTGF2d8Deol0FkQvgII2aJDf3/Jp5mxVU",
+                    "session_id": "X3lUlvLELLYxeTx4yOVu6UDpasGEVO0Jbu+QFnm0cKQ",
+                    "session_key": "AgAAAADxKHa9uFxcXzwYoNueL5Xqi69IkD4sni8Llf..."
+                },
+                "sender": "@alice:example.com",
+                "type": "m.forwarded_room_key"
+            },
+            {
+                "content": {
+                    "code": "m.user",
+                    "reason": "User rejected the key verification request",
+                    "transaction_id": "S0meUniqueAndOpaqueString"
+                },
+                "sender": "@alice:example.com",
+                "type": "m.key.verification.cancel"
+            },
+            {"content": {}, "sender": "@alice:example.com", "type": "org.example.ping"},
+            {
+                "content": {
+                    "action": "request_cancellation",
+                    "request_id": "1495474790150.19",
+                    "requesting_device_id": "RJYKSTBOIE"
+                },
+                "sender": "@alice:example.com",
+                "type": "m.room_key_request"
+            },
+            {
+                "content": {
+                    "algorithm": "m.olm.v1.curve25519-aes-sha2",
+                    "ciphertext": {"7qZcfnBmbEGzxxaWfBjElJuvn7BZx": {"body": "AwogGJJ", "type": 0}},
+                    "sender_key": "Szl29ksW/L8yZGWAX+8dY1XyFi+i5wm+DRhTGkbMiwU"
+                },
+                "sender": "@alice:example.com",
+                "type": "m.room.encrypted"
+            }
+        ]"#).unwrap();
+
+        match events[0] {
+            ToDeviceEvent::RoomKey(ref event) => {
+                assert_eq!(event.content.algorithm, Algorithm::MegolmV1AesSha2);
+            }
+            _ => unreachable!(),
+        }
+
+        match events[1] {
+            ToDeviceEvent::ForwardedRoomKey(ref event) => {
+                assert_eq!(event.content.forwarding_curve25519_key_chain.len(), 1);
+            }
+            _ => unreachable!(),
+        }
+
+        match events[2] {
+            ToDeviceEvent::KeyVerificationCancel(ref event) => {
+                assert_eq!(event.content.code, CancelCode::User);
+            }
+            _ => unreachable!(),
+        }
+
+        match events[3] {
+            ToDeviceEvent::Custom(ref event) => {
+                assert_eq!(event.sender.to_string(), "@alice:example.com");
+            }
+            _ => unreachable!(),
+        }
+
+        match events[4] {
+            ToDeviceEvent::RoomKeyRequest(ref event) => {
+                assert_eq!(event.content.action, RequestAction::RequestCancellation);
+                assert_eq!(event.content.body, None);
+            }
+            _ => unreachable!(),
+        }
+
+        match events[5] {
+            ToDeviceEvent::RoomEncrypted(ref event) => match event.content {
+                EncryptedEventContent::OlmV1Curve25519AesSha2(ref content) => {
+                    assert_eq!(content.ciphertext.len(), 1);
+                }
+                _ => unreachable!(),
+            },
+            _ => unreachable!(),
+        }
+
+        assert_eq!(to_value(&events[0]).unwrap()["type"], "m.room_key");
+    }
+
+    #[cfg(feature = "events-encryption")]
+    #[test]
+    fn room_key_request_round_trip() {
+        let json = r#"{
+            "content": {
+                "action": "request",
+                "body": {
+                    "algorithm": "m.megolm.v1.aes-sha2",
+                    "room_id": "!Cuyf34gef24t:localhost",
+                    "sender_key": "RF3s+E7RkTQTGF2d8Deol0FkQvgII2aJDf3/Jp5mxVU",
+                    "session_id": "X3lUlvLELLYxeTx4yOVu6UDpasGEVO0Jbu+QFnm0cKQ"
+                },
+                "request_id": "1495474790150.19",
+                "requesting_device_id": "RJYKSTBOIE"
+            },
+            "sender": "@alice:example.com",
+            "type": "m.room_key_request"
+        }"#;
+
+        let event = from_str::<ToDeviceEvent>(json).unwrap();
+
+        match event {
+            ToDeviceEvent::RoomKeyRequest(ref event) => {
+                assert_eq!(event.content.action, RequestAction::Request);
+                assert_eq!(event.content.requesting_device_id, "RJYKSTBOIE");
+                assert_eq!(event.content.body.as_ref().unwrap().session_id.len(), 43);
+            }
+            _ => unreachable!(),
+        }
+
+        assert_eq!(to_value(&event).unwrap(), from_str::<Value>(json).unwrap());
+    }
+
+    #[cfg(feature = "events-encryption")]
+    #[test]
+    fn encrypted_to_device_round_trip() {
+        let json = r#"{
+            "content": {
+                "algorithm": "m.olm.v1.curve25519-aes-sha2",
+                "ciphertext": {"7qZcfnBmbEGzxxaWfBjElJuvn7BZx": {"body": "AwogGJJ", "type": 0}},
+                "sender_key": "Szl29ksW/L8yZGWAX+8dY1XyFi+i5wm+DRhTGkbMiwU"
+            },
+            "sender": "@alice:example.com",
+            "type": "m.room.encrypted"
+        }"#;
+
+        let event = from_str::<ToDeviceEvent>(json).unwrap();
+
+        assert_eq!(event.sender().to_string(), "@alice:example.com");
+        assert_eq!(to_value(&event).unwrap(), from_str::<Value>(json).unwrap());
+    }
+
+    #[test]
+    fn unknown_types_are_custom_events() {
+        let json = r#"{
+            "content": {"nonce": 42},
+            "sender": "@alice:example.com",
+            "type": "org.example.ping"
+        }"#;
+
+        let event = from_str::<ToDeviceEvent>(json).unwrap();
+
+        assert!(matches!(event, ToDeviceEvent::Custom(_)));
+        assert_eq!(event.sender().to_string(), "@alice:example.com");
+
+        assert_eq!(to_value(&event).unwrap(), from_str::<Value>(json).unwrap());
+    }
+
+    #[test]
+    fn non_to_device_types_are_rejected() {
+        assert!(from_str::<ToDeviceEvent>(r#"{
+            "content": {"body": "hi", "msgtype": "m.text"},
+            "sender": "@alice:example.com",
+            "type": "m.room.message"
+        }"#).is_err());
+        assert!(from_str::<ToDeviceEvent>(
+            r#"{"content": {}, "sender": "@alice:example.com"}"#
+        ).is_err());
+    }
+}
@@ -0,0 +1,757 @@
+//! Types and functions for working with the content of events apart from the events themselves.
+//!
+//! This is useful for callers that store or receive the type and content of events separately,
+//! such as state stores keyed by event type and state key.
+
+use serde::{Serialize, Serializer};
+use serde::de::Error;
+use serde_json::{Error as JsonError, Value, from_value};
+
+use {EventContent, EventType};
+#[cfg(feature = "events-call")]
+use call::answer::AnswerEventContent;
+#[cfg(feature = "events-call")]
+use call::candidates::CandidatesEventContent;
+#[cfg(feature = "events-call")]
+use call::hangup::HangupEventContent;
+#[cfg(feature = "events-call")]
+use call::invite::InviteEventContent;
+#[cfg(feature = "events-call")]
+use call::negotiate::NegotiateEventContent;
+#[cfg(feature = "events-call")]
+use call::reject::RejectEventContent;
+#[cfg(feature = "events-call")]
+use call::select_answer::SelectAnswerEventContent;
+use direct::DirectEventContent;
+#[cfg(feature = "events-encryption")]
+use forwarded_room_key::ForwardedRoomKeyEventContent;
+use fully_read::FullyReadEventContent;
+use ignored_user_list::IgnoredUserListEventContent;
+#[cfg(feature = "events-encryption")]
+use key::backup::MegolmBackupEventContent;
+#[cfg(feature = "events-encryption")]
+use key::verification::accept::AcceptEventContent;
+#[cfg(feature = "events-encryption")]
+use key::verification::cancel::CancelEventContent;
+#[cfg(feature = "events-encryption")]
+use key::verification::done::DoneEventContent;
+#[cfg(feature = "events-encryption")]
+use key::verification::key::KeyEventContent;
+#[cfg(feature = "events-encryption")]
+use key::verification::mac::MacEventContent;
+#[cfg(feature = "events-encryption")]
+use key::verification::request::RequestEventContent;
+#[cfg(feature = "events-encryption")]
+use key::verification::start::StartEventContent;
+#[cfg(feature = "events-policy")]
+use policy::rule::room::RoomRuleEventContent;
+#[cfg(feature = "events-policy")]
+use policy::rule::server::ServerRuleEventContent;
+#[cfg(feature = "events-policy")]
+use policy::rule::user::UserRuleEventContent;
+#[cfg(feature = "events-poll")]
+use poll::end::PollEndEventContent;
+#[cfg(feature = "events-poll")]
+use poll::response::PollResponseEventContent;
+#[cfg(feature = "events-poll")]
+use poll::start::PollStartEventContent;
+use presence::PresenceEventContent;
+use push_rules::PushRulesEventContent;
+use reaction::ReactionEventContent;
+use receipt::ReceiptEventContent;
+use room::aliases::AliasesEventContent;
+use room::avatar::AvatarEventContent;
+use room::canonical_alias::CanonicalAliasEventContent;
+use room::create::CreateEventContent;
+#[cfg(feature = "events-encryption")]
+use room::encrypted::EncryptedEventContent;
+use room::encryption::EncryptionEventContent;
+use room::guest_access::GuestAccessEventContent;
+use room::history_visibility::HistoryVisibilityEventContent;
+use room::join_rules::JoinRulesEventContent;
+use room::member::MemberEventContent;
+use room::message::MessageEventContent;
+use room::name::NameEventContent;
+use room::pinned_events::PinnedEventsEventContent;
+use room::power_levels::PowerLevelsEventContent;
+use room::redaction::RedactionEventContent;
+use room::server_acl::ServerAclEventContent;
+use room::third_party_invite::ThirdPartyInviteEventContent;
+use room::tombstone::TombstoneEventContent;
+use room::topic::TopicEventContent;
+#[cfg(feature = "events-encryption")]
+use room_key::RoomKeyEventContent;
+#[cfg(feature = "events-encryption")]
+use room_key_request::RoomKeyRequestEventContent;
+#[cfg(feature = "events-encryption")]
+use secret::request::SecretRequestEventContent;
+#[cfg(feature = "events-encryption")]
+use secret::send::SecretSendEventContent;
+use space::child::ChildEventContent;
+use space::parent::ParentEventContent;
+use sticker::StickerEventContent;
+use tag::TagEventContent;
+use typing::TypingEventContent;
+
+/// Defines an enum over content types, along with an `event_type` method and a `Serialize`
+/// implementation that serializes the contained content. Every enum gets a `Custom` variant for
+/// content of event types not covered by the Matrix specification.
+macro_rules! any_content {
+    (   $(#[$attr:meta])*
+        pub enum $name:ident {
+            $(
+                $(#[doc = $doc:literal])*
+                $(#[cfg($cfg:meta)])*
+                $variant:ident($content_type:ty),
+            )*
+        }
+    ) => {
+        $(#[$attr])*
+        #[derive(Clone, Debug)]
+        #[non_exhaustive]
+        pub enum $name {
+            $(
+                $(#[doc = $doc])*
+                $(#[cfg($cfg)])*
+                $variant($content_type),
+            )*
+
+            /// The content of an event not covered by the Matrix specification, along with its
+            /// type.
+            Custom(EventType, Value),
+        }
+
+        impl $name {
+            /// The type of the events that carry this content.
+            pub fn event_type(&self) -> EventType {
+                match *self {
+                    $($(#[cfg($cfg)])* $name::$variant(_) => EventType::$variant,)*
+                    $name::Custom(ref event_type, _) => event_type.clone(),
+                }
+            }
+        }
+
+        impl Serialize for $name {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
+                match *self {
+                    $(
+                        $(#[cfg($cfg)])*
+                        $name::$variant(ref content) => content.serialize(serializer),
+                    )*
+                    $name::Custom(_, ref content) => content.serialize(serializer),
+                }
+            }
+        }
+
+        $(
+            $(#[cfg($cfg)])*
+            impl From<$content_type> for $name {
+                fn from(content: $content_type) -> $name {
+                    $name::$variant(content)
+                }
+            }
+        )*
+    }
+}
+
+/// Implements the conversion of an enum defined with `any_content!` into `AnyContent`, and the
+/// fallible conversion back.
+macro_rules! impl_into_any_content {
+    ($name:ident { $($(#[cfg($cfg:meta)])* $variant:ident,)* }) => {
+        impl $name {
+            /// Converts `AnyContent` into this kind of content, if it is content of this kind.
+            ///
+            /// Custom content is always converted, since its kind is unknown.
+            pub fn from_any_content(content: AnyContent) -> Option<$name> {
+                match content {
+                    $(
+                        $(#[cfg($cfg)])*
+                        AnyContent::$variant(content) => Some($name::$variant(content)),
+                    )*
+                    AnyContent::Custom(event_type, content) => {
+                        Some($name::Custom(event_type, content))
+                    }
+                    _ => None,
+                }
+            }
+
+            /// Whether `from_any_content` would convert `content`.
+            pub fn can_convert_from(content: &AnyContent) -> bool {
+                match *content {
+                    $(
+                        $(#[cfg($cfg)])*
+                        AnyContent::$variant(_) => true,
+                    )*
+                    AnyContent::Custom(..) => true,
+                    _ => false,
+                }
+            }
+        }
+
+        impl From<$name> for AnyContent {
+            fn from(content: $name) -> AnyContent {
+                match content {
+                    $(
+                        $(#[cfg($cfg)])*
+                        $name::$variant(content) => AnyContent::$variant(content),
+                    )*
+                    $name::Custom(event_type, content) => AnyContent::Custom(event_type, content),
+                }
+            }
+        }
+    }
+}
+
+any_content! {
+    /// The content of any event.
+    pub enum AnyContent {
+        /// The content of an *m.call.answer* event.
+        #[cfg(feature = "events-call")]
+        CallAnswer(AnswerEventContent),
+
+        /// The content of an *m.call.candidates* event.
+        #[cfg(feature = "events-call")]
+        CallCandidates(CandidatesEventContent),
+
+        /// The content of an *m.call.hangup* event.
+        #[cfg(feature = "events-call")]
+        CallHangup(HangupEventContent),
+
+        /// The content of an *m.call.invite* event.
+        #[cfg(feature = "events-call")]
+        CallInvite(InviteEventContent),
+
+        /// The content of an *m.call.negotiate* event.
+        #[cfg(feature = "events-call")]
+        CallNegotiate(NegotiateEventContent),
+
+        /// The content of an *m.call.reject* event.
+        #[cfg(feature = "events-call")]
+        CallReject(RejectEventContent),
+
+        /// The content of an *m.call.select_answer* event.
+        #[cfg(feature = "events-call")]
+        CallSelectAnswer(SelectAnswerEventContent),
+
+        /// The content of an *m.direct* event.
+        Direct(DirectEventContent),
+
+        /// The content of an *m.forwarded_room_key* event.
+        #[cfg(feature = "events-encryption")]
+        ForwardedRoomKey(ForwardedRoomKeyEventContent),
+
+        /// The content of an *m.fully_read* event.
+        FullyRead(FullyReadEventContent),
+
+        /// The content of an *m.ignored_user_list* event.
+        IgnoredUserList(IgnoredUserListEventContent),
+
+        /// The content of an *m.key.verification.accept* event.
+        #[cfg(feature = "events-encryption")]
+        KeyVerificationAccept(AcceptEventContent),
+
+        /// The content of an *m.key.verification.cancel* event.
+        #[cfg(feature = "events-encryption")]
+        KeyVerificationCancel(CancelEventContent),
+
+        /// The content of an *m.key.verification.done* event.
+        #[cfg(feature = "events-encryption")]
+        KeyVerificationDone(DoneEventContent),
+
+        /// The content of an *m.key.verification.key* event.
+        #[cfg(feature = "events-encryption")]
+        KeyVerificationKey(KeyEventContent),
+
+        /// The content of an *m.key.verification.mac* event.
+        #[cfg(feature = "events-encryption")]
+        KeyVerificationMac(MacEventContent),
+
+        /// The content of an *m.key.verification.request* event.
+        #[cfg(feature = "events-encryption")]
+        KeyVerificationRequest(RequestEventContent),
+
+        /// The content of an *m.key.verification.start* event.
+        #[cfg(feature = "events-encryption")]
+        KeyVerificationStart(StartEventContent),
+
+        /// The content of an *m.megolm_backup.v1* event.
+        #[cfg(feature = "events-encryption")]
+        MegolmBackupV1(MegolmBackupEventContent),
+
+        /// The content of an *m.policy.rule.room* event.
+        #[cfg(feature = "events-policy")]
+        PolicyRuleRoom(RoomRuleEventContent),
+
+        /// The content of an *m.policy.rule.server* event.
+        #[cfg(feature = "events-policy")]
+        PolicyRuleServer(ServerRuleEventContent),
+
+        /// The content of an *m.policy.rule.user* event.
+        #[cfg(feature = "events-policy")]
+        PolicyRuleUser(UserRuleEventContent),
+
+        /// The content of an *m.poll.end* event.
+        #[cfg(feature = "events-poll")]
+        PollEnd(PollEndEventContent),
+
+        /// The content of an *m.poll.response* event.
+        #[cfg(feature = "events-poll")]
+        PollResponse(PollResponseEventContent),
+
+        /// The content of an *m.poll.start* event.
+        #[cfg(feature = "events-poll")]
+        PollStart(PollStartEventContent),
+
+        /// The content of an *m.presence* event.
+        Presence(PresenceEventContent),
+
+        /// The content of an *m.push_rules* event.
+        PushRules(PushRulesEventContent),
+
+        /// The content of an *m.reaction* event.
+        Reaction(ReactionEventContent),
+
+        /// The content of an *m.receipt* event.
+        Receipt(ReceiptEventContent),
+
+        /// The content of an *m.room.aliases* event.
+        RoomAliases(AliasesEventContent),
+
+        /// The content of an *m.room.avatar* event.
+        RoomAvatar(AvatarEventContent),
+
+        /// The content of an *m.room.canonical_alias* event.
+        RoomCanonicalAlias(CanonicalAliasEventContent),
+
+        /// The content of an *m.room.create* event.
+        RoomCreate(CreateEventContent),
+
+        /// The content of an *m.room.encrypted* event.
+        #[cfg(feature = "events-encryption")]
+        RoomEncrypted(EncryptedEventContent),
+
+        /// The content of an *m.room.encryption* event.
+        RoomEncryption(EncryptionEventContent),
+
+        /// The content of an *m.room.guest_access* event.
+        RoomGuestAccess(GuestAccessEventContent),
+
+        /// The content of an *m.room.history_visibility* event.
+        RoomHistoryVisibility(HistoryVisibilityEventContent),
+
+        /// The content of an *m.room.join_rules* event.
+        RoomJoinRules(JoinRulesEventContent),
+
+        /// The content of an *m.room_key* event.
+        #[cfg(feature = "events-encryption")]
+        RoomKey(RoomKeyEventContent),
+
+        /// The content of an *m.room_key_request* event.
+        #[cfg(feature = "events-encryption")]
+        RoomKeyRequest(RoomKeyRequestEventContent),
+
+        /// The content of an *m.room.member* event.
+        RoomMember(MemberEventContent),
+
+        /// The content of an *m.room.message* event.
+        RoomMessage(MessageEventContent),
+
+        /// The content of an *m.room.name* event.
+        RoomName(NameEventContent),
+
+        /// The content of an *m.room.pinned_events* event.
+        RoomPinnedEvents(PinnedEventsEventContent),
+
+        /// The content of an *m.room.power_levels* event.
+        RoomPowerLevels(PowerLevelsEventContent),
+
+        /// The content of an *m.room.redaction* event.
+        RoomRedaction(RedactionEventContent),
+
+        /// The content of an *m.room.server_acl* event.
+        RoomServerAcl(ServerAclEventContent),
+
+        /// The content of an *m.room.third_party_invite* event.
+        RoomThirdPartyInvite(ThirdPartyInviteEventContent),
+
+        /// The content of an *m.room.tombstone* event.
+        RoomTombstone(TombstoneEventContent),
+
+        /// The content of an *m.room.topic* event.
+        RoomTopic(TopicEventContent),
+
+        /// The content of an *m.secret.request* event.
+        #[cfg(feature = "events-encryption")]
+        SecretRequest(SecretRequestEventContent),
+
+        /// The content of an *m.secret.send* event.
+        #[cfg(feature = "events-encryption")]
+        SecretSend(SecretSendEventContent),
+
+        /// The content of an *m.space.child* event.
+        SpaceChild(ChildEventContent),
+
+        /// The content of an *m.space.parent* event.
+        SpaceParent(ParentEventContent),
+
+        /// The content of an *m.sticker* event.
+        Sticker(StickerEventContent),
+
+        /// The content of an *m.tag* event.
+        Tag(TagEventContent),
+
+        /// The content of an *m.typing* event.
+        Typing(TypingEventContent),
+    }
+}
+
+any_content! {
+    /// The content of any room event that is not a state event.
+    pub enum AnyMessageLikeEventContent {
+        /// The content of an *m.call.answer* event.
+        #[cfg(feature = "events-call")]
+        CallAnswer(AnswerEventContent),
+
+        /// The content of an *m.call.candidates* event.
+        #[cfg(feature = "events-call")]
+        CallCandidates(CandidatesEventContent),
+
+        /// The content of an *m.call.hangup* event.
+        #[cfg(feature = "events-call")]
+        CallHangup(HangupEventContent),
+
+        /// The content of an *m.call.invite* event.
+        #[cfg(feature = "events-call")]
+        CallInvite(InviteEventContent),
+
+        /// The content of an *m.call.negotiate* event.
+        #[cfg(feature = "events-call")]
+        CallNegotiate(NegotiateEventContent),
+
+        /// The content of an *m.call.reject* event.
+        #[cfg(feature = "events-call")]
+        CallReject(RejectEventContent),
+
+        /// The content of an *m.call.select_answer* event.
+        #[cfg(feature = "events-call")]
+        CallSelectAnswer(SelectAnswerEventContent),
+
+        /// The content of an *m.poll.end* event.
+        #[cfg(feature = "events-poll")]
+        PollEnd(PollEndEventContent),
+
+        /// The content of an *m.poll.response* event.
+        #[cfg(feature = "events-poll")]
+        PollResponse(PollResponseEventContent),
+
+        /// The content of an *m.poll.start* event.
+        #[cfg(feature = "events-poll")]
+        PollStart(PollStartEventContent),
+
+        /// The content of an *m.reaction* event.
+        Reaction(ReactionEventContent),
+
+        /// The content of an *m.room.encrypted* event.
+        #[cfg(feature = "events-encryption")]
+        RoomEncrypted(EncryptedEventContent),
+
+        /// The content of an *m.room.message* event.
+        RoomMessage(MessageEventContent),
+
+        /// The content of an *m.room.redaction* event.
+        RoomRedaction(RedactionEventContent),
+
+        /// The content of an *m.sticker* event.
+        Sticker(StickerEventContent),
+    }
+}
+
+any_content! {
+    /// The content of any state event.
+    pub enum AnyStateEventContent {
+        /// The content of an *m.policy.rule.room* event.
+        #[cfg(feature = "events-policy")]
+        PolicyRuleRoom(RoomRuleEventContent),
+
+        /// The content of an *m.policy.rule.server* event.
+        #[cfg(feature = "events-policy")]
+        PolicyRuleServer(ServerRuleEventContent),
+
+        /// The content of an *m.policy.rule.user* event.
+        #[cfg(feature = "events-policy")]
+        PolicyRuleUser(UserRuleEventContent),
+
+        /// The content of an *m.room.aliases* event.
+        RoomAliases(AliasesEventContent),
+
+        /// The content of an *m.room.avatar* event.
+        RoomAvatar(AvatarEventContent),
+
+        /// The content of an *m.room.canonical_alias* event.
+        RoomCanonicalAlias(CanonicalAliasEventContent),
+
+        /// The content of an *m.room.create* event.
+        RoomCreate(CreateEventContent),
+
+        /// The content of an *m.room.encryption* event.
+        RoomEncryption(EncryptionEventContent),
+
+        /// The content of an *m.room.guest_access* event.
+        RoomGuestAccess(GuestAccessEventContent),
+
+        /// The content of an *m.room.history_visibility* event.
+        RoomHistoryVisibility(HistoryVisibilityEventContent),
+
+        /// The content of an *m.room.join_rules* event.
+        RoomJoinRules(JoinRulesEventContent),
+
+        /// The content of an *m.room.member* event.
+        RoomMember(MemberEventContent),
+
+        /// The content of an *m.room.name* event.
+        RoomName(NameEventContent),
+
+        /// The content of an *m.room.pinned_events* event.
+        RoomPinnedEvents(PinnedEventsEventContent),
+
+        /// The content of an *m.room.power_levels* event.
+        RoomPowerLevels(PowerLevelsEventContent),
+
+        /// The content of an *m.room.server_acl* event.
+        RoomServerAcl(ServerAclEventContent),
+
+        /// The content of an *m.room.third_party_invite* event.
+        RoomThirdPartyInvite(ThirdPartyInviteEventContent),
+
+        /// The content of an *m.room.tombstone* event.
+        RoomTombstone(TombstoneEventContent),
+
+        /// The content of an *m.room.topic* event.
+        RoomTopic(TopicEventContent),
+
+        /// The content of an *m.space.child* event.
+        SpaceChild(ChildEventContent),
+
+        /// The content of an *m.space.parent* event.
+        SpaceParent(ParentEventContent),
+    }
+}
+
+impl_into_any_content!(AnyMessageLikeEventContent {
+    #[cfg(feature = "events-call")]
+    CallAnswer,
+    #[cfg(feature = "events-call")]
+    CallCandidates,
+    #[cfg(feature = "events-call")]
+    CallHangup,
+    #[cfg(feature = "events-call")]
+    CallInvite,
+    #[cfg(feature = "events-call")]
+    CallNegotiate,
+    #[cfg(feature = "events-call")]
+    CallReject,
+    #[cfg(feature = "events-call")]
+    CallSelectAnswer,
+    #[cfg(feature = "events-poll")]
+    PollEnd,
+    #[cfg(feature = "events-poll")]
+    PollResponse,
+    #[cfg(feature = "events-poll")]
+    PollStart,
+    Reaction,
+    #[cfg(feature = "events-encryption")]
+    RoomEncrypted,
+    RoomMessage,
+    RoomRedaction,
+    Sticker,
+});
+
+impl_into_any_content!(AnyStateEventContent {
+    #[cfg(feature = "events-policy")]
+    PolicyRuleRoom,
+    #[cfg(feature = "events-policy")]
+    PolicyRuleServer,
+    #[cfg(feature = "events-policy")]
+    PolicyRuleUser,
+    RoomAliases,
+    RoomAvatar,
+    RoomCanonicalAlias,
+    RoomCreate,
+    RoomEncryption,
+    RoomGuestAccess,
+    RoomHistoryVisibility,
+    RoomJoinRules,
+    RoomMember,
+    RoomName,
+    RoomPinnedEvents,
+    RoomPowerLevels,
+    RoomServerAcl,
+    RoomThirdPartyInvite,
+    RoomTombstone,
+    RoomTopic,
+    SpaceChild,
+    SpaceParent,
+});
+
+/// Deserializes the content of an event of the given type into the content type `C`.
+///
+/// Fails if `C` is not the content type of `event_type` events, or if `value` is not valid content
+/// for them.
+pub fn from_json_for_type<C>(event_type: &EventType, value: Value) -> Result<C, JsonError>
+where C: EventContent {
+    let expected = C::event_type();
+
+    if *event_type != expected {
+        return Err(JsonError::custom(
+            format!("expected content of a {} event, found a {} event", expected, event_type)
+        ));
+    }
+
+    from_value(value)
+}
+
+/// Deserializes the content of an event of the given type into the matching content type.
+///
+/// Content of event types not covered by the Matrix specification is kept as a JSON value.
+pub fn deserialize_content(event_type: &EventType, value: Value)
+-> Result<AnyContent, JsonError> {
+    let content = match *event_type {
+        #[cfg(feature = "events-call")]
+        EventType::CallAnswer => AnyContent::CallAnswer(from_value(value)?),
+        #[cfg(feature = "events-call")]
+        EventType::CallCandidates => AnyContent::CallCandidates(from_value(value)?),
+        #[cfg(feature = "events-call")]
+        EventType::CallHangup => AnyContent::CallHangup(from_value(value)?),
+        #[cfg(feature = "events-call")]
+        EventType::CallInvite => AnyContent::CallInvite(from_value(value)?),
+        #[cfg(feature = "events-call")]
+        EventType::CallNegotiate => AnyContent::CallNegotiate(from_value(value)?),
+        #[cfg(feature = "events-call")]
+        EventType::CallReject => AnyContent::CallReject(from_value(value)?),
+        #[cfg(feature = "events-call")]
+        EventType::CallSelectAnswer => AnyContent::CallSelectAnswer(from_value(value)?),
+        EventType::Direct => AnyContent::Direct(from_value(value)?),
+        #[cfg(feature = "events-encryption")]
+        EventType::ForwardedRoomKey => AnyContent::ForwardedRoomKey(from_value(value)?),
+        EventType::FullyRead => AnyContent::FullyRead(from_value(value)?),
+        EventType::IgnoredUserList => AnyContent::IgnoredUserList(from_value(value)?),
+        #[cfg(feature = "events-encryption")]
+        EventType::KeyVerificationAccept => AnyContent::KeyVerificationAccept(from_value(value)?),
+        #[cfg(feature = "events-encryption")]
+        EventType::KeyVerificationCancel => AnyContent::KeyVerificationCancel(from_value(value)?),
+        #[cfg(feature = "events-encryption")]
+        EventType::KeyVerificationDone => AnyContent::KeyVerificationDone(from_value(value)?),
+        #[cfg(feature = "events-encryption")]
+        EventType::KeyVerificationKey => AnyContent::KeyVerificationKey(from_value(value)?),
+        #[cfg(feature = "events-encryption")]
+        EventType::KeyVerificationMac => AnyContent::KeyVerificationMac(from_value(value)?),
+        #[cfg(feature = "events-encryption")]
+        EventType::KeyVerificationRequest => AnyContent::KeyVerificationRequest(from_value(value)?),
+        #[cfg(feature = "events-encryption")]
+        EventType::KeyVerificationStart => AnyContent::KeyVerificationStart(from_value(value)?),
+        #[cfg(feature = "events-encryption")]
+        EventType::MegolmBackupV1 => AnyContent::MegolmBackupV1(from_value(value)?),
+        #[cfg(feature = "events-policy")]
+        EventType::PolicyRuleRoom => AnyContent::PolicyRuleRoom(from_value(value)?),
+        #[cfg(feature = "events-policy")]
+        EventType::PolicyRuleServer => AnyContent::PolicyRuleServer(from_value(value)?),
+        #[cfg(feature = "events-policy")]
+        EventType::PolicyRuleUser => AnyContent::PolicyRuleUser(from_value(value)?),
+        #[cfg(feature = "events-poll")]
+        EventType::PollEnd => AnyContent::PollEnd(from_value(value)?),
+        #[cfg(feature = "events-poll")]
+        EventType::PollResponse => AnyContent::PollResponse(from_value(value)?),
+        #[cfg(feature = "events-poll")]
+        EventType::PollStart => AnyContent::PollStart(from_value(value)?),
+        EventType::Presence => AnyContent::Presence(from_value(value)?),
+        EventType::PushRules => AnyContent::PushRules(from_value(value)?),
+        EventType::Reaction => AnyContent::Reaction(from_value(value)?),
+        EventType::Receipt => AnyContent::Receipt(from_value(value)?),
+        EventType::RoomAliases => AnyContent::RoomAliases(from_value(value)?),
+        EventType::RoomAvatar => AnyContent::RoomAvatar(from_value(value)?),
+        EventType::RoomCanonicalAlias => AnyContent::RoomCanonicalAlias(from_value(value)?),
+        EventType::RoomCreate => AnyContent::RoomCreate(from_value(value)?),
+        #[cfg(feature = "events-encryption")]
+        EventType::RoomEncrypted => AnyContent::RoomEncrypted(from_value(value)?),
+        EventType::RoomEncryption => AnyContent::RoomEncryption(from_value(value)?),
+        EventType::RoomGuestAccess => AnyContent::RoomGuestAccess(from_value(value)?),
+        EventType::RoomHistoryVisibility => {
+            AnyContent::RoomHistoryVisibility(from_value(value)?)
+        }
+        EventType::RoomJoinRules => AnyContent::RoomJoinRules(from_value(value)?),
+        #[cfg(feature = "events-encryption")]
+        EventType::RoomKey => AnyContent::RoomKey(from_value(value)?),
+        #[cfg(feature = "events-encryption")]
+        EventType::RoomKeyRequest => AnyContent::RoomKeyRequest(from_value(value)?),
+        EventType::RoomMember => AnyContent::RoomMember(from_value(value)?),
+        EventType::RoomMessage => AnyContent::RoomMessage(from_value(value)?),
+        EventType::RoomName => AnyContent::RoomName(from_value(value)?),
+        EventType::RoomPinnedEvents => AnyContent::RoomPinnedEvents(from_value(value)?),
+        EventType::RoomPowerLevels => AnyContent::RoomPowerLevels(from_value(value)?),
+        EventType::RoomRedaction => AnyContent::RoomRedaction(from_value(value)?),
+        EventType::RoomServerAcl => AnyContent::RoomServerAcl(from_value(value)?),
+        EventType::RoomThirdPartyInvite => AnyContent::RoomThirdPartyInvite(from_value(value)?),
+        EventType::RoomTombstone => AnyContent::RoomTombstone(from_value(value)?),
+        EventType::RoomTopic => AnyContent::RoomTopic(from_value(value)?),
+        #[cfg(feature = "events-encryption")]
+        EventType::SecretRequest => AnyContent::SecretRequest(from_value(value)?),
+        #[cfg(feature = "events-encryption")]
+        EventType::SecretSend => AnyContent::SecretSend(from_value(value)?),
+        EventType::SpaceChild => AnyContent::SpaceChild(from_value(value)?),
+        EventType::SpaceParent => AnyContent::SpaceParent(from_value(value)?),
+        EventType::Sticker => AnyContent::Sticker(from_value(value)?),
+        EventType::Tag => AnyContent::Tag(from_value(value)?),
+        EventType::Typing => AnyContent::Typing(from_value(value)?),
+        EventType::Custom(_) => AnyContent::Custom(event_type.clone(), value),
+    };
+
+    Ok(content)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{from_str, to_string, Value};
+
+    use EventType;
+    use room::name::NameEventContent;
+    use room::topic::TopicEventContent;
+    use super::{AnyContent, AnyStateEventContent, deserialize_content, from_json_for_type};
+
+    #[test]
+    fn content_for_event_type() {
+        let value = from_str::<Value>(r#"{"name":"Ruma"}"#).unwrap();
+
+        let content =
+            from_json_for_type::<NameEventContent>(&EventType::RoomName, value.clone()).unwrap();
+        assert_eq!(content.name, "Ruma");
+
+        let topic = from_json_for_type::<TopicEventContent>(&EventType::RoomName, value.clone());
+        assert!(topic.is_err());
+
+        match deserialize_content(&EventType::RoomName, value.clone()).unwrap() {
+            AnyContent::RoomName(content) => assert_eq!(content.name, "Ruma"),
+            _ => unreachable!(),
+        }
+
+        let custom = deserialize_content(&EventType::from("org.example.custom"), value).unwrap();
+        assert_eq!(custom.event_type(), EventType::Custom("org.example.custom".into()));
+        assert_eq!(to_string(&custom).unwrap(), r#"{"name":"Ruma"}"#);
+    }
+
+    #[test]
+    fn kind_specific_content() {
+        let content = AnyStateEventContent::RoomTopic(TopicEventContent {
+            topic: "Testing room".to_string(),
+            #[cfg(feature = "preserve-extra-fields")]
+            extra: Default::default(),
+        });
+
+        assert_eq!(content.event_type(), EventType::RoomTopic);
+        assert_eq!(to_string(&content).unwrap(), r#"{"topic":"Testing room"}"#);
+
+        match AnyContent::from(content) {
+            AnyContent::RoomTopic(content) => assert_eq!(content.topic, "Testing room"),
+            _ => unreachable!(),
+        }
+    }
+}
@@ -0,0 +1,293 @@
+//! A compact, serializable description of how one state event supersedes another.
+//!
+//! An `EventDelta` records only the fields of the content that differ between two state events
+//! with the same type and state key, so that storage and replication layers can keep a change log
+//! of room state without storing every full event.
+
+use std::error::Error;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+use ruma_identifiers::EventId;
+use serde::{Deserialize, Deserializer};
+use serde_json::{Error as JsonError, Map, Value, from_value, to_value};
+
+use EventType;
+use collections::all::StateEvent;
+
+/// The difference between an old and a new state event for the same piece of room state.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct EventDelta {
+    /// The changed fields of the content, in order of their paths.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub changes: Vec<FieldChange>,
+
+    /// The ID of the new event.
+    pub event_id: EventId,
+
+    /// The type of both events.
+    #[serde(rename = "type")]
+    pub event_type: EventType,
+
+    /// The ID of the event that was superseded.
+    pub replaces: EventId,
+
+    /// The state key of both events.
+    pub state_key: String,
+}
+
+/// A single changed field of the content.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct FieldChange {
+    /// The object keys leading from the content to the field.
+    pub path: Vec<String>,
+
+    /// The value of the field in the old event, or `None` if the field was added.
+    ///
+    /// A field that was `null` is `Some(Value::Null)` and is serialized as `null`, while an added
+    /// field is left out of the JSON.
+    #[serde(
+        default,
+        deserialize_with = "deserialize_present",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub old: Option<Value>,
+
+    /// The value of the field in the new event, or `None` if the field was removed.
+    ///
+    /// As with `old`, a `null` value is distinct from a removed field.
+    #[serde(
+        default,
+        deserialize_with = "deserialize_present",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub new: Option<Value>,
+}
+
+/// An error when computing or applying an `EventDelta`.
+#[derive(Debug)]
+pub enum DeltaError {
+    /// An event could not be converted to or from JSON.
+    Json(JsonError),
+    /// The events are not for the same piece of room state.
+    StateMismatch,
+    /// The content does not have the old values recorded in the delta.
+    StaleContent(Vec<String>),
+}
+
+impl EventDelta {
+    /// Computes the delta by which `new` supersedes `old`.
+    ///
+    /// Objects are compared key by key; any other value that differs is recorded as a whole.
+    pub fn between(old: &StateEvent, new: &StateEvent) -> Result<Self, DeltaError> {
+        if old.event_type() != new.event_type() || old.state_key() != new.state_key() {
+            return Err(DeltaError::StateMismatch);
+        }
+
+        let (replaces, old_content) = split_event(old)?;
+        let (event_id, new_content) = split_event(new)?;
+
+        let mut changes = Vec::new();
+        diff(&mut Vec::new(), &old_content, &new_content, &mut changes);
+
+        Ok(EventDelta {
+            changes,
+            event_id,
+            event_type: new.event_type().clone(),
+            replaces,
+            state_key: new.state_key().to_string(),
+        })
+    }
+
+    /// Whether the content of the new event is identical to that of the old one.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Applies the delta to the content of the superseded event, turning it into the content of
+    /// the new event.
+    ///
+    /// Fails without modifying `content` if any changed field does not hold the recorded old
+    /// value.
+    pub fn apply(&self, content: &mut Value) -> Result<(), DeltaError> {
+        if let Some(change) = self.changes.iter().find(|change| {
+            lookup(content, &change.path) != change.old.as_ref()
+        }) {
+            return Err(DeltaError::StaleContent(change.path.clone()));
+        }
+
+        for change in &self.changes {
+            set(content, &change.path, change.new.clone());
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for DeltaError {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match *self {
+            DeltaError::Json(ref error) => write!(f, "invalid event: {}", error),
+            DeltaError::StateMismatch => write!(f, "events are for different room state"),
+            DeltaError::StaleContent(ref path) => {
+                write!(f, "content does not match delta at `{}`", path.join("."))
+            }
+        }
+    }
+}
+
+impl Error for DeltaError {}
+
+/// Deserializes a field that is present, including one that is `null`, as `Some`.
+///
+/// Together with `#[serde(default)]`, this keeps an absent field apart from a `null` one.
+fn deserialize_present<'de, D>(deserializer: D) -> Result<Option<Value>, D::Error>
+where D: Deserializer<'de> {
+    Value::deserialize(deserializer).map(Some)
+}
+
+/// Serializes a state event and takes its ID and content apart.
+fn split_event(event: &StateEvent) -> Result<(EventId, Value), DeltaError> {
+    let mut value = to_value(event).map_err(DeltaError::Json)?;
+    let event_id = value.get_mut("event_id").map(Value::take).unwrap_or(Value::Null);
+    let content = value.get_mut("content").map(Value::take).unwrap_or(Value::Null);
+
+    Ok((from_value(event_id).map_err(DeltaError::Json)?, content))
+}
+
+/// Records the changes between `old` and `new` at `path`.
+fn diff(path: &mut Vec<String>, old: &Value, new: &Value, changes: &mut Vec<FieldChange>) {
+    match (old, new) {
+        (Value::Object(old), Value::Object(new)) => {
+            let mut keys: Vec<&String> = old.keys().chain(new.keys()).collect();
+            keys.sort();
+            keys.dedup();
+
+            for key in keys {
+                path.push(key.clone());
+
+                match (old.get(key), new.get(key)) {
+                    (Some(old), Some(new)) => diff(path, old, new, changes),
+                    (old, new) => changes.push(FieldChange {
+                        path: path.clone(),
+                        old: old.cloned(),
+                        new: new.cloned(),
+                    }),
+                }
+
+                path.pop();
+            }
+        }
+        _ if old != new => changes.push(FieldChange {
+            path: path.clone(),
+            old: Some(old.clone()),
+            new: Some(new.clone()),
+        }),
+        _ => {}
+    }
+}
+
+/// The value at `path`, if there is one.
+fn lookup<'a>(value: &'a Value, path: &[String]) -> Option<&'a Value> {
+    path.iter().try_fold(value, |value, key| value.get(key))
+}
+
+/// Sets or, for `None`, removes the value at `path`, creating objects along the way.
+fn set(value: &mut Value, path: &[String], new: Option<Value>) {
+    let (last, parents) = match path.split_last() {
+        Some(split) => split,
+        None => {
+            *value = new.unwrap_or(Value::Null);
+            return;
+        }
+    };
+
+    let mut target = value;
+    for key in parents {
+        if !target.is_object() {
+            *target = Value::Object(Map::new());
+        }
+        target = target.as_object_mut()
+            .expect("target was just made an object")
+            .entry(key.clone())
+            .or_insert(Value::Null);
+    }
+
+    if !target.is_object() {
+        *target = Value::Object(Map::new());
+    }
+    let object = target.as_object_mut().expect("target was just made an object");
+    match new {
+        Some(new) => {
+            object.insert(last.clone(), new);
+        }
+        None => {
+            object.remove(last);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{Value, from_str, to_string};
+
+    use collections::all::StateEvent;
+    use super::{DeltaError, EventDelta};
+
+    fn power_levels(event_id: &str, ban: u64, users: &str) -> StateEvent {
+        from_str(&format!(
+            r#"{{"content":{{"ban":{},"events":{{}},"events_default":0,"invite":0,"kick":50,
+            "redact":50,"state_default":50,"users":{},"users_default":0}},
+            "event_id":"{}","origin_server_ts":1,"room_id":"!n8f893n9:example.com",
+            "sender":"@carl:example.com","state_key":"","type":"m.room.power_levels"}}"#,
+            ban, users, event_id
+        )).unwrap()
+    }
+
+    #[test]
+    fn diff_and_apply() {
+        let old = power_levels("$old:example.com", 50, r#"{"@carl:example.com":100}"#);
+        let new = power_levels("$new:example.com", 75, r#"{"@dana:example.com":50}"#);
+
+        let delta = EventDelta::between(&old, &new).unwrap();
+        let paths: Vec<String> = delta.changes.iter().map(|c| c.path.join(".")).collect();
+        assert_eq!(paths, vec!["ban", "users.@carl:example.com", "users.@dana:example.com"]);
+
+        let delta: EventDelta = from_str(&to_string(&delta).unwrap()).unwrap();
+
+        let mut content: Value = from_str(&to_string(&old).unwrap()).unwrap();
+        let mut content = content["content"].take();
+        delta.apply(&mut content).unwrap();
+
+        let new_json: Value = from_str(&to_string(&new).unwrap()).unwrap();
+        assert_eq!(content, new_json["content"]);
+
+        match delta.apply(&mut content) {
+            Err(DeltaError::StaleContent(ref path)) => assert_eq!(path.join("."), "ban"),
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn null_values_round_trip() {
+        let custom = |event_id: &str, content: &str| from_str::<StateEvent>(&format!(
+            r#"{{"content":{},"event_id":"{}","origin_server_ts":1,
+            "room_id":"!n8f893n9:example.com","sender":"@carl:example.com","state_key":"",
+            "type":"org.example.custom"}}"#,
+            content, event_id
+        )).unwrap();
+        let old = custom("$old:example.com", r#"{"a":null}"#);
+        let new = custom("$new:example.com", r#"{"b":null}"#);
+
+        let delta = EventDelta::between(&old, &new).unwrap();
+        assert_eq!(delta.changes[0].old, Some(Value::Null));
+        assert_eq!(delta.changes[0].new, None);
+        assert_eq!(delta.changes[1].old, None);
+        assert_eq!(delta.changes[1].new, Some(Value::Null));
+
+        let json = to_string(&delta).unwrap();
+        assert!(json.starts_with(
+            r#"{"changes":[{"path":["a"],"old":null},{"path":["b"],"new":null}],"#
+        ));
+        assert_eq!(from_str::<EventDelta>(&json).unwrap(), delta);
+    }
+}
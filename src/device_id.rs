@@ -0,0 +1,140 @@
+//! The IDs of the devices of a user.
+
+use std::borrow::Borrow;
+use std::convert::TryFrom;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::str::FromStr;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde::de::Error as SerdeError;
+
+use ParseError;
+
+/// The ID of a device of a user.
+///
+/// Device IDs are opaque strings chosen by the homeserver or the client. Unlike the other
+/// identifiers they have no sigil or server name, but they must be between 1 and 255 bytes long
+/// and may not contain whitespace or control characters.
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct DeviceId(String);
+
+/// The maximum length of a device ID in bytes.
+const MAX_LENGTH: usize = 255;
+
+impl DeviceId {
+    /// The device ID as a string.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+/// Whether `id` is a valid device ID.
+fn is_valid(id: &str) -> bool {
+    !id.is_empty() && id.len() <= MAX_LENGTH &&
+        !id.chars().any(|c| c.is_whitespace() || c.is_control())
+}
+
+impl FromStr for DeviceId {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if is_valid(s) {
+            Ok(DeviceId(s.to_string()))
+        } else {
+            Err(ParseError)
+        }
+    }
+}
+
+impl<'a> TryFrom<&'a str> for DeviceId {
+    type Error = ParseError;
+
+    fn try_from(s: &'a str) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl TryFrom<String> for DeviceId {
+    type Error = ParseError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        if is_valid(&s) {
+            Ok(DeviceId(s))
+        } else {
+            Err(ParseError)
+        }
+    }
+}
+
+impl AsRef<str> for DeviceId {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Borrow<str> for DeviceId {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Display for DeviceId {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl PartialEq<str> for DeviceId {
+    fn eq(&self, other: &str) -> bool {
+        self.0 == other
+    }
+}
+
+impl<'a> PartialEq<&'a str> for DeviceId {
+    fn eq(&self, other: &&'a str) -> bool {
+        self.0 == *other
+    }
+}
+
+impl Serialize for DeviceId {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for DeviceId {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where D: Deserializer<'de> {
+        let id = String::deserialize(deserializer)?;
+
+        if is_valid(&id) {
+            Ok(DeviceId(id))
+        } else {
+            Err(D::Error::custom(format!("invalid device ID {:?}", id)))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{from_str, to_string};
+
+    use super::DeviceId;
+
+    #[test]
+    fn device_id_round_trip() {
+        let id = from_str::<DeviceId>(r#""RJYKSTBOIE""#).unwrap();
+
+        assert_eq!(id, "RJYKSTBOIE");
+        assert_eq!(id.to_string(), "RJYKSTBOIE");
+        assert_eq!(to_string(&id).unwrap(), r#""RJYKSTBOIE""#);
+    }
+
+    #[test]
+    fn invalid_device_ids() {
+        assert!(from_str::<DeviceId>(r#""""#).is_err());
+        assert!(from_str::<DeviceId>(r#""MY DEVICE""#).is_err());
+        assert!(from_str::<DeviceId>(r#""DEVICE\n""#).is_err());
+        assert!("A".repeat(256).parse::<DeviceId>().is_err());
+        assert!("A".repeat(255).parse::<DeviceId>().is_ok());
+    }
+}
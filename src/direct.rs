@@ -0,0 +1,143 @@
+//! Types for the *m.direct* event.
+
+use std::collections::HashMap;
+
+use ruma_identifiers::{RoomId, UserId};
+
+event! {
+    /// Informs the client about the rooms that are considered direct by a user.
+    ///
+    /// This event appears in the user's global account data.
+    pub struct DirectEvent(DirectEventContent) {}
+}
+
+/// The payload of a `DirectEvent`.
+///
+/// A mapping of user ID to a list of room IDs, which are the direct chat rooms with that user.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct DirectEventContent(pub HashMap<UserId, Vec<RoomId>>);
+
+impl_event_content!(DirectEventContent, Direct);
+
+/// The differences between two versions of a user's direct chat rooms.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DirectChanges {
+    /// Pairs of user and room that are only present in the newer version, ordered by user ID and
+    /// then room ID.
+    pub added: Vec<(UserId, RoomId)>,
+    /// Pairs of user and room that are only present in the older version, ordered by user ID and
+    /// then room ID.
+    pub removed: Vec<(UserId, RoomId)>,
+}
+
+impl DirectEventContent {
+    /// The direct chat rooms with `user`.
+    pub fn rooms_with(&self, user: &UserId) -> &[RoomId] {
+        self.0.get(user).map(|rooms| &rooms[..]).unwrap_or(&[])
+    }
+
+    /// The first direct chat room with `user`, if any.
+    pub fn room_with(&self, user: &UserId) -> Option<&RoomId> {
+        self.rooms_with(user).first()
+    }
+
+    /// Inverts the mapping into a mapping of room ID to the users the room is a direct chat with.
+    pub fn users_by_room(&self) -> HashMap<RoomId, Vec<UserId>> {
+        let mut users_by_room: HashMap<RoomId, Vec<UserId>> = HashMap::new();
+
+        for (user, rooms) in &self.0 {
+            for room in rooms {
+                users_by_room.entry(room.clone()).or_default().push(user.clone());
+            }
+        }
+
+        users_by_room
+    }
+
+    /// Computes which direct chat rooms were added or removed since `previous`.
+    pub fn changes_since(&self, previous: &DirectEventContent) -> DirectChanges {
+        DirectChanges {
+            added: self.rooms_missing_from(previous),
+            removed: previous.rooms_missing_from(self),
+        }
+    }
+
+    /// The pairs of user and room in `self` that are not in `other`, sorted.
+    fn rooms_missing_from(&self, other: &DirectEventContent) -> Vec<(UserId, RoomId)> {
+        let mut missing = Vec::new();
+
+        for (user, rooms) in &self.0 {
+            let other_rooms = other.rooms_with(user);
+
+            for room in rooms {
+                if !other_rooms.contains(room) {
+                    missing.push((user.clone(), room.clone()));
+                }
+            }
+        }
+
+        missing.sort_by_key(|(user, room)| (user.to_string(), room.to_string()));
+
+        missing
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use ruma_identifiers::{RoomId, UserId};
+    use serde_json::{from_str, to_string};
+
+    use super::{DirectChanges, DirectEventContent};
+
+    #[test]
+    fn direct_rooms_lookup() {
+        let content = from_str::<DirectEventContent>(
+            r#"{"@alice:example.com":["!a:example.com","!b:example.com"]}"#
+        ).unwrap();
+
+        let alice = UserId::try_from("@alice:example.com").unwrap();
+        let bob = UserId::try_from("@bob:example.com").unwrap();
+        let room = RoomId::try_from("!b:example.com").unwrap();
+
+        assert_eq!(content.rooms_with(&alice).len(), 2);
+        assert_eq!(content.room_with(&alice).unwrap().to_string(), "!a:example.com");
+        assert!(content.room_with(&bob).is_none());
+        assert_eq!(content.users_by_room()[&room], vec![alice]);
+        assert_eq!(
+            to_string(&content).unwrap(),
+            r#"{"@alice:example.com":["!a:example.com","!b:example.com"]}"#
+        );
+    }
+    #[test]
+    fn changes_since() {
+        let previous = from_str::<DirectEventContent>(r#"{
+            "@alice:example.com": ["!a:example.com", "!b:example.com"],
+            "@bob:example.com": ["!c:example.com"]
+        }"#).unwrap();
+        let current = from_str::<DirectEventContent>(r#"{
+            "@alice:example.com": ["!b:example.com", "!d:example.com"],
+            "@carol:example.com": ["!f:example.com", "!e:example.com"]
+        }"#).unwrap();
+
+        let user = |user_id: &str| UserId::try_from(user_id).unwrap();
+        let room = |room_id: &str| RoomId::try_from(room_id).unwrap();
+
+        assert_eq!(
+            current.changes_since(&previous),
+            DirectChanges {
+                added: vec![
+                    (user("@alice:example.com"), room("!d:example.com")),
+                    (user("@carol:example.com"), room("!e:example.com")),
+                    (user("@carol:example.com"), room("!f:example.com")),
+                ],
+                removed: vec![
+                    (user("@alice:example.com"), room("!a:example.com")),
+                    (user("@bob:example.com"), room("!c:example.com")),
+                ],
+            }
+        );
+        assert_eq!(current.changes_since(&current), DirectChanges::default());
+    }
+}
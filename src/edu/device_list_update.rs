@@ -0,0 +1,119 @@
+//! Types for the *m.device_list_update* EDU.
+
+use std::collections::HashMap;
+
+use ruma_identifiers::UserId;
+
+use DeviceId;
+
+/// The payload of an *m.device_list_update* EDU.
+///
+/// Sent by a server when one of its users adds, updates, or deletes a device, so that other
+/// servers can update their caches of the user's device list.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct DeviceListUpdateEduContent {
+    /// Whether the device has been deleted.
+    #[serde(default)]
+    pub deleted: bool,
+
+    /// The public human-readable name of the device.
+    #[serde(skip_serializing_if="Option::is_none")]
+    pub device_display_name: Option<String>,
+
+    /// The ID of the device whose details are changing.
+    pub device_id: DeviceId,
+
+    /// The updated identity keys for the device, if they changed.
+    #[serde(skip_serializing_if="Option::is_none")]
+    pub keys: Option<DeviceKeys>,
+
+    /// The `stream_id`s of any prior updates the receiving server should have seen.
+    #[serde(default, skip_serializing_if="Vec::is_empty")]
+    pub prev_id: Vec<u64>,
+
+    /// An ID sent by the sending server for this update, unique for a given user.
+    pub stream_id: u64,
+
+    /// The user who owns the device.
+    pub user_id: UserId,
+}
+
+/// The identity keys of a device.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct DeviceKeys {
+    /// The encryption algorithms supported by the device.
+    pub algorithms: Vec<String>,
+
+    /// The ID of the device these keys belong to.
+    pub device_id: DeviceId,
+
+    /// A map from `<algorithm>:<device_id>` to the public key, encoded as unpadded base64.
+    pub keys: HashMap<String, String>,
+
+    /// Signatures of the device keys, as a map from user ID to a map from `<algorithm>:<key_id>`
+    /// to the signature.
+    pub signatures: HashMap<UserId, HashMap<String, String>>,
+
+    /// The user the device belongs to.
+    pub user_id: UserId,
+}
+
+/// Information on end-to-end device updates, as included in the `device_lists` section of a sync
+/// response.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct DeviceLists {
+    /// Users who have updated their device identity keys or who now share an encrypted room with
+    /// the client since the previous sync.
+    #[serde(default, skip_serializing_if="Vec::is_empty")]
+    pub changed: Vec<UserId>,
+
+    /// Users who no longer share an encrypted room with the client since the previous sync.
+    #[serde(default, skip_serializing_if="Vec::is_empty")]
+    pub left: Vec<UserId>,
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{from_str, to_string};
+
+    use edu::{AnyEdu, Edu};
+    use super::DeviceListUpdateEduContent;
+
+    #[test]
+    fn device_list_update_round_trip() {
+        let json = concat!(
+            r#"{"content":{"deleted":false,"device_display_name":"Mobile","#,
+            r#""device_id":"QBUAZIFURK","keys":{"algorithms":["m.megolm.v1.aes-sha2"],"#,
+            r#""device_id":"QBUAZIFURK","#,
+            r#""keys":{"ed25519:QBUAZIFURK":"2MX1WOCAmE9eyywGdiMsQ4RxL2SIK"},"#,
+            r#""signatures":{"@john:example.com":{"ed25519:QBUAZIFURK":"dSO80A01XiigH3uBiDVx"}},"#,
+            r#""user_id":"@john:example.com"},"prev_id":[5],"stream_id":6,"#,
+            r#""user_id":"@john:example.com"},"edu_type":"m.device_list_update"}"#
+        );
+
+        let edu = from_str::<Edu<DeviceListUpdateEduContent>>(json).unwrap();
+
+        assert_eq!(edu.content.device_id, "QBUAZIFURK");
+        assert_eq!(edu.content.prev_id, vec![5]);
+        assert_eq!(to_string(&edu).unwrap(), json);
+
+        match from_str::<AnyEdu>(json).unwrap() {
+            AnyEdu::DeviceListUpdate(content) => assert_eq!(content, edu.content),
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn deleted_device() {
+        let content = from_str::<DeviceListUpdateEduContent>(r#"{
+            "deleted": true,
+            "device_id": "QBUAZIFURK",
+            "stream_id": 7,
+            "user_id": "@john:example.com"
+        }"#).unwrap();
+
+        assert!(content.deleted);
+        assert!(content.keys.is_none());
+        assert!(content.prev_id.is_empty());
+    }
+}
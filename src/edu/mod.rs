@@ -0,0 +1,131 @@
+//! Types for the ephemeral data units (EDUs) servers exchange over federation.
+//!
+//! Several EDUs correspond to ephemeral events clients receive, but have a different shape on the
+//! wire. The types in this module convert to and from their client-facing counterparts, so servers
+//! can share code between both formats.
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde::de::Error;
+use serde_json::{Value, from_value};
+
+use EventType;
+
+use self::device_list_update::DeviceListUpdateEduContent;
+use self::presence::PresenceEduContent;
+use self::receipt::ReceiptEduContent;
+use self::signing_key_update::SigningKeyUpdateEduContent;
+use self::typing::TypingEduContent;
+
+pub mod device_list_update;
+pub mod presence;
+pub mod receipt;
+pub mod signing_key_update;
+pub mod typing;
+
+/// An ephemeral data unit as it appears in a federation transaction.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Edu<C> {
+    /// The EDU-type-specific payload.
+    pub content: C,
+    /// The type of the EDU.
+    pub edu_type: EventType,
+}
+
+/// Any EDU, dispatched on its `edu_type`.
+#[derive(Clone, Debug)]
+pub enum AnyEdu {
+    /// m.device_list_update
+    DeviceListUpdate(DeviceListUpdateEduContent),
+    /// m.presence
+    Presence(PresenceEduContent),
+    /// m.receipt
+    Receipt(ReceiptEduContent),
+    /// m.signing_key_update
+    SigningKeyUpdate(SigningKeyUpdateEduContent),
+    /// m.typing
+    Typing(TypingEduContent),
+    /// An EDU of a type that is not known to this library.
+    Custom(Edu<Value>),
+}
+
+impl AnyEdu {
+    /// The type of the EDU.
+    pub fn edu_type(&self) -> EventType {
+        match *self {
+            AnyEdu::DeviceListUpdate(_) => EventType::from("m.device_list_update"),
+            AnyEdu::Presence(_) => EventType::Presence,
+            AnyEdu::Receipt(_) => EventType::Receipt,
+            AnyEdu::SigningKeyUpdate(_) => EventType::from("m.signing_key_update"),
+            AnyEdu::Typing(_) => EventType::Typing,
+            AnyEdu::Custom(ref edu) => edu.edu_type.clone(),
+        }
+    }
+}
+
+impl Serialize for AnyEdu {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
+        let edu_type = self.edu_type();
+
+        match *self {
+            AnyEdu::DeviceListUpdate(ref content) => {
+                Edu { content, edu_type }.serialize(serializer)
+            }
+            AnyEdu::Presence(ref content) => Edu { content, edu_type }.serialize(serializer),
+            AnyEdu::Receipt(ref content) => Edu { content, edu_type }.serialize(serializer),
+            AnyEdu::SigningKeyUpdate(ref content) => {
+                Edu { content, edu_type }.serialize(serializer)
+            }
+            AnyEdu::Typing(ref content) => Edu { content, edu_type }.serialize(serializer),
+            AnyEdu::Custom(ref edu) => edu.serialize(serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for AnyEdu {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where D: Deserializer<'de> {
+        let edu = Edu::<Value>::deserialize(deserializer)?;
+
+        let any_edu = match edu.edu_type.as_str() {
+            "m.device_list_update" => from_value(edu.content).map(AnyEdu::DeviceListUpdate),
+            "m.presence" => from_value(edu.content).map(AnyEdu::Presence),
+            "m.receipt" => from_value(edu.content).map(AnyEdu::Receipt),
+            "m.signing_key_update" => from_value(edu.content).map(AnyEdu::SigningKeyUpdate),
+            "m.typing" => from_value(edu.content).map(AnyEdu::Typing),
+            _ => Ok(AnyEdu::Custom(edu)),
+        };
+
+        any_edu.map_err(|error| D::Error::custom(error.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{from_str, to_string};
+
+    use super::AnyEdu;
+
+    #[test]
+    fn edus_are_dispatched_on_their_type() {
+        let typing = concat!(
+            r#"{"content":{"room_id":"!jEsUZKDJdhlrceRyVU:example.org","typing":true,"#,
+            r#""user_id":"@john:example.org"},"edu_type":"m.typing"}"#
+        );
+        let custom = r#"{"content":{"a":1},"edu_type":"org.example.edu"}"#;
+
+        match from_str::<AnyEdu>(typing).unwrap() {
+            AnyEdu::Typing(ref content) => assert!(content.typing),
+            _ => unreachable!(),
+        }
+
+        match from_str::<AnyEdu>(custom).unwrap() {
+            AnyEdu::Custom(ref edu) => assert_eq!(edu.edu_type.as_str(), "org.example.edu"),
+            _ => unreachable!(),
+        }
+
+        for json in &[typing, custom] {
+            assert_eq!(to_string(&from_str::<AnyEdu>(json).unwrap()).unwrap(), *json);
+        }
+
+        assert!(from_str::<AnyEdu>(r#"{"content":{},"edu_type":"m.typing"}"#).is_err());
+    }
+}
@@ -0,0 +1,106 @@
+//! Types for the *m.presence* EDU.
+
+use ruma_identifiers::UserId;
+
+use int::UInt;
+use presence::{PresenceEvent, PresenceEventContent, PresenceState};
+
+/// The payload of an *m.presence* EDU.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct PresenceEduContent {
+    /// A list of presence updates that the receiving server is likely to be interested in.
+    pub push: Vec<PresenceUpdate>,
+}
+
+/// An update to the presence state of a user, as sent over federation.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct PresenceUpdate {
+    /// Whether or not the user is currently active.
+    #[serde(default)]
+    pub currently_active: bool,
+
+    /// The number of milliseconds that have elapsed since the user last did something.
+    pub last_active_ago: UInt,
+
+    /// The presence state of the user.
+    pub presence: PresenceState,
+
+    /// An optional description to accompany the presence.
+    #[serde(skip_serializing_if="Option::is_none")]
+    pub status_msg: Option<String>,
+
+    /// The user ID this presence update is for.
+    pub user_id: UserId,
+}
+
+impl From<PresenceEventContent> for PresenceUpdate {
+    /// Converts client-facing presence content into an update for federation.
+    ///
+    /// The avatar URL and display name are dropped, as they are not part of the EDU. A missing
+    /// `last_active_ago` is sent as `0`.
+    fn from(content: PresenceEventContent) -> Self {
+        PresenceUpdate {
+            currently_active: content.currently_active,
+            last_active_ago: content.last_active_ago.unwrap_or_default(),
+            presence: content.presence,
+            status_msg: None,
+            user_id: content.user_id,
+        }
+    }
+}
+
+impl From<PresenceEvent> for PresenceUpdate {
+    fn from(event: PresenceEvent) -> Self {
+        event.content.into()
+    }
+}
+
+impl From<PresenceUpdate> for PresenceEventContent {
+    /// Converts an update received over federation into client-facing presence content.
+    ///
+    /// The status message is dropped, and the avatar URL and display name are left empty for the
+    /// server to fill in from the user's profile.
+    fn from(update: PresenceUpdate) -> Self {
+        PresenceEventContent {
+            avatar_url: None,
+            currently_active: update.currently_active,
+            displayname: None,
+            last_active_ago: Some(update.last_active_ago),
+            presence: update.presence,
+            user_id: update.user_id,
+            #[cfg(feature = "preserve-extra-fields")]
+            extra: Default::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::from_str;
+
+    use edu::Edu;
+    use int::UInt;
+    use presence::{PresenceEventContent, PresenceState};
+    use super::PresenceEduContent;
+
+    #[test]
+    fn presence_edu_converts_to_client_content() {
+        let edu = from_str::<Edu<PresenceEduContent>>(r#"{
+            "content": {
+                "push": [{
+                    "currently_active": true,
+                    "last_active_ago": 5000,
+                    "presence": "online",
+                    "user_id": "@john:matrix.org"
+                }]
+            },
+            "edu_type": "m.presence"
+        }"#).unwrap();
+
+        let content: PresenceEventContent = edu.content.push[0].clone().into();
+
+        assert_eq!(content.presence, PresenceState::Online);
+        assert_eq!(content.last_active_ago, Some(UInt::from(5000u32)));
+        assert!(content.currently_active);
+    }
+}
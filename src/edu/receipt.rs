@@ -0,0 +1,117 @@
+//! Types for the *m.receipt* EDU.
+
+use std::collections::HashMap;
+
+use ruma_identifiers::{EventId, RoomId, UserId};
+
+use EventType;
+use receipt::{Receipt, ReceiptEvent, ReceiptEventContent, Receipts};
+
+/// The payload of an *m.receipt* EDU.
+///
+/// A mapping of room ID to the receipts sent in that room.
+pub type ReceiptEduContent = HashMap<RoomId, RoomReceipts>;
+
+/// The receipts sent in a single room.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct RoomReceipts {
+    /// A mapping of user ID to the read receipt the user sent.
+    #[serde(default, rename="m.read")]
+    pub read: HashMap<UserId, ReadReceipt>,
+}
+
+/// A read receipt of a user, as sent over federation.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ReadReceipt {
+    /// Metadata about the receipt.
+    pub data: ReceiptData,
+    /// The events the user has read up to.
+    pub event_ids: Vec<EventId>,
+}
+
+/// Metadata about a read receipt.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ReceiptData {
+    /// The timestamp the receipt was sent at.
+    pub ts: u64,
+}
+
+impl RoomReceipts {
+    /// Converts the receipts of this room into the client-facing receipt content, which is keyed
+    /// by event ID instead of user ID.
+    pub fn to_client_content(&self) -> ReceiptEventContent {
+        let mut content = ReceiptEventContent::new();
+
+        for (user_id, receipt) in &self.read {
+            for event_id in &receipt.event_ids {
+                content.entry(event_id.clone())
+                    .or_insert_with(|| Receipts { m_read: HashMap::new() })
+                    .m_read
+                    .insert(user_id.clone(), Receipt { ts: receipt.data.ts });
+            }
+        }
+
+        content
+    }
+}
+
+impl<'a> From<&'a ReceiptEventContent> for RoomReceipts {
+    /// Converts client-facing receipt content into the receipts of a room for federation.
+    fn from(content: &'a ReceiptEventContent) -> Self {
+        let mut receipts = RoomReceipts::default();
+
+        for (event_id, event_receipts) in content {
+            for (user_id, receipt) in &event_receipts.m_read {
+                receipts.read.entry(user_id.clone())
+                    .or_insert_with(|| ReadReceipt {
+                        data: ReceiptData { ts: receipt.ts },
+                        event_ids: Vec::new(),
+                    })
+                    .event_ids
+                    .push(event_id.clone());
+            }
+        }
+
+        receipts
+    }
+}
+
+/// Converts the payload of an *m.receipt* EDU into one client-facing receipt event per room.
+pub fn receipt_events(content: &ReceiptEduContent) -> Vec<ReceiptEvent> {
+    content.iter().map(|(room_id, receipts)| {
+        ReceiptEvent {
+            content: receipts.to_client_content(),
+            event_type: EventType::Receipt,
+            room_id: room_id.clone(),
+        }
+    }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::from_str;
+
+    use super::{ReceiptEduContent, receipt_events};
+
+    #[test]
+    fn receipt_edu_converts_to_client_events() {
+        let content = from_str::<ReceiptEduContent>(r#"{
+            "!n8f893n9:example.com": {
+                "m.read": {
+                    "@john:matrix.org": {
+                        "data": {"ts": 1533358089009},
+                        "event_ids": ["$h29iv0s8:example.com"]
+                    }
+                }
+            }
+        }"#).unwrap();
+
+        let events = receipt_events(&content);
+
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].content.len(), 1);
+
+        let receipts = events[0].content.values().next().unwrap();
+        assert_eq!(receipts.m_read.values().next().unwrap().ts, 1533358089009);
+    }
+}
@@ -0,0 +1,69 @@
+//! Types for the *m.signing_key_update* EDU.
+
+use std::collections::HashMap;
+
+use ruma_identifiers::UserId;
+
+/// The payload of an *m.signing_key_update* EDU.
+///
+/// Sent by a server when one of its users updates their cross-signing keys.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct SigningKeyUpdateEduContent {
+    /// The user's updated master key, if it changed.
+    #[serde(skip_serializing_if="Option::is_none")]
+    pub master_key: Option<CrossSigningKey>,
+
+    /// The user's updated self-signing key, if it changed.
+    #[serde(skip_serializing_if="Option::is_none")]
+    pub self_signing_key: Option<CrossSigningKey>,
+
+    /// The user whose cross-signing keys have changed.
+    pub user_id: UserId,
+}
+
+/// A cross-signing key.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct CrossSigningKey {
+    /// A map from `<algorithm>:<unpadded base64 public key>` to the public key, encoded as
+    /// unpadded base64.
+    pub keys: HashMap<String, String>,
+
+    /// Signatures of the key, as a map from user ID to a map from `<algorithm>:<key_id>` to the
+    /// signature.
+    #[serde(default, skip_serializing_if="HashMap::is_empty")]
+    pub signatures: HashMap<UserId, HashMap<String, String>>,
+
+    /// What the key is used for, e.g. "master" or "self_signing".
+    pub usage: Vec<String>,
+
+    /// The user the key belongs to.
+    pub user_id: UserId,
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{from_str, to_string};
+
+    use edu::{AnyEdu, Edu};
+    use super::SigningKeyUpdateEduContent;
+
+    #[test]
+    fn signing_key_update_round_trip() {
+        let json = concat!(
+            r#"{"content":{"master_key":{"keys":{"ed25519:base64+master+public+key":"#,
+            r#""base64+master+public+key"},"usage":["master"],"user_id":"@alice:example.com"},"#,
+            r#""user_id":"@alice:example.com"},"edu_type":"m.signing_key_update"}"#
+        );
+
+        let edu = from_str::<Edu<SigningKeyUpdateEduContent>>(json).unwrap();
+
+        assert_eq!(edu.content.master_key.as_ref().unwrap().usage, vec!["master"]);
+        assert!(edu.content.self_signing_key.is_none());
+        assert_eq!(to_string(&edu).unwrap(), json);
+
+        match from_str::<AnyEdu>(json).unwrap() {
+            AnyEdu::SigningKeyUpdate(content) => assert_eq!(content, edu.content),
+            _ => unreachable!(),
+        }
+    }
+}
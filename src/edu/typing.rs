@@ -0,0 +1,31 @@
+//! Types for the *m.typing* EDU.
+
+use ruma_identifiers::{RoomId, UserId};
+
+use typing::TypingEventContent;
+
+/// The payload of an *m.typing* EDU.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct TypingEduContent {
+    /// The room where the user's typing status has been updated.
+    pub room_id: RoomId,
+    /// Whether the user is typing in the room or not.
+    pub typing: bool,
+    /// The user whose typing status has been updated.
+    pub user_id: UserId,
+}
+
+impl TypingEduContent {
+    /// Applies this update to the client-facing list of users typing in the same room.
+    pub fn apply_to(&self, content: &mut TypingEventContent) {
+        let position = content.user_ids.iter().position(|user_id| *user_id == self.user_id);
+
+        match (self.typing, position) {
+            (true, None) => content.user_ids.push(self.user_id.clone()),
+            (false, Some(position)) => {
+                content.user_ids.remove(position);
+            }
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,218 @@
+//! Conversion between *m.room.message* content and the extensible events format proposed in
+//! MSC1767, where the content of an event is made of blocks such as *m.text*.
+//!
+//! Not every field of *m.room.message* content has a block yet, so conversions report the fields
+//! they could not carry over.
+
+use std::collections::BTreeMap;
+
+use serde_json::{Value, to_value};
+
+use room::message::{
+    EmoteMessageEventContent,
+    MessageEventContent,
+    MessageType,
+    NoticeMessageEventContent,
+    TextMessageEventContent,
+};
+
+/// The kind of an extensible message, which determines the type of its event.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum ExtensibleKind {
+    /// m.emote
+    Emote,
+    /// m.message
+    Message,
+    /// m.notice
+    Notice,
+    /// Any kind that is not part of the proposal.
+    Custom(String),
+}
+
+impl_string_enum! {
+    ExtensibleKind {
+        Emote => "m.emote",
+        Message => "m.message",
+        Notice => "m.notice",
+    }
+}
+
+/// A representation of a message's text in a specific mimetype.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct TextRepresentation {
+    /// The text in this representation.
+    pub body: String,
+    /// The mimetype of the text, *text/plain* if omitted.
+    #[serde(default="plain_text_mimetype")]
+    pub mimetype: String,
+}
+
+/// The content of a message in the extensible events format.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct ExtensibleMessageContent {
+    /// The representations of the text of the message, in order of preference of the sender.
+    #[serde(rename="m.text")]
+    pub text: Vec<TextRepresentation>,
+
+    /// Blocks other than *m.text*, keyed by their type.
+    #[serde(flatten)]
+    pub blocks: BTreeMap<String, Value>,
+}
+
+/// A message in the extensible events format.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExtensibleMessage {
+    /// The kind of the message.
+    pub kind: ExtensibleKind,
+    /// The content of the message.
+    pub content: ExtensibleMessageContent,
+}
+
+/// The result of a conversion between message formats.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Conversion<T> {
+    /// The converted value.
+    pub value: T,
+    /// The fields of the original value that the converted value does not carry, in
+    /// lexicographical order.
+    pub lost_fields: Vec<String>,
+}
+
+impl ExtensibleMessage {
+    /// Converts *m.room.message* content into an extensible message.
+    ///
+    /// Emotes and notices become messages of the same kind, every other message type becomes an
+    /// *m.message* with the body of the original message as its text. Every field other than
+    /// `body` and `msgtype` is reported as lost.
+    pub fn from_legacy(content: &MessageEventContent) -> Conversion<ExtensibleMessage> {
+        let value = to_value(content).expect("message content should always serialize");
+
+        let kind = match value.get("msgtype").and_then(Value::as_str) {
+            Some("m.emote") => ExtensibleKind::Emote,
+            Some("m.notice") => ExtensibleKind::Notice,
+            _ => ExtensibleKind::Message,
+        };
+
+        let body = value.get("body").and_then(Value::as_str).unwrap_or("").to_string();
+
+        let lost_fields = match value {
+            Value::Object(map) => {
+                map.into_iter()
+                    .map(|(key, _)| key)
+                    .filter(|key| key != "body" && key != "msgtype")
+                    .collect()
+            }
+            _ => Vec::new(),
+        };
+
+        Conversion {
+            value: ExtensibleMessage {
+                kind,
+                content: ExtensibleMessageContent {
+                    text: vec![TextRepresentation {
+                        body,
+                        mimetype: plain_text_mimetype(),
+                    }],
+                    blocks: BTreeMap::new(),
+                },
+            },
+            lost_fields,
+        }
+    }
+
+    /// Converts the message into *m.room.message* content.
+    ///
+    /// The plain text representation of the text becomes the body, falling back to the first
+    /// representation. Other representations are reported as lost as `m.text (<mimetype>)`, other
+    /// blocks by their type.
+    pub fn to_legacy(&self) -> Conversion<MessageEventContent> {
+        let text = &self.content.text;
+        let index = text.iter().position(|representation| representation.mimetype == "text/plain")
+            .unwrap_or(0);
+        let body = text.get(index).map(|representation| representation.body.clone())
+            .unwrap_or_default();
+
+        let mut lost_fields: Vec<String> = text.iter()
+            .enumerate()
+            .filter(|&(i, _)| i != index)
+            .map(|(_, representation)| format!("m.text ({})", representation.mimetype))
+            .chain(self.content.blocks.keys().cloned())
+            .collect();
+
+        lost_fields.sort();
+
+        let value = match self.kind {
+            ExtensibleKind::Emote => MessageEventContent::Emote(EmoteMessageEventContent {
+                body,
+                msgtype: MessageType::Emote,
+                #[cfg(feature = "preserve-extra-fields")]
+                extra: Default::default(),
+            }),
+            ExtensibleKind::Notice => MessageEventContent::Notice(NoticeMessageEventContent {
+                body,
+                msgtype: MessageType::Notice,
+                #[cfg(feature = "preserve-extra-fields")]
+                extra: Default::default(),
+            }),
+            ExtensibleKind::Message | ExtensibleKind::Custom(_) => {
+                MessageEventContent::Text(TextMessageEventContent {
+                    body,
+                    msgtype: MessageType::Text,
+                    #[cfg(feature = "preserve-extra-fields")]
+                    extra: Default::default(),
+                })
+            }
+        };
+
+        Conversion { value, lost_fields }
+    }
+}
+
+fn plain_text_mimetype() -> String {
+    "text/plain".to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{from_str, to_string};
+
+    use room::message::MessageEventContent;
+    use super::{ExtensibleKind, ExtensibleMessage, ExtensibleMessageContent};
+
+    #[test]
+    fn legacy_location_to_extensible() {
+        let content = from_str::<MessageEventContent>(
+            r#"{"body":"Big Ben","geo_uri":"geo:51.5008,0.1247","msgtype":"m.location"}"#
+        ).unwrap();
+
+        let conversion = ExtensibleMessage::from_legacy(&content);
+
+        assert_eq!(conversion.value.kind, ExtensibleKind::Message);
+        assert_eq!(
+            to_string(&conversion.value.content).unwrap(),
+            r#"{"m.text":[{"body":"Big Ben","mimetype":"text/plain"}]}"#
+        );
+        assert_eq!(conversion.lost_fields, vec!["geo_uri".to_string()]);
+    }
+
+    #[test]
+    fn extensible_emote_to_legacy() {
+        let content = from_str::<ExtensibleMessageContent>(r#"{
+            "m.text": [
+                {"body": "<b>waves</b>", "mimetype": "text/html"},
+                {"body": "waves"}
+            ],
+            "m.topic": {}
+        }"#).unwrap();
+
+        let message = ExtensibleMessage { kind: ExtensibleKind::Emote, content };
+        let conversion = message.to_legacy();
+
+        match conversion.value {
+            MessageEventContent::Emote(ref content) => assert_eq!(content.body, "waves"),
+            _ => unreachable!(),
+        }
+
+        assert_eq!(conversion.lost_fields, vec!["m.text (text/html)", "m.topic"]);
+    }
+}
@@ -0,0 +1,75 @@
+//! Types for the *m.forwarded_room_key* event.
+
+use std::fmt::{Debug, Formatter, Result as FmtResult};
+
+use ruma_identifiers::{RoomId, UserId};
+
+use redact::Redacted;
+use room::encryption::Algorithm;
+
+event! {
+    /// This event type is used to forward keys for end-to-end encryption.
+    ///
+    /// Typically it is encrypted as an *m.room.encrypted* event, then sent as a to-device event.
+    pub struct ForwardedRoomKeyEvent(ForwardedRoomKeyEventContent) {
+        /// The user who sent the event.
+        pub sender: UserId
+    }
+}
+
+/// The payload of a `ForwardedRoomKeyEvent`.
+///
+/// The `Debug` output redacts `session_key`.
+#[derive(Clone, Deserialize, PartialEq, Serialize)]
+pub struct ForwardedRoomKeyEventContent {
+    /// The encryption algorithm the key in this event is to be used with.
+    pub algorithm: Algorithm,
+
+    /// Chain of Curve25519 keys through which this session was forwarded, via
+    /// *m.forwarded_room_key* events.
+    pub forwarding_curve25519_key_chain: Vec<String>,
+
+    /// The room where the key is used.
+    pub room_id: RoomId,
+
+    /// The Ed25519 key of the device which initiated the session originally.
+    ///
+    /// It is "claimed" because the receiving device has no way to tell that the original room_key
+    /// actually came from a device which owns the private part of this key unless they have done
+    /// device verification.
+    pub sender_claimed_ed25519_key: String,
+
+    /// The Curve25519 key of the device which initiated the session originally.
+    pub sender_key: String,
+
+    /// The ID of the session that the key is for.
+    pub session_id: String,
+
+    /// The key to be exchanged.
+    pub session_key: String,
+
+    /// Fields that are not covered by the Matrix specification.
+    #[cfg(feature = "preserve-extra-fields")]
+    #[serde(flatten)]
+    pub extra: ::std::collections::BTreeMap<String, ::serde_json::Value>,
+}
+
+impl Debug for ForwardedRoomKeyEventContent {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        let mut debug = f.debug_struct("ForwardedRoomKeyEventContent");
+        debug
+            .field("algorithm", &self.algorithm)
+            .field("forwarding_curve25519_key_chain", &self.forwarding_curve25519_key_chain)
+            .field("room_id", &self.room_id)
+            .field("sender_claimed_ed25519_key", &self.sender_claimed_ed25519_key)
+            .field("sender_key", &self.sender_key)
+            .field("session_id", &self.session_id)
+            .field("session_key", &Redacted);
+        #[cfg(feature = "preserve-extra-fields")]
+        debug.field("extra", &self.extra);
+
+        debug.finish()
+    }
+}
+
+impl_event_content!(ForwardedRoomKeyEventContent, ForwardedRoomKey);
@@ -0,0 +1,64 @@
+//! Types for the *m.fully_read* event.
+
+use ruma_identifiers::{EventId, RoomId};
+
+event! {
+    /// The current location of the user's read marker in a room.
+    ///
+    /// This event appears in the user's room account data for the room the marker is applicable
+    /// for.
+    pub struct FullyReadEvent(FullyReadEventContent) {
+        /// The unique identifier for the room associated with this event.
+        ///
+        /// Absent when the event is part of the account data of a room in a sync response.
+        #[serde(skip_serializing_if="Option::is_none")]
+        pub room_id: Option<RoomId>
+    }
+}
+
+impl FullyReadEvent {
+    /// Attaches the ID of the room the event belongs to, such as the room whose account data in
+    /// a sync response contains the event.
+    pub fn into_full_event(self, room_id: RoomId) -> FullyReadEvent {
+        FullyReadEvent {
+            room_id: Some(room_id),
+            ..self
+        }
+    }
+}
+
+/// The payload of a `FullyReadEvent`.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct FullyReadEventContent {
+    /// The event the user's read marker is located at in the room.
+    pub event_id: EventId,
+    /// Fields that are not covered by the Matrix specification.
+    #[cfg(feature = "preserve-extra-fields")]
+    #[serde(flatten)]
+    pub extra: ::std::collections::BTreeMap<String, ::serde_json::Value>,
+}
+
+impl_event_content!(FullyReadEventContent, FullyRead);
+
+#[cfg(test)]
+mod tests {
+    use serde_json::from_str;
+
+    use collections::all::Event;
+
+    #[test]
+    fn fully_read_is_not_custom() {
+        let event = from_str::<Event>(r#"{
+            "content": {"event_id": "$someplace:example.org"},
+            "room_id": "!somewhere:example.org",
+            "type": "m.fully_read"
+        }"#).unwrap();
+
+        match event {
+            Event::FullyRead(ref event) => {
+                assert_eq!(event.content.event_id.to_string(), "$someplace:example.org");
+            }
+            _ => unreachable!(),
+        }
+    }
+}
@@ -0,0 +1,36 @@
+//! Types for the *m.ignored_user_list* event.
+
+use std::collections::HashMap;
+
+use ruma_identifiers::UserId;
+
+event! {
+    /// A list of users to ignore.
+    ///
+    /// This event appears in the user's global account data.
+    pub struct IgnoredUserListEvent(IgnoredUserListEventContent) {}
+}
+
+/// The payload of an `IgnoredUserListEvent`.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct IgnoredUserListEventContent {
+    /// The users to ignore, mapped to an empty object.
+    pub ignored_users: HashMap<UserId, IgnoredUser>,
+    /// Fields that are not covered by the Matrix specification.
+    #[cfg(feature = "preserve-extra-fields")]
+    #[serde(flatten)]
+    pub extra: ::std::collections::BTreeMap<String, ::serde_json::Value>,
+}
+
+impl_event_content!(IgnoredUserListEventContent, IgnoredUserList);
+
+impl IgnoredUserListEventContent {
+    /// Whether `user` is ignored.
+    pub fn is_ignored(&self, user: &UserId) -> bool {
+        self.ignored_users.contains_key(user)
+    }
+}
+
+/// Details about an ignored user, currently always empty.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct IgnoredUser {}
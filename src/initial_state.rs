@@ -0,0 +1,175 @@
+//! Types for the state events sent in the `initial_state` of a room creation request.
+//!
+//! These events only have `content`, `state_key` and `type` fields, since the server fills in the
+//! rest when it creates the room. The state key is optional and defaults to an empty string.
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde::de::Error;
+use serde::ser::SerializeStruct;
+use serde_json::Value;
+
+use {EmptyStateKey, EventContent, EventType, StaticStateEventContent};
+use content::{AnyStateEventContent, deserialize_content, from_json_for_type};
+
+/// A state event to be sent in the `initial_state` of a room creation request, with content of a
+/// specific type.
+#[derive(Clone, Debug)]
+pub struct InitialStateEvent<C> {
+    /// Data specific to the event type.
+    pub content: C,
+    /// A key that determines which piece of room state the event represents.
+    pub state_key: String,
+}
+
+/// A state event to be sent in the `initial_state` of a room creation request, with content of any
+/// state event type.
+#[derive(Clone, Debug)]
+pub struct AnyInitialStateEvent {
+    /// Data specific to the event type.
+    pub content: AnyStateEventContent,
+    /// A key that determines which piece of room state the event represents.
+    pub state_key: String,
+}
+
+/// The JSON representation shared by both kinds of initial state events.
+#[derive(Deserialize)]
+struct RawInitialStateEvent {
+    content: Value,
+    #[serde(default)]
+    state_key: String,
+    #[serde(rename="type")]
+    event_type: EventType,
+}
+
+impl<C> InitialStateEvent<C> where C: StaticStateEventContent {
+    /// Creates an initial state event from its content and a state key of the type that events
+    /// with this content require.
+    pub fn new(content: C, state_key: &C::StateKey) -> Self {
+        InitialStateEvent {
+            content,
+            state_key: state_key.to_string(),
+        }
+    }
+}
+
+impl<C> From<C> for InitialStateEvent<C>
+where C: StaticStateEventContent<StateKey = EmptyStateKey> {
+    fn from(content: C) -> Self {
+        InitialStateEvent::new(content, &EmptyStateKey)
+    }
+}
+
+impl<C> From<InitialStateEvent<C>> for AnyInitialStateEvent where C: Into<AnyStateEventContent> {
+    fn from(event: InitialStateEvent<C>) -> Self {
+        AnyInitialStateEvent {
+            content: event.content.into(),
+            state_key: event.state_key,
+        }
+    }
+}
+
+impl<C> Serialize for InitialStateEvent<C> where C: EventContent {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
+        serialize_initial_state(&self.content, &self.state_key, &C::event_type(), serializer)
+    }
+}
+
+impl<'de, C> Deserialize<'de> for InitialStateEvent<C> where C: EventContent {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where D: Deserializer<'de> {
+        let raw = RawInitialStateEvent::deserialize(deserializer)?;
+
+        let content = match from_json_for_type(&raw.event_type, raw.content) {
+            Ok(content) => content,
+            Err(error) => return Err(D::Error::custom(error.to_string())),
+        };
+
+        Ok(InitialStateEvent {
+            content,
+            state_key: raw.state_key,
+        })
+    }
+}
+
+impl Serialize for AnyInitialStateEvent {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
+        serialize_initial_state(&self.content, &self.state_key, &self.content.event_type(),
+                                serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for AnyInitialStateEvent {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where D: Deserializer<'de> {
+        let raw = RawInitialStateEvent::deserialize(deserializer)?;
+
+        let content = match deserialize_content(&raw.event_type, raw.content) {
+            Ok(content) => content,
+            Err(error) => return Err(D::Error::custom(error.to_string())),
+        };
+
+        match AnyStateEventContent::from_any_content(content) {
+            Some(content) => Ok(AnyInitialStateEvent { content, state_key: raw.state_key }),
+            None => Err(D::Error::custom(format!("{} is not a state event", raw.event_type))),
+        }
+    }
+}
+
+/// Serializes the fields of an initial state event, leaving out an empty state key.
+fn serialize_initial_state<C, S>(content: &C, state_key: &str, event_type: &EventType,
+                                 serializer: S) -> Result<S::Ok, S::Error>
+where C: Serialize, S: Serializer {
+    let len = if state_key.is_empty() { 2 } else { 3 };
+    let mut state = serializer.serialize_struct("InitialStateEvent", len)?;
+
+    state.serialize_field("content", content)?;
+
+    if !state_key.is_empty() {
+        state.serialize_field("state_key", state_key)?;
+    }
+
+    state.serialize_field("type", event_type)?;
+
+    state.end()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{from_str, to_string};
+
+    use content::AnyStateEventContent;
+    use room::join_rules::{JoinRule, JoinRulesEventContent};
+    use room::name::NameEventContent;
+    use super::{AnyInitialStateEvent, InitialStateEvent};
+
+    #[test]
+    fn initial_state_from_content() {
+        let content = JoinRulesEventContent {
+            join_rule: JoinRule::Invite,
+            #[cfg(feature = "preserve-extra-fields")]
+            extra: Default::default(),
+        };
+
+        let event = AnyInitialStateEvent::from(InitialStateEvent::from(content));
+
+        assert_eq!(
+            to_string(&event).unwrap(),
+            r#"{"content":{"join_rule":"invite"},"type":"m.room.join_rules"}"#
+        );
+    }
+
+    #[test]
+    fn deserialize_initial_state() {
+        let json = r#"{"content":{"name":"Ruma"},"type":"m.room.name"}"#;
+
+        let event = from_str::<InitialStateEvent<NameEventContent>>(json).unwrap();
+        assert_eq!(event.content.name, "Ruma");
+        assert_eq!(event.state_key, "");
+
+        match from_str::<AnyInitialStateEvent>(json).unwrap().content {
+            AnyStateEventContent::RoomName(content) => assert_eq!(content.name, "Ruma"),
+            _ => unreachable!(),
+        }
+
+        let message = r#"{"content":{"body":"hi","msgtype":"m.text"},"type":"m.room.message"}"#;
+        assert!(from_str::<AnyInitialStateEvent>(message).is_err());
+    }
+}
@@ -0,0 +1,155 @@
+//! Integers in the range that canonical JSON allows.
+//!
+//! The Matrix specification limits integers in events to the range from -(2<sup>53</sup> - 1) to
+//! 2<sup>53</sup> - 1, which can be represented exactly by every JSON implementation. `UInt` and
+//! `Int` enforce this range, and fail to deserialize from values outside of it.
+
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde::de::Error as SerdeError;
+
+/// The largest integer that canonical JSON allows, 2<sup>53</sup> - 1.
+const MAX_SAFE_INT: i64 = 0x001F_FFFF_FFFF_FFFF;
+
+/// A non-negative integer of at most 2<sup>53</sup> - 1.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct UInt(u64);
+
+/// An integer between -(2<sup>53</sup> - 1) and 2<sup>53</sup> - 1.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Int(i64);
+
+impl UInt {
+    /// The smallest value, 0.
+    pub const MIN: UInt = UInt(0);
+
+    /// The largest value, 2<sup>53</sup> - 1.
+    pub const MAX: UInt = UInt(MAX_SAFE_INT as u64);
+
+    /// Creates a `UInt`, or returns `None` if `value` is out of range.
+    pub fn new(value: u64) -> Option<Self> {
+        if value <= UInt::MAX.0 {
+            Some(UInt(value))
+        } else {
+            None
+        }
+    }
+
+    /// Creates a `UInt`, clamping `value` to the largest value if it is out of range.
+    pub fn new_saturating(value: u64) -> Self {
+        UInt::new(value).unwrap_or(UInt::MAX)
+    }
+}
+
+impl Int {
+    /// The smallest value, -(2<sup>53</sup> - 1).
+    pub const MIN: Int = Int(-MAX_SAFE_INT);
+
+    /// The largest value, 2<sup>53</sup> - 1.
+    pub const MAX: Int = Int(MAX_SAFE_INT);
+
+    /// Creates an `Int`, or returns `None` if `value` is out of range.
+    pub fn new(value: i64) -> Option<Self> {
+        if (Int::MIN.0..=Int::MAX.0).contains(&value) {
+            Some(Int(value))
+        } else {
+            None
+        }
+    }
+}
+
+macro_rules! impl_from {
+    ($int:ident, $inner:ty, $($from:ty),*) => {
+        $(
+            impl From<$from> for $int {
+                fn from(value: $from) -> Self {
+                    $int(<$inner>::from(value))
+                }
+            }
+        )*
+    };
+}
+
+impl_from!(UInt, u64, u8, u16, u32);
+impl_from!(Int, i64, i8, i16, i32, u8, u16, u32);
+
+impl From<UInt> for u64 {
+    fn from(value: UInt) -> Self {
+        value.0
+    }
+}
+
+impl From<UInt> for Int {
+    fn from(value: UInt) -> Self {
+        Int(value.0 as i64)
+    }
+}
+
+impl From<Int> for i64 {
+    fn from(value: Int) -> Self {
+        value.0
+    }
+}
+
+impl Display for UInt {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Display for Int {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Serialize for UInt {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
+        serializer.serialize_u64(self.0)
+    }
+}
+
+impl Serialize for Int {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
+        serializer.serialize_i64(self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for UInt {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where D: Deserializer<'de> {
+        let value = u64::deserialize(deserializer)?;
+
+        UInt::new(value).ok_or_else(|| {
+            D::Error::custom(format!("integer {} is larger than 2^53 - 1", value))
+        })
+    }
+}
+
+impl<'de> Deserialize<'de> for Int {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where D: Deserializer<'de> {
+        let value = i64::deserialize(deserializer)?;
+
+        Int::new(value).ok_or_else(|| {
+            D::Error::custom(format!("integer {} is outside of ±(2^53 - 1)", value))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{from_str, to_string};
+
+    use super::{Int, UInt};
+
+    #[test]
+    fn range_is_enforced() {
+        assert_eq!(from_str::<UInt>("9007199254740991").unwrap(), UInt::MAX);
+        assert!(from_str::<UInt>("9007199254740992").is_err());
+        assert!(from_str::<UInt>("-1").is_err());
+        assert_eq!(from_str::<Int>("-9007199254740991").unwrap(), Int::MIN);
+        assert!(from_str::<Int>("-9007199254740992").is_err());
+        assert_eq!(to_string(&Int::from(-5)).unwrap(), "-5");
+        assert_eq!(UInt::new_saturating(u64::MAX), UInt::MAX);
+    }
+}
@@ -0,0 +1,158 @@
+//! Shared strings for values that repeat across many events, such as the types of custom events.
+//!
+//! With the `interning` feature, equal strings created through `InternedStr::new`, including the
+//! ones the deserializers create, share a single allocation. Without it, every `InternedStr` has
+//! its own allocation, like a `String`.
+
+use std::borrow::Borrow;
+#[cfg(feature = "interning")]
+use std::collections::HashSet;
+use std::fmt::{Debug, Display, Formatter, Result as FmtResult};
+use std::ops::Deref;
+use std::sync::Arc;
+#[cfg(feature = "interning")]
+use std::sync::{Mutex, OnceLock};
+
+/// An immutable string that may share its allocation with equal strings.
+#[derive(Clone, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct InternedStr(Arc<str>);
+
+#[cfg(feature = "interning")]
+static POOL: OnceLock<Mutex<HashSet<Arc<str>>>> = OnceLock::new();
+
+impl InternedStr {
+    /// Creates a string equal to `s`, sharing the allocation of an equal string that is still in
+    /// use if the `interning` feature is enabled.
+    #[cfg(feature = "interning")]
+    pub fn new(s: &str) -> Self {
+        let mut pool = POOL.get_or_init(Default::default)
+            .lock()
+            .unwrap_or_else(|error| error.into_inner());
+
+        if let Some(shared) = pool.get(s) {
+            return InternedStr(shared.clone());
+        }
+
+        let shared = Arc::<str>::from(s);
+        pool.insert(shared.clone());
+
+        InternedStr(shared)
+    }
+
+    /// Creates a string equal to `s`, sharing the allocation of an equal string that is still in
+    /// use if the `interning` feature is enabled.
+    #[cfg(not(feature = "interning"))]
+    pub fn new(s: &str) -> Self {
+        InternedStr(Arc::from(s))
+    }
+
+    /// The string as a `&str`.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Whether `self` and `other` share their allocation.
+    pub fn ptr_eq(&self, other: &InternedStr) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+/// Frees the strings that only the interning pool still refers to, returning how many strings
+/// remain in the pool.
+///
+/// The pool keeps every string it has seen until this is called, so long-running processes that
+/// deserialize many distinct custom values should call it periodically.
+#[cfg(feature = "interning")]
+pub fn release_unused() -> usize {
+    let mut pool = POOL.get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|error| error.into_inner());
+
+    pool.retain(|shared| Arc::strong_count(shared) > 1);
+
+    pool.len()
+}
+
+impl AsRef<str> for InternedStr {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Borrow<str> for InternedStr {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Deref for InternedStr {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Debug for InternedStr {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        Debug::fmt(&*self.0, f)
+    }
+}
+
+impl Display for InternedStr {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        Display::fmt(&*self.0, f)
+    }
+}
+
+impl<'a> From<&'a str> for InternedStr {
+    fn from(s: &'a str) -> Self {
+        InternedStr::new(s)
+    }
+}
+
+impl From<String> for InternedStr {
+    fn from(s: String) -> Self {
+        InternedStr::new(&s)
+    }
+}
+
+impl PartialEq<str> for InternedStr {
+    fn eq(&self, other: &str) -> bool {
+        &*self.0 == other
+    }
+}
+
+impl<'a> PartialEq<&'a str> for InternedStr {
+    fn eq(&self, other: &&'a str) -> bool {
+        &*self.0 == *other
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::InternedStr;
+
+    #[test]
+    fn interned_strings_compare_by_value() {
+        let first = InternedStr::new("org.example.custom");
+        let second = InternedStr::from("org.example.custom".to_string());
+
+        assert_eq!(first, second);
+        assert_eq!(first, "org.example.custom");
+        assert_eq!(first.ptr_eq(&second), cfg!(feature = "interning"));
+    }
+
+    #[cfg(feature = "interning")]
+    #[test]
+    fn unused_strings_are_released() {
+        use super::release_unused;
+
+        let kept = InternedStr::new("org.example.kept");
+        drop(InternedStr::new("org.example.released"));
+
+        release_unused();
+
+        assert!(kept.ptr_eq(&InternedStr::new("org.example.kept")));
+    }
+}
@@ -0,0 +1,213 @@
+//! An append-only log of events.
+//!
+//! Each record of a journal is the length of the event's JSON in bytes, as a big-endian 32-bit
+//! integer, followed by the event serialized as canonical JSON: compact, with object keys in
+//! lexicographical order. Records can be appended to a file as events arrive and read back in
+//! order, without any further dependencies.
+
+use std::error::Error;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::io::{Error as IoError, ErrorKind, Read, Write};
+use std::marker::PhantomData;
+use std::mem;
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::{Error as JsonError, Map, Value, from_slice, to_value, to_vec};
+
+use collections::all::Event;
+
+/// Appends events to a journal.
+#[derive(Debug)]
+pub struct JournalWriter<W> {
+    writer: W,
+}
+
+/// Reads the events of a journal in the order they were appended.
+///
+/// A record that does not hold a valid event yields an error, and reading continues with the next
+/// record. Reading stops after an I/O error or a record that is cut off, as left behind by a
+/// write that was interrupted.
+#[derive(Debug)]
+pub struct JournalReader<R, E = Event> {
+    done: bool,
+    event_type: PhantomData<E>,
+    reader: R,
+}
+
+/// An error when writing or reading a journal.
+#[derive(Debug)]
+pub enum JournalError {
+    /// The underlying writer or reader failed.
+    Io(IoError),
+    /// An event could not be serialized, or a record could not be deserialized.
+    Json(JsonError),
+    /// The serialized event is longer than a record can hold.
+    RecordTooLarge(usize),
+    /// The journal ends in the middle of a record.
+    Truncated,
+}
+
+impl<W> JournalWriter<W> where W: Write {
+    /// Creates a writer that appends records to `writer`.
+    ///
+    /// To continue an existing journal, `writer` must be positioned at its end, e.g. a file opened
+    /// in append mode.
+    pub fn new(writer: W) -> Self {
+        JournalWriter { writer }
+    }
+
+    /// Appends an event as a single record.
+    pub fn append<E>(&mut self, event: &E) -> Result<(), JournalError> where E: Serialize {
+        let mut value = to_value(event).map_err(JournalError::Json)?;
+        sort_keys(&mut value);
+        let json = to_vec(&value).map_err(JournalError::Json)?;
+
+        if json.len() > u32::MAX as usize {
+            return Err(JournalError::RecordTooLarge(json.len()));
+        }
+
+        let mut record = Vec::with_capacity(4 + json.len());
+        record.extend_from_slice(&(json.len() as u32).to_be_bytes());
+        record.extend_from_slice(&json);
+
+        self.writer.write_all(&record).map_err(JournalError::Io)
+    }
+
+    /// Flushes the underlying writer.
+    pub fn flush(&mut self) -> Result<(), JournalError> {
+        self.writer.flush().map_err(JournalError::Io)
+    }
+
+    /// Returns the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+/// Sorts the keys of every object in `value` lexicographically.
+///
+/// `Map` only keeps its keys sorted while the `preserve_order` feature of serde_json is disabled,
+/// which another crate in the dependency graph may enable.
+fn sort_keys(value: &mut Value) {
+    match *value {
+        Value::Array(ref mut values) => values.iter_mut().for_each(sort_keys),
+        Value::Object(ref mut object) => {
+            let mut entries = mem::replace(object, Map::new()).into_iter().collect::<Vec<_>>();
+            entries.sort_by(|(key, _), (other_key, _)| key.cmp(other_key));
+
+            for (key, mut value) in entries {
+                sort_keys(&mut value);
+                object.insert(key, value);
+            }
+        }
+        _ => {}
+    }
+}
+
+impl<R, E> JournalReader<R, E> where R: Read {
+    /// Creates a reader over the records in `reader`.
+    pub fn new(reader: R) -> Self {
+        JournalReader {
+            done: false,
+            event_type: PhantomData,
+            reader,
+        }
+    }
+
+    /// Reads the next record, or `None` at the end of the journal.
+    fn next_record(&mut self) -> Result<Option<Vec<u8>>, JournalError> {
+        let mut length = [0; 4];
+        let mut filled = 0;
+
+        while filled < length.len() {
+            match self.reader.read(&mut length[filled..]) {
+                Ok(0) if filled == 0 => return Ok(None),
+                Ok(0) => return Err(JournalError::Truncated),
+                Ok(read) => filled += read,
+                Err(ref error) if error.kind() == ErrorKind::Interrupted => {}
+                Err(error) => return Err(JournalError::Io(error)),
+            }
+        }
+
+        let length = u32::from_be_bytes(length) as u64;
+        let mut json = Vec::new();
+        (&mut self.reader).take(length).read_to_end(&mut json).map_err(JournalError::Io)?;
+
+        if (json.len() as u64) < length {
+            return Err(JournalError::Truncated);
+        }
+
+        Ok(Some(json))
+    }
+}
+
+impl<R, E> Iterator for JournalReader<R, E> where R: Read, E: DeserializeOwned {
+    type Item = Result<E, JournalError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        match self.next_record() {
+            Ok(Some(json)) => Some(from_slice(&json).map_err(JournalError::Json)),
+            Ok(None) => {
+                self.done = true;
+                None
+            }
+            Err(error) => {
+                self.done = true;
+                Some(Err(error))
+            }
+        }
+    }
+}
+
+impl Display for JournalError {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match *self {
+            JournalError::Io(ref error) => write!(f, "journal I/O failed: {}", error),
+            JournalError::Json(ref error) => write!(f, "invalid journal record: {}", error),
+            JournalError::RecordTooLarge(length) => {
+                write!(f, "event of {} bytes is too large for a journal record", length)
+            }
+            JournalError::Truncated => write!(f, "journal ends in the middle of a record"),
+        }
+    }
+}
+
+impl Error for JournalError {}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::from_str;
+
+    use collections::all::Event;
+    use super::{JournalError, JournalReader, JournalWriter};
+
+    #[test]
+    fn journal_round_trip() {
+        let tag = from_str::<Event>(r#"{"type":"m.tag","content":{"tags":{}}}"#).unwrap();
+        let typing = from_str::<Event>(
+            r#"{"type":"m.typing","room_id":"!n8f893n9:example.com","content":{"user_ids":[]}}"#
+        ).unwrap();
+
+        let mut writer = JournalWriter::new(Vec::new());
+        writer.append(&tag).unwrap();
+        writer.append(&typing).unwrap();
+        let mut journal = writer.into_inner();
+
+        let json = br#"{"content":{"tags":{}},"type":"m.tag"}"#;
+        assert_eq!(&journal[..4], &(json.len() as u32).to_be_bytes());
+        assert_eq!(&journal[4..4 + json.len()], &json[..]);
+
+        journal.extend_from_slice(&[0, 0, 0, 9, b'{']);
+
+        let mut events = JournalReader::<_, Event>::new(&journal[..]);
+        assert!(matches!(events.next(), Some(Ok(Event::Tag(_)))));
+        assert!(matches!(events.next(), Some(Ok(Event::Typing(_)))));
+        assert!(matches!(events.next(), Some(Err(JournalError::Truncated))));
+        assert!(events.next().is_none());
+    }
+}
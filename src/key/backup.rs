@@ -0,0 +1,144 @@
+//! Types for server-side backups of room keys, and for the *m.megolm_backup.v1* event.
+
+use std::collections::HashMap;
+use std::fmt::{Debug, Formatter, Result as FmtResult};
+
+use ruma_identifiers::UserId;
+
+use redact::{Redacted, Sorted};
+
+event! {
+    /// The private key of the user's current key backup, encrypted for secret storage.
+    ///
+    /// This is stored in the user's account data so that other devices of the user can restore
+    /// room keys from the backup.
+    pub struct MegolmBackupEvent(MegolmBackupEventContent) {}
+}
+
+/// The payload of a `MegolmBackupEvent`.
+#[derive(Clone, Deserialize, PartialEq, Serialize)]
+pub struct MegolmBackupEventContent {
+    /// A map from the ID of a secret storage key to the backup key encrypted with it.
+    pub encrypted: HashMap<String, EncryptedSecret>,
+    /// Fields that are not covered by the Matrix specification.
+    #[cfg(feature = "preserve-extra-fields")]
+    #[serde(flatten)]
+    pub extra: ::std::collections::BTreeMap<String, ::serde_json::Value>,
+}
+
+impl Debug for MegolmBackupEventContent {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        let mut debug = f.debug_struct("MegolmBackupEventContent");
+        debug
+            .field("encrypted", &Sorted(&self.encrypted));
+        #[cfg(feature = "preserve-extra-fields")]
+        debug.field("extra", &self.extra);
+
+        debug.finish()
+    }
+}
+
+impl_event_content!(MegolmBackupEventContent, MegolmBackupV1);
+
+/// A secret encrypted with a secret storage key using *m.secret_storage.v1.aes-hmac-sha2*.
+///
+/// The `Debug` output redacts `ciphertext` and `mac`.
+#[derive(Clone, Deserialize, PartialEq, Serialize)]
+pub struct EncryptedSecret {
+    /// The encrypted secret, encoded as unpadded base64.
+    pub ciphertext: String,
+    /// The initialization vector used for encryption, encoded as unpadded base64.
+    pub iv: String,
+    /// The MAC of the ciphertext, encoded as unpadded base64.
+    pub mac: String,
+}
+
+impl Debug for EncryptedSecret {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        f.debug_struct("EncryptedSecret")
+            .field("ciphertext", &Redacted)
+            .field("iv", &self.iv)
+            .field("mac", &Redacted)
+            .finish()
+    }
+}
+
+/// The algorithm used by a key backup, along with its algorithm-specific data.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(tag="algorithm", content="auth_data")]
+pub enum BackupAlgorithm {
+    /// *m.megolm_backup.v1.curve25519-aes-sha2*
+    #[serde(rename="m.megolm_backup.v1.curve25519-aes-sha2")]
+    MegolmBackupV1Curve25519AesSha2 {
+        /// The Curve25519 public key used to encrypt the backups, encoded as unpadded base64.
+        public_key: String,
+        /// Signatures of the `auth_data`, as a map from user ID to a map from
+        /// `<algorithm>:<key_id>` to the signature.
+        #[serde(default, skip_serializing_if="HashMap::is_empty")]
+        signatures: HashMap<UserId, HashMap<String, String>>,
+    },
+}
+
+/// A room key stored in a key backup.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct KeyBackupData {
+    /// The index of the first message in the session that the key can decrypt.
+    pub first_message_index: u64,
+    /// The number of times this key has been forwarded via key-sharing between devices.
+    pub forwarded_count: u64,
+    /// Whether the device backing up the key verified the device that the key is from.
+    pub is_verified: bool,
+    /// The encrypted key.
+    pub session_data: SessionData,
+}
+
+/// A room key encrypted with *m.megolm_backup.v1.curve25519-aes-sha2*.
+///
+/// The `Debug` output redacts `ciphertext` and `mac`.
+#[derive(Clone, Deserialize, PartialEq, Serialize)]
+pub struct SessionData {
+    /// The encrypted key, encoded as unpadded base64.
+    pub ciphertext: String,
+    /// The public part of the ephemeral key used for encryption, encoded as unpadded base64.
+    pub ephemeral: String,
+    /// The MAC of the ciphertext, encoded as unpadded base64.
+    pub mac: String,
+}
+
+impl Debug for SessionData {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        f.debug_struct("SessionData")
+            .field("ciphertext", &Redacted)
+            .field("ephemeral", &self.ephemeral)
+            .field("mac", &Redacted)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{Value, from_str, to_value};
+
+    use super::BackupAlgorithm;
+
+    #[test]
+    fn backup_algorithm_serialization() {
+        let json = r#"{
+            "algorithm": "m.megolm_backup.v1.curve25519-aes-sha2",
+            "auth_data": {
+                "public_key": "abcdefg",
+                "signatures": {"@alice:example.org": {"ed25519:deviceid": "signature"}}
+            }
+        }"#;
+
+        let algorithm = from_str::<BackupAlgorithm>(json).unwrap();
+
+        match algorithm {
+            BackupAlgorithm::MegolmBackupV1Curve25519AesSha2 { ref public_key, .. } => {
+                assert_eq!(public_key, "abcdefg");
+            }
+        }
+
+        assert_eq!(to_value(&algorithm).unwrap(), from_str::<Value>(json).unwrap());
+    }
+}
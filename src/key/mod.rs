@@ -0,0 +1,4 @@
+//! Modules for events in the *m.key* namespace.
+
+pub mod backup;
+pub mod verification;
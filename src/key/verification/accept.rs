@@ -0,0 +1,62 @@
+//! Types for the *m.key.verification.accept* event.
+
+use ruma_identifiers::UserId;
+
+use super::{
+    HashAlgorithm,
+    KeyAgreementProtocol,
+    MessageAuthenticationCode,
+    ShortAuthenticationString,
+    VerificationMethod,
+};
+
+event! {
+    /// Accepts a previously sent *m.key.verification.start* message.
+    ///
+    /// Typically sent as a to-device event.
+    pub struct AcceptEvent(AcceptEventContent) {
+        /// The user who sent the event.
+        pub sender: UserId
+    }
+}
+
+/// The payload of an `AcceptEvent`.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct AcceptEventContent {
+    /// The hash (encoded as unpadded base64) of the concatenation of the device's ephemeral public
+    /// key (encoded as unpadded base64) and the canonical JSON representation of the
+    /// *m.key.verification.start* message.
+    pub commitment: String,
+
+    /// The hash method the device is choosing to use, out of the options in the
+    /// *m.key.verification.start* message.
+    pub hash: HashAlgorithm,
+
+    /// The key agreement protocol the device is choosing to use, out of the options in the
+    /// *m.key.verification.start* message.
+    pub key_agreement_protocol: KeyAgreementProtocol,
+
+    /// The message authentication code the device is choosing to use, out of the options in the
+    /// *m.key.verification.start* message.
+    pub message_authentication_code: MessageAuthenticationCode,
+
+    /// The verification method to use. Must be *m.sas.v1*.
+    pub method: VerificationMethod,
+
+    /// The SAS methods both devices involved in the verification process understand.
+    ///
+    /// Must be a subset of the options in the *m.key.verification.start* message.
+    pub short_authentication_string: Vec<ShortAuthenticationString>,
+
+    /// An opaque identifier for the verification process.
+    ///
+    /// Must be the same as the one used for the *m.key.verification.start* message.
+    pub transaction_id: String,
+
+    /// Fields that are not covered by the Matrix specification.
+    #[cfg(feature = "preserve-extra-fields")]
+    #[serde(flatten)]
+    pub extra: ::std::collections::BTreeMap<String, ::serde_json::Value>,
+}
+
+impl_event_content!(AcceptEventContent, KeyVerificationAccept);
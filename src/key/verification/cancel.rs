@@ -0,0 +1,50 @@
+//! Types for the *m.key.verification.cancel* event.
+
+use ruma_identifiers::UserId;
+
+use super::CancelCode;
+
+event! {
+    /// Cancels a key verification process or request.
+    ///
+    /// Typically sent as a to-device event.
+    pub struct CancelEvent(CancelEventContent) {
+        /// The user who sent the event.
+        pub sender: UserId
+    }
+}
+
+/// The payload of a `CancelEvent`.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct CancelEventContent {
+    /// The error code for why the process or request was cancelled by the user.
+    pub code: CancelCode,
+
+    /// A human readable description of the `code`.
+    ///
+    /// The client should only rely on this string if it does not understand the `code`.
+    pub reason: String,
+
+    /// The opaque identifier for the verification process or request.
+    pub transaction_id: String,
+
+    /// Fields that are not covered by the Matrix specification.
+    #[cfg(feature = "preserve-extra-fields")]
+    #[serde(flatten)]
+    pub extra: ::std::collections::BTreeMap<String, ::serde_json::Value>,
+}
+
+impl_event_content!(CancelEventContent, KeyVerificationCancel);
+
+impl CancelEventContent {
+    /// Creates a cancellation with the default reason of the cancel code.
+    pub fn new(transaction_id: String, code: CancelCode) -> Self {
+        CancelEventContent {
+            reason: code.default_reason().to_string(),
+            code,
+            transaction_id,
+            #[cfg(feature = "preserve-extra-fields")]
+            extra: Default::default(),
+        }
+    }
+}
@@ -0,0 +1,27 @@
+//! Types for the *m.key.verification.done* event.
+
+use ruma_identifiers::UserId;
+
+event! {
+    /// Indicates that a verification process or request has completed successfully.
+    ///
+    /// Typically sent as a to-device event.
+    pub struct DoneEvent(DoneEventContent) {
+        /// The user who sent the event.
+        pub sender: UserId
+    }
+}
+
+/// The payload of a `DoneEvent`.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct DoneEventContent {
+    /// The opaque identifier for the verification process or request.
+    pub transaction_id: String,
+
+    /// Fields that are not covered by the Matrix specification.
+    #[cfg(feature = "preserve-extra-fields")]
+    #[serde(flatten)]
+    pub extra: ::std::collections::BTreeMap<String, ::serde_json::Value>,
+}
+
+impl_event_content!(DoneEventContent, KeyVerificationDone);
@@ -0,0 +1,32 @@
+//! Types for the *m.key.verification.key* event.
+
+use ruma_identifiers::UserId;
+
+event! {
+    /// Sends the ephemeral public key for a device to the partner device.
+    ///
+    /// Typically sent as a to-device event.
+    pub struct KeyEvent(KeyEventContent) {
+        /// The user who sent the event.
+        pub sender: UserId
+    }
+}
+
+/// The payload of a `KeyEvent`.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct KeyEventContent {
+    /// The device's ephemeral public key, encoded as unpadded base64.
+    pub key: String,
+
+    /// An opaque identifier for the verification process.
+    ///
+    /// Must be the same as the one used for the *m.key.verification.start* message.
+    pub transaction_id: String,
+
+    /// Fields that are not covered by the Matrix specification.
+    #[cfg(feature = "preserve-extra-fields")]
+    #[serde(flatten)]
+    pub extra: ::std::collections::BTreeMap<String, ::serde_json::Value>,
+}
+
+impl_event_content!(KeyEventContent, KeyVerificationKey);
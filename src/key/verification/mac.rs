@@ -0,0 +1,59 @@
+//! Types for the *m.key.verification.mac* event.
+
+use std::collections::HashMap;
+use std::fmt::{Debug, Formatter, Result as FmtResult};
+
+use ruma_identifiers::UserId;
+
+use redact::{Redacted, RedactedValues};
+
+event! {
+    /// Sends the MAC of a device's key to the partner device.
+    ///
+    /// Typically sent as a to-device event.
+    pub struct MacEvent(MacEventContent) {
+        /// The user who sent the event.
+        pub sender: UserId
+    }
+}
+
+/// The payload of a `MacEvent`.
+///
+/// The `Debug` output redacts the MACs.
+#[derive(Clone, Deserialize, PartialEq, Serialize)]
+pub struct MacEventContent {
+    /// The MAC of the comma-separated, sorted list of key IDs given in the `mac` property, encoded
+    /// as unpadded base64.
+    pub keys: String,
+
+    /// A map of the key ID to the MAC of the key, using the algorithm in the verification process.
+    ///
+    /// The MAC is encoded as unpadded base64.
+    pub mac: HashMap<String, String>,
+
+    /// An opaque identifier for the verification process.
+    ///
+    /// Must be the same as the one used for the *m.key.verification.start* message.
+    pub transaction_id: String,
+
+    /// Fields that are not covered by the Matrix specification.
+    #[cfg(feature = "preserve-extra-fields")]
+    #[serde(flatten)]
+    pub extra: ::std::collections::BTreeMap<String, ::serde_json::Value>,
+}
+
+impl Debug for MacEventContent {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        let mut debug = f.debug_struct("MacEventContent");
+        debug
+            .field("keys", &Redacted)
+            .field("mac", &RedactedValues(&self.mac))
+            .field("transaction_id", &self.transaction_id);
+        #[cfg(feature = "preserve-extra-fields")]
+        debug.field("extra", &self.extra);
+
+        debug.finish()
+    }
+}
+
+impl_event_content!(MacEventContent, KeyVerificationMac);
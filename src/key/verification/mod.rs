@@ -0,0 +1,216 @@
+//! Modules for events in the *m.key.verification* namespace.
+//!
+//! This module also contains types shared by events in its child namespaces.
+
+pub mod accept;
+pub mod cancel;
+pub mod done;
+pub mod key;
+pub mod mac;
+#[cfg(feature = "qr-code")]
+pub mod qr_code;
+pub mod request;
+pub mod start;
+
+/// An error code for why a key verification process was cancelled.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum CancelCode {
+    /// m.accepted
+    Accepted,
+    /// m.invalid_message
+    InvalidMessage,
+    /// m.key_mismatch
+    KeyMismatch,
+    /// m.timeout
+    Timeout,
+    /// m.unexpected_message
+    UnexpectedMessage,
+    /// m.unknown_method
+    UnknownMethod,
+    /// m.unknown_transaction
+    UnknownTransaction,
+    /// m.user
+    User,
+    /// m.user_mismatch
+    UserMismatch,
+    /// Any cancel code that is not part of the specification.
+    Custom(String),
+}
+
+impl CancelCode {
+    /// A human-readable description of the cancel code, suitable for the `reason` field of a
+    /// cancellation when the application has nothing more specific to say.
+    pub fn default_reason(&self) -> &str {
+        match *self {
+            CancelCode::Accepted => "The verification request was accepted by a different device",
+            CancelCode::InvalidMessage => "An invalid message was received",
+            CancelCode::KeyMismatch => "The key was not verified",
+            CancelCode::Timeout => "The verification process timed out",
+            CancelCode::UnexpectedMessage => "An unexpected message was received",
+            CancelCode::UnknownMethod => "The verification method is not supported",
+            CancelCode::UnknownTransaction => "The transaction ID is not known",
+            CancelCode::User => "The user cancelled the verification",
+            CancelCode::UserMismatch => "The expected user did not match the user verified",
+            CancelCode::Custom(ref code) => code,
+        }
+    }
+}
+
+impl_string_enum! {
+    CancelCode {
+        Accepted => "m.accepted",
+        InvalidMessage => "m.invalid_message",
+        KeyMismatch => "m.key_mismatch",
+        Timeout => "m.timeout",
+        UnexpectedMessage => "m.unexpected_message",
+        UnknownMethod => "m.unknown_method",
+        UnknownTransaction => "m.unknown_transaction",
+        User => "m.user",
+        UserMismatch => "m.user_mismatch",
+    }
+}
+
+/// A method of verifying devices.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum VerificationMethod {
+    /// m.qr_code.scan.v1
+    QrCodeScanV1,
+    /// m.qr_code.show.v1
+    QrCodeShowV1,
+    /// m.reciprocate.v1
+    ReciprocateV1,
+    /// m.sas.v1
+    SasV1,
+    /// Any verification method that is not part of the specification.
+    Custom(String),
+}
+
+impl_string_enum! {
+    VerificationMethod {
+        QrCodeScanV1 => "m.qr_code.scan.v1",
+        QrCodeShowV1 => "m.qr_code.show.v1",
+        ReciprocateV1 => "m.reciprocate.v1",
+        SasV1 => "m.sas.v1",
+    }
+}
+
+/// A key agreement protocol used in SAS verification.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum KeyAgreementProtocol {
+    /// curve25519
+    Curve25519,
+    /// curve25519-hkdf-sha256
+    Curve25519HkdfSha256,
+    /// Any key agreement protocol that is not part of the specification.
+    Custom(String),
+}
+
+impl_string_enum! {
+    KeyAgreementProtocol {
+        Curve25519 => "curve25519",
+        Curve25519HkdfSha256 => "curve25519-hkdf-sha256",
+    }
+}
+
+/// A hash algorithm used in SAS verification.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum HashAlgorithm {
+    /// sha256
+    Sha256,
+    /// Any hash algorithm that is not part of the specification.
+    Custom(String),
+}
+
+impl_string_enum! {
+    HashAlgorithm {
+        Sha256 => "sha256",
+    }
+}
+
+/// A message authentication code algorithm used in SAS verification.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum MessageAuthenticationCode {
+    /// hkdf-hmac-sha256
+    HkdfHmacSha256,
+    /// hkdf-hmac-sha256.v2
+    HkdfHmacSha256V2,
+    /// Any message authentication code that is not part of the specification.
+    Custom(String),
+}
+
+impl_string_enum! {
+    MessageAuthenticationCode {
+        HkdfHmacSha256 => "hkdf-hmac-sha256",
+        HkdfHmacSha256V2 => "hkdf-hmac-sha256.v2",
+    }
+}
+
+/// A way of presenting the short authentication string to the user.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum ShortAuthenticationString {
+    /// decimal
+    Decimal,
+    /// emoji
+    Emoji,
+    /// Any short authentication string method that is not part of the specification.
+    Custom(String),
+}
+
+impl_string_enum! {
+    ShortAuthenticationString {
+        Decimal => "decimal",
+        Emoji => "emoji",
+    }
+}
+
+/// The options supported by both parties of a verification, in the order of preference given by
+/// `ours`.
+pub fn intersect<T>(ours: &[T], theirs: &[T]) -> Vec<T> where T: Clone + PartialEq {
+    ours.iter().filter(|option| theirs.contains(option)).cloned().collect()
+}
+
+/// The most preferred option according to `ours` that is supported by both parties of a
+/// verification, if any.
+pub fn negotiate<T>(ours: &[T], theirs: &[T]) -> Option<T> where T: Clone + PartialEq {
+    ours.iter().find(|option| theirs.contains(option)).cloned()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{from_str, to_string};
+
+    use super::{CancelCode, KeyAgreementProtocol, ShortAuthenticationString, intersect, negotiate};
+
+    #[test]
+    fn cancel_codes_serialize_to_display_form() {
+        assert_eq!(to_string(&CancelCode::User).unwrap(), r#""m.user""#);
+        assert_eq!(
+            to_string(&CancelCode::Custom("io.ruma.test".to_string())).unwrap(),
+            r#""io.ruma.test""#
+        );
+    }
+
+    #[test]
+    fn cancel_codes_deserialize_from_display_form() {
+        assert_eq!(from_str::<CancelCode>(r#""m.key_mismatch""#).unwrap(), CancelCode::KeyMismatch);
+        assert_eq!(
+            from_str::<CancelCode>(r#""io.ruma.test""#).unwrap(),
+            CancelCode::Custom("io.ruma.test".to_string())
+        );
+    }
+
+    #[test]
+    fn capability_negotiation() {
+        let ours = vec![ShortAuthenticationString::Emoji, ShortAuthenticationString::Decimal];
+        let theirs = vec![ShortAuthenticationString::Decimal];
+
+        assert_eq!(intersect(&ours, &theirs), vec![ShortAuthenticationString::Decimal]);
+        assert_eq!(
+            negotiate(
+                &[KeyAgreementProtocol::Curve25519HkdfSha256, KeyAgreementProtocol::Curve25519],
+                &[KeyAgreementProtocol::Curve25519, KeyAgreementProtocol::Curve25519HkdfSha256]
+            ),
+            Some(KeyAgreementProtocol::Curve25519HkdfSha256)
+        );
+    }
+}
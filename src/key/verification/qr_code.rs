@@ -0,0 +1,213 @@
+//! The binary payload of QR codes used with the *m.qr_code.show.v1* and *m.qr_code.scan.v1*
+//! verification methods.
+
+use std::error::Error;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+/// The fixed prefix of every QR code payload.
+const HEADER: &[u8] = b"MATRIX";
+
+/// The version of the QR code format this module implements.
+const VERSION: u8 = 0x02;
+
+/// The minimum length of the shared secret in bytes.
+const MIN_SHARED_SECRET_LENGTH: usize = 8;
+
+/// The contents of a verification QR code.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct QrCode {
+    /// The first key, whose meaning depends on the mode.
+    pub first_key: [u8; 32],
+    /// The mode of the verification.
+    pub mode: QrCodeMode,
+    /// The second key, whose meaning depends on the mode.
+    pub second_key: [u8; 32],
+    /// A random secret shared between the two devices. Must be at least 8 bytes long.
+    pub shared_secret: Vec<u8>,
+    /// The transaction ID, or the event ID of the *m.key.verification.request* event for
+    /// in-room verifications.
+    pub transaction_id: String,
+}
+
+/// The mode of a verification QR code, determining the meaning of its keys.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum QrCodeMode {
+    /// Verifying another user with cross-signing. The keys are the master keys of the displaying
+    /// and the scanning user.
+    VerifyingAnotherUser,
+    /// Self-verification by a device that trusts the master key. The keys are the master key and
+    /// the other device's key.
+    SelfVerifyingMasterKeyTrusted,
+    /// Self-verification by a device that does not yet trust the master key. The keys are the
+    /// displaying device's key and the master key.
+    SelfVerifyingMasterKeyUntrusted,
+}
+
+/// An error when encoding or decoding a QR code payload.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum QrCodeError {
+    /// The payload does not start with "MATRIX".
+    InvalidHeader,
+    /// The mode byte is not one of the known modes.
+    InvalidMode(u8),
+    /// The transaction ID is not valid UTF-8.
+    InvalidTransactionId,
+    /// The shared secret is shorter than 8 bytes.
+    SharedSecretTooShort,
+    /// The payload ends before all fields are read.
+    TooShort,
+    /// The transaction ID is too long to be encoded.
+    TransactionIdTooLong,
+    /// The payload uses a version of the format other than 2.
+    UnsupportedVersion(u8),
+}
+
+impl QrCodeMode {
+    fn to_byte(self) -> u8 {
+        match self {
+            QrCodeMode::VerifyingAnotherUser => 0x00,
+            QrCodeMode::SelfVerifyingMasterKeyTrusted => 0x01,
+            QrCodeMode::SelfVerifyingMasterKeyUntrusted => 0x02,
+        }
+    }
+
+    fn from_byte(byte: u8) -> Result<Self, QrCodeError> {
+        match byte {
+            0x00 => Ok(QrCodeMode::VerifyingAnotherUser),
+            0x01 => Ok(QrCodeMode::SelfVerifyingMasterKeyTrusted),
+            0x02 => Ok(QrCodeMode::SelfVerifyingMasterKeyUntrusted),
+            mode => Err(QrCodeError::InvalidMode(mode)),
+        }
+    }
+}
+
+impl QrCode {
+    /// Encodes the QR code into its binary payload.
+    pub fn encode(&self) -> Result<Vec<u8>, QrCodeError> {
+        if self.shared_secret.len() < MIN_SHARED_SECRET_LENGTH {
+            return Err(QrCodeError::SharedSecretTooShort);
+        }
+
+        if self.transaction_id.len() > u16::MAX as usize {
+            return Err(QrCodeError::TransactionIdTooLong);
+        }
+
+        let transaction_id_length = self.transaction_id.len() as u16;
+        let mut payload = Vec::with_capacity(
+            HEADER.len() + 4 + self.transaction_id.len() + 64 + self.shared_secret.len()
+        );
+
+        payload.extend_from_slice(HEADER);
+        payload.push(VERSION);
+        payload.push(self.mode.to_byte());
+        payload.extend_from_slice(&transaction_id_length.to_be_bytes());
+        payload.extend_from_slice(self.transaction_id.as_bytes());
+        payload.extend_from_slice(&self.first_key);
+        payload.extend_from_slice(&self.second_key);
+        payload.extend_from_slice(&self.shared_secret);
+
+        Ok(payload)
+    }
+
+    /// Decodes a QR code from its binary payload.
+    pub fn decode(payload: &[u8]) -> Result<Self, QrCodeError> {
+        let mut reader = Reader { remaining: payload };
+
+        if reader.take(HEADER.len())? != HEADER {
+            return Err(QrCodeError::InvalidHeader);
+        }
+
+        let version = reader.take(1)?[0];
+
+        if version != VERSION {
+            return Err(QrCodeError::UnsupportedVersion(version));
+        }
+
+        let mode = QrCodeMode::from_byte(reader.take(1)?[0])?;
+
+        let length_bytes = reader.take(2)?;
+        let transaction_id_length = u16::from_be_bytes([length_bytes[0], length_bytes[1]]);
+        let transaction_id_bytes = reader.take(transaction_id_length as usize)?.to_vec();
+        let transaction_id = String::from_utf8(transaction_id_bytes)
+            .map_err(|_| QrCodeError::InvalidTransactionId)?;
+
+        let mut first_key = [0; 32];
+        first_key.copy_from_slice(reader.take(32)?);
+
+        let mut second_key = [0; 32];
+        second_key.copy_from_slice(reader.take(32)?);
+
+        if reader.remaining.len() < MIN_SHARED_SECRET_LENGTH {
+            return Err(QrCodeError::SharedSecretTooShort);
+        }
+
+        Ok(QrCode {
+            first_key,
+            mode,
+            second_key,
+            shared_secret: reader.remaining.to_vec(),
+            transaction_id,
+        })
+    }
+}
+
+/// Reads consecutive fields from a payload.
+struct Reader<'a> {
+    remaining: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, length: usize) -> Result<&'a [u8], QrCodeError> {
+        if self.remaining.len() < length {
+            return Err(QrCodeError::TooShort);
+        }
+
+        let (field, remaining) = self.remaining.split_at(length);
+        self.remaining = remaining;
+
+        Ok(field)
+    }
+}
+
+impl Display for QrCodeError {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match *self {
+            QrCodeError::InvalidHeader => write!(f, "missing MATRIX header"),
+            QrCodeError::InvalidMode(mode) => write!(f, "invalid mode {:#04x}", mode),
+            QrCodeError::InvalidTransactionId => write!(f, "transaction ID is not valid UTF-8"),
+            QrCodeError::SharedSecretTooShort => write!(f, "shared secret is too short"),
+            QrCodeError::TooShort => write!(f, "payload is too short"),
+            QrCodeError::TransactionIdTooLong => write!(f, "transaction ID is too long"),
+            QrCodeError::UnsupportedVersion(version) => {
+                write!(f, "unsupported version {:#04x}", version)
+            }
+        }
+    }
+}
+
+impl Error for QrCodeError {}
+
+#[cfg(test)]
+mod tests {
+    use super::{QrCode, QrCodeError, QrCodeMode};
+
+    #[test]
+    fn qr_code_round_trip() {
+        let qr_code = QrCode {
+            first_key: [1; 32],
+            mode: QrCodeMode::VerifyingAnotherUser,
+            second_key: [2; 32],
+            shared_secret: vec![3; 8],
+            transaction_id: "abcd".to_string(),
+        };
+
+        let payload = qr_code.encode().unwrap();
+
+        assert_eq!(&payload[..14], b"MATRIX\x02\x00\x00\x04abcd");
+        assert_eq!(QrCode::decode(&payload).unwrap(), qr_code);
+        assert_eq!(
+            QrCode::decode(&payload[..payload.len() - 1]),
+            Err(QrCodeError::SharedSecretTooShort)
+        );
+    }
+}
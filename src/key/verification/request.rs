@@ -0,0 +1,44 @@
+//! Types for the *m.key.verification.request* event.
+
+use ruma_identifiers::UserId;
+
+use DeviceId;
+use super::VerificationMethod;
+
+event! {
+    /// Requests a key verification with another user's devices.
+    ///
+    /// Typically sent as a to-device event.
+    pub struct RequestEvent(RequestEventContent) {
+        /// The user who sent the event.
+        pub sender: UserId
+    }
+}
+
+/// The payload of a `RequestEvent`.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct RequestEventContent {
+    /// The device ID which is initiating the request.
+    pub from_device: DeviceId,
+
+    /// The verification methods supported by the sender.
+    pub methods: Vec<VerificationMethod>,
+
+    /// The time in milliseconds for when the request was made.
+    ///
+    /// If the request is in the future by more than 5 minutes or more than 10 minutes in the
+    /// past, the message should be ignored by the receiver.
+    pub timestamp: u64,
+
+    /// An opaque identifier for the verification process.
+    ///
+    /// Must be unique with respect to the devices involved.
+    pub transaction_id: String,
+
+    /// Fields that are not covered by the Matrix specification.
+    #[cfg(feature = "preserve-extra-fields")]
+    #[serde(flatten)]
+    pub extra: ::std::collections::BTreeMap<String, ::serde_json::Value>,
+}
+
+impl_event_content!(RequestEventContent, KeyVerificationRequest);
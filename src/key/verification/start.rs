@@ -0,0 +1,219 @@
+//! Types for the *m.key.verification.start* event.
+
+use std::fmt::{Debug, Formatter, Result as FmtResult};
+
+use ruma_identifiers::UserId;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde::de::Error;
+use serde_json::from_value;
+
+use DeviceId;
+use collections::deserialize_limited_value;
+use redact::Redacted;
+use super::{
+    HashAlgorithm,
+    KeyAgreementProtocol,
+    MessageAuthenticationCode,
+    ShortAuthenticationString,
+    VerificationMethod,
+};
+
+event! {
+    /// Begins an SAS key verification process, or acknowledges a QR code scan.
+    ///
+    /// Typically sent as a to-device event.
+    pub struct StartEvent(StartEventContent) {
+        /// The user who sent the event.
+        pub sender: UserId
+    }
+}
+
+/// The payload of a `StartEvent`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum StartEventContent {
+    /// The *m.reciprocate.v1* verification method, acknowledging that a QR code was scanned.
+    ReciprocateV1(ReciprocateV1StartEventContent),
+
+    /// The *m.sas.v1* verification method.
+    SasV1(SasV1StartEventContent),
+}
+
+impl_event_content!(StartEventContent, KeyVerificationStart);
+
+impl StartEventContent {
+    /// The opaque identifier for the verification process.
+    pub fn transaction_id(&self) -> &str {
+        match *self {
+            StartEventContent::ReciprocateV1(ref content) => &content.transaction_id,
+            StartEventContent::SasV1(ref content) => &content.transaction_id,
+        }
+    }
+}
+
+/// The payload of a `StartEvent` using the *m.reciprocate.v1* method.
+///
+/// The `Debug` output redacts `secret`.
+#[derive(Clone, Deserialize, PartialEq, Serialize)]
+pub struct ReciprocateV1StartEventContent {
+    /// The device ID which is initiating the process.
+    pub from_device: DeviceId,
+
+    /// The verification method to use. Always *m.reciprocate.v1*.
+    pub method: VerificationMethod,
+
+    /// The shared secret from the QR code, encoded as unpadded base64.
+    pub secret: String,
+
+    /// An opaque identifier for the verification process.
+    ///
+    /// Must be unique with respect to the devices involved.
+    pub transaction_id: String,
+
+    /// Fields that are not covered by the Matrix specification.
+    #[cfg(feature = "preserve-extra-fields")]
+    #[serde(flatten)]
+    pub extra: ::std::collections::BTreeMap<String, ::serde_json::Value>,
+}
+
+impl Debug for ReciprocateV1StartEventContent {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        let mut debug = f.debug_struct("ReciprocateV1StartEventContent");
+        debug
+            .field("from_device", &self.from_device)
+            .field("method", &self.method)
+            .field("secret", &Redacted)
+            .field("transaction_id", &self.transaction_id);
+        #[cfg(feature = "preserve-extra-fields")]
+        debug.field("extra", &self.extra);
+
+        debug.finish()
+    }
+}
+
+/// The payload of a `StartEvent` using the *m.sas.v1* method.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct SasV1StartEventContent {
+    /// The device ID which is initiating the process.
+    pub from_device: DeviceId,
+
+    /// The hash methods the sending device understands.
+    ///
+    /// Must include at least *sha256*.
+    pub hashes: Vec<HashAlgorithm>,
+
+    /// The key agreement protocols the sending device understands.
+    ///
+    /// Must include at least *curve25519*.
+    pub key_agreement_protocols: Vec<KeyAgreementProtocol>,
+
+    /// The message authentication codes that the sending device understands.
+    ///
+    /// Must include at least *hkdf-hmac-sha256*.
+    pub message_authentication_codes: Vec<MessageAuthenticationCode>,
+
+    /// The verification method to use. Always *m.sas.v1*.
+    pub method: VerificationMethod,
+
+    /// The SAS methods the sending device (and the sending device's user) understands.
+    ///
+    /// Must include at least *decimal*.
+    pub short_authentication_string: Vec<ShortAuthenticationString>,
+
+    /// An opaque identifier for the verification process.
+    ///
+    /// Must be unique with respect to the devices involved.
+    pub transaction_id: String,
+
+    /// Fields that are not covered by the Matrix specification.
+    #[cfg(feature = "preserve-extra-fields")]
+    #[serde(flatten)]
+    pub extra: ::std::collections::BTreeMap<String, ::serde_json::Value>,
+}
+
+impl Serialize for StartEventContent {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
+        match *self {
+            StartEventContent::ReciprocateV1(ref content) => content.serialize(serializer),
+            StartEventContent::SasV1(ref content) => content.serialize(serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for StartEventContent {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where D: Deserializer<'de> {
+        let value = deserialize_limited_value(deserializer)?;
+
+        let method_value = match value.get("method") {
+            Some(value) => value,
+            None => return Err(D::Error::missing_field("method")),
+        };
+
+        let method = match VerificationMethod::deserialize(method_value) {
+            Ok(method) => method,
+            Err(error) => return Err(D::Error::custom(error.to_string())),
+        };
+
+        match method {
+            VerificationMethod::ReciprocateV1 => {
+                let content = match from_value::<ReciprocateV1StartEventContent>(value) {
+                    Ok(content) => content,
+                    Err(error) => return Err(D::Error::custom(error.to_string())),
+                };
+
+                Ok(StartEventContent::ReciprocateV1(content))
+            }
+            VerificationMethod::SasV1 => {
+                let content = match from_value::<SasV1StartEventContent>(value) {
+                    Ok(content) => content,
+                    Err(error) => return Err(D::Error::custom(error.to_string())),
+                };
+
+                Ok(StartEventContent::SasV1(content))
+            }
+            method => {
+                Err(D::Error::custom(format!("unsupported verification method {}", method)))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{from_str, to_string};
+
+    use super::super::{HashAlgorithm, ShortAuthenticationString};
+    use super::StartEventContent;
+
+    #[test]
+    fn sas_start_round_trip() {
+        let json = concat!(
+            r#"{"from_device":"BobDevice1","hashes":["sha256"],"#,
+            r#""key_agreement_protocols":["curve25519"],"#,
+            r#""message_authentication_codes":["hkdf-hmac-sha256"],"method":"m.sas.v1","#,
+            r#""short_authentication_string":["decimal","emoji"],"transaction_id":"S0meUniqueId"}"#
+        );
+
+        let content = from_str::<StartEventContent>(json).unwrap();
+
+        match content {
+            StartEventContent::SasV1(ref content) => {
+                assert_eq!(content.hashes, vec![HashAlgorithm::Sha256]);
+                assert_eq!(
+                    content.short_authentication_string,
+                    vec![ShortAuthenticationString::Decimal, ShortAuthenticationString::Emoji]
+                );
+            }
+            _ => unreachable!(),
+        }
+
+        assert_eq!(content.transaction_id(), "S0meUniqueId");
+        assert_eq!(to_string(&content).unwrap(), json);
+    }
+
+    #[test]
+    fn unsupported_method_is_rejected() {
+        let json = r#"{"from_device":"BobDevice1","method":"m.qr_code.show.v1"}"#;
+
+        assert!(from_str::<StartEventContent>(json).is_err());
+    }
+}
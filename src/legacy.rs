@@ -0,0 +1,159 @@
+//! Tolerant parsing of events in historical formats.
+//!
+//! Events created by early homeservers, as found in old archives and version 1 and 2 rooms, carry
+//! top-level fields that later versions of the specification moved into `unsigned` or dropped
+//! altogether, and may name the sender `user_id`. `from_legacy_value` normalizes such events
+//! before deserializing them, keeping the fields that have no place in the current format in
+//! `LegacyFields` instead of dropping them.
+
+use serde::Deserialize;
+use serde_json::{Error as JsonError, Map, Value, from_value};
+
+/// Top-level fields that have moved into `unsigned` in later versions of the specification.
+///
+/// `invite_room_state` and `prev_content` are left alone, since the event types of this crate
+/// still expect them at the top level.
+const UNSIGNED_FIELDS: &[&str] = &["age", "replaces_state"];
+
+/// Fields of historical events that have no equivalent in the current event format.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct LegacyFields {
+    /// The references to the events that authorize this event.
+    #[serde(skip_serializing_if="Option::is_none")]
+    pub auth_events: Option<Value>,
+    /// The depth of the event in the room's event graph.
+    #[serde(skip_serializing_if="Option::is_none")]
+    pub depth: Option<Value>,
+    /// The content hashes of the event.
+    #[serde(skip_serializing_if="Option::is_none")]
+    pub hashes: Option<Value>,
+    /// The top-level `membership` of *m.room.member* events, duplicating the one in `content`.
+    #[serde(skip_serializing_if="Option::is_none")]
+    pub membership: Option<Value>,
+    /// The name of the homeserver that created the event.
+    #[serde(skip_serializing_if="Option::is_none")]
+    pub origin: Option<Value>,
+    /// The references to the most recent events in the room when this event was created.
+    #[serde(skip_serializing_if="Option::is_none")]
+    pub prev_events: Option<Value>,
+    /// The references to the previous state events for the same state key.
+    #[serde(skip_serializing_if="Option::is_none")]
+    pub prev_state: Option<Value>,
+}
+
+impl LegacyFields {
+    /// Whether the event did not have any legacy fields.
+    pub fn is_empty(&self) -> bool {
+        self == &LegacyFields::default()
+    }
+}
+
+/// Deserializes an event that may be in a historical format.
+///
+/// Before deserializing, top-level fields that now belong in `unsigned` are moved there, unless
+/// `unsigned` already has them, and a `user_id` field is used as `sender` if the event has no
+/// `sender`. Fields without an equivalent in the current format are returned alongside the event.
+pub fn from_legacy_value<E>(value: Value) -> Result<(E, LegacyFields), JsonError>
+where E: for<'de> Deserialize<'de> {
+    let mut object = match value {
+        Value::Object(object) => object,
+        value => return Ok((from_value(value)?, LegacyFields::default())),
+    };
+
+    let legacy = LegacyFields {
+        auth_events: object.remove("auth_events"),
+        depth: object.remove("depth"),
+        hashes: object.remove("hashes"),
+        membership: object.remove("membership"),
+        origin: object.remove("origin"),
+        prev_events: object.remove("prev_events"),
+        prev_state: object.remove("prev_state"),
+    };
+
+    let mut moved = Map::new();
+
+    for field in UNSIGNED_FIELDS {
+        if let Some(value) = object.remove(*field) {
+            moved.insert(field.to_string(), value);
+        }
+    }
+
+    if !moved.is_empty() {
+        let unsigned = object.entry("unsigned").or_insert_with(|| Value::Object(Map::new()));
+
+        if let Value::Object(ref mut unsigned) = *unsigned {
+            for (field, value) in moved {
+                unsigned.entry(field).or_insert(value);
+            }
+        }
+    }
+
+    if !object.contains_key("sender") {
+        if let Some(user_id) = object.remove("user_id") {
+            object.insert("sender".to_string(), user_id);
+        }
+    }
+
+    Ok((from_value(Value::Object(object))?, legacy))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::from_str;
+
+    use RoomEvent;
+    use room::member::{MemberEvent, MembershipState};
+    use super::from_legacy_value;
+
+    #[test]
+    fn legacy_member_event() {
+        let value = from_str(r#"{
+            "age": 1234,
+            "content": {"membership": "join"},
+            "event_id": "$h29iv0s8:example.com",
+            "membership": "join",
+            "origin_server_ts": 1,
+            "prev_state": [["$a08fa3:example.com", {"sha256": "abc"}]],
+            "room_id": "!n8f893n9:example.com",
+            "state_key": "@carl:example.com",
+            "type": "m.room.member",
+            "user_id": "@carl:example.com"
+        }"#).unwrap();
+
+        let (event, legacy) = from_legacy_value::<MemberEvent>(value).unwrap();
+
+        assert_eq!(event.content.membership, MembershipState::Join);
+        assert_eq!(event.user_id().to_string(), "@carl:example.com");
+        assert_eq!(event.unsigned().unwrap()["age"], 1234);
+        assert_eq!(legacy.membership.unwrap(), "join");
+        assert!(legacy.prev_state.unwrap().is_array());
+    }
+
+    #[test]
+    fn legacy_federation_fields() {
+        let value = from_str(r#"{
+            "auth_events": [["$a08fa3:example.com", {"sha256": "abc"}]],
+            "content": {"membership": "join"},
+            "depth": 12,
+            "event_id": "$h29iv0s8:example.com",
+            "hashes": {"sha256": "def"},
+            "origin": "example.com",
+            "origin_server_ts": 1,
+            "prev_events": [["$b19gb4:example.com", {"sha256": "ghi"}]],
+            "room_id": "!n8f893n9:example.com",
+            "sender": "@carl:example.com",
+            "state_key": "@carl:example.com",
+            "type": "m.room.member"
+        }"#).unwrap();
+
+        let (_, legacy) = from_legacy_value::<MemberEvent>(value).unwrap();
+
+        assert!(!legacy.is_empty());
+        assert_eq!(legacy.depth.unwrap(), 12);
+        assert_eq!(legacy.hashes.unwrap()["sha256"], "def");
+        assert_eq!(legacy.origin.unwrap(), "example.com");
+        assert!(legacy.auth_events.unwrap().is_array());
+        assert!(legacy.prev_events.unwrap().is_array());
+        assert!(legacy.membership.is_none());
+    }
+}
@@ -33,6 +33,8 @@
 //!     the context of a Matrix room.
 //!     They have at least the following additional keys:
 //!     *   `event_id`, which is a unique identifier for the event
+//!     *   `origin_server_ts`, which is a timestamp in milliseconds on the originating homeserver
+//!         when the event was sent
 //!     *   `room_id`, which is a unique identifier for the room in which the event occurred
 //!     *   `sender`, which is the unique identifier of the Matrix user who created the event
 //!     *   Optionally, `unsigned`, which is a JSON object containing arbitrary additional metadata
@@ -94,56 +96,289 @@
 //! that event type is both an event and a room event.
 //! However, the `ruma_events::collections::only::Event` enum does *not* include *m.room.message*,
 //! because *m.room.message* implements a *more specific* event trait than `Event`.
+//!
+//! # Cargo features
+//!
+//! Families of event types can be left out to reduce compile times and binary size. Each of the
+//! following features is enabled by default and compiles the modules of its family, along with
+//! the corresponding `EventType` variants and the variants of the collection and content enums:
+//!
+//! *   `events-call`: the *m.call.\** events of VoIP calls.
+//! *   `events-encryption`: the events of end-to-end encryption, i.e. *m.room.encrypted*, the
+//!     *m.key.verification.\** events, *m.room_key*, *m.room_key_request*,
+//!     *m.forwarded_room_key*, the *m.secret.\** events and *m.megolm_backup.v1*. The
+//!     *m.room.encryption* state event, which only records that a room is encrypted, is always
+//!     available.
+//! *   `events-policy`: the *m.policy.rule.\** events of moderation policy lists.
+//! *   `events-poll`: the *m.poll.\** events of polls, as proposed in MSC3381.
+//!
+//! Events of a family that is left out deserialize as custom events. Without `events-encryption`,
+//! *m.room.message* content with the *m.key.verification.request* msgtype is rejected like any
+//! other unknown msgtype. Since another crate in the
+//! dependency graph can enable a feature, the enums whose variants depend on these features are
+//! `#[non_exhaustive]`, and matching on them requires a wildcard arm.
 
 #![deny(missing_debug_implementations)]
 #![deny(missing_docs)]
 #![deny(warnings)]
+#![allow(clippy::doc_lazy_continuation)]
 
+#[cfg(feature = "rayon")]
+extern crate rayon;
 extern crate ruma_identifiers;
 extern crate ruma_signatures;
 extern crate serde;
 #[macro_use] extern crate serde_derive;
 extern crate serde_json;
 
+use std::cmp::Ordering;
+use std::error::Error;
 use std::fmt::{Debug, Display, Formatter, Error as FmtError, Result as FmtResult};
 
-use ruma_identifiers::{EventId, RoomId, UserId};
+use ruma_identifiers::{EventId, RoomAliasId, RoomId, UserId};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use serde::de::{Error as SerdeError, Visitor};
 use serde_json::Value;
 
+use int::{Int, UInt};
+use intern::InternedStr;
+use room::power_levels::PowerLevelsEventContent;
+
+pub use device_id::DeviceId;
+
 #[macro_use] mod macros;
+mod glob;
+mod redact;
 
+#[cfg(feature = "events-call")]
 pub mod call;
-/// Enums for heterogeneous collections of events.
-pub mod collections {
-    pub mod all;
-    pub mod only;
-}
+pub mod clock;
+pub mod collections;
+pub mod content;
+pub mod delta;
+pub mod device_id;
+pub mod direct;
+pub mod edu;
+#[cfg(feature = "unstable-msc1767")]
+pub mod extensible;
+#[cfg(feature = "events-encryption")]
+pub mod forwarded_room_key;
+pub mod fully_read;
+pub mod ignored_user_list;
+pub mod initial_state;
+pub mod int;
+pub mod intern;
+pub mod journal;
+#[cfg(feature = "events-encryption")]
+pub mod key;
+pub mod legacy;
+pub mod migrate;
+pub mod mxc_uri;
+pub mod ordering;
+#[cfg(feature = "events-policy")]
+pub mod policy;
+#[cfg(feature = "events-poll")]
+pub mod poll;
+pub mod prelude;
 pub mod presence;
+pub mod preset;
+pub mod push_rules;
+pub mod queue;
+pub mod reaction;
 pub mod receipt;
 pub mod room;
+#[cfg(feature = "events-encryption")]
+pub mod room_key;
+#[cfg(feature = "events-encryption")]
+pub mod room_key_request;
+pub mod room_version;
+#[cfg(feature = "events-encryption")]
+pub mod secret;
+pub mod space;
+pub mod sticker;
+pub mod storage;
 pub mod stripped;
 pub mod tag;
+pub mod timeline;
 pub mod typing;
+pub mod validation;
 
 /// An error when attempting to convert a string to an enum that only accepts certain values.
 #[derive(Clone, Copy, Debug)]
 pub struct ParseError;
 
+/// An error when event content breaks a rule of the specification that its type cannot express.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ContentBuildError {
+    /// A room alias belongs to a server other than the one validating it.
+    ForeignAlias {
+        /// The alias that belongs to another server.
+        alias: RoomAliasId,
+        /// The server name of the server the alias belongs to.
+        server_name: String,
+    },
+
+    /// The sender of an event belongs to a server other than the one the event requires.
+    ForeignSender {
+        /// The sender that belongs to another server.
+        sender: UserId,
+        /// The server name of the server the event requires the sender to belong to.
+        server_name: String,
+    },
+
+    /// A demotion would raise the power level of a user.
+    DemotionRaisesLevel {
+        /// The current power level of the user.
+        current: Int,
+        /// The power level the user would be demoted to.
+        level: Int,
+    },
+
+    /// A space child order is longer than 50 characters or has characters outside `\x20` to
+    /// `\x7E`.
+    InvalidSpaceOrder(String),
+
+    /// The acting user's power level is too low to make a change to the power levels of a room.
+    PowerLevelTooLow {
+        /// The power level the change requires.
+        required: Int,
+        /// The power level of the acting user.
+        actual: Int,
+    },
+
+    /// A promotion would lower the power level of a user.
+    PromotionLowersLevel {
+        /// The current power level of the user.
+        current: Int,
+        /// The power level the user would be promoted to.
+        level: Int,
+    },
+
+    /// A room name is longer than 255 bytes.
+    RoomNameTooLong {
+        /// The length of the name in bytes.
+        length: usize,
+    },
+
+    /// A tag order is not between 0 and 1.
+    TagOrderOutOfRange(f64),
+}
+
+impl Display for ContentBuildError {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match *self {
+            ContentBuildError::ForeignAlias { ref alias, ref server_name } => {
+                write!(f, "alias {} belongs to server {}", alias, server_name)
+            }
+            ContentBuildError::ForeignSender { ref sender, ref server_name } => {
+                write!(f, "sender {} does not belong to server {}", sender, server_name)
+            }
+            ContentBuildError::DemotionRaisesLevel { current, level } => {
+                write!(f, "cannot demote a user from power level {} to {}", current, level)
+            }
+            ContentBuildError::InvalidSpaceOrder(ref order) => {
+                write!(f, "space child order {:?} is invalid", order)
+            }
+            ContentBuildError::PowerLevelTooLow { required, actual } => {
+                write!(f, "power level {} is required, but the user only has {}", required, actual)
+            }
+            ContentBuildError::PromotionLowersLevel { current, level } => {
+                write!(f, "cannot promote a user from power level {} to {}", current, level)
+            }
+            ContentBuildError::RoomNameTooLong { length } => {
+                write!(f, "room name is {} bytes long, but at most 255 bytes are allowed", length)
+            }
+            ContentBuildError::TagOrderOutOfRange(order) => {
+                write!(f, "tag order {} is not between 0 and 1", order)
+            }
+        }
+    }
+}
+
+impl Error for ContentBuildError {}
+
 /// The type of an event.
 #[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
 pub enum EventType {
     /// m.call.answer
+    #[cfg(feature = "events-call")]
     CallAnswer,
     /// m.call.candidates
+    #[cfg(feature = "events-call")]
     CallCandidates,
     /// m.call.hangup
+    #[cfg(feature = "events-call")]
     CallHangup,
     /// m.call.invite
+    #[cfg(feature = "events-call")]
     CallInvite,
+    /// m.call.negotiate
+    #[cfg(feature = "events-call")]
+    CallNegotiate,
+    /// m.call.reject
+    #[cfg(feature = "events-call")]
+    CallReject,
+    /// m.call.select_answer
+    #[cfg(feature = "events-call")]
+    CallSelectAnswer,
+    /// m.direct
+    Direct,
+    /// m.forwarded_room_key
+    #[cfg(feature = "events-encryption")]
+    ForwardedRoomKey,
+    /// m.fully_read
+    FullyRead,
+    /// m.ignored_user_list
+    IgnoredUserList,
+    /// m.key.verification.accept
+    #[cfg(feature = "events-encryption")]
+    KeyVerificationAccept,
+    /// m.key.verification.cancel
+    #[cfg(feature = "events-encryption")]
+    KeyVerificationCancel,
+    /// m.key.verification.done
+    #[cfg(feature = "events-encryption")]
+    KeyVerificationDone,
+    /// m.key.verification.key
+    #[cfg(feature = "events-encryption")]
+    KeyVerificationKey,
+    /// m.key.verification.mac
+    #[cfg(feature = "events-encryption")]
+    KeyVerificationMac,
+    /// m.key.verification.request
+    #[cfg(feature = "events-encryption")]
+    KeyVerificationRequest,
+    /// m.key.verification.start
+    #[cfg(feature = "events-encryption")]
+    KeyVerificationStart,
+    /// m.megolm_backup.v1
+    #[cfg(feature = "events-encryption")]
+    MegolmBackupV1,
+    /// m.policy.rule.room
+    #[cfg(feature = "events-policy")]
+    PolicyRuleRoom,
+    /// m.policy.rule.server
+    #[cfg(feature = "events-policy")]
+    PolicyRuleServer,
+    /// m.policy.rule.user
+    #[cfg(feature = "events-policy")]
+    PolicyRuleUser,
+    /// m.poll.end
+    #[cfg(feature = "events-poll")]
+    PollEnd,
+    /// m.poll.response
+    #[cfg(feature = "events-poll")]
+    PollResponse,
+    /// m.poll.start
+    #[cfg(feature = "events-poll")]
+    PollStart,
     /// m.presence
     Presence,
+    /// m.push_rules
+    PushRules,
+    /// m.reaction
+    Reaction,
     /// m.receipt
     Receipt,
     /// m.room.aliases
@@ -154,32 +389,61 @@ pub enum EventType {
     RoomCanonicalAlias,
     /// m.room.create
     RoomCreate,
+    /// m.room.encrypted
+    #[cfg(feature = "events-encryption")]
+    RoomEncrypted,
+    /// m.room.encryption
+    RoomEncryption,
     /// m.room.guest_access
     RoomGuestAccess,
     /// m.room.history_visibility
     RoomHistoryVisibility,
     /// m.room.join_rules
     RoomJoinRules,
+    /// m.room_key
+    #[cfg(feature = "events-encryption")]
+    RoomKey,
+    /// m.room_key_request
+    #[cfg(feature = "events-encryption")]
+    RoomKeyRequest,
     /// m.room.member
     RoomMember,
     /// m.room.message
     RoomMessage,
     /// m.room.name
     RoomName,
+    /// m.room.pinned_events
+    RoomPinnedEvents,
     /// m.room.power_levels
     RoomPowerLevels,
     /// m.room.redaction
     RoomRedaction,
+    /// m.room.server_acl
+    RoomServerAcl,
     /// m.room.third_party_invite
     RoomThirdPartyInvite,
+    /// m.room.tombstone
+    RoomTombstone,
     /// m.room.topic
     RoomTopic,
+    /// m.secret.request
+    #[cfg(feature = "events-encryption")]
+    SecretRequest,
+    /// m.secret.send
+    #[cfg(feature = "events-encryption")]
+    SecretSend,
+    /// m.space.child
+    SpaceChild,
+    /// m.space.parent
+    SpaceParent,
+    /// m.sticker
+    Sticker,
     /// m.tag
     Tag,
     /// m.typing
     Typing,
     /// Any event that is not part of the specification.
-    Custom(String),
+    Custom(InternedStr),
 }
 
 /// A basic event.
@@ -200,11 +464,46 @@ pub trait Event where Self: Debug + for<'a> Deserialize<'a> + Serialize {
     }
 }
 
+/// The payload of events of a specific type.
+pub trait EventContent where Self: Debug + for<'a> Deserialize<'a> + Serialize {
+    /// The type of the events that carry this content.
+    fn event_type() -> EventType;
+}
+
+/// The payload of state events of a specific type, with the type of their state key.
+///
+/// State events whose state key must be empty use `EmptyStateKey`, those keyed by a user use
+/// `UserId`.
+pub trait StaticStateEventContent: EventContent {
+    /// The type of the state key of events that carry this content.
+    type StateKey: Display;
+}
+
+/// The payload of events that are sent into a room, whose sender needs a minimum power level.
+pub trait RoomEventContent: EventContent {
+    /// The power level a user needs to send an event with this content, according to the power
+    /// levels of the room.
+    fn required_power_level(&self, power_levels: &PowerLevelsEventContent) -> Int;
+}
+
+/// The state key of state events whose state key is always an empty string.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct EmptyStateKey;
+
+impl Display for EmptyStateKey {
+    fn fmt(&self, _: &mut Formatter) -> FmtResult {
+        Ok(())
+    }
+}
+
 /// An event within the context of a room.
 pub trait RoomEvent: Event {
     /// The unique identifier for the event.
     fn event_id(&self) -> &EventId;
 
+    /// Timestamp in milliseconds on originating homeserver when this event was sent.
+    fn origin_server_ts(&self) -> UInt;
+
     /// The unique identifier for the room associated with this event.
     fn room_id(&self) -> &RoomId;
 
@@ -226,77 +525,281 @@ pub trait StateEvent: RoomEvent {
 
 event! {
     /// A custom basic event not covered by the Matrix specification.
-    pub struct CustomEvent(Value) {}
+    ///
+    /// The `Debug` output redacts well-known secrets, such as `access_token`, in the content.
+    masked pub struct CustomEvent(Value) {}
 }
 
 room_event! {
     /// A custom room event not covered by the Matrix specification.
-    pub struct CustomRoomEvent(Value) {}
+    ///
+    /// The `Debug` output redacts well-known secrets, such as `access_token`, in the content and
+    /// `unsigned`.
+    masked pub struct CustomRoomEvent(Value) {}
 }
 
 state_event! {
     /// A custom state event not covered by the Matrix specification.
-    pub struct CustomStateEvent(Value) {}
+    ///
+    /// The `Debug` output redacts well-known secrets, such as `access_token`, in the content, the
+    /// previous content and `unsigned`.
+    masked pub struct CustomStateEvent(Value) {}
 }
 
-impl Display for EventType {
-    fn fmt(&self, f: &mut Formatter) -> Result<(), FmtError> {
-        let event_type_str = match *self {
+impl EventType {
+    /// The string representation of this event type, e.g. "m.room.member".
+    pub fn as_str(&self) -> &str {
+        match *self {
+            #[cfg(feature = "events-call")]
             EventType::CallAnswer => "m.call.answer",
+            #[cfg(feature = "events-call")]
             EventType::CallCandidates => "m.call.candidates",
+            #[cfg(feature = "events-call")]
             EventType::CallHangup => "m.call.hangup",
+            #[cfg(feature = "events-call")]
             EventType::CallInvite => "m.call.invite",
+            #[cfg(feature = "events-call")]
+            EventType::CallNegotiate => "m.call.negotiate",
+            #[cfg(feature = "events-call")]
+            EventType::CallReject => "m.call.reject",
+            #[cfg(feature = "events-call")]
+            EventType::CallSelectAnswer => "m.call.select_answer",
+            EventType::Direct => "m.direct",
+            #[cfg(feature = "events-encryption")]
+            EventType::ForwardedRoomKey => "m.forwarded_room_key",
+            EventType::FullyRead => "m.fully_read",
+            EventType::IgnoredUserList => "m.ignored_user_list",
+            #[cfg(feature = "events-encryption")]
+            EventType::KeyVerificationAccept => "m.key.verification.accept",
+            #[cfg(feature = "events-encryption")]
+            EventType::KeyVerificationCancel => "m.key.verification.cancel",
+            #[cfg(feature = "events-encryption")]
+            EventType::KeyVerificationDone => "m.key.verification.done",
+            #[cfg(feature = "events-encryption")]
+            EventType::KeyVerificationKey => "m.key.verification.key",
+            #[cfg(feature = "events-encryption")]
+            EventType::KeyVerificationMac => "m.key.verification.mac",
+            #[cfg(feature = "events-encryption")]
+            EventType::KeyVerificationRequest => "m.key.verification.request",
+            #[cfg(feature = "events-encryption")]
+            EventType::KeyVerificationStart => "m.key.verification.start",
+            #[cfg(feature = "events-encryption")]
+            EventType::MegolmBackupV1 => "m.megolm_backup.v1",
+            #[cfg(feature = "events-policy")]
+            EventType::PolicyRuleRoom => "m.policy.rule.room",
+            #[cfg(feature = "events-policy")]
+            EventType::PolicyRuleServer => "m.policy.rule.server",
+            #[cfg(feature = "events-policy")]
+            EventType::PolicyRuleUser => "m.policy.rule.user",
+            #[cfg(feature = "events-poll")]
+            EventType::PollEnd => "m.poll.end",
+            #[cfg(feature = "events-poll")]
+            EventType::PollResponse => "m.poll.response",
+            #[cfg(feature = "events-poll")]
+            EventType::PollStart => "m.poll.start",
             EventType::Presence => "m.presence",
+            EventType::PushRules => "m.push_rules",
+            EventType::Reaction => "m.reaction",
             EventType::Receipt => "m.receipt",
             EventType::RoomAliases => "m.room.aliases",
             EventType::RoomAvatar => "m.room.avatar",
             EventType::RoomCanonicalAlias => "m.room.canonical_alias",
             EventType::RoomCreate => "m.room.create",
+            #[cfg(feature = "events-encryption")]
+            EventType::RoomEncrypted => "m.room.encrypted",
+            EventType::RoomEncryption => "m.room.encryption",
             EventType::RoomGuestAccess => "m.room.guest_access",
             EventType::RoomHistoryVisibility => "m.room.history_visibility",
             EventType::RoomJoinRules => "m.room.join_rules",
+            #[cfg(feature = "events-encryption")]
+            EventType::RoomKey => "m.room_key",
+            #[cfg(feature = "events-encryption")]
+            EventType::RoomKeyRequest => "m.room_key_request",
             EventType::RoomMember => "m.room.member",
             EventType::RoomMessage => "m.room.message",
             EventType::RoomName => "m.room.name",
+            EventType::RoomPinnedEvents => "m.room.pinned_events",
             EventType::RoomPowerLevels => "m.room.power_levels",
             EventType::RoomRedaction => "m.room.redaction",
+            EventType::RoomServerAcl => "m.room.server_acl",
             EventType::RoomThirdPartyInvite => "m.room.third_party_invite",
+            EventType::RoomTombstone => "m.room.tombstone",
             EventType::RoomTopic => "m.room.topic",
+            #[cfg(feature = "events-encryption")]
+            EventType::SecretRequest => "m.secret.request",
+            #[cfg(feature = "events-encryption")]
+            EventType::SecretSend => "m.secret.send",
+            EventType::SpaceChild => "m.space.child",
+            EventType::SpaceParent => "m.space.parent",
+            EventType::Sticker => "m.sticker",
             EventType::Tag => "m.tag",
             EventType::Typing => "m.typing",
             EventType::Custom(ref event_type) => event_type,
-        };
+        }
+    }
+
+    /// The family of this event type, e.g. "m.room" for *m.room.member* and
+    /// *m.room.message.feedback*, "m.key" for *m.key.verification.start* or "m" for *m.tag*.
+    ///
+    /// The family of a custom event type is its reverse-domain prefix, e.g. "com.example" for
+    /// *com.example.foo.bar*.
+    pub fn namespace(&self) -> &str {
+        match *self {
+            #[cfg(feature = "events-call")]
+            EventType::CallAnswer | EventType::CallCandidates | EventType::CallHangup |
+            EventType::CallInvite | EventType::CallNegotiate | EventType::CallReject |
+            EventType::CallSelectAnswer => "m.call",
+            #[cfg(feature = "events-encryption")]
+            EventType::KeyVerificationAccept | EventType::KeyVerificationCancel |
+            EventType::KeyVerificationDone | EventType::KeyVerificationKey |
+            EventType::KeyVerificationMac | EventType::KeyVerificationRequest |
+            EventType::KeyVerificationStart => "m.key",
+            #[cfg(feature = "events-policy")]
+            EventType::PolicyRuleRoom | EventType::PolicyRuleServer |
+            EventType::PolicyRuleUser => "m.policy",
+            #[cfg(feature = "events-poll")]
+            EventType::PollEnd | EventType::PollResponse | EventType::PollStart => "m.poll",
+            #[cfg(feature = "events-encryption")]
+            EventType::RoomEncrypted => "m.room",
+            EventType::RoomAliases | EventType::RoomAvatar | EventType::RoomCanonicalAlias |
+            EventType::RoomCreate | EventType::RoomEncryption | EventType::RoomGuestAccess |
+            EventType::RoomHistoryVisibility | EventType::RoomJoinRules | EventType::RoomMember |
+            EventType::RoomMessage | EventType::RoomName | EventType::RoomPinnedEvents |
+            EventType::RoomPowerLevels | EventType::RoomRedaction | EventType::RoomServerAcl |
+            EventType::RoomThirdPartyInvite | EventType::RoomTombstone |
+            EventType::RoomTopic => "m.room",
+            #[cfg(feature = "events-encryption")]
+            EventType::SecretRequest | EventType::SecretSend => "m.secret",
+            EventType::SpaceChild | EventType::SpaceParent => "m.space",
+            #[cfg(feature = "events-encryption")]
+            EventType::ForwardedRoomKey | EventType::MegolmBackupV1 | EventType::RoomKey |
+            EventType::RoomKeyRequest => "m",
+            EventType::Direct | EventType::FullyRead | EventType::IgnoredUserList |
+            EventType::Presence | EventType::PushRules | EventType::Reaction | EventType::Receipt |
+            EventType::Sticker | EventType::Tag | EventType::Typing => "m",
+            EventType::Custom(ref event_type) => {
+                if event_type.starts_with("m.") && event_type.matches('.').count() < 2 {
+                    "m"
+                } else {
+                    // The first two segments, e.g. "m.room" or the reverse domain "com.example".
+                    match event_type.match_indices('.').nth(1) {
+                        Some((index, _)) => &event_type[..index],
+                        None => event_type,
+                    }
+                }
+            }
+        }
+    }
+}
 
-        write!(f, "{}", event_type_str)
+impl Display for EventType {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), FmtError> {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Event types are ordered by their string representation, so that maps keyed by event type
+/// serialize in the same order as the corresponding JSON object in canonical JSON.
+impl Ord for EventType {
+    fn cmp(&self, other: &EventType) -> Ordering {
+        self.as_str().cmp(other.as_str())
+    }
+}
+
+impl PartialOrd for EventType {
+    fn partial_cmp(&self, other: &EventType) -> Option<Ordering> {
+        Some(self.cmp(other))
     }
 }
 
 impl<'a> From<&'a str> for EventType {
     fn from(s: &'a str) -> EventType {
         match s {
+            #[cfg(feature = "events-call")]
             "m.call.answer" => EventType::CallAnswer,
+            #[cfg(feature = "events-call")]
             "m.call.candidates" => EventType::CallCandidates,
+            #[cfg(feature = "events-call")]
             "m.call.hangup" => EventType::CallHangup,
+            #[cfg(feature = "events-call")]
             "m.call.invite" => EventType::CallInvite,
+            #[cfg(feature = "events-call")]
+            "m.call.negotiate" => EventType::CallNegotiate,
+            #[cfg(feature = "events-call")]
+            "m.call.reject" => EventType::CallReject,
+            #[cfg(feature = "events-call")]
+            "m.call.select_answer" => EventType::CallSelectAnswer,
+            "m.direct" => EventType::Direct,
+            #[cfg(feature = "events-encryption")]
+            "m.forwarded_room_key" => EventType::ForwardedRoomKey,
+            "m.fully_read" => EventType::FullyRead,
+            "m.ignored_user_list" => EventType::IgnoredUserList,
+            #[cfg(feature = "events-encryption")]
+            "m.key.verification.accept" => EventType::KeyVerificationAccept,
+            #[cfg(feature = "events-encryption")]
+            "m.key.verification.cancel" => EventType::KeyVerificationCancel,
+            #[cfg(feature = "events-encryption")]
+            "m.key.verification.done" => EventType::KeyVerificationDone,
+            #[cfg(feature = "events-encryption")]
+            "m.key.verification.key" => EventType::KeyVerificationKey,
+            #[cfg(feature = "events-encryption")]
+            "m.key.verification.mac" => EventType::KeyVerificationMac,
+            #[cfg(feature = "events-encryption")]
+            "m.key.verification.request" => EventType::KeyVerificationRequest,
+            #[cfg(feature = "events-encryption")]
+            "m.key.verification.start" => EventType::KeyVerificationStart,
+            #[cfg(feature = "events-encryption")]
+            "m.megolm_backup.v1" => EventType::MegolmBackupV1,
+            #[cfg(feature = "events-policy")]
+            "m.policy.rule.room" => EventType::PolicyRuleRoom,
+            #[cfg(feature = "events-policy")]
+            "m.policy.rule.server" => EventType::PolicyRuleServer,
+            #[cfg(feature = "events-policy")]
+            "m.policy.rule.user" => EventType::PolicyRuleUser,
+            #[cfg(feature = "events-poll")]
+            "m.poll.end" => EventType::PollEnd,
+            #[cfg(feature = "events-poll")]
+            "m.poll.response" => EventType::PollResponse,
+            #[cfg(feature = "events-poll")]
+            "m.poll.start" => EventType::PollStart,
             "m.presence" => EventType::Presence,
+            "m.push_rules" => EventType::PushRules,
+            "m.reaction" => EventType::Reaction,
             "m.receipt" => EventType::Receipt,
             "m.room.aliases" => EventType::RoomAliases,
             "m.room.avatar" => EventType::RoomAvatar,
             "m.room.canonical_alias" => EventType::RoomCanonicalAlias,
             "m.room.create" => EventType::RoomCreate,
+            #[cfg(feature = "events-encryption")]
+            "m.room.encrypted" => EventType::RoomEncrypted,
+            "m.room.encryption" => EventType::RoomEncryption,
             "m.room.guest_access" => EventType::RoomGuestAccess,
             "m.room.history_visibility" => EventType::RoomHistoryVisibility,
             "m.room.join_rules" => EventType::RoomJoinRules,
+            #[cfg(feature = "events-encryption")]
+            "m.room_key" => EventType::RoomKey,
+            #[cfg(feature = "events-encryption")]
+            "m.room_key_request" => EventType::RoomKeyRequest,
             "m.room.member" => EventType::RoomMember,
             "m.room.message" => EventType::RoomMessage,
             "m.room.name" => EventType::RoomName,
+            "m.room.pinned_events" => EventType::RoomPinnedEvents,
             "m.room.power_levels" => EventType::RoomPowerLevels,
             "m.room.redaction" => EventType::RoomRedaction,
+            "m.room.server_acl" => EventType::RoomServerAcl,
             "m.room.third_party_invite" => EventType::RoomThirdPartyInvite,
+            "m.room.tombstone" => EventType::RoomTombstone,
             "m.room.topic" => EventType::RoomTopic,
+            #[cfg(feature = "events-encryption")]
+            "m.secret.request" => EventType::SecretRequest,
+            #[cfg(feature = "events-encryption")]
+            "m.secret.send" => EventType::SecretSend,
+            "m.space.child" => EventType::SpaceChild,
+            "m.space.parent" => EventType::SpaceParent,
+            "m.sticker" => EventType::Sticker,
             "m.tag" => EventType::Tag,
             "m.typing" => EventType::Typing,
-            event_type => EventType::Custom(event_type.to_string()),
+            event_type => EventType::Custom(InternedStr::new(event_type)),
         }
     }
 }
@@ -344,7 +847,7 @@ mod tests {
     #[test]
     fn custom_event_types_serialize_to_display_form() {
         assert_eq!(
-            to_string(&EventType::Custom("io.ruma.test".to_string())).unwrap(),
+            to_string(&EventType::Custom("io.ruma.test".into())).unwrap(),
             r#""io.ruma.test""#
         );
     }
@@ -361,7 +864,27 @@ mod tests {
     fn custom_event_types_deserialize_from_display_form() {
         assert_eq!(
             from_str::<EventType>(r#""io.ruma.test""#).unwrap(),
-            EventType::Custom("io.ruma.test".to_string())
+            EventType::Custom("io.ruma.test".into())
         )
     }
+    #[test]
+    fn event_type_namespaces() {
+        assert_eq!(EventType::RoomMember.namespace(), "m.room");
+        #[cfg(feature = "events-call")]
+        assert_eq!(EventType::CallInvite.namespace(), "m.call");
+        assert_eq!(EventType::Tag.namespace(), "m");
+        #[cfg(feature = "events-encryption")]
+        assert_eq!(EventType::MegolmBackupV1.namespace(), "m");
+        #[cfg(feature = "events-encryption")]
+        assert_eq!(EventType::KeyVerificationStart.namespace(), "m.key");
+        #[cfg(feature = "events-poll")]
+        assert_eq!(EventType::PollResponse.namespace(), "m.poll");
+        assert_eq!(EventType::from("m.room.message.feedback").namespace(), "m.room");
+        assert_eq!(EventType::from("m.key.verification.ready").namespace(), "m.key");
+        assert_eq!(EventType::from("m.call.sdp_stream_metadata_changed").namespace(), "m.call");
+        assert_eq!(EventType::from("m.unknown").namespace(), "m");
+        assert_eq!(EventType::from("com.example.foo.bar").namespace(), "com.example");
+        assert_eq!(EventType::Custom("io.ruma.test".into()).namespace(), "io.ruma");
+        assert_eq!(EventType::Custom("test".into()).namespace(), "test");
+    }
 }
@@ -1,9 +1,9 @@
 macro_rules! impl_enum {
-    ($name:ident { $($variant:ident => $s:expr,)+ }) => {
+    ($name:ident { $($(#[$attr:meta])* $variant:ident => $s:expr,)+ }) => {
         impl ::std::fmt::Display for $name {
             fn fmt(&self, f: &mut ::std::fmt::Formatter) -> Result<(), ::std::fmt::Error> {
                 let variant = match *self {
-                    $($name::$variant => $s,)*
+                    $($(#[$attr])* $name::$variant => $s,)*
                 };
 
                 write!(f, "{}", variant)
@@ -15,7 +15,7 @@ macro_rules! impl_enum {
 
             fn from_str(s: &str) -> Result<Self, Self::Err> {
                 match s {
-                    $($s => Ok($name::$variant),)*
+                    $($(#[$attr])* $s => Ok($name::$variant),)*
                     _ => Err($crate::ParseError),
                 }
             }
@@ -23,8 +23,123 @@ macro_rules! impl_enum {
     }
 }
 
+macro_rules! impl_string_enum {
+    ($name:ident { $($variant:ident => $s:expr,)+ }) => {
+        impl $name {
+            /// The string representation of this value.
+            pub fn as_str(&self) -> &str {
+                match *self {
+                    $($name::$variant => $s,)*
+                    $name::Custom(ref s) => s,
+                }
+            }
+        }
+
+        impl ::std::fmt::Display for $name {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter) -> Result<(), ::std::fmt::Error> {
+                write!(f, "{}", self.as_str())
+            }
+        }
+
+        impl<'a> From<&'a str> for $name {
+            fn from(s: &'a str) -> $name {
+                match s {
+                    $($s => $name::$variant,)*
+                    s => $name::Custom(s.to_string()),
+                }
+            }
+        }
+
+        impl ::std::str::FromStr for $name {
+            type Err = $crate::ParseError;
+
+            /// Parses the string representation of a value. Unknown values become `Custom`, so
+            /// this never fails.
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                Ok($name::from(s))
+            }
+        }
+
+        impl ::serde::Serialize for $name {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where S: ::serde::Serializer {
+                serializer.serialize_str(self.as_str())
+            }
+        }
+
+        impl<'de> ::serde::Deserialize<'de> for $name {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where D: ::serde::Deserializer<'de> {
+                struct StringEnumVisitor;
+
+                impl<'de> ::serde::de::Visitor<'de> for StringEnumVisitor {
+                    type Value = $name;
+
+                    fn expecting(&self, formatter: &mut ::std::fmt::Formatter)
+                    -> ::std::fmt::Result {
+                        write!(formatter, "a string")
+                    }
+
+                    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+                    where E: ::serde::de::Error {
+                        Ok($name::from(v))
+                    }
+                }
+
+                deserializer.deserialize_str(StringEnumVisitor)
+            }
+        }
+    }
+}
+
+macro_rules! impl_event_content {
+    ($content_type:ty, $variant:ident) => {
+        impl $crate::EventContent for $content_type {
+            fn event_type() -> $crate::EventType {
+                $crate::EventType::$variant
+            }
+        }
+    }
+}
+
+macro_rules! impl_static_state_event_content {
+    ($content_type:ty, $state_key:ty) => {
+        impl $crate::StaticStateEventContent for $content_type {
+            type StateKey = $state_key;
+        }
+    }
+}
+
+macro_rules! impl_room_event_content {
+    (message_like, $content_type:ty) => {
+        impl $crate::RoomEventContent for $content_type {
+            fn required_power_level(
+                &self,
+                power_levels: &$crate::room::power_levels::PowerLevelsEventContent,
+            ) -> $crate::int::Int {
+                let event_type = <$content_type as $crate::EventContent>::event_type();
+
+                power_levels.message_level(&event_type)
+            }
+        }
+    };
+    (state, $content_type:ty) => {
+        impl $crate::RoomEventContent for $content_type {
+            fn required_power_level(
+                &self,
+                power_levels: &$crate::room::power_levels::PowerLevelsEventContent,
+            ) -> $crate::int::Int {
+                let event_type = <$content_type as $crate::EventContent>::event_type();
+
+                power_levels.state_level(&event_type)
+            }
+        }
+    };
+}
+
 macro_rules! event {
-    (   $(#[$attr:meta])*
+    (   @struct [$($derive:ident),*]
+        $(#[$attr:meta])*
         pub struct $name:ident($content_type:ty) {
             $(
                 $(#[$field_attr:meta])*
@@ -33,7 +148,7 @@ macro_rules! event {
         }
     ) => {
         $(#[$attr])*
-        #[derive(Clone, Debug, Deserialize, Serialize)]
+        #[derive($($derive),*)]
         pub struct $name {
             /// The event's content.
             pub content: $content_type,
@@ -49,7 +164,55 @@ macro_rules! event {
         }
 
         impl_event!($name, $content_type);
-    }
+    };
+    (   $(#[$attr:meta])*
+        masked pub struct $name:ident($content_type:ty) {
+            $(
+                $(#[$field_attr:meta])*
+                pub $field_name:ident: $field_type:ty
+            ),*
+        }
+    ) => {
+        event! {
+            @struct [Clone, Deserialize, Serialize]
+            $(#[$attr])*
+            pub struct $name($content_type) {
+                $(
+                    $(#[$field_attr])*
+                    pub $field_name: $field_type
+                ),*
+            }
+        }
+
+        impl ::std::fmt::Debug for $name {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+                f.debug_struct(stringify!($name))
+                    .field("content", &$crate::redact::MaskedJson(&self.content))
+                    .field("event_type", &self.event_type)
+                    $(.field(stringify!($field_name), &self.$field_name))*
+                    .finish()
+            }
+        }
+    };
+    (   $(#[$attr:meta])*
+        pub struct $name:ident($content_type:ty) {
+            $(
+                $(#[$field_attr:meta])*
+                pub $field_name:ident: $field_type:ty
+            ),*
+        }
+    ) => {
+        event! {
+            @struct [Clone, Debug, Deserialize, Serialize]
+            $(#[$attr])*
+            pub struct $name($content_type) {
+                $(
+                    $(#[$field_attr])*
+                    pub $field_name: $field_type
+                ),*
+            }
+        }
+    };
 }
 
 macro_rules! impl_event {
@@ -69,7 +232,8 @@ macro_rules! impl_event {
 }
 
 macro_rules! room_event {
-    (   $(#[$attr:meta])*
+    (   @struct [$($derive:ident),*]
+        $(#[$attr:meta])*
         pub struct $name:ident($content_type:ty) {
             $(
                 $(#[$field_attr:meta])*
@@ -78,7 +242,7 @@ macro_rules! room_event {
         }
     ) => {
         $(#[$attr])*
-        #[derive(Clone, Debug, Deserialize, Serialize)]
+        #[derive($($derive),*)]
         pub struct $name {
             /// The event's content.
             pub content: $content_type,
@@ -90,6 +254,9 @@ macro_rules! room_event {
             #[serde(rename="type")]
             pub event_type: $crate::EventType,
 
+            /// Timestamp in milliseconds on originating homeserver when this event was sent.
+            pub origin_server_ts: $crate::int::UInt,
+
             /// The unique identifier for the room associated with this event.
             pub room_id: ::ruma_identifiers::RoomId,
 
@@ -108,7 +275,60 @@ macro_rules! room_event {
         }
 
         impl_room_event!($name, $content_type);
-    }
+    };
+    (   $(#[$attr:meta])*
+        masked pub struct $name:ident($content_type:ty) {
+            $(
+                $(#[$field_attr:meta])*
+                pub $field_name:ident: $field_type:ty
+            ),*
+        }
+    ) => {
+        room_event! {
+            @struct [Clone, Deserialize, Serialize]
+            $(#[$attr])*
+            pub struct $name($content_type) {
+                $(
+                    $(#[$field_attr])*
+                    pub $field_name: $field_type
+                ),*
+            }
+        }
+
+        impl ::std::fmt::Debug for $name {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+                f.debug_struct(stringify!($name))
+                    .field("content", &$crate::redact::MaskedJson(&self.content))
+                    .field("event_id", &self.event_id)
+                    .field("event_type", &self.event_type)
+                    .field("origin_server_ts", &self.origin_server_ts)
+                    .field("room_id", &self.room_id)
+                    .field("unsigned", &self.unsigned.as_ref().map($crate::redact::MaskedJson))
+                    .field("user_id", &self.user_id)
+                    $(.field(stringify!($field_name), &self.$field_name))*
+                    .finish()
+            }
+        }
+    };
+    (   $(#[$attr:meta])*
+        pub struct $name:ident($content_type:ty) {
+            $(
+                $(#[$field_attr:meta])*
+                pub $field_name:ident: $field_type:ty
+            ),*
+        }
+    ) => {
+        room_event! {
+            @struct [Clone, Debug, Deserialize, Serialize]
+            $(#[$attr])*
+            pub struct $name($content_type) {
+                $(
+                    $(#[$field_attr])*
+                    pub $field_name: $field_type
+                ),*
+            }
+        }
+    };
 }
 
 macro_rules! impl_room_event {
@@ -120,6 +340,10 @@ macro_rules! impl_room_event {
                 &self.event_id
             }
 
+            fn origin_server_ts(&self) -> $crate::int::UInt {
+                self.origin_server_ts
+            }
+
             fn room_id(&self) -> &::ruma_identifiers::RoomId {
                 &self.room_id
             }
@@ -136,7 +360,8 @@ macro_rules! impl_room_event {
 }
 
 macro_rules! state_event {
-    (   $(#[$attr:meta])*
+    (   @struct [$($derive:ident),*]
+        $(#[$attr:meta])*
         pub struct $name:ident($content_type:ty) {
             $(
                 $(#[$field_attr:meta])*
@@ -146,7 +371,7 @@ macro_rules! state_event {
     ) => {
         $(#[$attr])*
         #[allow(missing_docs)]
-        #[derive(Clone, Debug, Deserialize, Serialize)]
+        #[derive($($derive),*)]
         pub struct $name {
             /// The event's content.
             pub content: $content_type,
@@ -162,6 +387,9 @@ macro_rules! state_event {
             #[serde(skip_serializing_if="Option::is_none")]
             pub prev_content: Option<$content_type>,
 
+            /// Timestamp in milliseconds on originating homeserver when this event was sent.
+            pub origin_server_ts: $crate::int::UInt,
+
             /// The unique identifier for the room associated with this event.
             pub room_id: ::ruma_identifiers::RoomId,
 
@@ -183,7 +411,65 @@ macro_rules! state_event {
         }
 
         impl_state_event!($name, $content_type);
-    }
+    };
+    (   $(#[$attr:meta])*
+        masked pub struct $name:ident($content_type:ty) {
+            $(
+                $(#[$field_attr:meta])*
+                pub $field_name:ident: $field_type:ty
+            ),*
+        }
+    ) => {
+        state_event! {
+            @struct [Clone, Deserialize, Serialize]
+            $(#[$attr])*
+            pub struct $name($content_type) {
+                $(
+                    $(#[$field_attr])*
+                    pub $field_name: $field_type
+                ),*
+            }
+        }
+
+        impl ::std::fmt::Debug for $name {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+                f.debug_struct(stringify!($name))
+                    .field("content", &$crate::redact::MaskedJson(&self.content))
+                    .field("event_id", &self.event_id)
+                    .field("event_type", &self.event_type)
+                    .field(
+                        "prev_content",
+                        &self.prev_content.as_ref().map($crate::redact::MaskedJson),
+                    )
+                    .field("origin_server_ts", &self.origin_server_ts)
+                    .field("room_id", &self.room_id)
+                    .field("state_key", &self.state_key)
+                    .field("unsigned", &self.unsigned.as_ref().map($crate::redact::MaskedJson))
+                    .field("user_id", &self.user_id)
+                    $(.field(stringify!($field_name), &self.$field_name))*
+                    .finish()
+            }
+        }
+    };
+    (   $(#[$attr:meta])*
+        pub struct $name:ident($content_type:ty) {
+            $(
+                $(#[$field_attr:meta])*
+                pub $field_name:ident: $field_type:ty
+            ),*
+        }
+    ) => {
+        state_event! {
+            @struct [Clone, Debug, Deserialize, Serialize]
+            $(#[$attr])*
+            pub struct $name($content_type) {
+                $(
+                    $(#[$field_attr])*
+                    pub $field_name: $field_type
+                ),*
+            }
+        }
+    };
 }
 
 macro_rules! impl_state_event {
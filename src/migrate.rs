@@ -0,0 +1,228 @@
+//! Migration of events between room versions.
+//!
+//! Tools that re-publish the events of an archived room into an upgraded room need the events in
+//! the format of the new room version. The migration works on the JSON of an event, so that
+//! events of any type, including custom ones, can be migrated without losing fields.
+
+use std::error::Error;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+use serde_json::{Map, Value};
+
+use room_version::{EventIdFormat, RedactionRules, RoomVersionId, room_version_rules};
+
+/// Top-level keys that survive redactions before room version 11, which are only meaningful to
+/// servers and were dropped from the event format afterwards.
+const PRE_V11_KEYS: &[&str] = &["membership", "origin", "prev_state"];
+
+/// An error when migrating an event between room versions.
+#[derive(Clone, Debug, PartialEq)]
+pub enum MigrationError {
+    /// The event is not a JSON object.
+    NotAnObject,
+    /// A room version is not part of the specification, so its rules are unknown.
+    UnknownRoomVersion(RoomVersionId),
+}
+
+impl Display for MigrationError {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match *self {
+            MigrationError::NotAnObject => write!(f, "event is not a JSON object"),
+            MigrationError::UnknownRoomVersion(ref version) => {
+                write!(f, "room version {} is unknown", version)
+            }
+        }
+    }
+}
+
+impl Error for MigrationError {}
+
+/// Converts the JSON of an event from the format of room version `from` to that of room version
+/// `to`.
+///
+/// The migration:
+///
+/// * converts event IDs in `event_id`, `redacts` and `m.relates_to` between the standard and the
+///   URL-safe base64 alphabet of hash-based event IDs. Event IDs of room versions 1 and 2 cannot be
+///   converted to or from hashes and are kept as they are;
+/// * moves `redacts` of *m.room.redaction* events into or out of the content for room version 11;
+/// * removes `creator` from the content of *m.room.create* events for room version 11, and
+///   restores it from the `sender` for earlier versions;
+/// * drops the top-level `membership`, `origin` and `prev_state` keys for room version 11, which
+///   no longer protects them from redaction.
+///
+/// All other fields are kept as they are.
+pub fn migrate_event(
+    event: Value,
+    from: &RoomVersionId,
+    to: &RoomVersionId,
+) -> Result<Value, MigrationError> {
+    let from_rules = room_version_rules(from)
+        .ok_or_else(|| MigrationError::UnknownRoomVersion(from.clone()))?;
+    let to_rules = room_version_rules(to)
+        .ok_or_else(|| MigrationError::UnknownRoomVersion(to.clone()))?;
+
+    let mut event = match event {
+        Value::Object(event) => event,
+        _ => return Err(MigrationError::NotAnObject),
+    };
+
+    let convert = |id: &mut Value| {
+        convert_event_id(id, from_rules.event_id_format, to_rules.event_id_format)
+    };
+
+    if let Some(event_id) = event.get_mut("event_id") {
+        convert(event_id);
+    }
+    if let Some(redacts) = event.get_mut("redacts") {
+        convert(redacts);
+    }
+    if let Some(content) = event.get_mut("content").and_then(Value::as_object_mut) {
+        if let Some(redacts) = content.get_mut("redacts") {
+            convert(redacts);
+        }
+        if let Some(relates_to) = content.get_mut("m.relates_to") {
+            if let Some(event_id) = relates_to.get_mut("event_id") {
+                convert(event_id);
+            }
+            if let Some(event_id) = relates_to.get_mut("m.in_reply_to")
+                .and_then(|in_reply_to| in_reply_to.get_mut("event_id"))
+            {
+                convert(event_id);
+            }
+        }
+    }
+
+    if from_rules.redacts_in_content != to_rules.redacts_in_content {
+        move_redacts(&mut event, to_rules.redacts_in_content);
+    }
+
+    let from_v11 = from_rules.redaction >= RedactionRules::V11;
+    let to_v11 = to_rules.redaction >= RedactionRules::V11;
+
+    if to_v11 {
+        for key in PRE_V11_KEYS {
+            event.remove(*key);
+        }
+    }
+    if from_v11 != to_v11 && event.get("type").and_then(Value::as_str) == Some("m.room.create") {
+        let sender = event.get("sender").cloned();
+
+        if let Some(content) = event.get_mut("content").and_then(Value::as_object_mut) {
+            if to_v11 {
+                content.remove("creator");
+            } else if let Some(sender) = sender {
+                content.entry("creator").or_insert(sender);
+            }
+        }
+    }
+
+    Ok(Value::Object(event))
+}
+
+/// Converts a hash-based event ID between the standard and the URL-safe base64 alphabet.
+fn convert_event_id(id: &mut Value, from: EventIdFormat, to: EventIdFormat) {
+    let (from_chars, to_chars) = match (from, to) {
+        (EventIdFormat::Base64Hash, EventIdFormat::UrlSafeBase64Hash) => (['+', '/'], ['-', '_']),
+        (EventIdFormat::UrlSafeBase64Hash, EventIdFormat::Base64Hash) => (['-', '_'], ['+', '/']),
+        _ => return,
+    };
+
+    if let Value::String(ref mut id) = *id {
+        *id = id
+            .chars()
+            .map(|c| if c == from_chars[0] {
+                to_chars[0]
+            } else if c == from_chars[1] {
+                to_chars[1]
+            } else {
+                c
+            })
+            .collect();
+    }
+}
+
+/// Moves `redacts` of a redaction event into its content, or out of it.
+fn move_redacts(event: &mut Map<String, Value>, into_content: bool) {
+    if event.get("type").and_then(Value::as_str) != Some("m.room.redaction") {
+        return;
+    }
+
+    if into_content {
+        if let Some(redacts) = event.remove("redacts") {
+            if let Some(content) = event.get_mut("content").and_then(Value::as_object_mut) {
+                content.insert("redacts".to_string(), redacts);
+            }
+        }
+    } else if let Some(redacts) = event
+        .get_mut("content")
+        .and_then(Value::as_object_mut)
+        .and_then(|content| content.remove("redacts"))
+    {
+        event.insert("redacts".to_string(), redacts);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{Value, from_str, json};
+
+    use room_version::RoomVersionId;
+    use super::{MigrationError, migrate_event};
+
+    #[test]
+    fn migrate_redaction_to_v11() {
+        let event = from_str::<Value>(r#"{
+            "content": {
+                "m.relates_to": {"m.in_reply_to": {"event_id": "$a+b/c"}},
+                "reason": "spam"
+            },
+            "event_id": "$d+e/f",
+            "origin": "example.com",
+            "origin_server_ts": 1,
+            "redacts": "$g+h/i",
+            "room_id": "!n8f893n9:example.com",
+            "sender": "@carl:example.com",
+            "type": "m.room.redaction"
+        }"#).unwrap();
+
+        let migrated = migrate_event(event, &RoomVersionId::V3, &RoomVersionId::V11).unwrap();
+
+        assert_eq!(migrated, json!({
+            "content": {
+                "m.relates_to": {"m.in_reply_to": {"event_id": "$a-b_c"}},
+                "reason": "spam",
+                "redacts": "$g-h_i"
+            },
+            "event_id": "$d-e_f",
+            "origin_server_ts": 1,
+            "room_id": "!n8f893n9:example.com",
+            "sender": "@carl:example.com",
+            "type": "m.room.redaction"
+        }));
+
+        let restored = migrate_event(migrated, &RoomVersionId::V11, &RoomVersionId::V3).unwrap();
+        assert_eq!(restored["redacts"], "$g+h/i");
+        assert_eq!(restored["content"].get("redacts"), None);
+    }
+
+    #[test]
+    fn migrate_create_event() {
+        let event = json!({
+            "content": {"creator": "@carl:example.com"},
+            "sender": "@carl:example.com",
+            "type": "m.room.create"
+        });
+
+        let migrated = migrate_event(event, &RoomVersionId::V1, &RoomVersionId::V11).unwrap();
+        assert_eq!(migrated["content"], json!({}));
+
+        let restored = migrate_event(migrated, &RoomVersionId::V11, &RoomVersionId::V10).unwrap();
+        assert_eq!(restored["content"]["creator"], "@carl:example.com");
+
+        assert_eq!(
+            migrate_event(json!({}), &RoomVersionId::V1, &RoomVersionId::from("org.example")),
+            Err(MigrationError::UnknownRoomVersion(RoomVersionId::from("org.example")))
+        );
+    }
+}
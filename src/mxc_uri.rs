@@ -0,0 +1,105 @@
+//! URIs of media stored in the content repository of a homeserver.
+
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::str::FromStr;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde::de::Error as SerdeError;
+
+use ParseError;
+
+/// The URI of a piece of media in the content repository, of the form
+/// `mxc://<server-name>/<media-id>`.
+///
+/// The media ID may only contain the characters `A-Z`, `a-z`, `0-9`, `_` and `-`.
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct MxcUri(String);
+
+impl MxcUri {
+    /// The URI as a string.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// The server name of the homeserver that stores the media.
+    pub fn server_name(&self) -> &str {
+        self.parts().0
+    }
+
+    /// The ID of the media on its homeserver.
+    pub fn media_id(&self) -> &str {
+        self.parts().1
+    }
+
+    fn parts(&self) -> (&str, &str) {
+        split(&self.0).expect("MxcUri should always be valid")
+    }
+}
+
+/// Splits an MXC URI into its server name and media ID, or returns `None` if it is invalid.
+fn split(uri: &str) -> Option<(&str, &str)> {
+    let rest = uri.strip_prefix("mxc://")?;
+    let slash = rest.find('/')?;
+    let (server_name, media_id) = (&rest[..slash], &rest[slash + 1..]);
+    let valid_media_id = !media_id.is_empty() && media_id.chars().all(|c| {
+        c.is_ascii_alphanumeric() || c == '_' || c == '-'
+    });
+
+    if server_name.is_empty() || !valid_media_id {
+        return None;
+    }
+
+    Some((server_name, media_id))
+}
+
+impl FromStr for MxcUri {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match split(s) {
+            Some(_) => Ok(MxcUri(s.to_string())),
+            None => Err(ParseError),
+        }
+    }
+}
+
+impl Display for MxcUri {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Serialize for MxcUri {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for MxcUri {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where D: Deserializer<'de> {
+        let uri = String::deserialize(deserializer)?;
+
+        uri.parse().map_err(|_| D::Error::custom(format!("invalid MXC URI {:?}", uri)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{from_str, to_string};
+
+    use super::MxcUri;
+
+    #[test]
+    fn mxc_uri_parts() {
+        let uri = from_str::<MxcUri>(r#""mxc://example.com/AQwafuaFswefuhsfAFAgsw""#).unwrap();
+
+        assert_eq!(uri.server_name(), "example.com");
+        assert_eq!(uri.media_id(), "AQwafuaFswefuhsfAFAgsw");
+        assert_eq!(to_string(&uri).unwrap(), r#""mxc://example.com/AQwafuaFswefuhsfAFAgsw""#);
+
+        assert!("https://example.com/image.jpg".parse::<MxcUri>().is_err());
+        assert!("mxc://example.com/".parse::<MxcUri>().is_err());
+        assert!("mxc:///media".parse::<MxcUri>().is_err());
+        assert!("mxc://example.com/image.jpg".parse::<MxcUri>().is_err());
+    }
+}
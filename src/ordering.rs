@@ -0,0 +1,109 @@
+//! Consistent ordering of room events using only data available on the events themselves.
+//!
+//! Clients that merge events from different sources, such as backfilled history from `/messages`
+//! and live events from `/sync`, need to order them the same way regardless of where each event
+//! came from.
+
+use std::cmp::Ordering;
+
+use RoomEvent;
+use int::UInt;
+
+/// A key for ordering room events.
+///
+/// Events are ordered by their `origin_server_ts`, then by a stream ordering hint if the server
+/// included one as `stream_ordering` in the event's `unsigned` data, and finally by event ID, so
+/// that the order is total and deterministic.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EventOrdering {
+    event_id: String,
+    origin_server_ts: UInt,
+    stream_hint: Option<u64>,
+}
+
+impl EventOrdering {
+    /// Creates the ordering key of the given event.
+    pub fn of<E>(event: &E) -> Self where E: RoomEvent {
+        EventOrdering {
+            event_id: event.event_id().to_string(),
+            origin_server_ts: event.origin_server_ts(),
+            stream_hint: event.unsigned()
+                .and_then(|unsigned| unsigned.get("stream_ordering"))
+                .and_then(|stream_ordering| stream_ordering.as_u64()),
+        }
+    }
+
+    /// The timestamp this key orders by first.
+    pub fn origin_server_ts(&self) -> UInt {
+        self.origin_server_ts
+    }
+
+    /// The stream ordering hint from the event's `unsigned` data, if any.
+    pub fn stream_hint(&self) -> Option<u64> {
+        self.stream_hint
+    }
+}
+
+impl Ord for EventOrdering {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.origin_server_ts.cmp(&other.origin_server_ts)
+            .then_with(|| self.stream_hint.cmp(&other.stream_hint))
+            .then_with(|| self.event_id.cmp(&other.event_id))
+    }
+}
+
+impl PartialOrd for EventOrdering {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Compares two room events by their `EventOrdering`.
+pub fn compare_events<E>(a: &E, b: &E) -> Ordering where E: RoomEvent {
+    EventOrdering::of(a).cmp(&EventOrdering::of(b))
+}
+
+/// Sorts room events from oldest to newest by their `EventOrdering`.
+pub fn sort_events<E>(events: &mut [E]) where E: RoomEvent {
+    events.sort_by_cached_key(EventOrdering::of);
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::from_str;
+
+    use CustomRoomEvent;
+    use super::sort_events;
+
+    fn event(event_id: &str, origin_server_ts: u64, unsigned: &str) -> CustomRoomEvent {
+        from_str(&format!(r#"{{
+            "content": {{}},
+            "event_id": "{}",
+            "origin_server_ts": {},
+            "room_id": "!n8f893n9:example.com",
+            "sender": "@carl:example.com",
+            "type": "io.ruma.test",
+            "unsigned": {}
+        }}"#, event_id, origin_server_ts, unsigned)).unwrap()
+    }
+
+    #[test]
+    fn events_are_sorted_by_timestamp_then_hint_then_id() {
+        let mut events = vec![
+            event("$c:example.com", 2, "{}"),
+            event("$b:example.com", 1, r#"{"stream_ordering": 7}"#),
+            event("$a:example.com", 1, r#"{"stream_ordering": 8}"#),
+            event("$d:example.com", 1, "{}"),
+        ];
+
+        sort_events(&mut events);
+
+        let event_ids: Vec<String> = events.iter().map(|event| event.event_id.to_string())
+            .collect();
+
+        assert_eq!(
+            event_ids,
+            vec!["$d:example.com", "$b:example.com", "$a:example.com", "$c:example.com"]
+        );
+    }
+}
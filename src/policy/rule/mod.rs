@@ -0,0 +1,88 @@
+//! Modules for events in the *m.policy.rule* namespace.
+//!
+//! This module also contains types shared by events in its child namespaces.
+
+use glob::glob_matches;
+
+pub mod room;
+pub mod server;
+pub mod user;
+
+/// The payload shared by all policy rule events.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct PolicyRuleEventContent {
+    /// The entity affected by this rule. Glob characters `*` and `?` can be used to match zero or
+    /// more characters or exactly one character respectively.
+    pub entity: String,
+    /// The suggested action to take.
+    pub recommendation: Recommendation,
+    /// The human-readable description for the recommendation.
+    pub reason: String,
+    /// Fields that are not covered by the Matrix specification.
+    #[cfg(feature = "preserve-extra-fields")]
+    #[serde(flatten)]
+    pub extra: ::std::collections::BTreeMap<String, ::serde_json::Value>,
+}
+
+impl PolicyRuleEventContent {
+    /// Creates the content of a policy rule.
+    pub fn new(entity: String, recommendation: Recommendation, reason: String) -> Self {
+        PolicyRuleEventContent {
+            entity,
+            recommendation,
+            reason,
+            #[cfg(feature = "preserve-extra-fields")]
+            extra: Default::default(),
+        }
+    }
+
+    /// Whether the rule applies to the given user ID, room ID, room alias or server name.
+    pub fn matches(&self, entity: &str) -> bool {
+        glob_matches(&self.entity, entity)
+    }
+}
+
+/// The action recommended by a policy rule.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum Recommendation {
+    /// The entity should be banned from participating where possible.
+    Ban,
+    /// Any recommendation that is not part of the specification.
+    Custom(String),
+}
+
+impl_string_enum! {
+    Recommendation {
+        Ban => "m.ban",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::from_str;
+
+    use super::Recommendation;
+    use super::user::UserRuleEvent;
+
+    #[test]
+    fn user_rule_matches() {
+        let event = from_str::<UserRuleEvent>(r#"{
+            "content": {
+                "entity": "@alice*:example.org",
+                "reason": "undesirable behaviour",
+                "recommendation": "m.ban"
+            },
+            "event_id": "$143273582443PhrSn:example.org",
+            "origin_server_ts": 1432735824653,
+            "room_id": "!jEsUZKDJdhlrceRyVU:example.org",
+            "sender": "@example:example.org",
+            "state_key": "rule:@alice*:example.org",
+            "type": "m.policy.rule.user"
+        }"#).unwrap();
+
+        assert_eq!(event.content.recommendation, Recommendation::Ban);
+        assert!(event.content.matches("@alice:example.org"));
+        assert!(event.content.matches("@alice2:example.org"));
+        assert!(!event.content.matches("@bob:example.org"));
+    }
+}
@@ -0,0 +1,33 @@
+//! Types for the *m.policy.rule.room* event.
+
+use std::ops::{Deref, DerefMut};
+
+use super::PolicyRuleEventContent;
+
+state_event! {
+    /// A moderation policy rule that applies to rooms.
+    pub struct RoomRuleEvent(RoomRuleEventContent) {}
+}
+
+/// The payload of a `RoomRuleEvent`.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(transparent)]
+pub struct RoomRuleEventContent(pub PolicyRuleEventContent);
+
+impl_event_content!(RoomRuleEventContent, PolicyRuleRoom);
+impl_static_state_event_content!(RoomRuleEventContent, String);
+impl_room_event_content!(state, RoomRuleEventContent);
+
+impl Deref for RoomRuleEventContent {
+    type Target = PolicyRuleEventContent;
+
+    fn deref(&self) -> &PolicyRuleEventContent {
+        &self.0
+    }
+}
+
+impl DerefMut for RoomRuleEventContent {
+    fn deref_mut(&mut self) -> &mut PolicyRuleEventContent {
+        &mut self.0
+    }
+}
@@ -0,0 +1,33 @@
+//! Types for the *m.policy.rule.server* event.
+
+use std::ops::{Deref, DerefMut};
+
+use super::PolicyRuleEventContent;
+
+state_event! {
+    /// A moderation policy rule that applies to servers.
+    pub struct ServerRuleEvent(ServerRuleEventContent) {}
+}
+
+/// The payload of a `ServerRuleEvent`.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(transparent)]
+pub struct ServerRuleEventContent(pub PolicyRuleEventContent);
+
+impl_event_content!(ServerRuleEventContent, PolicyRuleServer);
+impl_static_state_event_content!(ServerRuleEventContent, String);
+impl_room_event_content!(state, ServerRuleEventContent);
+
+impl Deref for ServerRuleEventContent {
+    type Target = PolicyRuleEventContent;
+
+    fn deref(&self) -> &PolicyRuleEventContent {
+        &self.0
+    }
+}
+
+impl DerefMut for ServerRuleEventContent {
+    fn deref_mut(&mut self) -> &mut PolicyRuleEventContent {
+        &mut self.0
+    }
+}
@@ -0,0 +1,33 @@
+//! Types for the *m.policy.rule.user* event.
+
+use std::ops::{Deref, DerefMut};
+
+use super::PolicyRuleEventContent;
+
+state_event! {
+    /// A moderation policy rule that applies to users.
+    pub struct UserRuleEvent(UserRuleEventContent) {}
+}
+
+/// The payload of a `UserRuleEvent`.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(transparent)]
+pub struct UserRuleEventContent(pub PolicyRuleEventContent);
+
+impl_event_content!(UserRuleEventContent, PolicyRuleUser);
+impl_static_state_event_content!(UserRuleEventContent, String);
+impl_room_event_content!(state, UserRuleEventContent);
+
+impl Deref for UserRuleEventContent {
+    type Target = PolicyRuleEventContent;
+
+    fn deref(&self) -> &PolicyRuleEventContent {
+        &self.0
+    }
+}
+
+impl DerefMut for UserRuleEventContent {
+    fn deref_mut(&mut self) -> &mut PolicyRuleEventContent {
+        &mut self.0
+    }
+}
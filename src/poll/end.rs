@@ -0,0 +1,57 @@
+//! Types for the *m.poll.end* event.
+
+use room::relation::Reference;
+
+use super::PollText;
+
+room_event! {
+    /// Ends a poll. Responses sent after the poll ended do not count.
+    pub struct PollEndEvent(PollEndEventContent) {}
+}
+
+/// The payload of a `PollEndEvent`.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct PollEndEventContent {
+    /// The *m.poll.start* event of the poll.
+    #[serde(rename = "m.relates_to")]
+    pub relates_to: Reference,
+    /// A textual fallback for clients that do not support polls, e.g. the results.
+    #[serde(default, rename = "m.text", skip_serializing_if = "Vec::is_empty")]
+    pub text: Vec<PollText>,
+    /// Fields that are not covered by the Matrix specification.
+    #[cfg(feature = "preserve-extra-fields")]
+    #[serde(flatten)]
+    pub extra: ::std::collections::BTreeMap<String, ::serde_json::Value>,
+}
+
+impl_event_content!(PollEndEventContent, PollEnd);
+impl_room_event_content!(message_like, PollEndEventContent);
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{Value, from_str, to_value};
+
+    use collections::all::Event;
+
+    #[test]
+    fn poll_end_round_trip() {
+        let json = concat!(
+            r#"{"content":{"m.relates_to":{"event_id":"$h29iv0s8:example.com","#,
+            r#""rel_type":"m.reference"},"m.text":[{"body":"The poll has ended."}]},"#,
+            r#""event_id":"$a08fa3:example.com","origin_server_ts":1,"#,
+            r#""room_id":"!n8f893n9:example.com","sender":"@carl:example.com","#,
+            r#""type":"m.poll.end"}"#
+        );
+
+        let event = from_str::<Event>(json).unwrap();
+
+        match event {
+            Event::PollEnd(ref event) => {
+                assert_eq!(event.content.text[0].body, "The poll has ended.");
+            }
+            _ => panic!("expected a poll end event"),
+        }
+
+        assert_eq!(to_value(&event).unwrap(), from_str::<Value>(json).unwrap());
+    }
+}
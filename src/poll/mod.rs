@@ -0,0 +1,17 @@
+//! Modules for events in the *m.poll* namespace, as proposed in MSC3381.
+//!
+//! This module also contains types shared by events in its child namespaces.
+
+pub mod end;
+pub mod response;
+pub mod start;
+
+/// A representation of a text of a poll in a specific mimetype.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct PollText {
+    /// The text in this representation.
+    pub body: String,
+    /// The mimetype of the text, *text/plain* if omitted.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mimetype: Option<String>,
+}
@@ -0,0 +1,56 @@
+//! Types for the *m.poll.response* event.
+
+use room::relation::Reference;
+
+room_event! {
+    /// A vote in a poll.
+    ///
+    /// Only the latest response of each user counts.
+    pub struct PollResponseEvent(PollResponseEventContent) {}
+}
+
+/// The payload of a `PollResponseEvent`.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct PollResponseEventContent {
+    /// The *m.poll.start* event of the poll.
+    #[serde(rename = "m.relates_to")]
+    pub relates_to: Reference,
+    /// The IDs of the selected answers.
+    #[serde(rename = "m.selections")]
+    pub selections: Vec<String>,
+    /// Fields that are not covered by the Matrix specification.
+    #[cfg(feature = "preserve-extra-fields")]
+    #[serde(flatten)]
+    pub extra: ::std::collections::BTreeMap<String, ::serde_json::Value>,
+}
+
+impl_event_content!(PollResponseEventContent, PollResponse);
+impl_room_event_content!(message_like, PollResponseEventContent);
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{Value, from_str, to_value};
+
+    use collections::all::Event;
+
+    #[test]
+    fn poll_response_round_trip() {
+        let json = concat!(
+            r#"{"content":{"m.relates_to":{"event_id":"$h29iv0s8:example.com","#,
+            r#""rel_type":"m.reference"},"m.selections":["yes"]},"#,
+            r#""event_id":"$a08fa3:example.com","origin_server_ts":1,"#,
+            r#""room_id":"!n8f893n9:example.com","sender":"@carl:example.com","#,
+            r#""type":"m.poll.response"}"#
+        );
+
+        let event = from_str::<Event>(json).unwrap();
+
+        match event {
+            Event::PollResponse(ref event) => assert_eq!(event.content.selections, vec!["yes"]),
+            _ => panic!("expected a poll response event"),
+        }
+
+        assert_eq!(to_value(&event).unwrap(), from_str::<Value>(json).unwrap());
+        assert!(from_str::<Event>(&json.replace("m.reference", "m.replace")).is_err());
+    }
+}
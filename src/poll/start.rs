@@ -0,0 +1,142 @@
+//! Types for the *m.poll.start* event.
+
+use int::UInt;
+
+use super::PollText;
+
+room_event! {
+    /// Starts a poll in a room.
+    pub struct PollStartEvent(PollStartEventContent) {}
+}
+
+/// The payload of a `PollStartEvent`.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct PollStartEventContent {
+    /// The poll.
+    #[serde(rename = "m.poll")]
+    pub poll: PollContent,
+    /// A textual fallback for clients that do not support polls.
+    #[serde(default, rename = "m.text", skip_serializing_if = "Vec::is_empty")]
+    pub text: Vec<PollText>,
+    /// Fields that are not covered by the Matrix specification.
+    #[cfg(feature = "preserve-extra-fields")]
+    #[serde(flatten)]
+    pub extra: ::std::collections::BTreeMap<String, ::serde_json::Value>,
+}
+
+impl_event_content!(PollStartEventContent, PollStart);
+impl_room_event_content!(message_like, PollStartEventContent);
+
+/// The question and answers of a poll.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct PollContent {
+    /// The possible answers.
+    pub answers: Vec<PollAnswer>,
+    /// Whether the results are visible before the poll ends.
+    #[serde(default)]
+    pub kind: PollKind,
+    /// The maximum number of answers a voter may select, 1 if omitted.
+    #[serde(default = "one", skip_serializing_if = "is_one")]
+    pub max_selections: UInt,
+    /// The question of the poll.
+    pub question: PollQuestion,
+}
+
+/// The question of a poll.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct PollQuestion {
+    /// The representations of the question, in order of preference of the sender.
+    #[serde(rename = "m.text")]
+    pub text: Vec<PollText>,
+}
+
+/// A possible answer to a poll.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct PollAnswer {
+    /// The ID of the answer, unique within the poll.
+    #[serde(rename = "m.id")]
+    pub id: String,
+    /// The representations of the answer, in order of preference of the sender.
+    #[serde(rename = "m.text")]
+    pub text: Vec<PollText>,
+}
+
+/// Whether the results of a poll are visible before it ends.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub enum PollKind {
+    /// The results are visible while the poll is running.
+    Disclosed,
+    /// The results are only visible once the poll ends.
+    #[default]
+    Undisclosed,
+    /// Any kind that is not part of the proposal.
+    Custom(String),
+}
+
+impl_string_enum! {
+    PollKind {
+        Disclosed => "m.disclosed",
+        Undisclosed => "m.undisclosed",
+    }
+}
+
+impl PollContent {
+    /// The answers of `selections` that count as a vote in this poll.
+    ///
+    /// Unknown answer IDs are ignored and only the first `max_selections` known answers are
+    /// kept, so a response that selects nothing valid counts as a spoiled vote.
+    pub fn valid_selections<'a>(&self, selections: &'a [String]) -> Vec<&'a str> {
+        let max = u64::from(self.max_selections) as usize;
+
+        selections.iter()
+            .map(String::as_str)
+            .filter(|selection| self.answers.iter().any(|answer| answer.id == *selection))
+            .take(max)
+            .collect()
+    }
+}
+
+fn one() -> UInt {
+    UInt::from(1u32)
+}
+
+fn is_one(value: &UInt) -> bool {
+    *value == one()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{Value, from_str, to_value};
+
+    use collections::all::Event;
+    use super::PollKind;
+
+    #[test]
+    fn poll_start_round_trip() {
+        let json = concat!(
+            r#"{"content":{"m.poll":{"answers":[{"m.id":"yes","m.text":[{"body":"Yes"}]},"#,
+            r#"{"m.id":"no","m.text":[{"body":"No"}]}],"kind":"m.disclosed","#,
+            r#""question":{"m.text":[{"body":"Lunch?"}]}},"m.text":[{"body":"Lunch? Yes/No"}]},"#,
+            r#""event_id":"$h29iv0s8:example.com","origin_server_ts":1,"#,
+            r#""room_id":"!n8f893n9:example.com","sender":"@carl:example.com","#,
+            r#""type":"m.poll.start"}"#
+        );
+
+        let event = from_str::<Event>(json).unwrap();
+
+        match event {
+            Event::PollStart(ref event) => {
+                let poll = &event.content.poll;
+
+                assert_eq!(poll.kind, PollKind::Disclosed);
+                assert_eq!(u64::from(poll.max_selections), 1);
+
+                let selections = vec!["maybe".to_string(), "no".to_string(), "yes".to_string()];
+                assert_eq!(poll.valid_selections(&selections), vec!["no"]);
+            }
+            _ => panic!("expected a poll start event"),
+        }
+
+        assert_eq!(to_value(&event).unwrap(), from_str::<Value>(json).unwrap());
+    }
+}
@@ -0,0 +1,29 @@
+//! Re-exports of the most commonly used items, meant to be glob imported with
+//! `use ruma_events::prelude::*;`.
+//!
+//! The collection enums are re-exported with an `Any` prefix, because their names are taken by
+//! the event traits.
+
+pub use {
+    ContentBuildError, CustomEvent, CustomRoomEvent, CustomStateEvent, EmptyStateKey, Event,
+    EventContent, EventType, RoomEvent, StateEvent, StaticStateEventContent,
+};
+pub use collections::all::{
+    Event as AnyEvent, RoomEvent as AnyRoomEvent, StateEvent as AnyStateEvent,
+};
+pub use collections::to_device::ToDeviceEvent as AnyToDeviceEvent;
+pub use content::{AnyContent, AnyMessageLikeEventContent, AnyStateEventContent};
+pub use initial_state::{AnyInitialStateEvent, InitialStateEvent};
+pub use reaction::{ReactionEvent, ReactionEventContent};
+pub use room::create::{CreateEvent, CreateEventContent};
+#[cfg(feature = "events-encryption")]
+pub use room::encrypted::{EncryptedEvent, EncryptedEventContent};
+pub use room::member::{MemberEvent, MemberEventContent, MembershipState};
+pub use room::message::{MessageEvent, MessageEventContent, MessageType};
+pub use room::name::{NameEvent, NameEventContent};
+pub use room::power_levels::{PowerLevelsEvent, PowerLevelsEventContent};
+pub use room::redaction::{RedactionEvent, RedactionEventContent};
+pub use room::topic::{TopicEvent, TopicEventContent};
+pub use ruma_identifiers::{EventId, RoomAliasId, RoomId, UserId};
+pub use stripped::StrippedState;
+pub use timeline::AnySyncTimelineEvent;
@@ -2,6 +2,8 @@
 
 use ruma_identifiers::{EventId, UserId};
 
+use int::UInt;
+
 event! {
     /// Informs the client of a user's presence state change.
     pub struct PresenceEvent(PresenceEventContent) {
@@ -26,15 +28,22 @@ pub struct PresenceEventContent {
 
     /// The last time since this used performed some action, in milliseconds.
     #[serde(skip_serializing_if="Option::is_none")]
-    pub last_active_ago: Option<u64>,
+    pub last_active_ago: Option<UInt>,
 
     /// The presence state for this user.
     pub presence: PresenceState,
 
     /// The unique identifier for the user associated with this event.
     pub user_id: UserId,
+
+    /// Fields that are not covered by the Matrix specification.
+    #[cfg(feature = "preserve-extra-fields")]
+    #[serde(flatten)]
+    pub extra: ::std::collections::BTreeMap<String, ::serde_json::Value>,
 }
 
+impl_event_content!(PresenceEventContent, Presence);
+
 /// A description of a user's connectivity and availability for chat.
 #[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
 pub enum PresenceState {
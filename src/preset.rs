@@ -0,0 +1,108 @@
+//! The presets of room creation requests, and the initial state they stand for.
+
+use initial_state::{AnyInitialStateEvent, InitialStateEvent};
+use room::encryption::{Algorithm, EncryptionEventContent};
+use room::guest_access::{GuestAccess, GuestAccessEventContent};
+use room::history_visibility::{HistoryVisibility, HistoryVisibilityEventContent};
+use room::join_rules::{JoinRule, JoinRulesEventContent};
+
+/// A preset of a room creation request, which sets several state events at once.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum RoomPreset {
+    /// Only invited users can join, and guests are allowed.
+    #[serde(rename="private_chat")]
+    PrivateChat,
+
+    /// Anyone can join, and guests are forbidden.
+    #[serde(rename="public_chat")]
+    PublicChat,
+
+    /// Like `PrivateChat`, but all invited users are given the same power level as the creator.
+    #[serde(rename="trusted_private_chat")]
+    TrustedPrivateChat,
+}
+
+impl_enum! {
+    RoomPreset {
+        PrivateChat => "private_chat",
+        PublicChat => "public_chat",
+        TrustedPrivateChat => "trusted_private_chat",
+    }
+}
+
+impl RoomPreset {
+    /// The initial state events this preset stands for: join rules, history visibility and guest
+    /// access, followed by *m.megolm.v1.aes-sha2* encryption if `encrypted` is set.
+    ///
+    /// The power levels of `TrustedPrivateChat` depend on the users invited to the room, so they
+    /// are not included.
+    pub fn initial_state(&self, encrypted: bool) -> Vec<AnyInitialStateEvent> {
+        let (join_rule, guest_access) = match *self {
+            RoomPreset::PrivateChat | RoomPreset::TrustedPrivateChat => {
+                (JoinRule::Invite, GuestAccess::CanJoin)
+            }
+            RoomPreset::PublicChat => (JoinRule::Public, GuestAccess::Forbidden),
+        };
+
+        let mut state = vec![
+            InitialStateEvent::from(JoinRulesEventContent {
+                join_rule,
+                #[cfg(feature = "preserve-extra-fields")]
+                extra: Default::default(),
+            }).into(),
+            InitialStateEvent::from(HistoryVisibilityEventContent {
+                history_visibility: HistoryVisibility::Shared,
+                #[cfg(feature = "preserve-extra-fields")]
+                extra: Default::default(),
+            }).into(),
+            InitialStateEvent::from(GuestAccessEventContent {
+                guest_access,
+                #[cfg(feature = "preserve-extra-fields")]
+                extra: Default::default(),
+            }).into(),
+        ];
+
+        if encrypted {
+            state.push(InitialStateEvent::from(EncryptionEventContent {
+                algorithm: Algorithm::MegolmV1AesSha2,
+                rotation_period_ms: None,
+                rotation_period_msgs: None,
+                #[cfg(feature = "preserve-extra-fields")]
+                extra: Default::default(),
+            }).into());
+        }
+
+        state
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::to_string;
+
+    use super::RoomPreset;
+
+    #[test]
+    fn public_chat_initial_state() {
+        assert_eq!(
+            to_string(&RoomPreset::PublicChat.initial_state(false)).unwrap(),
+            concat!(
+                r#"[{"content":{"join_rule":"public"},"type":"m.room.join_rules"},"#,
+                r#"{"content":{"history_visibility":"shared"},"#,
+                r#""type":"m.room.history_visibility"},"#,
+                r#"{"content":{"guest_access":"forbidden"},"type":"m.room.guest_access"}]"#
+            )
+        );
+    }
+
+    #[test]
+    fn encrypted_private_chat_initial_state() {
+        let state = RoomPreset::PrivateChat.initial_state(true);
+
+        assert_eq!(state.len(), 4);
+        assert_eq!(
+            to_string(&state[3]).unwrap(),
+            r#"{"content":{"algorithm":"m.megolm.v1.aes-sha2"},"type":"m.room.encryption"}"#
+        );
+    }
+}
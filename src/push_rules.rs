@@ -0,0 +1,388 @@
+//! Types for the *m.push_rules* event.
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde::de::Error;
+use serde::ser::SerializeMap;
+use serde_json::{Value, from_value};
+
+use collections::deserialize_limited_value;
+
+event! {
+    /// Describes all push rules for a user.
+    pub struct PushRulesEvent(PushRulesEventContent) {}
+}
+
+/// The payload of a `PushRulesEvent`.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct PushRulesEventContent {
+    /// The global ruleset.
+    pub global: Ruleset,
+    /// Fields that are not covered by the Matrix specification.
+    #[cfg(feature = "preserve-extra-fields")]
+    #[serde(flatten)]
+    pub extra: ::std::collections::BTreeMap<String, ::serde_json::Value>,
+}
+
+impl_event_content!(PushRulesEventContent, PushRules);
+
+/// A push ruleset scopes a set of rules according to some criteria.
+///
+/// For example, some rules may only be applied for messages from a particular sender, a particular
+/// room, or by default. The push ruleset contains the entire set of scopes and rules.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Ruleset {
+    /// These rules configure behaviour for (unencrypted) messages that match certain patterns.
+    #[serde(default)]
+    pub content: Vec<PushRule>,
+
+    /// These user-configured rules are given the highest priority.
+    #[serde(default, rename="override")]
+    pub override_rules: Vec<PushRule>,
+
+    /// These rules change the behaviour of all messages for a given room.
+    #[serde(default)]
+    pub room: Vec<PushRule>,
+
+    /// These rules configure notification behaviour for messages from a specific Matrix user ID.
+    #[serde(default)]
+    pub sender: Vec<PushRule>,
+
+    /// These rules are identical to override rules, but have a lower priority than content,
+    /// room and sender rules.
+    #[serde(default)]
+    pub underride: Vec<PushRule>,
+}
+
+/// A push rule is a single rule that states under what conditions an event should be passed onto
+/// a push gateway and how the notification should be presented.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct PushRule {
+    /// Actions to determine if and how a notification is delivered for events matching this rule.
+    pub actions: Vec<Action>,
+
+    /// The conditions that must hold true for an event in order for a rule to be applied to an
+    /// event.
+    ///
+    /// A rule with no conditions always matches. Only applicable to underride and override rules.
+    #[serde(skip_serializing_if="Option::is_none")]
+    pub conditions: Option<Vec<PushCondition>>,
+
+    /// Whether this is a default rule, or has been set explicitly.
+    pub default: bool,
+
+    /// Whether the push rule is enabled or not.
+    pub enabled: bool,
+
+    /// The glob-style pattern to match against. Only applicable to content rules.
+    #[serde(skip_serializing_if="Option::is_none")]
+    pub pattern: Option<String>,
+
+    /// The ID of this rule.
+    pub rule_id: String,
+}
+
+/// A condition that must apply for an associated push rule's action to be taken.
+#[derive(Clone, Debug, PartialEq)]
+pub enum PushCondition {
+    /// This is a glob pattern match on a field of the event.
+    EventMatch {
+        /// The dot-separated field of the event to match, e.g. `content.body`.
+        key: String,
+        /// The glob-style pattern to match against.
+        pattern: String,
+    },
+
+    /// This matches unencrypted messages where `content.body` contains the owner's display name
+    /// in that room.
+    ContainsDisplayName,
+
+    /// This matches the current number of members in the room.
+    RoomMemberCount {
+        /// A decimal integer optionally prefixed by one of `==`, `<`, `>`, `>=` or `<=`.
+        ///
+        /// A prefix of `<` matches rooms where the member count is strictly less than the given
+        /// number and so forth. If no prefix is present, this parameter defaults to `==`.
+        is: String,
+    },
+
+    /// This takes into account the current power levels in the room, ensuring the sender of the
+    /// event has high enough power to trigger the notification.
+    SenderNotificationPermission {
+        /// The field in the power level event the user needs a minimum power level for, e.g.
+        /// `room`.
+        key: String,
+    },
+
+    /// A condition of a kind that is not part of the specification, kept as a JSON value.
+    ///
+    /// Rules with conditions of unknown kinds never match.
+    Custom(Value),
+}
+
+/// An action affects if and how a notification is delivered for a matching event.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Action {
+    /// This causes each matching event to generate a notification.
+    Notify,
+
+    /// This prevents each matching event from generating a notification.
+    DontNotify,
+
+    /// This enables notifications for matching events but activates homeserver specific behaviour
+    /// to intelligently coalesce multiple events into a single notification.
+    Coalesce,
+
+    /// Sets an entry in the `tweaks` dictionary sent to the push gateway.
+    SetTweak(Tweak),
+
+    /// An action that is not part of the specification, kept as a JSON value.
+    Custom(Value),
+}
+
+/// A tweak that changes how a notification is presented.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Tweak {
+    /// A string representing the sound to be played when this notification arrives.
+    ///
+    /// A value of "default" means to play a default sound.
+    Sound(String),
+
+    /// A boolean representing whether or not this message should be highlighted in the UI.
+    Highlight(bool),
+
+    /// A tweak that is not part of the specification, with its name and value.
+    Custom(String, Option<Value>),
+}
+
+#[derive(Deserialize)]
+struct RawTweak {
+    set_tweak: String,
+    value: Option<Value>,
+}
+
+impl Serialize for PushCondition {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
+        match *self {
+            PushCondition::EventMatch { ref key, ref pattern } => {
+                let mut map = serializer.serialize_map(Some(3))?;
+                map.serialize_entry("key", key)?;
+                map.serialize_entry("kind", "event_match")?;
+                map.serialize_entry("pattern", pattern)?;
+                map.end()
+            }
+            PushCondition::ContainsDisplayName => {
+                let mut map = serializer.serialize_map(Some(1))?;
+                map.serialize_entry("kind", "contains_display_name")?;
+                map.end()
+            }
+            PushCondition::RoomMemberCount { ref is } => {
+                let mut map = serializer.serialize_map(Some(2))?;
+                map.serialize_entry("is", is)?;
+                map.serialize_entry("kind", "room_member_count")?;
+                map.end()
+            }
+            PushCondition::SenderNotificationPermission { ref key } => {
+                let mut map = serializer.serialize_map(Some(2))?;
+                map.serialize_entry("key", key)?;
+                map.serialize_entry("kind", "sender_notification_permission")?;
+                map.end()
+            }
+            PushCondition::Custom(ref value) => value.serialize(serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for PushCondition {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where D: Deserializer<'de> {
+        #[derive(Deserialize)]
+        struct Key {
+            key: String,
+        }
+
+        #[derive(Deserialize)]
+        struct EventMatch {
+            key: String,
+            pattern: String,
+        }
+
+        #[derive(Deserialize)]
+        struct RoomMemberCount {
+            is: String,
+        }
+
+        let value = deserialize_limited_value(deserializer)?;
+
+        let kind = match value.get("kind") {
+            Some(kind) => match kind.as_str() {
+                Some(kind) => kind.to_string(),
+                None => return Err(D::Error::custom("kind is not a string")),
+            },
+            None => return Err(D::Error::missing_field("kind")),
+        };
+
+        let condition = match &kind[..] {
+            "event_match" => {
+                let EventMatch { key, pattern } = from_value(value).map_err(D::Error::custom)?;
+
+                PushCondition::EventMatch { key, pattern }
+            }
+            "contains_display_name" => PushCondition::ContainsDisplayName,
+            "room_member_count" => {
+                let RoomMemberCount { is } = from_value(value).map_err(D::Error::custom)?;
+
+                PushCondition::RoomMemberCount { is }
+            }
+            "sender_notification_permission" => {
+                let Key { key } = from_value(value).map_err(D::Error::custom)?;
+
+                PushCondition::SenderNotificationPermission { key }
+            }
+            _ => PushCondition::Custom(value),
+        };
+
+        Ok(condition)
+    }
+}
+
+impl Serialize for Action {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
+        match *self {
+            Action::Notify => serializer.serialize_str("notify"),
+            Action::DontNotify => serializer.serialize_str("dont_notify"),
+            Action::Coalesce => serializer.serialize_str("coalesce"),
+            Action::SetTweak(ref tweak) => tweak.serialize(serializer),
+            Action::Custom(ref value) => value.serialize(serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Action {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where D: Deserializer<'de> {
+        let value = deserialize_limited_value(deserializer)?;
+
+        let action = match value {
+            Value::String(ref action) if action == "notify" => Action::Notify,
+            Value::String(ref action) if action == "dont_notify" => Action::DontNotify,
+            Value::String(ref action) if action == "coalesce" => Action::Coalesce,
+            Value::Object(ref object) if object.contains_key("set_tweak") => {
+                Action::SetTweak(Tweak::deserialize(&value).map_err(D::Error::custom)?)
+            }
+            value => Action::Custom(value),
+        };
+
+        Ok(action)
+    }
+}
+
+impl Serialize for Tweak {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
+        match *self {
+            Tweak::Sound(ref sound) => {
+                let mut map = serializer.serialize_map(Some(2))?;
+                map.serialize_entry("set_tweak", "sound")?;
+                map.serialize_entry("value", sound)?;
+                map.end()
+            }
+            Tweak::Highlight(highlight) => {
+                let mut map = serializer.serialize_map(Some(2))?;
+                map.serialize_entry("set_tweak", "highlight")?;
+                map.serialize_entry("value", &highlight)?;
+                map.end()
+            }
+            Tweak::Custom(ref name, ref value) => {
+                let mut map = serializer.serialize_map(None)?;
+                map.serialize_entry("set_tweak", name)?;
+
+                if let Some(ref value) = *value {
+                    map.serialize_entry("value", value)?;
+                }
+
+                map.end()
+            }
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Tweak {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where D: Deserializer<'de> {
+        let RawTweak { set_tweak, value } = RawTweak::deserialize(deserializer)?;
+
+        let tweak = match (&set_tweak[..], value) {
+            ("sound", Some(Value::String(sound))) => Tweak::Sound(sound),
+            ("sound", _) => return Err(D::Error::custom("the sound tweak needs a string value")),
+            // The value of the highlight tweak defaults to true.
+            ("highlight", None) => Tweak::Highlight(true),
+            ("highlight", Some(Value::Bool(highlight))) => Tweak::Highlight(highlight),
+            ("highlight", _) => {
+                return Err(D::Error::custom("the highlight tweak needs a boolean value"));
+            }
+            (_, value) => Tweak::Custom(set_tweak.clone(), value),
+        };
+
+        Ok(tweak)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{from_str, to_string};
+
+    use super::{Action, PushCondition, PushRulesEventContent, Tweak};
+
+    #[test]
+    fn deserialize_push_rules() {
+        let content = from_str::<PushRulesEventContent>(r#"{
+            "global": {
+                "content": [{
+                    "actions": ["notify", {"set_tweak": "sound", "value": "default"},
+                                {"set_tweak": "highlight"}],
+                    "default": true,
+                    "enabled": true,
+                    "pattern": "alice",
+                    "rule_id": ".m.rule.contains_user_name"
+                }],
+                "override": [{
+                    "actions": ["dont_notify"],
+                    "conditions": [],
+                    "default": true,
+                    "enabled": false,
+                    "rule_id": ".m.rule.master"
+                }],
+                "underride": [{
+                    "actions": ["notify", {"set_tweak": "highlight", "value": false}],
+                    "conditions": [
+                        {"kind": "room_member_count", "is": "2"},
+                        {"kind": "event_match", "key": "type", "pattern": "m.room.message"},
+                        {"kind": "org.example.unknown"}
+                    ],
+                    "default": true,
+                    "enabled": true,
+                    "rule_id": ".m.rule.room_one_to_one"
+                }]
+            }
+        }"#).unwrap();
+
+        let rule = &content.global.content[0];
+        assert_eq!(rule.actions[1], Action::SetTweak(Tweak::Sound("default".to_string())));
+        assert_eq!(rule.actions[2], Action::SetTweak(Tweak::Highlight(true)));
+
+        assert!(!content.global.override_rules[0].enabled);
+        assert!(content.global.room.is_empty());
+
+        let conditions = content.global.underride[0].conditions.as_ref().unwrap();
+        assert_eq!(conditions[0], PushCondition::RoomMemberCount { is: "2".to_string() });
+        match conditions[2] {
+            PushCondition::Custom(_) => {}
+            _ => unreachable!(),
+        }
+
+        assert_eq!(
+            to_string(&content.global.underride[0].conditions).unwrap(),
+            concat!(
+                r#"[{"is":"2","kind":"room_member_count"},"#,
+                r#"{"key":"type","kind":"event_match","pattern":"m.room.message"},"#,
+                r#"{"kind":"org.example.unknown"}]"#
+            )
+        );
+    }
+}
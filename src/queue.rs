@@ -0,0 +1,141 @@
+//! Types for events that are queued for sending, and for matching them against the events the
+//! server sends back for them.
+//!
+//! A client shows a queued event as a local echo until the server returns the sent event, which
+//! carries the transaction ID of the request that sent it in `unsigned.transaction_id`.
+
+use ruma_identifiers::EventId;
+use serde_json::{Error as JsonError, Value, to_value};
+
+use EventType;
+use content::AnyMessageLikeEventContent;
+use timeline::AnySyncTimelineEvent;
+
+/// An event that is queued for sending to a room.
+#[derive(Clone, Debug)]
+pub struct QueuedEvent {
+    /// The transaction ID the event is sent with, unique for the sending device.
+    pub txn_id: String,
+    /// The content of the event.
+    pub content: AnyMessageLikeEventContent,
+    /// The relation of the event to another event, sent as `m.relates_to` in the content.
+    pub relations: Option<Value>,
+    /// How far sending the event has progressed.
+    pub state: SendState,
+}
+
+/// How far sending a queued event has progressed.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SendState {
+    /// The event is waiting to be sent.
+    Queued,
+    /// The event was sent and the server returned its ID, but the event has not come back in a
+    /// sync response yet.
+    Sent(EventId),
+    /// The event came back from the server with the given ID and can be replaced by the remote
+    /// event.
+    Echoed(EventId),
+    /// Sending the event failed with the given error and it will not be retried automatically.
+    Failed(String),
+}
+
+impl QueuedEvent {
+    /// Queues an event with the given transaction ID and content.
+    pub fn new(txn_id: String, content: AnyMessageLikeEventContent) -> Self {
+        QueuedEvent {
+            txn_id,
+            content,
+            relations: None,
+            state: SendState::Queued,
+        }
+    }
+
+    /// The type of the queued event.
+    pub fn event_type(&self) -> EventType {
+        self.content.event_type()
+    }
+
+    /// The JSON content to send, with `relations` included as `m.relates_to`.
+    pub fn content_json(&self) -> Result<Value, JsonError> {
+        let mut content = to_value(&self.content)?;
+
+        if let (Some(relations), Some(object)) = (&self.relations, content.as_object_mut()) {
+            object.insert("m.relates_to".to_string(), relations.clone());
+        }
+
+        Ok(content)
+    }
+
+    /// Whether the given `unsigned` data of an event carries the transaction ID of this event.
+    pub fn matches_unsigned(&self, unsigned: &Value) -> bool {
+        unsigned.get("transaction_id").and_then(Value::as_str) == Some(self.txn_id.as_str())
+    }
+
+    /// Whether the given event from a sync response is the remote echo of this event.
+    pub fn is_echoed_by(&self, event: &AnySyncTimelineEvent) -> bool {
+        let unsigned = match *event {
+            AnySyncTimelineEvent::MessageLike(ref event) => event.unsigned.as_ref(),
+            AnySyncTimelineEvent::State(ref event) => event.unsigned.as_ref(),
+            #[cfg(feature = "events-encryption")]
+            AnySyncTimelineEvent::Encrypted(ref event) => event.unsigned.as_ref(),
+            AnySyncTimelineEvent::Redacted(ref event) => Some(&event.unsigned),
+        };
+
+        unsigned.is_some_and(|unsigned| self.matches_unsigned(unsigned))
+    }
+
+    /// Moves the event to `SendState::Echoed` if the given event is its remote echo, returning
+    /// whether it was.
+    pub fn receive_echo(&mut self, event: &AnySyncTimelineEvent) -> bool {
+        if !self.is_echoed_by(event) {
+            return false;
+        }
+
+        self.state = SendState::Echoed(event.event_id().clone());
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{Value, from_str};
+
+    use room::message::MessageEventContent;
+    use timeline::AnySyncTimelineEvent;
+    use super::{QueuedEvent, SendState};
+
+    #[test]
+    fn remote_echo() {
+        let content = from_str::<MessageEventContent>(r#"{"body":"Hello","msgtype":"m.text"}"#)
+            .unwrap();
+        let mut queued = QueuedEvent::new("m1234".to_string(), content.into());
+        queued.relations = Some(from_str(r#"{"rel_type":"m.thread","event_id":"$root:a.b"}"#)
+            .unwrap());
+
+        let content = queued.content_json().unwrap();
+        assert_eq!(content["m.relates_to"]["rel_type"], Value::from("m.thread"));
+
+        let other = from_str::<AnySyncTimelineEvent>(r#"{
+            "content": {"body": "Hello", "msgtype": "m.text"},
+            "event_id": "$h29iv0s7:example.com",
+            "origin_server_ts": 1,
+            "sender": "@carl:example.com",
+            "type": "m.room.message",
+            "unsigned": {"transaction_id": "m1233"}
+        }"#).unwrap();
+        let echo = from_str::<AnySyncTimelineEvent>(r#"{
+            "content": {"body": "Hello", "msgtype": "m.text"},
+            "event_id": "$h29iv0s8:example.com",
+            "origin_server_ts": 1,
+            "sender": "@carl:example.com",
+            "type": "m.room.message",
+            "unsigned": {"transaction_id": "m1234"}
+        }"#).unwrap();
+
+        assert!(!queued.receive_echo(&other));
+        assert_eq!(queued.state, SendState::Queued);
+        assert!(queued.receive_echo(&echo));
+        assert_eq!(queued.state, SendState::Echoed(echo.event_id().clone()));
+    }
+}
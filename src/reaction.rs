@@ -0,0 +1,115 @@
+//! Types for the *m.reaction* event.
+
+use std::collections::HashSet;
+
+use ruma_identifiers::{EventId, UserId};
+
+use room::relation::Annotation;
+
+room_event! {
+    /// A reaction to another event.
+    pub struct ReactionEvent(ReactionEventContent) {}
+}
+
+/// The payload of a `ReactionEvent`.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct ReactionEventContent {
+    /// The event being reacted to, and the reaction.
+    #[serde(rename="m.relates_to")]
+    pub relates_to: Annotation,
+    /// Fields that are not covered by the Matrix specification.
+    #[cfg(feature = "preserve-extra-fields")]
+    #[serde(flatten)]
+    pub extra: ::std::collections::BTreeMap<String, ::serde_json::Value>,
+}
+
+impl_event_content!(ReactionEventContent, Reaction);
+impl_room_event_content!(message_like, ReactionEventContent);
+
+impl ReactionEvent {
+    /// The annotated event, the sender and the key of the reaction.
+    ///
+    /// Servers allow only one annotation with the same key per sender and event, so two reactions
+    /// with the same annotation key are duplicates.
+    pub fn annotation_key(&self) -> (&EventId, &UserId, &str) {
+        (&self.content.relates_to.event_id, &self.user_id, &self.content.relates_to.key)
+    }
+}
+
+/// The reactions in `reactions` without duplicates, keeping the first reaction for each
+/// annotation key.
+pub fn dedup_annotations(reactions: &[ReactionEvent]) -> Vec<&ReactionEvent> {
+    let mut seen = HashSet::new();
+
+    reactions.iter().filter(|reaction| seen.insert(reaction.annotation_key())).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{Value, from_str, to_value};
+
+    use collections::all::Event;
+    use super::{ReactionEvent, dedup_annotations};
+
+    #[test]
+    fn reaction_round_trip() {
+        let json = r#"{
+            "content": {
+                "m.relates_to": {
+                    "event_id": "$h29iv0s8:example.com",
+                    "key": "👍",
+                    "rel_type": "m.annotation"
+                }
+            },
+            "event_id": "$a08fa3:example.com",
+            "origin_server_ts": 1,
+            "room_id": "!n8f893n9:example.com",
+            "sender": "@carl:example.com",
+            "type": "m.reaction"
+        }"#;
+
+        let event = from_str::<Event>(json).unwrap();
+
+        match event {
+            Event::Reaction(ref event) => assert_eq!(event.content.relates_to.key, "👍"),
+            _ => unreachable!(),
+        }
+
+        assert_eq!(to_value(&event).unwrap(), from_str::<Value>(json).unwrap());
+        assert!(from_str::<Event>(&json.replace("m.annotation", "m.replace")).is_err());
+    }
+    #[test]
+    fn annotations_are_deduplicated() {
+        let reaction = |event_id: &str, sender: &str, key: &str| {
+            from_str::<ReactionEvent>(&format!(r#"{{
+                "content": {{
+                    "m.relates_to": {{
+                        "event_id": "$h29iv0s8:example.com",
+                        "key": "{}",
+                        "rel_type": "m.annotation"
+                    }}
+                }},
+                "event_id": "{}",
+                "origin_server_ts": 1,
+                "room_id": "!n8f893n9:example.com",
+                "sender": "{}",
+                "type": "m.reaction"
+            }}"#, key, event_id, sender)).unwrap()
+        };
+
+        let reactions = vec![
+            reaction("$a:example.com", "@carl:example.com", "👍"),
+            reaction("$b:example.com", "@carl:example.com", "👍"),
+            reaction("$c:example.com", "@carl:example.com", "🎉"),
+            reaction("$d:example.com", "@dana:example.com", "👍"),
+        ];
+
+        let event_ids = dedup_annotations(&reactions)
+            .iter()
+            .map(|reaction| reaction.event_id.to_string())
+            .collect::<Vec<_>>();
+
+        assert_eq!(event_ids, vec!["$a:example.com", "$c:example.com", "$d:example.com"]);
+        assert_eq!(reactions[0].annotation_key(), reactions[1].annotation_key());
+    }
+}
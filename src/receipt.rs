@@ -18,10 +18,13 @@ event! {
 /// the event being acknowledged and *not* an ID for the receipt itself.
 pub type ReceiptEventContent = HashMap<EventId, Receipts>;
 
+impl_event_content!(ReceiptEventContent, Receipt);
+
 /// A collection of receipts.
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
 pub struct Receipts {
     /// A collection of users who have sent *m.read* receipts for this event.
+    #[serde(default, rename="m.read")]
     pub m_read: UserReceipts,
 }
 
@@ -0,0 +1,124 @@
+//! Helpers for the `Debug` implementations of types that hold secrets or ciphertext, so that
+//! logging an event does not leak key material.
+
+#[cfg(feature = "events-encryption")]
+use std::collections::HashMap;
+use std::fmt::{Debug, Formatter, Result as FmtResult};
+
+use serde_json::Value;
+
+/// Keys whose values are masked wherever they appear in arbitrary JSON, such as the content of
+/// custom events.
+const SECRET_KEYS: &[&str] = &[
+    "access_token",
+    "password",
+    "private_key",
+    "refresh_token",
+    "secret",
+    "session_key",
+];
+
+/// Stands in for a secret value in `Debug` output.
+pub struct Redacted;
+
+impl Debug for Redacted {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        f.write_str("<redacted>")
+    }
+}
+
+/// Formats a map ordered by key, so that the output does not depend on the hash order.
+#[cfg(feature = "events-encryption")]
+pub struct Sorted<'a, V: 'a>(pub &'a HashMap<String, V>);
+
+#[cfg(feature = "events-encryption")]
+impl<'a, V> Debug for Sorted<'a, V> where V: Debug {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        let mut entries: Vec<(&String, &V)> = self.0.iter().collect();
+        entries.sort_by(|a, b| a.0.cmp(b.0));
+
+        f.debug_map().entries(entries).finish()
+    }
+}
+
+/// Formats a map ordered by key, with every value redacted.
+#[cfg(feature = "events-encryption")]
+pub struct RedactedValues<'a, V: 'a>(pub &'a HashMap<String, V>);
+
+#[cfg(feature = "events-encryption")]
+impl<'a, V> Debug for RedactedValues<'a, V> {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        let mut keys: Vec<&String> = self.0.keys().collect();
+        keys.sort();
+
+        f.debug_map().entries(keys.into_iter().map(|key| (key, Redacted))).finish()
+    }
+}
+
+/// Formats arbitrary JSON, redacting the values of well-known secret keys at any depth.
+pub struct MaskedJson<'a>(pub &'a Value);
+
+impl<'a> Debug for MaskedJson<'a> {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match *self.0 {
+            Value::Array(ref values) => {
+                f.debug_list().entries(values.iter().map(MaskedJson)).finish()
+            }
+            Value::Object(ref object) => {
+                let mut map = f.debug_map();
+
+                for (key, value) in object {
+                    if SECRET_KEYS.contains(&key.as_str()) {
+                        map.entry(key, &Redacted);
+                    } else {
+                        map.entry(key, &MaskedJson(value));
+                    }
+                }
+
+                map.finish()
+            }
+            ref value => value.fmt(f),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::from_str;
+
+    use CustomStateEvent;
+    use collections::to_device::CustomToDeviceEvent;
+
+    #[test]
+    fn custom_content_secrets_are_masked() {
+        let event = from_str::<CustomStateEvent>(r#"{
+            "content": {
+                "access_token": "syt_secret",
+                "bridge": {"password": "hunter2", "user": "alice"},
+                "tokens": [{"refresh_token": "syr_secret"}]
+            },
+            "event_id": "$h29iv0s8:example.com",
+            "origin_server_ts": 1,
+            "room_id": "!n8f893n9:example.com",
+            "sender": "@carl:example.com",
+            "state_key": "",
+            "type": "org.example.bridge",
+            "unsigned": {"secret": "s3cr3t"}
+        }"#).unwrap();
+        let debug = format!("{:?}", event);
+
+        for secret in &["syt_secret", "hunter2", "syr_secret", "s3cr3t"] {
+            assert!(!debug.contains(secret), "{} leaked in {}", secret, debug);
+        }
+        assert!(debug.contains(r#""access_token": <redacted>"#));
+        assert!(debug.contains(r#""user": String("alice")"#));
+
+        let event = from_str::<CustomToDeviceEvent>(r#"{
+            "content": {"access_token": "syt_secret"},
+            "sender": "@carl:example.com",
+            "type": "org.example.login"
+        }"#).unwrap();
+
+        assert!(!format!("{:?}", event).contains("syt_secret"));
+    }
+}
@@ -2,8 +2,14 @@
 
 use ruma_identifiers::RoomAliasId;
 
+use ContentBuildError;
+use super::{sender_server, server_name};
+
 state_event! {
     /// Informs the room about what room aliases it has been given.
+    ///
+    /// Each homeserver publishes its own *m.room.aliases* event, using its server name as the state
+    /// key. Only aliases on that server are valid in the event.
     pub struct AliasesEvent(AliasesEventContent) {}
 }
 
@@ -12,4 +18,111 @@ state_event! {
 pub struct AliasesEventContent {
     /// A list of room aliases.
     pub aliases: Vec<RoomAliasId>,
+    /// Fields that are not covered by the Matrix specification.
+    #[cfg(feature = "preserve-extra-fields")]
+    #[serde(flatten)]
+    pub extra: ::std::collections::BTreeMap<String, ::serde_json::Value>,
+}
+
+impl_event_content!(AliasesEventContent, RoomAliases);
+impl_static_state_event_content!(AliasesEventContent, String);
+impl_room_event_content!(state, AliasesEventContent);
+
+impl AliasesEvent {
+    /// The aliases of this event that belong to the server named by its state key.
+    pub fn valid_aliases(&self) -> Vec<&RoomAliasId> {
+        self.content.aliases.iter().filter(|alias| self.is_valid_alias(alias)).collect()
+    }
+
+    /// The aliases of this event that belong to a server other than the one named by its state
+    /// key, and which should therefore be ignored.
+    pub fn invalid_aliases(&self) -> Vec<&RoomAliasId> {
+        self.content.aliases.iter().filter(|alias| !self.is_valid_alias(alias)).collect()
+    }
+
+    /// Checks that the sender belongs to the server named by the state key, as servers do when
+    /// authorizing the event.
+    pub fn validate_sender_domain(&self) -> Result<(), ContentBuildError> {
+        if sender_server(self) == self.state_key {
+            Ok(())
+        } else {
+            Err(ContentBuildError::ForeignSender {
+                sender: self.user_id.clone(),
+                server_name: self.state_key.clone(),
+            })
+        }
+    }
+
+    fn is_valid_alias(&self, alias: &RoomAliasId) -> bool {
+        server_name(alias.hostname(), alias.port()) == self.state_key
+    }
+}
+
+/// Merges the aliases published by several servers into a single list of valid aliases.
+///
+/// The events are processed in order of their state keys, so the result does not depend on the
+/// order in which the events were received. Aliases that do not belong to the server that
+/// published them are stripped, and duplicates are removed.
+pub fn merge_aliases(events: &[AliasesEvent]) -> Vec<RoomAliasId> {
+    let mut events: Vec<&AliasesEvent> = events.iter().collect();
+    events.sort_by(|a, b| a.state_key.cmp(&b.state_key));
+
+    let mut aliases: Vec<RoomAliasId> = Vec::new();
+
+    for event in events {
+        for alias in event.valid_aliases() {
+            if !aliases.contains(alias) {
+                aliases.push(alias.clone());
+            }
+        }
+    }
+
+    aliases
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::from_str;
+
+    use ContentBuildError;
+    use super::{AliasesEvent, merge_aliases};
+
+    fn aliases_event(state_key: &str, aliases: &str) -> AliasesEvent {
+        from_str(&format!(r#"{{
+            "content": {{"aliases": {}}},
+            "event_id": "$h29iv0s8:example.com",
+            "origin_server_ts": 1,
+            "room_id": "!n8f893n9:example.com",
+            "sender": "@carl:example.com",
+            "state_key": "{}",
+            "type": "m.room.aliases"
+        }}"#, aliases, state_key)).unwrap()
+    }
+
+    #[test]
+    fn merge_strips_foreign_aliases() {
+        let events = vec![
+            aliases_event("example.org", r##"["#b:example.org", "#evil:example.com"]"##),
+            aliases_event("example.com", r##"["#a:example.com", "#c:example.com:8448"]"##),
+        ];
+
+        assert_eq!(events[0].invalid_aliases().len(), 1);
+
+        let merged: Vec<String> = merge_aliases(&events).iter().map(|alias| alias.to_string())
+            .collect();
+
+        assert_eq!(merged, vec!["#a:example.com", "#b:example.org"]);
+    }
+
+    #[test]
+    fn sender_must_belong_to_state_key_server() {
+        assert!(aliases_event("example.com", "[]").validate_sender_domain().is_ok());
+
+        match aliases_event("example.org", "[]").validate_sender_domain() {
+            Err(ContentBuildError::ForeignSender { ref server_name, .. }) => {
+                assert_eq!(server_name, "example.org");
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
 }
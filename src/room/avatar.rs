@@ -1,6 +1,6 @@
 //! Types for the *m.room.avatar* event.
 
-use super::ImageInfo;
+use super::{ImageInfo, MediaSource};
 
 state_event! {
     /// A picture that is associated with the room.
@@ -12,12 +12,88 @@ state_event! {
 /// The payload of an `AvatarEvent`.
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct AvatarEventContent {
-    /// Information about the avatar image.
-    pub info: ImageInfo,
-    /// Information about the avatar thumbnail image.
-    pub thumbnail_info: ImageInfo,
-    /// URL of the avatar thumbnail image.
-    pub thumbnail_url: String,
-    /// URL of the avatar image.
-    pub url: String,
+    /// Information about the avatar image, including its thumbnail.
+    #[serde(skip_serializing_if="Option::is_none")]
+    pub info: Option<ImageInfo>,
+    /// The location of the avatar image, serialized as either `url` or `file`.
+    ///
+    /// `None` if the room has no avatar, which is sent as a missing or empty `url`.
+    #[serde(flatten, with="source")]
+    pub source: Option<MediaSource>,
+    /// Fields that are not covered by the Matrix specification.
+    #[cfg(feature = "preserve-extra-fields")]
+    #[serde(flatten)]
+    pub extra: ::std::collections::BTreeMap<String, ::serde_json::Value>,
+}
+
+impl_event_content!(AvatarEventContent, RoomAvatar);
+impl_static_state_event_content!(AvatarEventContent, ::EmptyStateKey);
+impl_room_event_content!(state, AvatarEventContent);
+
+/// Serializes an optional `MediaSource` into the `url` or `file` key of its containing object,
+/// treating an empty `url` as no source. Used with `#[serde(flatten)]`.
+mod source {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use serde::de::Error;
+
+    use mxc_uri::MxcUri;
+    use room::{EncryptedFile, MediaSource};
+
+    #[derive(Deserialize, Serialize)]
+    struct RawSource {
+        #[serde(skip_serializing_if="Option::is_none")]
+        file: Option<Box<EncryptedFile>>,
+        #[serde(skip_serializing_if="Option::is_none")]
+        url: Option<String>,
+    }
+
+    pub fn serialize<S>(source: &Option<MediaSource>, serializer: S) -> Result<S::Ok, S::Error>
+    where S: Serializer {
+        let raw = match *source {
+            Some(MediaSource::Encrypted(ref file)) => {
+                RawSource { file: Some(file.clone()), url: None }
+            }
+            Some(MediaSource::Plain(ref url)) => {
+                RawSource { file: None, url: Some(url.to_string()) }
+            }
+            None => RawSource { file: None, url: None },
+        };
+
+        raw.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<MediaSource>, D::Error>
+    where D: Deserializer<'de> {
+        let raw = RawSource::deserialize(deserializer)?;
+
+        match (raw.file, raw.url) {
+            (Some(file), _) => Ok(Some(MediaSource::Encrypted(file))),
+            (None, Some(ref url)) if !url.is_empty() => {
+                url.parse::<MxcUri>()
+                    .map(|url| Some(MediaSource::Plain(url)))
+                    .map_err(|_| D::Error::custom(format!("invalid MXC URI {:?}", url)))
+            }
+            (None, _) => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{from_str, to_string};
+
+    use super::AvatarEventContent;
+
+    #[test]
+    fn empty_url_removes_avatar() {
+        let content = from_str::<AvatarEventContent>(r#"{"url":""}"#).unwrap();
+        assert!(content.source.is_none());
+        assert_eq!(to_string(&content).unwrap(), "{}");
+
+        assert!(from_str::<AvatarEventContent>("{}").unwrap().source.is_none());
+        assert!(from_str::<AvatarEventContent>(r#"{"url":"https://example.com/a.png"}"#).is_err());
+
+        let content = from_str::<AvatarEventContent>(r#"{"url":"mxc://example.com/a"}"#).unwrap();
+        assert_eq!(to_string(&content).unwrap(), r#"{"url":"mxc://example.com/a"}"#);
+    }
 }
@@ -0,0 +1,44 @@
+//! Types for the unstable *org.matrix.msc2716.batch* event, as proposed in MSC2716.
+
+use {EventContent, EventType};
+
+room_event! {
+    /// Closes a batch of historical messages and links it to the insertion event it is inserted
+    /// at.
+    pub struct BatchEvent(BatchEventContent) {}
+}
+
+/// The payload of a `BatchEvent`.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct BatchEventContent {
+    /// The `next_batch_id` of the insertion event this batch is inserted at.
+    #[serde(rename = "org.matrix.msc2716.batch_id")]
+    pub batch_id: String,
+
+    /// Whether the event is part of imported history. Always true.
+    #[serde(rename = "org.matrix.msc2716.historical", default)]
+    pub historical: bool,
+
+    /// Fields that are not covered by the Matrix specification.
+    #[cfg(feature = "preserve-extra-fields")]
+    #[serde(flatten)]
+    pub extra: ::std::collections::BTreeMap<String, ::serde_json::Value>,
+}
+
+impl EventContent for BatchEventContent {
+    fn event_type() -> EventType {
+        EventType::Custom("org.matrix.msc2716.batch".into())
+    }
+}
+
+impl BatchEventContent {
+    /// Creates the content of a batch event for the given batch ID.
+    pub fn new(batch_id: String) -> Self {
+        BatchEventContent {
+            batch_id,
+            historical: true,
+            #[cfg(feature = "preserve-extra-fields")]
+            extra: Default::default(),
+        }
+    }
+}
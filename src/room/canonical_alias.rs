@@ -2,6 +2,9 @@
 
 use ruma_identifiers::RoomAliasId;
 
+use ContentBuildError;
+use super::server_name;
+
 state_event! {
     /// Informs the room as to which alias is the canonical one.
     pub struct CanonicalAliasEvent(CanonicalAliasEventContent) {}
@@ -12,4 +15,96 @@ state_event! {
 pub struct CanonicalAliasEventContent {
     /// The canonical alias.
     pub alias: RoomAliasId,
+    /// Alternative aliases the room advertises.
+    #[serde(default, skip_serializing_if="Vec::is_empty")]
+    pub alt_aliases: Vec<RoomAliasId>,
+    /// Fields that are not covered by the Matrix specification.
+    #[cfg(feature = "preserve-extra-fields")]
+    #[serde(flatten)]
+    pub extra: ::std::collections::BTreeMap<String, ::serde_json::Value>,
+}
+
+impl_event_content!(CanonicalAliasEventContent, RoomCanonicalAlias);
+impl_static_state_event_content!(CanonicalAliasEventContent, ::EmptyStateKey);
+impl_room_event_content!(state, CanonicalAliasEventContent);
+
+impl CanonicalAliasEvent {
+    /// Checks that the canonical alias and all alternative aliases belong to `own_server`.
+    ///
+    /// A server can only verify that aliases on itself point at the room, so servers which do not
+    /// allow canonical aliases on other servers can use this to reject such events. The format of
+    /// the aliases themselves is already validated when the event is deserialized.
+    pub fn validate_alias_domains(&self, own_server: &str) -> Result<(), ContentBuildError> {
+        let aliases = Some(&self.content.alias).into_iter().chain(&self.content.alt_aliases);
+
+        for alias in aliases {
+            let alias_server = server_name(alias.hostname(), alias.port());
+
+            if alias_server != own_server {
+                return Err(ContentBuildError::ForeignAlias {
+                    alias: alias.clone(),
+                    server_name: alias_server,
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::from_str;
+
+    use ContentBuildError;
+    use super::CanonicalAliasEvent;
+
+    fn event(content: &str) -> CanonicalAliasEvent {
+        from_str(&format!(r#"{{
+            "content": {},
+            "event_id": "$h29iv0s8:example.com",
+            "origin_server_ts": 1,
+            "room_id": "!n8f893n9:example.com",
+            "sender": "@carl:example.com",
+            "state_key": "",
+            "type": "m.room.canonical_alias"
+        }}"#, content)).unwrap()
+    }
+
+    #[test]
+    fn local_aliases_are_valid() {
+        let event = event(
+            r##"{"alias": "#room:example.com", "alt_aliases": ["#alt:example.com"]}"##
+        );
+
+        assert_eq!(event.validate_alias_domains("example.com"), Ok(()));
+    }
+
+    #[test]
+    fn foreign_alias_is_rejected() {
+        let event = event(r##"{"alias": "#room:example.org"}"##);
+
+        assert_eq!(
+            event.validate_alias_domains("example.com"),
+            Err(ContentBuildError::ForeignAlias {
+                alias: from_str(r##""#room:example.org""##).unwrap(),
+                server_name: "example.org".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn foreign_alt_alias_is_rejected() {
+        let event = event(
+            r##"{"alias": "#room:example.com", "alt_aliases": ["#alt:example.org:8448"]}"##
+        );
+
+        assert_eq!(
+            event.validate_alias_domains("example.com"),
+            Err(ContentBuildError::ForeignAlias {
+                alias: from_str(r##""#alt:example.org:8448""##).unwrap(),
+                server_name: "example.org:8448".to_string(),
+            })
+        );
+    }
 }
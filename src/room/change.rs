@@ -0,0 +1,203 @@
+//! Structured descriptions of changes to a room's name, topic, and avatar.
+//!
+//! These are meant for timeline renderers, which can turn them into localized text such as "Alice
+//! changed the topic to ...".
+
+use collections::all::StateEvent;
+use room::avatar::AvatarEventContent;
+
+/// A change to the name, topic, or avatar of a room.
+///
+/// An empty name, topic, or avatar URL counts as the room having none.
+#[derive(Clone, Debug, PartialEq)]
+pub enum RoomChange {
+    /// The avatar was replaced by another one.
+    AvatarChanged {
+        /// The URL of the new avatar.
+        new: String,
+        /// The URL of the previous avatar.
+        old: String,
+    },
+
+    /// The avatar was removed.
+    AvatarRemoved {
+        /// The URL of the previous avatar.
+        old: String,
+    },
+
+    /// An avatar was set for a room that did not have one.
+    AvatarSet {
+        /// The URL of the new avatar.
+        new: String,
+    },
+
+    /// The name was replaced by another one.
+    NameChanged {
+        /// The new name.
+        new: String,
+        /// The previous name.
+        old: String,
+    },
+
+    /// The name was removed.
+    NameRemoved {
+        /// The previous name.
+        old: String,
+    },
+
+    /// A name was set for a room that did not have one.
+    NameSet {
+        /// The new name.
+        new: String,
+    },
+
+    /// The topic was replaced by another one.
+    TopicChanged {
+        /// The new topic.
+        new: String,
+        /// The previous topic.
+        old: String,
+    },
+
+    /// The topic was removed.
+    TopicRemoved {
+        /// The previous topic.
+        old: String,
+    },
+
+    /// A topic was set for a room that did not have one.
+    TopicSet {
+        /// The new topic.
+        new: String,
+    },
+}
+
+/// What happened to a single piece of text-like room state.
+enum TextChange {
+    Changed(String, String),
+    Removed(String),
+    Set(String),
+}
+
+impl RoomChange {
+    /// Describes the change made by an *m.room.avatar*, *m.room.name*, or *m.room.topic* event,
+    /// using its `prev_content`.
+    ///
+    /// Returns `None` for other event types and for events that did not change anything.
+    pub fn from_state_event(event: &StateEvent) -> Option<RoomChange> {
+        match *event {
+            StateEvent::RoomAvatar(ref event) => {
+                let old = event.prev_content.as_ref().map(avatar_url);
+
+                text_change(old, avatar_url(&event.content)).map(|change| match change {
+                    TextChange::Changed(old, new) => RoomChange::AvatarChanged { new, old },
+                    TextChange::Removed(old) => RoomChange::AvatarRemoved { old },
+                    TextChange::Set(new) => RoomChange::AvatarSet { new },
+                })
+            }
+            StateEvent::RoomName(ref event) => {
+                let old = event.prev_content.as_ref().map(|content| &content.name[..]);
+
+                text_change(old, &event.content.name).map(|change| match change {
+                    TextChange::Changed(old, new) => RoomChange::NameChanged { new, old },
+                    TextChange::Removed(old) => RoomChange::NameRemoved { old },
+                    TextChange::Set(new) => RoomChange::NameSet { new },
+                })
+            }
+            StateEvent::RoomTopic(ref event) => {
+                let old = event.prev_content.as_ref().map(|content| &content.topic[..]);
+
+                text_change(old, &event.content.topic).map(|change| match change {
+                    TextChange::Changed(old, new) => RoomChange::TopicChanged { new, old },
+                    TextChange::Removed(old) => RoomChange::TopicRemoved { old },
+                    TextChange::Set(new) => RoomChange::TopicSet { new },
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Compares the previous and current value of a piece of state, treating empty values as unset.
+/// The URL of an avatar, or an empty string if the room has none.
+fn avatar_url(content: &AvatarEventContent) -> &str {
+    content.source.as_ref().map_or("", |source| source.url().as_str())
+}
+
+fn text_change(old: Option<&str>, new: &str) -> Option<TextChange> {
+    match (old.unwrap_or(""), new) {
+        ("", "") => None,
+        ("", new) => Some(TextChange::Set(new.to_string())),
+        (old, "") => Some(TextChange::Removed(old.to_string())),
+        (old, new) if old == new => None,
+        (old, new) => Some(TextChange::Changed(old.to_string(), new.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::from_str;
+
+    use collections::all::StateEvent;
+    use super::RoomChange;
+
+    fn topic_event(prev_content: &str, content: &str) -> StateEvent {
+        from_str(&format!(
+            r#"{{
+                "content": {},
+                "event_id": "$h29iv0s8:example.com",
+                "origin_server_ts": 1,
+                "prev_content": {},
+                "room_id": "!n8f893n9:example.com",
+                "sender": "@carl:example.com",
+                "state_key": "",
+                "type": "m.room.topic"
+            }}"#,
+            content,
+            prev_content,
+        )).unwrap()
+    }
+
+    #[test]
+    fn empty_avatar_url_removes_avatar() {
+        let event = from_str::<StateEvent>(r#"{
+            "content": {"url": ""},
+            "event_id": "$h29iv0s8:example.com",
+            "origin_server_ts": 1,
+            "prev_content": {"url": "mxc://example.com/avatar"},
+            "room_id": "!n8f893n9:example.com",
+            "sender": "@carl:example.com",
+            "state_key": "",
+            "type": "m.room.avatar"
+        }"#).unwrap();
+
+        assert_eq!(
+            RoomChange::from_state_event(&event),
+            Some(RoomChange::AvatarRemoved { old: "mxc://example.com/avatar".to_string() })
+        );
+    }
+
+    #[test]
+    fn topic_changes() {
+        assert_eq!(
+            RoomChange::from_state_event(&topic_event("null", r#"{"topic":"Ruma"}"#)),
+            Some(RoomChange::TopicSet { new: "Ruma".to_string() })
+        );
+        assert_eq!(
+            RoomChange::from_state_event(
+                &topic_event(r#"{"topic":"Ruma"}"#, r#"{"topic":"Matrix"}"#)
+            ),
+            Some(RoomChange::TopicChanged { new: "Matrix".to_string(), old: "Ruma".to_string() })
+        );
+        assert_eq!(
+            RoomChange::from_state_event(&topic_event(r#"{"topic":"Ruma"}"#, r#"{"topic":""}"#)),
+            Some(RoomChange::TopicRemoved { old: "Ruma".to_string() })
+        );
+        assert_eq!(
+            RoomChange::from_state_event(
+                &topic_event(r#"{"topic":"Ruma"}"#, r#"{"topic":"Ruma"}"#)
+            ),
+            None
+        );
+    }
+}
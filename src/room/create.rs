@@ -2,6 +2,10 @@
 
 use ruma_identifiers::UserId;
 
+use ContentBuildError;
+use room_version::RoomVersionId;
+use super::{sender_server, server_name};
+
 state_event! {
     /// This is the first event in a room and cannot be changed. It acts as the root of all other
     /// events.
@@ -15,4 +19,32 @@ pub struct CreateEventContent {
     pub creator: UserId,
     /// Whether or not this room's data should be transferred to other homeservers.
     pub federate: Option<bool>,
+    /// The version of the room. Defaults to version 1 if it is not given.
+    #[serde(default)]
+    pub room_version: RoomVersionId,
+    /// Fields that are not covered by the Matrix specification.
+    #[cfg(feature = "preserve-extra-fields")]
+    #[serde(flatten)]
+    pub extra: ::std::collections::BTreeMap<String, ::serde_json::Value>,
+}
+
+impl_event_content!(CreateEventContent, RoomCreate);
+impl_static_state_event_content!(CreateEventContent, ::EmptyStateKey);
+impl_room_event_content!(state, CreateEventContent);
+
+impl CreateEvent {
+    /// Checks that the sender belongs to the server that the room ID was created on, as servers
+    /// do when authorizing the event.
+    pub fn validate_sender_domain(&self) -> Result<(), ContentBuildError> {
+        let room_server = server_name(self.room_id.hostname(), self.room_id.port());
+
+        if sender_server(self) == room_server {
+            Ok(())
+        } else {
+            Err(ContentBuildError::ForeignSender {
+                sender: self.user_id.clone(),
+                server_name: room_server,
+            })
+        }
+    }
 }
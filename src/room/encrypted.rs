@@ -0,0 +1,453 @@
+//! Types for the *m.room.encrypted* event.
+
+use std::collections::HashMap;
+use std::fmt::{Debug, Formatter, Result as FmtResult};
+
+use ruma_identifiers::{EventId, RoomId, UserId};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde::de::Error;
+use serde_json::{Error as JsonError, Value, from_value};
+
+use DeviceId;
+use collections::all::RoomEvent;
+use collections::deserialize_limited_value;
+use int::UInt;
+use redact::{Redacted, Sorted};
+use super::encryption::Algorithm;
+use super::relation::{Relation, RelationType};
+
+room_event! {
+    /// This event type is used when sending encrypted events.
+    pub struct EncryptedEvent(EncryptedEventContent) {}
+}
+
+event! {
+    /// An encrypted event sent directly to a device, such as an *m.room_key* event encrypted with
+    /// *m.olm.v1.curve25519-aes-sha2*.
+    pub struct EncryptedToDeviceEvent(EncryptedEventContent) {
+        /// The user who sent the event.
+        pub sender: UserId
+    }
+}
+
+/// The payload of an `EncryptedEvent`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum EncryptedEventContent {
+    /// An event encrypted with *m.megolm.v1.aes-sha2*.
+    MegolmV1AesSha2(MegolmV1AesSha2Content),
+
+    /// An event encrypted with *m.olm.v1.curve25519-aes-sha2*.
+    OlmV1Curve25519AesSha2(OlmV1Curve25519AesSha2Content),
+}
+
+impl_event_content!(EncryptedEventContent, RoomEncrypted);
+impl_room_event_content!(message_like, EncryptedEventContent);
+
+/// A decryption backend, implemented by higher layers that hold the keys.
+pub trait Decryptor {
+    /// The error returned when an event cannot be decrypted.
+    type Error;
+
+    /// Decrypts the content of an encrypted event into the event it was encrypted from.
+    fn decrypt(&self, content: &EncryptedEventContent, metadata: &DecryptionMetadata)
+    -> Result<RoomEvent, Self::Error>;
+}
+
+/// The cleartext fields of an encrypted event, which the decrypted event shares.
+#[derive(Clone, Copy, Debug)]
+pub struct DecryptionMetadata<'a> {
+    /// The unique identifier for the event.
+    pub event_id: &'a EventId,
+    /// Timestamp in milliseconds on originating homeserver when this event was sent.
+    pub origin_server_ts: UInt,
+    /// The unique identifier for the room associated with this event.
+    pub room_id: &'a RoomId,
+    /// The unique identifier for the user who sent this event.
+    pub user_id: &'a UserId,
+}
+
+impl<'a> DecryptionMetadata<'a> {
+    /// Builds the decrypted event from the decrypted payload, a JSON object with the `type` and
+    /// `content` of the event, and this metadata.
+    pub fn into_event(self, payload: Value) -> Result<RoomEvent, JsonError> {
+        let mut event = match payload {
+            Value::Object(event) => event,
+            _ => return Err(JsonError::custom("decrypted payload is not an object")),
+        };
+
+        event.insert("event_id".to_string(), Value::String(self.event_id.to_string()));
+        event.insert("origin_server_ts".to_string(), Value::from(u64::from(self.origin_server_ts)));
+        event.insert("room_id".to_string(), Value::String(self.room_id.to_string()));
+        event.insert("sender".to_string(), Value::String(self.user_id.to_string()));
+
+        from_value(Value::Object(event))
+    }
+}
+
+impl EncryptedEvent {
+    /// Decrypts the event with the given decryptor.
+    pub fn decrypt_with<D>(&self, decryptor: &D) -> Result<RoomEvent, D::Error>
+    where D: Decryptor {
+        let metadata = DecryptionMetadata {
+            event_id: &self.event_id,
+            origin_server_ts: self.origin_server_ts,
+            room_id: &self.room_id,
+            user_id: &self.user_id,
+        };
+
+        decryptor.decrypt(&self.content, &metadata)
+    }
+}
+
+impl EncryptedEventContent {
+    /// The encryption algorithm of the event.
+    pub fn algorithm(&self) -> &Algorithm {
+        match *self {
+            EncryptedEventContent::MegolmV1AesSha2(ref content) => &content.algorithm,
+            EncryptedEventContent::OlmV1Curve25519AesSha2(ref content) => &content.algorithm,
+        }
+    }
+
+    /// The cleartext relation of the event to another event, if any.
+    pub fn relates_to(&self) -> Option<&Relation> {
+        match *self {
+            EncryptedEventContent::MegolmV1AesSha2(ref content) => content.relates_to.as_ref(),
+            EncryptedEventContent::OlmV1Curve25519AesSha2(ref content) => {
+                content.relates_to.as_ref()
+            }
+        }
+    }
+
+    /// The type of the cleartext relation of the event, if any.
+    pub fn relation_type(&self) -> Option<RelationType> {
+        self.relates_to()?.rel_type()
+    }
+
+    /// Whether the event replaces the content of another event, without decrypting it.
+    pub fn is_edit(&self) -> bool {
+        matches!(self.relates_to(), Some(&Relation::Replacement(_)))
+    }
+
+    /// Whether the event is a reply in a thread, without decrypting it.
+    pub fn is_thread_reply(&self) -> bool {
+        matches!(self.relates_to(), Some(&Relation::Thread(_)))
+    }
+}
+
+/// The payload of an event encrypted with *m.megolm.v1.aes-sha2*.
+///
+/// The `Debug` output redacts `ciphertext`.
+#[derive(Clone, Deserialize, PartialEq, Serialize)]
+pub struct MegolmV1AesSha2Content {
+    /// The encryption algorithm. Always *m.megolm.v1.aes-sha2*.
+    pub algorithm: Algorithm,
+
+    /// The encrypted content of the event.
+    pub ciphertext: String,
+
+    /// The ID of the sending device.
+    pub device_id: DeviceId,
+
+    /// The cleartext relation of the event to another event, which lets servers aggregate
+    /// encrypted events.
+    #[serde(rename="m.relates_to")]
+    #[serde(skip_serializing_if="Option::is_none")]
+    pub relates_to: Option<Relation>,
+
+    /// The Curve25519 key of the sender.
+    pub sender_key: String,
+
+    /// The ID of the session used to encrypt the message.
+    pub session_id: String,
+
+    /// Fields that are not covered by the Matrix specification.
+    #[cfg(feature = "preserve-extra-fields")]
+    #[serde(flatten)]
+    pub extra: ::std::collections::BTreeMap<String, ::serde_json::Value>,
+}
+
+impl Debug for MegolmV1AesSha2Content {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        let mut debug = f.debug_struct("MegolmV1AesSha2Content");
+        debug
+            .field("algorithm", &self.algorithm)
+            .field("ciphertext", &Redacted)
+            .field("device_id", &self.device_id)
+            .field("relates_to", &self.relates_to)
+            .field("sender_key", &self.sender_key)
+            .field("session_id", &self.session_id);
+        #[cfg(feature = "preserve-extra-fields")]
+        debug.field("extra", &self.extra);
+
+        debug.finish()
+    }
+}
+
+/// The payload of an event encrypted with *m.olm.v1.curve25519-aes-sha2*.
+#[derive(Clone, Deserialize, PartialEq, Serialize)]
+pub struct OlmV1Curve25519AesSha2Content {
+    /// The encryption algorithm. Always *m.olm.v1.curve25519-aes-sha2*.
+    pub algorithm: Algorithm,
+
+    /// A map from the Curve25519 keys of the recipient devices to the encrypted payload for
+    /// each of them.
+    pub ciphertext: HashMap<String, OlmCiphertext>,
+
+    /// The cleartext relation of the event to another event, which lets servers aggregate
+    /// encrypted events.
+    #[serde(rename="m.relates_to")]
+    #[serde(skip_serializing_if="Option::is_none")]
+    pub relates_to: Option<Relation>,
+
+    /// The Curve25519 key of the sender.
+    pub sender_key: String,
+
+    /// Fields that are not covered by the Matrix specification.
+    #[cfg(feature = "preserve-extra-fields")]
+    #[serde(flatten)]
+    pub extra: ::std::collections::BTreeMap<String, ::serde_json::Value>,
+}
+
+impl Debug for OlmV1Curve25519AesSha2Content {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        let mut debug = f.debug_struct("OlmV1Curve25519AesSha2Content");
+        debug
+            .field("algorithm", &self.algorithm)
+            .field("ciphertext", &Sorted(&self.ciphertext))
+            .field("relates_to", &self.relates_to)
+            .field("sender_key", &self.sender_key);
+        #[cfg(feature = "preserve-extra-fields")]
+        debug.field("extra", &self.extra);
+
+        debug.finish()
+    }
+}
+
+/// An Olm message encrypted for a single device.
+///
+/// The `Debug` output redacts `body`.
+#[derive(Clone, Deserialize, PartialEq, Serialize)]
+pub struct OlmCiphertext {
+    /// The encrypted payload.
+    pub body: String,
+
+    /// The Olm message type: 0 for pre-key messages, 1 for normal messages.
+    #[serde(rename="type")]
+    pub message_type: u64,
+}
+
+impl Debug for OlmCiphertext {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        f.debug_struct("OlmCiphertext")
+            .field("body", &Redacted)
+            .field("message_type", &self.message_type)
+            .finish()
+    }
+}
+
+impl Serialize for EncryptedEventContent {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
+        match *self {
+            EncryptedEventContent::MegolmV1AesSha2(ref content) => content.serialize(serializer),
+            EncryptedEventContent::OlmV1Curve25519AesSha2(ref content) => {
+                content.serialize(serializer)
+            }
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for EncryptedEventContent {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where D: Deserializer<'de> {
+        let value = deserialize_limited_value(deserializer)?;
+
+        let algorithm_value = match value.get("algorithm") {
+            Some(value) => value,
+            None => return Err(D::Error::missing_field("algorithm")),
+        };
+
+        let algorithm = match Algorithm::deserialize(algorithm_value) {
+            Ok(algorithm) => algorithm,
+            Err(error) => return Err(D::Error::custom(error.to_string())),
+        };
+
+        match algorithm {
+            Algorithm::MegolmV1AesSha2 => {
+                let content = match from_value::<MegolmV1AesSha2Content>(value) {
+                    Ok(content) => content,
+                    Err(error) => return Err(D::Error::custom(error.to_string())),
+                };
+
+                Ok(EncryptedEventContent::MegolmV1AesSha2(content))
+            }
+            Algorithm::OlmV1Curve25519AesSha2 => {
+                let content = match from_value::<OlmV1Curve25519AesSha2Content>(value) {
+                    Ok(content) => content,
+                    Err(error) => return Err(D::Error::custom(error.to_string())),
+                };
+
+                Ok(EncryptedEventContent::OlmV1Curve25519AesSha2(content))
+            }
+            Algorithm::Custom(algorithm) => {
+                Err(D::Error::custom(format!("unknown encryption algorithm {}", algorithm)))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{from_str, to_string};
+
+    use collections::all::RoomEvent;
+    use room::relation::Relation;
+    use super::{DecryptionMetadata, Decryptor, EncryptedEvent, EncryptedEventContent};
+
+    struct PlaintextDecryptor;
+
+    impl Decryptor for PlaintextDecryptor {
+        type Error = String;
+
+        fn decrypt(&self, content: &EncryptedEventContent, metadata: &DecryptionMetadata)
+        -> Result<RoomEvent, String> {
+            let ciphertext = match *content {
+                EncryptedEventContent::MegolmV1AesSha2(ref content) => &content.ciphertext,
+                _ => return Err("unsupported algorithm".to_string()),
+            };
+
+            let payload = from_str(ciphertext).map_err(|error| error.to_string())?;
+            metadata.into_event(payload).map_err(|error| error.to_string())
+        }
+    }
+
+    #[test]
+    fn decrypt_with() {
+        let payload = r#"{"content":{"body":"hi","msgtype":"m.text"},"type":"m.room.message"}"#;
+        let event = from_str::<EncryptedEvent>(&format!(r#"{{
+            "content": {{
+                "algorithm": "m.megolm.v1.aes-sha2",
+                "ciphertext": {},
+                "device_id": "RJYKSTBOIE",
+                "sender_key": "IlRMeOPX2e0MurIyfWEucYBRVOEEUMrOHqn",
+                "session_id": "X3lUlvLELLYxeTx4"
+            }},
+            "event_id": "$h29iv0s8:example.com",
+            "origin_server_ts": 1,
+            "room_id": "!n8f893n9:example.com",
+            "sender": "@carl:example.com",
+            "type": "m.room.encrypted"
+        }}"#, to_string(payload).unwrap())).unwrap();
+
+        match event.decrypt_with(&PlaintextDecryptor).unwrap() {
+            RoomEvent::RoomMessage(message) => {
+                assert_eq!(message.event_id, event.event_id);
+                assert_eq!(message.user_id, event.user_id);
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn megolm_content_round_trip() {
+        let json = concat!(
+            r#"{"algorithm":"m.megolm.v1.aes-sha2","ciphertext":"AwgAEnACgAkLmt6qF84IK","#,
+            r#""device_id":"RJYKSTBOIE","#,
+            r#""m.relates_to":{"rel_type":"m.replace","event_id":"$h29iv0s8:example.com"},"#,
+            r#""sender_key":"IlRMeOPX2e0MurIyfWEucYBRVOEEUMrOHqn","session_id":"X3lUlvLELLYxeTx4"}"#
+        );
+
+        let content = from_str::<EncryptedEventContent>(json).unwrap();
+
+        match content {
+            EncryptedEventContent::MegolmV1AesSha2(ref content) => {
+                assert_eq!(content.device_id, "RJYKSTBOIE");
+            }
+            _ => unreachable!(),
+        }
+
+        match content.relates_to() {
+            Some(Relation::Replacement(relation)) => {
+                assert_eq!(relation.event_id.to_string(), "$h29iv0s8:example.com");
+            }
+            _ => unreachable!(),
+        }
+
+        assert!(content.is_edit());
+        assert!(!content.is_thread_reply());
+        assert_eq!(to_string(&content).unwrap(), json);
+    }
+
+    #[test]
+    fn olm_content_without_relation() {
+        let content = from_str::<EncryptedEventContent>(r#"{
+            "algorithm": "m.olm.v1.curve25519-aes-sha2",
+            "ciphertext": {"7qZcfnBmbEGzxxaWfBjElJuvn7BZx": {"body": "AwogGJJ", "type": 0}},
+            "sender_key": "Szl29ksW/L8yZGWAX+8dY1XyFi+i5wm+DRhTGkbMiwU"
+        }"#).unwrap();
+
+        match content {
+            EncryptedEventContent::OlmV1Curve25519AesSha2(ref content) => {
+                assert_eq!(content.ciphertext.len(), 1);
+            }
+            _ => unreachable!(),
+        }
+
+        assert!(content.relation_type().is_none());
+    }
+
+    #[test]
+    fn debug_redacts_ciphertext() {
+        let content = from_str::<EncryptedEventContent>(r#"{
+            "algorithm": "m.olm.v1.curve25519-aes-sha2",
+            "ciphertext": {
+                "device_b": {"body": "AwogGJJ", "type": 0},
+                "device_a": {"body": "AwogHKK", "type": 1}
+            },
+            "sender_key": "Szl29ksW/L8yZGWAX+8dY1XyFi+i5wm+DRhTGkbMiwU"
+        }"#).unwrap();
+        let debug = format!("{:?}", content);
+
+        assert!(!debug.contains("AwogGJJ") && !debug.contains("AwogHKK"));
+        assert!(debug.find("device_a").unwrap() < debug.find("device_b").unwrap());
+        assert_eq!(debug, format!("{:?}", content.clone()));
+    }
+
+    #[test]
+    fn thread_reply() {
+        let content = from_str::<EncryptedEventContent>(r#"{
+            "algorithm": "m.megolm.v1.aes-sha2",
+            "ciphertext": "AwgAEnACgAkLmt6qF84IK",
+            "device_id": "RJYKSTBOIE",
+            "m.relates_to": {"event_id": "$root:example.com", "rel_type": "m.thread"},
+            "sender_key": "IlRMeOPX2e0MurIyfWEucYBRVOEEUMrOHqn",
+            "session_id": "X3lUlvLELLYxeTx4"
+        }"#).unwrap();
+
+        assert!(content.is_thread_reply());
+        assert!(!content.is_edit());
+    }
+
+    #[test]
+    fn malformed_relation_is_rejected() {
+        let content = |relates_to: &str| format!(r#"{{
+            "algorithm": "m.megolm.v1.aes-sha2",
+            "ciphertext": "AwgAEnACgAkLmt6qF84IK",
+            "device_id": "RJYKSTBOIE",
+            "m.relates_to": {},
+            "sender_key": "IlRMeOPX2e0MurIyfWEucYBRVOEEUMrOHqn",
+            "session_id": "X3lUlvLELLYxeTx4"
+        }}"#, relates_to);
+
+        assert!(from_str::<EncryptedEventContent>(&content(r#"{"rel_type":"m.thread"}"#)).is_err());
+        assert!(
+            from_str::<EncryptedEventContent>(
+                &content(r#"{"event_id":1,"rel_type":"m.replace"}"#)
+            ).is_err()
+        );
+        assert!(from_str::<EncryptedEventContent>(&content(r#"{"event_id":"$a"}"#)).is_err());
+    }
+
+    #[test]
+    fn unknown_algorithm_is_rejected() {
+        assert!(from_str::<EncryptedEventContent>(r#"{"algorithm":"m.unknown"}"#).is_err());
+    }
+}
@@ -0,0 +1,73 @@
+//! Types for the *m.room.encryption* event.
+
+use int::UInt;
+
+state_event! {
+    /// Defines how messages sent in this room should be encrypted.
+    pub struct EncryptionEvent(EncryptionEventContent) {}
+}
+
+/// The payload of an `EncryptionEvent`.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct EncryptionEventContent {
+    /// The encryption algorithm to be used to encrypt messages sent in this room.
+    pub algorithm: Algorithm,
+
+    /// How long the session should be used before changing it, in milliseconds.
+    #[serde(skip_serializing_if="Option::is_none")]
+    pub rotation_period_ms: Option<UInt>,
+
+    /// How many messages should be sent before changing the session.
+    #[serde(skip_serializing_if="Option::is_none")]
+    pub rotation_period_msgs: Option<UInt>,
+
+    /// Fields that are not covered by the Matrix specification.
+    #[cfg(feature = "preserve-extra-fields")]
+    #[serde(flatten)]
+    pub extra: ::std::collections::BTreeMap<String, ::serde_json::Value>,
+}
+
+impl_event_content!(EncryptionEventContent, RoomEncryption);
+impl_static_state_event_content!(EncryptionEventContent, ::EmptyStateKey);
+impl_room_event_content!(state, EncryptionEventContent);
+
+/// An encryption algorithm used for end-to-end encryption of events.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum Algorithm {
+    /// m.megolm.v1.aes-sha2
+    MegolmV1AesSha2,
+    /// m.olm.v1.curve25519-aes-sha2
+    OlmV1Curve25519AesSha2,
+    /// Any algorithm that is not part of the specification.
+    Custom(String),
+}
+
+impl_string_enum! {
+    Algorithm {
+        MegolmV1AesSha2 => "m.megolm.v1.aes-sha2",
+        OlmV1Curve25519AesSha2 => "m.olm.v1.curve25519-aes-sha2",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{from_str, to_string};
+
+    use int::UInt;
+    use super::{Algorithm, EncryptionEventContent};
+
+    #[test]
+    fn encryption_content_round_trip() {
+        let content = from_str::<EncryptionEventContent>(
+            r#"{"algorithm":"m.megolm.v1.aes-sha2","rotation_period_ms":604800000}"#
+        ).unwrap();
+
+        assert_eq!(content.algorithm, Algorithm::MegolmV1AesSha2);
+        assert_eq!(content.rotation_period_ms, Some(UInt::from(604800000u32)));
+        assert_eq!(content.rotation_period_msgs, None);
+        assert_eq!(
+            to_string(&content).unwrap(),
+            r#"{"algorithm":"m.megolm.v1.aes-sha2","rotation_period_ms":604800000}"#
+        );
+    }
+}
@@ -13,8 +13,16 @@ state_event! {
 pub struct GuestAccessEventContent {
     /// A policy for guest user access to a room.
     pub guest_access: GuestAccess,
+    /// Fields that are not covered by the Matrix specification.
+    #[cfg(feature = "preserve-extra-fields")]
+    #[serde(flatten)]
+    pub extra: ::std::collections::BTreeMap<String, ::serde_json::Value>,
 }
 
+impl_event_content!(GuestAccessEventContent, RoomGuestAccess);
+impl_static_state_event_content!(GuestAccessEventContent, ::EmptyStateKey);
+impl_room_event_content!(state, GuestAccessEventContent);
+
 /// A policy for guest user access to a room.
 #[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
 pub enum GuestAccess {
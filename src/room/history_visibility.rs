@@ -11,8 +11,16 @@ state_event! {
 pub struct HistoryVisibilityEventContent {
     /// Who can see the room history.
     pub history_visibility: HistoryVisibility,
+    /// Fields that are not covered by the Matrix specification.
+    #[cfg(feature = "preserve-extra-fields")]
+    #[serde(flatten)]
+    pub extra: ::std::collections::BTreeMap<String, ::serde_json::Value>,
 }
 
+impl_event_content!(HistoryVisibilityEventContent, RoomHistoryVisibility);
+impl_static_state_event_content!(HistoryVisibilityEventContent, ::EmptyStateKey);
+impl_room_event_content!(state, HistoryVisibilityEventContent);
+
 /// Who can see a room's history.
 #[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
 pub enum HistoryVisibility {
@@ -0,0 +1,81 @@
+//! Types for the unstable *org.matrix.msc2716.insertion* event, as proposed in MSC2716.
+
+use {EventContent, EventType};
+
+room_event! {
+    /// Marks a point in the room's history where a batch of historical messages can be inserted.
+    pub struct InsertionEvent(InsertionEventContent) {}
+}
+
+/// The payload of an `InsertionEvent`.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct InsertionEventContent {
+    /// The ID that the next batch of historical messages must reference to be inserted here.
+    #[serde(rename = "org.matrix.msc2716.next_batch_id")]
+    pub next_batch_id: String,
+
+    /// Whether the event is part of imported history. Always true.
+    #[serde(rename = "org.matrix.msc2716.historical", default)]
+    pub historical: bool,
+
+    /// Fields that are not covered by the Matrix specification.
+    #[cfg(feature = "preserve-extra-fields")]
+    #[serde(flatten)]
+    pub extra: ::std::collections::BTreeMap<String, ::serde_json::Value>,
+}
+
+impl EventContent for InsertionEventContent {
+    fn event_type() -> EventType {
+        EventType::Custom("org.matrix.msc2716.insertion".into())
+    }
+}
+
+impl InsertionEventContent {
+    /// Creates the content of an insertion event for the given next batch ID.
+    pub fn new(next_batch_id: String) -> Self {
+        InsertionEventContent {
+            next_batch_id,
+            historical: true,
+            #[cfg(feature = "preserve-extra-fields")]
+            extra: Default::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{from_str, to_string};
+
+    use {EventContent, EventType};
+    use collections::all::RoomEvent;
+    use super::InsertionEventContent;
+
+    #[test]
+    fn insertion_content() {
+        let json = concat!(
+            r#"{"org.matrix.msc2716.next_batch_id":"w25ljc1kb4","#,
+            r#""org.matrix.msc2716.historical":true}"#
+        );
+
+        assert_eq!(to_string(&InsertionEventContent::new("w25ljc1kb4".to_string())).unwrap(), json);
+
+        let event = from_str::<RoomEvent>(&format!(
+            r#"{{"content":{},"event_id":"$h29iv0s8:example.com","origin_server_ts":1,
+            "room_id":"!n8f893n9:example.com","sender":"@carl:example.com",
+            "type":"org.matrix.msc2716.insertion"}}"#,
+            json
+        )).unwrap();
+
+        match event {
+            RoomEvent::CustomRoom(ref event) => {
+                assert_eq!(event.event_type, InsertionEventContent::event_type());
+            }
+            _ => unreachable!(),
+        }
+
+        assert_eq!(
+            InsertionEventContent::event_type(),
+            EventType::from("org.matrix.msc2716.insertion")
+        );
+    }
+}
@@ -1,5 +1,7 @@
 //! Types for the *m.room.join_rules* event.
 
+use room_version::RoomVersionRules;
+
 state_event! {
     /// Describes how users are allowed to join the room.
     pub struct JoinRulesEvent(JoinRulesEventContent) {}
@@ -10,8 +12,16 @@ state_event! {
 pub struct JoinRulesEventContent {
     /// The type of rules used for users wishing to join this room.
     pub join_rule: JoinRule,
+    /// Fields that are not covered by the Matrix specification.
+    #[cfg(feature = "preserve-extra-fields")]
+    #[serde(flatten)]
+    pub extra: ::std::collections::BTreeMap<String, ::serde_json::Value>,
 }
 
+impl_event_content!(JoinRulesEventContent, RoomJoinRules);
+impl_static_state_event_content!(JoinRulesEventContent, ::EmptyStateKey);
+impl_room_event_content!(state, JoinRulesEventContent);
+
 /// The rule used for users wishing to join this room.
 #[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
 pub enum JoinRule {
@@ -33,6 +43,16 @@ pub enum JoinRule {
     Public,
 }
 
+impl JoinRule {
+    /// Whether rooms with the given rules allow this join rule.
+    pub fn is_allowed_by(&self, rules: &RoomVersionRules) -> bool {
+        match *self {
+            JoinRule::Knock => rules.knocking,
+            _ => true,
+        }
+    }
+}
+
 impl_enum! {
     JoinRule {
         Invite => "invite",
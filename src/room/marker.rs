@@ -0,0 +1,35 @@
+//! Types for the unstable *org.matrix.msc2716.marker* event, as proposed in MSC2716.
+
+use ruma_identifiers::EventId;
+
+use {EventContent, EventType, StaticStateEventContent};
+
+state_event! {
+    /// Points homeservers at an insertion event so that they backfill the history inserted there.
+    ///
+    /// The state key is a unique string, so that every marker is kept in the room state.
+    pub struct MarkerEvent(MarkerEventContent) {}
+}
+
+/// The payload of a `MarkerEvent`.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct MarkerEventContent {
+    /// The insertion event to backfill from.
+    #[serde(rename = "org.matrix.msc2716.marker.insertion")]
+    pub insertion_event_id: EventId,
+
+    /// Fields that are not covered by the Matrix specification.
+    #[cfg(feature = "preserve-extra-fields")]
+    #[serde(flatten)]
+    pub extra: ::std::collections::BTreeMap<String, ::serde_json::Value>,
+}
+
+impl EventContent for MarkerEventContent {
+    fn event_type() -> EventType {
+        EventType::Custom("org.matrix.msc2716.marker".into())
+    }
+}
+
+impl StaticStateEventContent for MarkerEventContent {
+    type StateKey = String;
+}
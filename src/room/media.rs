@@ -0,0 +1,136 @@
+//! Types for metadata about media, shared by the events that refer to images, files, audio clips
+//! and videos.
+
+use int::UInt;
+
+use super::MediaSource;
+
+/// Metadata about an image.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct ImageInfo {
+    /// The height of the image in pixels.
+    #[serde(skip_serializing_if="Option::is_none")]
+    pub h: Option<UInt>,
+    /// The MIME type of the image, e.g. "image/png."
+    #[serde(skip_serializing_if="Option::is_none")]
+    pub mimetype: Option<String>,
+    /// The file size of the image in bytes.
+    #[serde(skip_serializing_if="Option::is_none")]
+    pub size: Option<UInt>,
+    /// Metadata about the image referred to in `thumbnail_source`.
+    #[serde(skip_serializing_if="Option::is_none")]
+    pub thumbnail_info: Option<ThumbnailInfo>,
+    /// The location of a thumbnail of the image, serialized as either `thumbnail_url` or
+    /// `thumbnail_file`.
+    #[serde(flatten, with="super::thumbnail_source")]
+    pub thumbnail_source: Option<MediaSource>,
+    /// The width of the image in pixels.
+    #[serde(skip_serializing_if="Option::is_none")]
+    pub w: Option<UInt>,
+}
+
+/// Metadata about a thumbnail.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct ThumbnailInfo {
+    /// The height of the thumbnail in pixels.
+    #[serde(skip_serializing_if="Option::is_none")]
+    pub h: Option<UInt>,
+    /// The MIME type of the thumbnail, e.g. "image/png."
+    #[serde(skip_serializing_if="Option::is_none")]
+    pub mimetype: Option<String>,
+    /// The file size of the thumbnail in bytes.
+    #[serde(skip_serializing_if="Option::is_none")]
+    pub size: Option<UInt>,
+    /// The width of the thumbnail in pixels.
+    #[serde(skip_serializing_if="Option::is_none")]
+    pub w: Option<UInt>,
+}
+
+/// Metadata about a file.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct FileInfo {
+    /// The mimetype of the file, e.g. "application/msword."
+    #[serde(skip_serializing_if="Option::is_none")]
+    pub mimetype: Option<String>,
+    /// The size of the file in bytes.
+    #[serde(skip_serializing_if="Option::is_none")]
+    pub size: Option<UInt>,
+    /// Metadata about the image referred to in `thumbnail_source`.
+    #[serde(skip_serializing_if="Option::is_none")]
+    pub thumbnail_info: Option<ThumbnailInfo>,
+    /// The location of a thumbnail of the file, serialized as either `thumbnail_url` or
+    /// `thumbnail_file`.
+    #[serde(flatten, with="super::thumbnail_source")]
+    pub thumbnail_source: Option<MediaSource>,
+}
+
+/// Metadata about an audio clip.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct AudioInfo {
+    /// The duration of the audio in milliseconds.
+    #[serde(skip_serializing_if="Option::is_none")]
+    pub duration: Option<UInt>,
+    /// The mimetype of the audio, e.g. "audio/aac."
+    #[serde(skip_serializing_if="Option::is_none")]
+    pub mimetype: Option<String>,
+    /// The size of the audio clip in bytes.
+    #[serde(skip_serializing_if="Option::is_none")]
+    pub size: Option<UInt>,
+}
+
+/// Metadata about a video.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct VideoInfo {
+    /// The duration of the video in milliseconds.
+    #[serde(skip_serializing_if="Option::is_none")]
+    pub duration: Option<UInt>,
+    /// The height of the video in pixels.
+    #[serde(skip_serializing_if="Option::is_none")]
+    pub h: Option<UInt>,
+    /// The mimetype of the video, e.g. "video/mp4."
+    #[serde(skip_serializing_if="Option::is_none")]
+    pub mimetype: Option<String>,
+    /// The size of the video in bytes.
+    #[serde(skip_serializing_if="Option::is_none")]
+    pub size: Option<UInt>,
+    /// Metadata about the image referred to in `thumbnail_source`.
+    #[serde(skip_serializing_if="Option::is_none")]
+    pub thumbnail_info: Option<ThumbnailInfo>,
+    /// The location of a thumbnail of the video clip, serialized as either `thumbnail_url` or
+    /// `thumbnail_file`.
+    #[serde(flatten, with="super::thumbnail_source")]
+    pub thumbnail_source: Option<MediaSource>,
+    /// The width of the video in pixels.
+    #[serde(skip_serializing_if="Option::is_none")]
+    pub w: Option<UInt>,
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{from_str, to_string};
+
+    use int::UInt;
+    use super::ImageInfo;
+
+    #[test]
+    fn image_info_with_thumbnail() {
+        let json = concat!(
+            r#"{"h":128,"mimetype":"image/jpeg","size":1024,"#,
+            r#""thumbnail_info":{"h":16,"mimetype":"image/jpeg","size":32,"w":16},"#,
+            r#""thumbnail_url":"mxc://example.com/thumbnail","w":128}"#
+        );
+        let info = from_str::<ImageInfo>(json).unwrap();
+
+        assert_eq!(info.h, Some(UInt::from(128u32)));
+        assert_eq!(
+            info.thumbnail_info.as_ref().and_then(|info| info.size),
+            Some(UInt::from(32u32))
+        );
+        assert_eq!(
+            info.thumbnail_source.as_ref().unwrap().url().as_str(),
+            "mxc://example.com/thumbnail"
+        );
+        assert_eq!(to_string(&info).unwrap(), json);
+        assert_eq!(from_str::<ImageInfo>("{}").unwrap(), ImageInfo::default());
+    }
+}
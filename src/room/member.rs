@@ -1,9 +1,19 @@
 //! Types for the *m.room.member* event.
 
+use std::convert::TryFrom;
+
 use ruma_identifiers::UserId;
 use ruma_signatures::Signatures;
 
-use stripped::StrippedState;
+use serde::{Deserialize, Deserializer};
+use serde::de::Error;
+use serde_json::{Error as JsonError, Value};
+
+use RoomEventContent;
+use int::{Int, UInt};
+use mxc_uri::MxcUri;
+use room::power_levels::PowerLevelsEventContent;
+use stripped::{StrippedState, StrippedStateContent};
 
 state_event! {
     /// The current membership state of a user in the room.
@@ -31,8 +41,11 @@ state_event! {
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct MemberEventContent {
     /// The avatar URL for this user.
+    ///
+    /// An empty `avatar_url`, which clients send to remove an avatar, is deserialized as `None`.
+    #[serde(default, deserialize_with="deserialize_avatar_url")]
     #[serde(skip_serializing_if="Option::is_none")]
-    pub avatar_url: Option<String>,
+    pub avatar_url: Option<MxcUri>,
 
     /// The display name for this user.
     #[serde(skip_serializing_if="Option::is_none")]
@@ -41,10 +54,76 @@ pub struct MemberEventContent {
     /// The membership state of this user.
     pub membership: MembershipState,
 
+    /// The reason for the change of membership, such as the reason for a kick or ban.
+    #[serde(skip_serializing_if="Option::is_none")]
+    pub reason: Option<String>,
+
     /// If this member event is the successor to a third party invitation, this field will contain
     /// information about that invitation.
     #[serde(skip_serializing_if="Option::is_none")]
     pub third_party_invite: Option<ThirdPartyInvite>,
+
+    /// Fields that are not covered by the Matrix specification.
+    #[cfg(feature = "preserve-extra-fields")]
+    #[serde(flatten)]
+    pub extra: ::std::collections::BTreeMap<String, ::serde_json::Value>,
+}
+
+impl_event_content!(MemberEventContent, RoomMember);
+impl_static_state_event_content!(MemberEventContent, UserId);
+
+impl RoomEventContent for MemberEventContent {
+    /// The level required for the change of membership: `invite` for invites and `ban` for bans.
+    ///
+    /// Kicks need `kick` and unbans need `ban`, but both leave the target with the membership
+    /// `leave`, like a user leaving on their own. Since the content cannot tell them apart, `leave`
+    /// requires the higher of `kick` and `ban`. Joining and knocking require no level.
+    fn required_power_level(&self, power_levels: &PowerLevelsEventContent) -> Int {
+        match self.membership {
+            MembershipState::Ban => power_levels.ban,
+            MembershipState::Invite => power_levels.invite,
+            MembershipState::Leave => power_levels.kick.max(power_levels.ban),
+            _ => Int::from(0),
+        }
+    }
+}
+
+/// Constructors for moderation actions.
+///
+/// The state key of the member event carrying the content must be the ID of the target user, not
+/// of the moderator sending it. Building the event with `StrippedStateContent::new` or
+/// `InitialStateEvent::new` enforces a user ID as the state key.
+impl MemberEventContent {
+    /// The content that makes the target user leave the room.
+    ///
+    /// Kicking a user, lifting a ban and leaving voluntarily all use this content. They differ only
+    /// in the sender, which is the target user when leaving voluntarily, and in the previous
+    /// membership of the target, which is `ban` for an unban.
+    pub fn leave_with_reason(reason: Option<String>) -> Self {
+        MemberEventContent::with_membership(MembershipState::Leave, reason)
+    }
+
+    /// The content that bans the target user from the room.
+    pub fn ban(reason: Option<String>) -> Self {
+        MemberEventContent::with_membership(MembershipState::Ban, reason)
+    }
+
+    /// The content that invites the target user to the room.
+    pub fn invite(reason: Option<String>) -> Self {
+        MemberEventContent::with_membership(MembershipState::Invite, reason)
+    }
+
+    fn with_membership(membership: MembershipState, reason: Option<String>) -> Self {
+        MemberEventContent {
+            avatar_url: None,
+            displayname: None,
+            membership,
+            reason,
+            third_party_invite: None,
+            #[cfg(feature = "preserve-extra-fields")]
+            extra: Default::default(),
+        }
+    }
 }
 
 /// The membership state of a user.
@@ -106,3 +185,429 @@ pub struct SignedContent {
     /// The token property of the containing third_party_invite object.
     pub token: String,
 }
+
+/// The fields of a member event's `unsigned` that carry stripped state of the room.
+///
+/// Entries of types that `StrippedState` does not cover are skipped, but malformed entries and
+/// malformed entries of covered types make deserialization fail.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct MemberUnsigned {
+    /// A subset of the state of the room at the time of an invite received over federation.
+    #[serde(default, deserialize_with="deserialize_stripped_state")]
+    #[serde(skip_serializing_if="Option::is_none")]
+    pub invite_room_state: Option<Vec<StrippedState>>,
+
+    /// A subset of the state of the room at the time of a knock.
+    #[serde(default, deserialize_with="deserialize_stripped_state")]
+    #[serde(skip_serializing_if="Option::is_none")]
+    pub knock_room_state: Option<Vec<StrippedState>>,
+}
+
+/// A summary of the room's membership, as included in the `summary` section of a sync response.
+///
+/// Clients use this information together with the room's *m.room.member* events to calculate a
+/// display name for rooms that have neither an *m.room.name* nor an *m.room.canonical_alias*.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct MembershipSummary {
+    /// The users which can be used to generate a room name if the room does not have one.
+    ///
+    /// This excludes the user the summary was generated for.
+    #[serde(default, rename="m.heroes", skip_serializing_if="Vec::is_empty")]
+    pub heroes: Vec<UserId>,
+
+    /// The number of users with *invite* membership.
+    #[serde(rename="m.invited_member_count", skip_serializing_if="Option::is_none")]
+    pub invited_member_count: Option<UInt>,
+
+    /// The number of users with *join* membership.
+    #[serde(rename="m.joined_member_count", skip_serializing_if="Option::is_none")]
+    pub joined_member_count: Option<UInt>,
+}
+
+/// Display data for one of the heroes of a `MembershipSummary`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Hero {
+    /// The avatar URL of the user, if their member event has one.
+    pub avatar_url: Option<MxcUri>,
+
+    /// The display name of the user, if their member event has one.
+    pub displayname: Option<String>,
+
+    /// The membership state of the user, if a member event for them was found.
+    pub membership: Option<MembershipState>,
+
+    /// The user this hero represents.
+    pub user_id: UserId,
+}
+
+/// A way in which a member's display name can mislead other users, as described in the security
+/// considerations of the specification.
+#[derive(Clone, Debug, PartialEq)]
+pub enum DisplayNameFinding {
+    /// The display name is the ID of a different user, so the member can pass as that user.
+    ImpersonatesUserId(UserId),
+
+    /// The display name contains the given bidirectional formatting character, which can reorder
+    /// the text around it when rendered.
+    BidiControl(char),
+
+    /// The display name contains the given control character.
+    ControlCharacter(char),
+}
+
+impl MemberEvent {
+    /// The stripped state of the room included with an invite.
+    ///
+    /// Invites received over federation carry this state in `unsigned.invite_room_state` rather
+    /// than at the top level, so this returns whichever is present, preferring the top-level
+    /// field. See `MemberUnsigned` for how the state in `unsigned` is deserialized.
+    pub fn invite_state(&self) -> Result<Option<Vec<StrippedState>>, JsonError> {
+        if let Some(ref invite_room_state) = self.invite_room_state {
+            return Ok(Some(invite_room_state.clone()));
+        }
+
+        Ok(self.member_unsigned()?.invite_room_state)
+    }
+
+    /// Deserializes the fields of `unsigned` that carry stripped state of the room.
+    pub fn member_unsigned(&self) -> Result<MemberUnsigned, JsonError> {
+        match self.unsigned {
+            Some(ref unsigned) => MemberUnsigned::deserialize(unsigned),
+            None => Ok(MemberUnsigned::default()),
+        }
+    }
+
+    /// The stripped state of the room included with a knock in `unsigned.knock_room_state`.
+    ///
+    /// See `MemberUnsigned` for how the state is deserialized.
+    pub fn knock_state(&self) -> Result<Option<Vec<StrippedState>>, JsonError> {
+        Ok(self.member_unsigned()?.knock_room_state)
+    }
+
+    /// Checks the display name set by this event for ways it could mislead other users.
+    ///
+    /// Each offending character is reported once, in order of its first occurrence. Moderation
+    /// tools can use the findings to flag the member or to disambiguate the name when rendering.
+    pub fn display_name_findings(&self) -> Vec<DisplayNameFinding> {
+        let displayname = match self.content.displayname {
+            Some(ref displayname) => displayname,
+            None => return Vec::new(),
+        };
+
+        let mut findings = Vec::new();
+
+        if let Ok(user_id) = UserId::try_from(displayname.trim()) {
+            if user_id.to_string() != self.state_key {
+                findings.push(DisplayNameFinding::ImpersonatesUserId(user_id));
+            }
+        }
+
+        for character in displayname.chars() {
+            let finding = if is_bidi_control(character) {
+                DisplayNameFinding::BidiControl(character)
+            } else if character.is_control() {
+                DisplayNameFinding::ControlCharacter(character)
+            } else {
+                continue;
+            };
+
+            if !findings.contains(&finding) {
+                findings.push(finding);
+            }
+        }
+
+        findings
+    }
+}
+
+/// Deserializes an optional array of stripped events, skipping those of types that
+/// `StrippedState` does not cover.
+fn deserialize_stripped_state<'de, D>(
+    deserializer: D,
+) -> Result<Option<Vec<StrippedState>>, D::Error>
+where D: Deserializer<'de> {
+    let events = match Option::<Vec<Value>>::deserialize(deserializer)? {
+        Some(events) => events,
+        None => return Ok(None),
+    };
+
+    let mut state = Vec::with_capacity(events.len());
+
+    for event in events {
+        let stripped = StrippedStateContent::<Value>::deserialize(&event).map_err(Error::custom)?;
+
+        if StrippedState::covers(&stripped.event_type) {
+            state.push(StrippedState::deserialize(event).map_err(Error::custom)?);
+        }
+    }
+
+    Ok(Some(state))
+}
+
+/// Deserializes an optional avatar URL, treating an empty string as no avatar.
+fn deserialize_avatar_url<'de, D>(deserializer: D) -> Result<Option<MxcUri>, D::Error>
+where D: Deserializer<'de> {
+    match Option::<String>::deserialize(deserializer)? {
+        None => Ok(None),
+        Some(ref url) if url.is_empty() => Ok(None),
+        Some(url) => {
+            url.parse().map(Some).map_err(|_| Error::custom(format!("invalid MXC URI {:?}", url)))
+        }
+    }
+}
+
+/// Whether the character is one of the Unicode bidirectional formatting characters.
+fn is_bidi_control(character: char) -> bool {
+    matches!(
+        character,
+        '\u{061C}' | '\u{200E}' | '\u{200F}' | '\u{202A}'..='\u{202E}' | '\u{2066}'..='\u{2069}'
+    )
+}
+
+impl MembershipSummary {
+    /// Joins the heroes of this summary with the given member events, in the order the heroes are
+    /// listed in the summary.
+    ///
+    /// If several member events exist for the same user, the last one in `members` is used.
+    pub fn heroes(&self, members: &[MemberEvent]) -> Vec<Hero> {
+        self.heroes.iter().map(|user_id| {
+            let user_id_str = user_id.to_string();
+            let member = members.iter().rev().find(|event| event.state_key == user_id_str);
+
+            Hero {
+                avatar_url: member.and_then(|event| event.content.avatar_url.clone()),
+                displayname: member.and_then(|event| event.content.displayname.clone()),
+                membership: member.map(|event| event.content.membership),
+                user_id: user_id.clone(),
+            }
+        }).collect()
+    }
+
+    /// The number of joined and invited members that are not listed as heroes, excluding the user
+    /// the summary was generated for.
+    ///
+    /// Clients typically render this as "and N others".
+    pub fn other_member_count(&self) -> UInt {
+        let joined = u64::from(self.joined_member_count.unwrap_or_default());
+        let total = joined.saturating_add(u64::from(self.invited_member_count.unwrap_or_default()));
+
+        UInt::new_saturating(total.saturating_sub(self.heroes.len() as u64 + 1))
+    }
+}
+
+impl Hero {
+    /// The name to display for this hero: the display name if one is set, or the user ID
+    /// otherwise.
+    pub fn name(&self) -> String {
+        match self.displayname {
+            Some(ref displayname) => displayname.clone(),
+            None => self.user_id.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{from_str, to_string};
+
+    use int::UInt;
+    use stripped::{StrippedRoomMember, StrippedState};
+    use super::{
+        DisplayNameFinding, MemberEvent, MemberEventContent, MembershipState, MembershipSummary,
+    };
+
+    #[test]
+    fn empty_avatar_url_is_none() {
+        let event = from_str::<MemberEvent>(r#"{
+            "content": {"avatar_url": "", "displayname": "Alice", "membership": "join"},
+            "event_id": "$h29iv0s8:example.com",
+            "origin_server_ts": 1,
+            "room_id": "!n8f893n9:example.com",
+            "sender": "@alice:example.com",
+            "state_key": "@alice:example.com",
+            "type": "m.room.member"
+        }"#).unwrap();
+
+        assert_eq!(event.content.avatar_url, None);
+        assert!(from_str::<MemberEventContent>(
+            r#"{"avatar_url":"not a uri","membership":"join"}"#
+        ).is_err());
+    }
+
+    #[test]
+    fn ban_with_reason() {
+        let target = from_str(r#""@spammer:example.com""#).unwrap();
+        let event = StrippedRoomMember::new(MemberEventContent::ban(Some("spam".to_string())),
+                                            &target);
+
+        assert_eq!(
+            to_string(&event).unwrap(),
+            concat!(
+                r#"{"content":{"membership":"ban","reason":"spam"},"#,
+                r#""type":"m.room.member","state_key":"@spammer:example.com"}"#
+            )
+        );
+    }
+
+    #[test]
+    fn heroes_are_joined_with_member_events() {
+        let summary = from_str::<MembershipSummary>(r#"{
+            "m.heroes": ["@alice:example.com", "@bob:example.com"],
+            "m.joined_member_count": 5,
+            "m.invited_member_count": 1
+        }"#).unwrap();
+
+        let member = from_str::<MemberEvent>(r#"{
+            "content": {"displayname": "Alice", "membership": "join"},
+            "event_id": "$h29iv0s8:example.com",
+            "origin_server_ts": 1,
+            "room_id": "!n8f893n9:example.com",
+            "sender": "@alice:example.com",
+            "state_key": "@alice:example.com",
+            "type": "m.room.member"
+        }"#).unwrap();
+
+        let heroes = summary.heroes(&[member]);
+
+        assert_eq!(heroes.len(), 2);
+        assert_eq!(heroes[0].name(), "Alice");
+        assert_eq!(heroes[0].membership, Some(MembershipState::Join));
+        assert_eq!(heroes[1].name(), "@bob:example.com");
+        assert_eq!(heroes[1].membership, None);
+        assert_eq!(summary.other_member_count(), UInt::from(3u32));
+    }
+
+    #[test]
+    fn other_member_count_saturates() {
+        let summary = from_str::<MembershipSummary>(r#"{
+            "m.heroes": [],
+            "m.joined_member_count": 9007199254740991,
+            "m.invited_member_count": 1
+        }"#).unwrap();
+
+        assert_eq!(summary.other_member_count(), UInt::MAX);
+        assert!(from_str::<MembershipSummary>(r#"{"m.joined_member_count": 9007199254740992}"#)
+            .is_err());
+    }
+
+    #[test]
+    fn invite_state_from_unsigned() {
+        let event = from_str::<MemberEvent>(r#"{
+            "content": {"membership": "invite"},
+            "event_id": "$h29iv0s8:example.com",
+            "origin_server_ts": 1,
+            "room_id": "!n8f893n9:example.com",
+            "sender": "@carl:example.com",
+            "state_key": "@alice:example.com",
+            "type": "m.room.member",
+            "unsigned": {
+                "invite_room_state": [
+                    {"content": {"name": "Ruma"}, "state_key": "", "type": "m.room.name"},
+                    {"content": {"via": []}, "state_key": "", "type": "org.example.custom"}
+                ]
+            }
+        }"#).unwrap();
+
+        let invite_state = event.invite_state().unwrap().unwrap();
+
+        assert_eq!(invite_state.len(), 1);
+
+        match invite_state[0] {
+            StrippedState::RoomName(ref event) => assert_eq!(event.content.name, "Ruma"),
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn malformed_invite_state_in_unsigned() {
+        let invite = |invite_room_state: &str| from_str::<MemberEvent>(&format!(
+            r#"{{"content":{{"membership":"invite"}},"event_id":"$h29iv0s8:example.com",
+            "origin_server_ts":1,"room_id":"!n8f893n9:example.com","sender":"@carl:example.com",
+            "state_key":"@alice:example.com","type":"m.room.member",
+            "unsigned":{{"invite_room_state":{}}}}}"#,
+            invite_room_state
+        )).unwrap();
+
+        assert!(invite(r#"[{"content":{"name":1},"state_key":"","type":"m.room.name"}]"#)
+            .invite_state()
+            .is_err());
+        assert!(invite(r#"[{"content":{"via":[]},"type":"org.example.custom"}]"#)
+            .invite_state()
+            .is_err());
+        assert!(invite(r#""not a list""#).invite_state().is_err());
+        assert!(invite("null").invite_state().unwrap().is_none());
+    }
+
+    #[test]
+    fn knock_state_from_unsigned() {
+        let event = from_str::<MemberEvent>(r#"{
+            "content": {"membership": "knock"},
+            "event_id": "$h29iv0s8:example.com",
+            "origin_server_ts": 1,
+            "room_id": "!n8f893n9:example.com",
+            "sender": "@alice:example.com",
+            "state_key": "@alice:example.com",
+            "type": "m.room.member",
+            "unsigned": {
+                "knock_room_state": [
+                    {
+                        "content": {"join_rule": "knock"},
+                        "state_key": "",
+                        "type": "m.room.join_rules"
+                    }
+                ]
+            }
+        }"#).unwrap();
+
+        assert_eq!(event.knock_state().unwrap().unwrap().len(), 1);
+        assert!(event.invite_state().unwrap().is_none());
+    }
+
+    #[test]
+    fn malformed_knock_state_in_unsigned() {
+        let event = from_str::<MemberEvent>(r#"{
+            "content": {"membership": "knock"},
+            "event_id": "$h29iv0s8:example.com",
+            "origin_server_ts": 1,
+            "room_id": "!n8f893n9:example.com",
+            "sender": "@alice:example.com",
+            "state_key": "@alice:example.com",
+            "type": "m.room.member",
+            "unsigned": {
+                "knock_room_state": [
+                    {"content": {"join_rule": "knock"}, "state_key": "", "type": "m.room.name"}
+                ]
+            }
+        }"#).unwrap();
+
+        assert!(event.knock_state().is_err());
+        assert!(event.member_unsigned().is_err());
+    }
+
+    #[test]
+    fn display_name_findings() {
+        let member = |displayname: &str| from_str::<MemberEvent>(&format!(
+            r#"{{"content":{{"displayname":"{}","membership":"join"}},
+            "event_id":"$h29iv0s8:example.com","origin_server_ts":1,
+            "room_id":"!n8f893n9:example.com","sender":"@mallory:example.com",
+            "state_key":"@mallory:example.com","type":"m.room.member"}}"#,
+            displayname
+        )).unwrap();
+
+        assert!(member("Mallory").display_name_findings().is_empty());
+        assert!(member("@mallory:example.com").display_name_findings().is_empty());
+        assert_eq!(
+            member(" @admin:example.com").display_name_findings(),
+            vec![DisplayNameFinding::ImpersonatesUserId(
+                from_str(r#""@admin:example.com""#).unwrap()
+            )]
+        );
+        assert_eq!(
+            member("moc.elpmaxe\\u202e\\u202e\\u0007").display_name_findings(),
+            vec![
+                DisplayNameFinding::BidiControl('\u{202E}'),
+                DisplayNameFinding::ControlCharacter('\u{7}'),
+            ]
+        );
+    }
+}
@@ -2,9 +2,18 @@
 
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use serde::de::Error;
-use serde_json::{Value, from_value};
-
-use super::ImageInfo;
+#[cfg(feature = "events-encryption")]
+use ruma_identifiers::UserId;
+use serde_json::from_value;
+
+#[cfg(feature = "events-encryption")]
+use DeviceId;
+use collections::deserialize_limited_value;
+#[cfg(feature = "events-encryption")]
+use key::verification::VerificationMethod;
+use super::MediaSource;
+use super::media::ThumbnailInfo;
+pub use super::media::{AudioInfo, FileInfo, ImageInfo, VideoInfo};
 
 room_event! {
     /// A message sent to a room.
@@ -13,6 +22,7 @@ room_event! {
 
 /// The message type of message event, e.g. `m.image` or `m.text`.
 #[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+#[non_exhaustive]
 pub enum MessageType {
     /// An audio message.
     #[serde(rename="m.audio")]
@@ -38,10 +48,19 @@ pub enum MessageType {
     #[serde(rename="m.notice")]
     Notice,
 
+    /// A notice from the homeserver administrators.
+    #[serde(rename="m.server_notice")]
+    ServerNotice,
+
     /// A text message.
     #[serde(rename="m.text")]
     Text,
 
+    /// A request to verify the devices of another user.
+    #[cfg(feature = "events-encryption")]
+    #[serde(rename="m.key.verification.request")]
+    VerificationRequest,
+
     /// A video message.
     #[serde(rename="m.video")]
     Video,
@@ -49,6 +68,7 @@ pub enum MessageType {
 
 /// The payload of a message event.
 #[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
 pub enum MessageEventContent {
     /// An audio message.
     Audio(AudioMessageEventContent),
@@ -68,39 +88,159 @@ pub enum MessageEventContent {
     /// An notice message.
     Notice(NoticeMessageEventContent),
 
+    /// A notice from the homeserver administrators.
+    ServerNotice(ServerNoticeMessageEventContent),
+
     /// An text message.
     Text(TextMessageEventContent),
 
+    /// A request to verify the devices of another user.
+    #[cfg(feature = "events-encryption")]
+    VerificationRequest(KeyVerificationRequestEventContent),
+
     /// An video message.
     Video(VideoMessageEventContent),
 }
 
+impl_event_content!(MessageEventContent, RoomMessage);
+impl_room_event_content!(message_like, MessageEventContent);
+
+impl MessageEventContent {
+    /// The textual representation of the message.
+    pub fn body(&self) -> &str {
+        match *self {
+            MessageEventContent::Audio(ref content) => &content.body,
+            MessageEventContent::Emote(ref content) => &content.body,
+            MessageEventContent::File(ref content) => &content.body,
+            MessageEventContent::Image(ref content) => &content.body,
+            MessageEventContent::Location(ref content) => &content.body,
+            MessageEventContent::Notice(ref content) => &content.body,
+            MessageEventContent::ServerNotice(ref content) => &content.body,
+            MessageEventContent::Text(ref content) => &content.body,
+            #[cfg(feature = "events-encryption")]
+            MessageEventContent::VerificationRequest(ref content) => &content.body,
+            MessageEventContent::Video(ref content) => &content.body,
+        }
+    }
+
+    /// The message type.
+    pub fn msgtype(&self) -> MessageType {
+        match *self {
+            MessageEventContent::Audio(_) => MessageType::Audio,
+            MessageEventContent::Emote(_) => MessageType::Emote,
+            MessageEventContent::File(_) => MessageType::File,
+            MessageEventContent::Image(_) => MessageType::Image,
+            MessageEventContent::Location(_) => MessageType::Location,
+            MessageEventContent::Notice(_) => MessageType::Notice,
+            MessageEventContent::ServerNotice(_) => MessageType::ServerNotice,
+            MessageEventContent::Text(_) => MessageType::Text,
+            #[cfg(feature = "events-encryption")]
+            MessageEventContent::VerificationRequest(_) => MessageType::VerificationRequest,
+            MessageEventContent::Video(_) => MessageType::Video,
+        }
+    }
+
+    /// A plain-text snippet of the message for room list previews and push notifications.
+    ///
+    /// The reply fallback and any HTML tags are removed from the body, whitespace including
+    /// newlines is collapsed into single spaces, and the result is cut to at most `max_len`
+    /// characters, ending with "…" if it was truncated.
+    pub fn preview(&self, max_len: usize) -> String {
+        let text = strip_html_tags(strip_reply_fallback(self.body()));
+        let text = text.split_whitespace().collect::<Vec<&str>>().join(" ");
+
+        if text.chars().count() <= max_len {
+            return text;
+        }
+
+        if max_len == 0 {
+            return String::new();
+        }
+
+        let mut preview: String = text.chars().take(max_len - 1).collect();
+        preview.truncate(preview.trim_end().len());
+        preview.push('…');
+        preview
+    }
+}
+
+/// Removes the quoted lines of a reply fallback, and the empty line after them, from the start
+/// of a body.
+fn strip_reply_fallback(body: &str) -> &str {
+    if !body.starts_with("> <") {
+        return body;
+    }
+
+    let mut rest = body;
+    while rest.starts_with('>') {
+        rest = match rest.find('\n') {
+            Some(end) => &rest[end + 1..],
+            None => "",
+        };
+    }
+
+    rest.strip_prefix('\n').unwrap_or(rest)
+}
+
+/// Removes anything that looks like an HTML tag, replacing line and paragraph breaks with a
+/// space. A `<` that does not start a tag is kept.
+fn strip_html_tags(text: &str) -> String {
+    let mut stripped = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find('<') {
+        stripped.push_str(&rest[..start]);
+        let tag = &rest[start + 1..];
+        let is_tag = tag.starts_with(|c: char| c.is_ascii_alphabetic() || c == '/' || c == '!');
+
+        match tag.find('>') {
+            Some(end) if is_tag => {
+                let name = tag[..end].trim_start_matches('/').to_ascii_lowercase();
+                if name.starts_with("br") || name == "p" || name.starts_with("p ") {
+                    stripped.push(' ');
+                }
+                rest = &tag[end + 1..];
+            }
+            _ => {
+                stripped.push('<');
+                rest = tag;
+            }
+        }
+    }
+
+    stripped.push_str(rest);
+    stripped
+}
+
 /// The payload of an audio message.
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 pub struct AudioMessageEventContent {
     /// The textual representation of this message.
     pub body: String,
-    /// Metadata for the audio clip referred to in `url`.
+    /// The original name of the file, in which case `body` is a caption of the media.
+    #[cfg(feature = "unstable-msc2530")]
     #[serde(skip_serializing_if="Option::is_none")]
-    pub info: Option<AudioInfo>,
-    /// The message type. Always *m.audio*.
-    pub msgtype: MessageType,
-    /// The URL to the audio clip.
-    pub url: String,
-}
-
-/// Metadata about an audio clip.
-#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
-pub struct AudioInfo {
-    /// The duration of the audio in milliseconds.
+    pub filename: Option<String>,
+    /// The format used in `formatted_body`. Currently only "org.matrix.custom.html" is supported.
+    #[cfg(feature = "unstable-msc2530")]
     #[serde(skip_serializing_if="Option::is_none")]
-    pub duration: Option<u64>,
-    /// The mimetype of the audio, e.g. "audio/aac."
+    pub format: Option<String>,
+    /// A formatted version of the caption in `body`, if `filename` is present.
+    #[cfg(feature = "unstable-msc2530")]
     #[serde(skip_serializing_if="Option::is_none")]
-    pub mimetype: Option<String>,
-    /// The size of the audio clip in bytes.
+    pub formatted_body: Option<String>,
+    /// Metadata for the audio clip referred to in `source`.
     #[serde(skip_serializing_if="Option::is_none")]
-    pub size: Option<u64>,
+    pub info: Option<AudioInfo>,
+    /// The message type. Always *m.audio*.
+    pub msgtype: MessageType,
+    /// The location of the audio clip, serialized as either `url` or `file`.
+    #[serde(flatten)]
+    pub source: MediaSource,
+    /// Fields that are not covered by the Matrix specification.
+    #[cfg(feature = "preserve-extra-fields")]
+    #[serde(flatten)]
+    pub extra: ::std::collections::BTreeMap<String, ::serde_json::Value>,
 }
 
 /// The payload of an emote message.
@@ -110,6 +250,10 @@ pub struct EmoteMessageEventContent {
     pub body: String,
     /// The message type. Always *m.emote*.
     pub msgtype: MessageType,
+    /// Fields that are not covered by the Matrix specification.
+    #[cfg(feature = "preserve-extra-fields")]
+    #[serde(flatten)]
+    pub extra: ::std::collections::BTreeMap<String, ::serde_json::Value>,
 }
 
 /// The payload of a file message.
@@ -118,28 +262,37 @@ pub struct FileMessageEventContent {
     /// A human-readable description of the file. This is recommended to be the filename of the
     /// original upload.
     pub body: String,
-    /// Metadata about the file referred to in `url`.
+    /// The original name of the file, in which case `body` is a caption of the media.
+    #[cfg(feature = "unstable-msc2530")]
+    #[serde(skip_serializing_if="Option::is_none")]
+    pub filename: Option<String>,
+    /// The format used in `formatted_body`. Currently only "org.matrix.custom.html" is supported.
+    #[cfg(feature = "unstable-msc2530")]
+    #[serde(skip_serializing_if="Option::is_none")]
+    pub format: Option<String>,
+    /// A formatted version of the caption in `body`, if `filename` is present.
+    #[cfg(feature = "unstable-msc2530")]
+    #[serde(skip_serializing_if="Option::is_none")]
+    pub formatted_body: Option<String>,
+    /// Metadata about the file referred to in `source`.
     #[serde(skip_serializing_if="Option::is_none")]
     pub info: Option<FileInfo>,
     /// The message type. Always *m.file*.
     pub msgtype: MessageType,
-    /// Metadata about the image referred to in `thumbnail_url`.
-    #[serde(skip_serializing_if="Option::is_none")]
-    pub thumbnail_info: Option<ImageInfo>,
-    /// The URL to the thumbnail of the file.
+    /// The location of the file, serialized as either `url` or `file`.
+    #[serde(flatten)]
+    pub source: MediaSource,
+    /// Metadata about the image referred to in `thumbnail_source`.
     #[serde(skip_serializing_if="Option::is_none")]
-    pub thumbnail_url: Option<String>,
-    /// The URL to the file.
-    pub url: String,
-}
-
-/// Metadata about a file.
-#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
-pub struct FileInfo {
-    /// The mimetype of the file, e.g. "application/msword."
-    pub mimetype: String,
-    /// The size of the file in bytes.
-    pub size: u64,
+    pub thumbnail_info: Option<ThumbnailInfo>,
+    /// The location of the thumbnail of the file, serialized as either `thumbnail_url` or
+    /// `thumbnail_file`.
+    #[serde(flatten, with="super::thumbnail_source")]
+    pub thumbnail_source: Option<MediaSource>,
+    /// Fields that are not covered by the Matrix specification.
+    #[cfg(feature = "preserve-extra-fields")]
+    #[serde(flatten)]
+    pub extra: ::std::collections::BTreeMap<String, ::serde_json::Value>,
 }
 
 /// The payload of an image message.
@@ -148,19 +301,37 @@ pub struct ImageMessageEventContent {
     /// A textual representation of the image. This could be the alt text of the image, the filename
     /// of the image, or some kind of content description for accessibility e.g. "image attachment."
     pub body: String,
-    /// Metadata about the image referred to in `url`.
+    /// The original name of the file, in which case `body` is a caption of the media.
+    #[cfg(feature = "unstable-msc2530")]
+    #[serde(skip_serializing_if="Option::is_none")]
+    pub filename: Option<String>,
+    /// The format used in `formatted_body`. Currently only "org.matrix.custom.html" is supported.
+    #[cfg(feature = "unstable-msc2530")]
+    #[serde(skip_serializing_if="Option::is_none")]
+    pub format: Option<String>,
+    /// A formatted version of the caption in `body`, if `filename` is present.
+    #[cfg(feature = "unstable-msc2530")]
+    #[serde(skip_serializing_if="Option::is_none")]
+    pub formatted_body: Option<String>,
+    /// Metadata about the image referred to in `source`.
     #[serde(skip_serializing_if="Option::is_none")]
     pub info: Option<ImageInfo>,
     /// The message type. Always *m.image*.
     pub msgtype: MessageType,
-    /// Metadata about the image referred to in `thumbnail_url`.
+    /// The location of the image, serialized as either `url` or `file`.
+    #[serde(flatten)]
+    pub source: MediaSource,
+    /// Metadata about the image referred to in `thumbnail_source`.
     #[serde(skip_serializing_if="Option::is_none")]
-    pub thumbnail_info: Option<ImageInfo>,
-    /// The URL to the thumbnail of the image.
-    #[serde(skip_serializing_if="Option::is_none")]
-    pub thumbnail_url: Option<String>,
-    /// The URL to the image.
-    pub url: String,
+    pub thumbnail_info: Option<ThumbnailInfo>,
+    /// The location of the thumbnail of the image, serialized as either `thumbnail_url` or
+    /// `thumbnail_file`.
+    #[serde(flatten, with="super::thumbnail_source")]
+    pub thumbnail_source: Option<MediaSource>,
+    /// Fields that are not covered by the Matrix specification.
+    #[cfg(feature = "preserve-extra-fields")]
+    #[serde(flatten)]
+    pub extra: ::std::collections::BTreeMap<String, ::serde_json::Value>,
 }
 
 /// The payload of a location message.
@@ -173,12 +344,17 @@ pub struct LocationMessageEventContent {
     pub geo_uri: String,
     /// The message type. Always *m.location*.
     pub msgtype: MessageType,
-    /// Metadata about the image referred to in `thumbnail_url`.
-    #[serde(skip_serializing_if="Option::is_none")]
-    pub thumbnail_info: Option<ImageInfo>,
-    /// The URL to a thumbnail of the location being represented.
+    /// Metadata about the image referred to in `thumbnail_source`.
     #[serde(skip_serializing_if="Option::is_none")]
-    pub thumbnail_url: Option<String>,
+    pub thumbnail_info: Option<ThumbnailInfo>,
+    /// The location of a thumbnail of the location being represented, serialized as either
+    /// `thumbnail_url` or `thumbnail_file`.
+    #[serde(flatten, with="super::thumbnail_source")]
+    pub thumbnail_source: Option<MediaSource>,
+    /// Fields that are not covered by the Matrix specification.
+    #[cfg(feature = "preserve-extra-fields")]
+    #[serde(flatten)]
+    pub extra: ::std::collections::BTreeMap<String, ::serde_json::Value>,
 }
 
 /// The payload of a notice message.
@@ -188,6 +364,65 @@ pub struct NoticeMessageEventContent {
     pub body: String,
     /// The message type. Always *m.notice*.
     pub msgtype: MessageType,
+    /// Fields that are not covered by the Matrix specification.
+    #[cfg(feature = "preserve-extra-fields")]
+    #[serde(flatten)]
+    pub extra: ::std::collections::BTreeMap<String, ::serde_json::Value>,
+}
+
+/// The payload of a server notice message.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct ServerNoticeMessageEventContent {
+    /// A human-readable description of the notice.
+    pub body: String,
+    /// The message type. Always *m.server_notice*.
+    pub msgtype: MessageType,
+    /// The type of notice being represented.
+    pub server_notice_type: ServerNoticeType,
+    /// A URI giving a contact method for the server administrator.
+    ///
+    /// Required if the notice type is *m.server_notice.usage_limit_reached*.
+    #[serde(skip_serializing_if="Option::is_none")]
+    pub admin_contact: Option<String>,
+    /// The kind of usage limit the server has exceeded.
+    ///
+    /// Required if the notice type is *m.server_notice.usage_limit_reached*.
+    #[serde(skip_serializing_if="Option::is_none")]
+    pub limit_type: Option<LimitType>,
+    /// Fields that are not covered by the Matrix specification.
+    #[cfg(feature = "preserve-extra-fields")]
+    #[serde(flatten)]
+    pub extra: ::std::collections::BTreeMap<String, ::serde_json::Value>,
+}
+
+/// The type of a server notice.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum ServerNoticeType {
+    /// m.server_notice.usage_limit_reached
+    UsageLimitReached,
+    /// Any notice type that is not part of the specification.
+    Custom(String),
+}
+
+impl_string_enum! {
+    ServerNoticeType {
+        UsageLimitReached => "m.server_notice.usage_limit_reached",
+    }
+}
+
+/// The kind of usage limit a server has exceeded.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum LimitType {
+    /// The server has exceeded its limit of monthly active users.
+    MonthlyActiveUser,
+    /// Any limit type that is not part of the specification.
+    Custom(String),
+}
+
+impl_string_enum! {
+    LimitType {
+        MonthlyActiveUser => "monthly_active_user",
+    }
 }
 
 /// The payload of a text message.
@@ -197,6 +432,30 @@ pub struct TextMessageEventContent {
     pub body: String,
     /// The message type. Always *m.text*.
     pub msgtype: MessageType,
+    /// Fields that are not covered by the Matrix specification.
+    #[cfg(feature = "preserve-extra-fields")]
+    #[serde(flatten)]
+    pub extra: ::std::collections::BTreeMap<String, ::serde_json::Value>,
+}
+
+/// The payload of a key verification request message.
+#[cfg(feature = "events-encryption")]
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct KeyVerificationRequestEventContent {
+    /// A fallback message for clients that do not support key verification.
+    pub body: String,
+    /// The device ID which is initiating the request.
+    pub from_device: DeviceId,
+    /// The verification methods supported by the sender.
+    pub methods: Vec<VerificationMethod>,
+    /// The message type. Always *m.key.verification.request*.
+    pub msgtype: MessageType,
+    /// The user ID which should receive the request.
+    pub to: UserId,
+    /// Fields that are not covered by the Matrix specification.
+    #[cfg(feature = "preserve-extra-fields")]
+    #[serde(flatten)]
+    pub extra: ::std::collections::BTreeMap<String, ::serde_json::Value>,
 }
 
 /// The payload of a video message.
@@ -205,41 +464,68 @@ pub struct VideoMessageEventContent {
     /// A description of the video, e.g. "Gangnam Style," or some kind of content description for
     /// accessibility, e.g. "video attachment."
     pub body: String,
-    /// Metadata about the video clip referred to in `url`.
+    /// The original name of the file, in which case `body` is a caption of the media.
+    #[cfg(feature = "unstable-msc2530")]
+    #[serde(skip_serializing_if="Option::is_none")]
+    pub filename: Option<String>,
+    /// The format used in `formatted_body`. Currently only "org.matrix.custom.html" is supported.
+    #[cfg(feature = "unstable-msc2530")]
+    #[serde(skip_serializing_if="Option::is_none")]
+    pub format: Option<String>,
+    /// A formatted version of the caption in `body`, if `filename` is present.
+    #[cfg(feature = "unstable-msc2530")]
+    #[serde(skip_serializing_if="Option::is_none")]
+    pub formatted_body: Option<String>,
+    /// Metadata about the video clip referred to in `source`.
     #[serde(skip_serializing_if="Option::is_none")]
     pub info: Option<VideoInfo>,
     /// The message type. Always *m.video*.
     pub msgtype: MessageType,
-    /// The URL to the video clip.
-    pub url: String,
+    /// The location of the video clip, serialized as either `url` or `file`.
+    #[serde(flatten)]
+    pub source: MediaSource,
+    /// Fields that are not covered by the Matrix specification.
+    #[cfg(feature = "preserve-extra-fields")]
+    #[serde(flatten)]
+    pub extra: ::std::collections::BTreeMap<String, ::serde_json::Value>,
 }
 
-/// Metadata about a video.
-#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
-pub struct VideoInfo {
-    /// The duration of the video in milliseconds.
-    #[serde(skip_serializing_if="Option::is_none")]
-    pub duration: Option<u64>,
-    /// The height of the video in pixels.
-    #[serde(skip_serializing_if="Option::is_none")]
-    pub h: Option<u64>,
-    /// The mimetype of the video, e.g. "video/mp4."
-    #[serde(skip_serializing_if="Option::is_none")]
-    pub mimetype: Option<String>,
-    /// The size of the video in bytes.
-    #[serde(skip_serializing_if="Option::is_none")]
-    pub size: Option<u64>,
-    /// Metadata about an image.
-    #[serde(skip_serializing_if="Option::is_none")]
-    pub thumbnail_info: Option<ImageInfo>,
-    /// The URL to a thumbnail of the video clip.
-    #[serde(skip_serializing_if="Option::is_none")]
-    pub thumbnail_url: Option<String>,
-    /// The width of the video in pixels.
-    #[serde(skip_serializing_if="Option::is_none")]
-    pub w: Option<u64>,
+/// Implements the accessors for captions of media messages, as proposed in MSC2530.
+macro_rules! impl_caption {
+    ($($content:ident),*) => {
+        $(
+            #[cfg(feature = "unstable-msc2530")]
+            impl $content {
+                /// The caption of the media, which is `body` if a `filename` other than `body` is
+                /// given.
+                pub fn caption(&self) -> Option<&str> {
+                    match self.filename {
+                        Some(ref filename) if *filename != self.body => Some(&self.body),
+                        _ => None,
+                    }
+                }
+
+                /// The formatted version of the caption, if the media has a formatted caption.
+                pub fn formatted_caption(&self) -> Option<&str> {
+                    self.caption().and(self.formatted_body.as_deref())
+                }
+
+                /// The name of the file, which is `filename` if given and `body` otherwise.
+                pub fn filename(&self) -> &str {
+                    self.filename.as_deref().unwrap_or(&self.body)
+                }
+            }
+        )*
+    };
 }
 
+impl_caption!(
+    AudioMessageEventContent,
+    FileMessageEventContent,
+    ImageMessageEventContent,
+    VideoMessageEventContent
+);
+
 impl_enum! {
     MessageType {
         Audio => "m.audio",
@@ -248,7 +534,10 @@ impl_enum! {
         Image => "m.image",
         Location => "m.location",
         Notice => "m.notice",
+        ServerNotice => "m.server_notice",
         Text => "m.text",
+        #[cfg(feature = "events-encryption")]
+        VerificationRequest => "m.key.verification.request",
         Video => "m.video",
     }
 }
@@ -262,7 +551,10 @@ impl Serialize for MessageEventContent {
             MessageEventContent::Image(ref content) => content.serialize(serializer),
             MessageEventContent::Location(ref content) => content.serialize(serializer),
             MessageEventContent::Notice(ref content) => content.serialize(serializer),
+            MessageEventContent::ServerNotice(ref content) => content.serialize(serializer),
             MessageEventContent::Text(ref content) => content.serialize(serializer),
+            #[cfg(feature = "events-encryption")]
+            MessageEventContent::VerificationRequest(ref content) => content.serialize(serializer),
             MessageEventContent::Video(ref content) => content.serialize(serializer),
         }
     }
@@ -270,14 +562,14 @@ impl Serialize for MessageEventContent {
 
 impl<'de> Deserialize<'de> for MessageEventContent {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where D: Deserializer<'de> {
-        let value: Value = Deserialize::deserialize(deserializer)?;
+        let value = deserialize_limited_value(deserializer)?;
 
         let message_type_value = match value.get("msgtype") {
-            Some(value) => value.clone(),
+            Some(value) => value,
             None => return Err(D::Error::missing_field("msgtype")),
         };
 
-        let message_type = match from_value::<MessageType>(message_type_value.clone()) {
+        let message_type = match MessageType::deserialize(message_type_value) {
             Ok(message_type) => message_type,
             Err(error) => return Err(D::Error::custom(error.to_string())),
         };
@@ -331,6 +623,14 @@ impl<'de> Deserialize<'de> for MessageEventContent {
 
                 Ok(MessageEventContent::Notice(content))
             }
+            MessageType::ServerNotice => {
+                let content = match from_value::<ServerNoticeMessageEventContent>(value) {
+                    Ok(content) => content,
+                    Err(error) => return Err(D::Error::custom(error.to_string())),
+                };
+
+                Ok(MessageEventContent::ServerNotice(content))
+            }
             MessageType::Text => {
                 let content = match from_value::<TextMessageEventContent>(value) {
                     Ok(content) => content,
@@ -339,6 +639,15 @@ impl<'de> Deserialize<'de> for MessageEventContent {
 
                 Ok(MessageEventContent::Text(content))
             }
+            #[cfg(feature = "events-encryption")]
+            MessageType::VerificationRequest => {
+                let content = match from_value::<KeyVerificationRequestEventContent>(value) {
+                    Ok(content) => content,
+                    Err(error) => return Err(D::Error::custom(error.to_string())),
+                };
+
+                Ok(MessageEventContent::VerificationRequest(content))
+            }
             MessageType::Video => {
                 let content = match from_value::<VideoMessageEventContent>(value) {
                     Ok(content) => content,
@@ -353,24 +662,38 @@ impl<'de> Deserialize<'de> for MessageEventContent {
 
 #[cfg(test)]
 mod tests {
+    #[cfg(feature = "preserve-extra-fields")]
+    use serde_json::{Value, to_value};
     use serde_json::{from_str, to_string};
 
-    use super::{AudioMessageEventContent, MessageType, MessageEventContent};
+    #[cfg(feature = "events-encryption")]
+    use key::verification::VerificationMethod;
+    use room::MediaSource;
+    use super::{AudioMessageEventContent, LimitType, MessageType, MessageEventContent};
+    use super::ServerNoticeType;
 
     #[test]
     fn serialization() {
         let message_event_content = MessageEventContent::Audio(
             AudioMessageEventContent {
                 body: "test".to_string(),
+                #[cfg(feature = "unstable-msc2530")]
+                filename: None,
+                #[cfg(feature = "unstable-msc2530")]
+                format: None,
+                #[cfg(feature = "unstable-msc2530")]
+                formatted_body: None,
                 info: None,
                 msgtype: MessageType::Audio,
-                url: "http://example.com/audio.mp3".to_string(),
+                source: MediaSource::Plain("mxc://example.com/audio".parse().unwrap()),
+                #[cfg(feature = "preserve-extra-fields")]
+                extra: Default::default(),
             }
         );
 
         assert_eq!(
             to_string(&message_event_content).unwrap(),
-            r#"{"body":"test","msgtype":"m.audio","url":"http://example.com/audio.mp3"}"#
+            r#"{"body":"test","msgtype":"m.audio","url":"mxc://example.com/audio"}"#
         );
     }
 
@@ -379,15 +702,23 @@ mod tests {
         let message_event_content = MessageEventContent::Audio(
             AudioMessageEventContent {
                 body: "test".to_string(),
+                #[cfg(feature = "unstable-msc2530")]
+                filename: None,
+                #[cfg(feature = "unstable-msc2530")]
+                format: None,
+                #[cfg(feature = "unstable-msc2530")]
+                formatted_body: None,
                 info: None,
                 msgtype: MessageType::Audio,
-                url: "http://example.com/audio.mp3".to_string(),
+                source: MediaSource::Plain("mxc://example.com/audio".parse().unwrap()),
+                #[cfg(feature = "preserve-extra-fields")]
+                extra: Default::default(),
             }
         );
 
         assert_eq!(
             from_str::<MessageEventContent>(
-                r#"{"body":"test","msgtype":"m.audio","url":"http://example.com/audio.mp3"}"#
+                r#"{"body":"test","msgtype":"m.audio","url":"mxc://example.com/audio"}"#
             ).unwrap(),
             message_event_content
         );
@@ -397,8 +728,163 @@ mod tests {
     fn deserialization_failure() {
         assert!(
             from_str::<MessageEventContent>(
-                r#"{"body":"test","msgtype":"m.location","url":"http://example.com/audio.mp3"}"#
+                r#"{"body":"test","msgtype":"m.location","url":"mxc://example.com/audio"}"#
             ).is_err()
         );
     }
+
+    #[test]
+    fn thumbnail_file_deserialization() {
+        let json = concat!(
+            r#"{"body":"test","msgtype":"m.image","url":"mxc://example.com/image","#,
+            r#""thumbnail_file":{"hashes":{"sha256":"hash"},"iv":"iv","#,
+            r#""key":{"alg":"A256CTR","ext":true,"k":"key","key_ops":["encrypt","decrypt"],"#,
+            r#""kty":"oct"},"url":"mxc://example.com/thumbnail","v":"v2"}}"#
+        );
+
+        match from_str::<MessageEventContent>(json).unwrap() {
+            MessageEventContent::Image(content) => match content.thumbnail_source {
+                Some(MediaSource::Encrypted(file)) => {
+                    assert_eq!(file.url.as_str(), "mxc://example.com/thumbnail");
+                }
+                _ => panic!("expected an encrypted thumbnail"),
+            },
+            _ => panic!("expected an image message"),
+        }
+
+        assert_eq!(to_string(&from_str::<MessageEventContent>(json).unwrap()).unwrap(), json);
+    }
+
+    #[test]
+    fn encrypted_source_serialization() {
+        let json = concat!(
+            r#"{"body":"test","msgtype":"m.file","file":{"hashes":{"sha256":"hash"},"iv":"iv","#,
+            r#""key":{"alg":"A256CTR","ext":true,"k":"key","key_ops":["encrypt","decrypt"],"#,
+            r#""kty":"oct"},"url":"mxc://example.com/file","v":"v2"}}"#
+        );
+        let content = from_str::<MessageEventContent>(json).unwrap();
+
+        match content {
+            MessageEventContent::File(ref content) => match content.source {
+                MediaSource::Encrypted(ref file) => {
+                    assert_eq!(file.url.as_str(), "mxc://example.com/file");
+                }
+                MediaSource::Plain(_) => panic!("expected an encrypted file"),
+            },
+            _ => panic!("expected a file message"),
+        }
+
+        assert_eq!(to_string(&content).unwrap(), json);
+    }
+
+    #[cfg(feature = "events-encryption")]
+    #[test]
+    fn verification_request_deserialization() {
+        let json = r#"{
+            "body": "@alice:example.com is requesting to verify your key",
+            "from_device": "AliceDevice2",
+            "methods": ["m.sas.v1"],
+            "msgtype": "m.key.verification.request",
+            "to": "@bob:example.com"
+        }"#;
+
+        match from_str::<MessageEventContent>(json).unwrap() {
+            MessageEventContent::VerificationRequest(content) => {
+                assert_eq!(content.from_device, "AliceDevice2");
+                assert_eq!(content.methods, vec![VerificationMethod::SasV1]);
+                assert_eq!(content.to.to_string(), "@bob:example.com");
+            }
+            _ => panic!("expected a verification request"),
+        }
+    }
+
+    #[cfg(feature = "preserve-extra-fields")]
+    #[test]
+    fn extra_fields_round_trip() {
+        let json = r#"{
+            "body": "test",
+            "msgtype": "m.image",
+            "org.example.caption": "A cat",
+            "thumbnail_url": "mxc://example.com/thumbnail",
+            "url": "mxc://example.com/image"
+        }"#;
+
+        let content = from_str::<MessageEventContent>(json).unwrap();
+
+        match content {
+            MessageEventContent::Image(ref content) => {
+                assert_eq!(content.extra.len(), 1);
+                assert_eq!(content.extra["org.example.caption"], "A cat");
+            }
+            _ => panic!("expected an image message"),
+        }
+
+        assert_eq!(to_value(&content).unwrap(), from_str::<Value>(json).unwrap());
+    }
+
+    #[test]
+    fn server_notice_deserialization() {
+        let content = from_str::<MessageEventContent>(r#"{
+            "admin_contact": "mailto:admin@example.com",
+            "body": "Monthly active user limit reached",
+            "limit_type": "monthly_active_user",
+            "msgtype": "m.server_notice",
+            "server_notice_type": "m.server_notice.usage_limit_reached"
+        }"#).unwrap();
+
+        assert_eq!(content.msgtype(), MessageType::ServerNotice);
+        assert_eq!(content.body(), "Monthly active user limit reached");
+
+        match content {
+            MessageEventContent::ServerNotice(ref content) => {
+                assert_eq!(content.server_notice_type, ServerNoticeType::UsageLimitReached);
+                assert_eq!(content.limit_type, Some(LimitType::MonthlyActiveUser));
+            }
+            _ => panic!("expected a server notice"),
+        }
+    }
+
+    #[test]
+    fn preview() {
+        let content = from_str::<MessageEventContent>(concat!(
+            r#"{"body":"> <@alice:example.com> What is\n> <b>up</b>?\n\n"#,
+            r#"Not much,\n  <i>really</i>. a < b","msgtype":"m.text"}"#
+        )).unwrap();
+
+        assert_eq!(content.preview(100), "Not much, really. a < b");
+        assert_eq!(content.preview(10), "Not much,…");
+        assert_eq!(content.preview(1), "…");
+        assert_eq!(content.preview(0), "");
+    }
+
+    #[cfg(feature = "unstable-msc2530")]
+    #[test]
+    fn captions() {
+        let captioned = from_str::<MessageEventContent>(concat!(
+            r#"{"body":"A *cat*","filename":"cat.jpg","format":"org.matrix.custom.html","#,
+            r#""formatted_body":"A <em>cat</em>","msgtype":"m.image","#,
+            r#""url":"mxc://example.com/cat"}"#
+        )).unwrap();
+
+        match captioned {
+            MessageEventContent::Image(ref content) => {
+                assert_eq!(content.caption(), Some("A *cat*"));
+                assert_eq!(content.formatted_caption(), Some("A <em>cat</em>"));
+                assert_eq!(content.filename(), "cat.jpg");
+            }
+            _ => panic!("expected an image message"),
+        }
+
+        let uncaptioned = from_str::<MessageEventContent>(
+            r#"{"body":"cat.jpg","msgtype":"m.image","url":"mxc://example.com/cat"}"#
+        ).unwrap();
+
+        match uncaptioned {
+            MessageEventContent::Image(ref content) => {
+                assert_eq!(content.caption(), None);
+                assert_eq!(content.filename(), "cat.jpg");
+            }
+            _ => panic!("expected an image message"),
+        }
+    }
 }
@@ -2,30 +2,168 @@
 //!
 //! This module also contains types shared by events in its child namespaces.
 
+use std::collections::HashMap;
+use std::fmt::{Debug, Display, Formatter, Result as FmtResult};
+
+use RoomEvent;
+use mxc_uri::MxcUri;
+use redact::Redacted;
+
 pub mod aliases;
 pub mod avatar;
+#[cfg(feature = "unstable-msc2716")]
+pub mod batch;
 pub mod canonical_alias;
+pub mod change;
 pub mod create;
+#[cfg(feature = "events-encryption")]
+pub mod encrypted;
+pub mod encryption;
 pub mod guest_access;
 pub mod history_visibility;
+#[cfg(feature = "unstable-msc2716")]
+pub mod insertion;
 pub mod join_rules;
+#[cfg(feature = "unstable-msc2716")]
+pub mod marker;
+pub mod media;
 pub mod member;
 pub mod message;
 pub mod name;
+pub mod pinned_events;
 pub mod power_levels;
+pub mod relation;
 pub mod redaction;
+#[cfg(feature = "unstable-msc1763")]
+pub mod retention;
+pub mod server_acl;
 pub mod third_party_invite;
+pub mod tombstone;
 pub mod topic;
 
-/// Metadata about an image.
+pub use self::media::ImageInfo;
+
+/// The server name of the homeserver of the user who sent the event, e.g. "example.com" for an
+/// event sent by "@alice:example.com".
+pub fn sender_server<E>(event: &E) -> String where E: RoomEvent {
+    let user_id = event.user_id();
+
+    server_name(user_id.hostname(), user_id.port())
+}
+
+/// Formats the server name of an identifier from its hostname and port, omitting the default port
+/// the same way identifiers do when displayed.
+fn server_name<H>(hostname: &H, port: u16) -> String where H: Display {
+    if port == 443 {
+        hostname.to_string()
+    } else {
+        format!("{}:{}", hostname, port)
+    }
+}
+
+/// A file sent to an encrypted room.
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
-pub struct ImageInfo {
-    /// The height of the image in pixels.
-    pub height: u64,
-    /// The MIME type of the image, e.g. "image/png."
-    pub mimetype: String,
-    /// The file size of the image in bytes.
-    pub size: u64,
-    /// The width of the image in pixels.
-    pub width: u64,
+pub struct EncryptedFile {
+    /// A map from an algorithm name to a hash of the ciphertext, encoded as unpadded base64.
+    ///
+    /// Clients should support the SHA-256 hash, which uses the key "sha256".
+    pub hashes: HashMap<String, String>,
+    /// The initialization vector used by AES-CTR, encoded as unpadded base64.
+    pub iv: String,
+    /// The key used to encrypt the file.
+    pub key: JsonWebKey,
+    /// The URL to the file.
+    pub url: MxcUri,
+    /// The version of the encrypted attachments protocol. Must be "v2".
+    pub v: String,
+}
+
+/// A JSON Web Key used to encrypt a file.
+///
+/// The `Debug` output redacts `k`.
+#[derive(Clone, Deserialize, PartialEq, Serialize)]
+pub struct JsonWebKey {
+    /// The encryption algorithm. Must be "A256CTR".
+    pub alg: String,
+    /// Whether the key is extractable. Must be `true`.
+    pub ext: bool,
+    /// The key, encoded as urlsafe unpadded base64.
+    pub k: String,
+    /// The key operations. Must at least contain "encrypt" and "decrypt".
+    pub key_ops: Vec<String>,
+    /// The key type. Must be "oct".
+    pub kty: String,
+}
+
+impl Debug for JsonWebKey {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        f.debug_struct("JsonWebKey")
+            .field("alg", &self.alg)
+            .field("ext", &self.ext)
+            .field("k", &Redacted)
+            .field("key_ops", &self.key_ops)
+            .field("kty", &self.kty)
+            .finish()
+    }
+}
+
+/// The location of a piece of media, which is either a plain URL or an encrypted file.
+///
+/// Content types include this with `#[serde(flatten)]`, so it is serialized as exactly one of the
+/// `url` and `file` keys of the content.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub enum MediaSource {
+    /// The media is encrypted and described by the given file.
+    #[serde(rename="file")]
+    Encrypted(Box<EncryptedFile>),
+    /// The media is unencrypted and available at the given URL.
+    #[serde(rename="url")]
+    Plain(MxcUri),
+}
+
+impl MediaSource {
+    /// The URL the media can be downloaded from, whether it is encrypted or not.
+    pub fn url(&self) -> &MxcUri {
+        match *self {
+            MediaSource::Encrypted(ref file) => &file.url,
+            MediaSource::Plain(ref url) => url,
+        }
+    }
+}
+
+/// Serializes an optional `MediaSource` into exactly one of the `thumbnail_file` and
+/// `thumbnail_url` keys of its containing object. Used with `#[serde(flatten)]`.
+mod thumbnail_source {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use mxc_uri::MxcUri;
+    use super::{EncryptedFile, MediaSource};
+
+    #[derive(Deserialize, Serialize)]
+    enum ThumbnailSource {
+        #[serde(rename="thumbnail_file")]
+        Encrypted(Box<EncryptedFile>),
+        #[serde(rename="thumbnail_url")]
+        Plain(MxcUri),
+    }
+
+    pub fn serialize<S>(source: &Option<MediaSource>, serializer: S) -> Result<S::Ok, S::Error>
+    where S: Serializer {
+        let source = source.as_ref().map(|source| match *source {
+            MediaSource::Encrypted(ref file) => ThumbnailSource::Encrypted(file.clone()),
+            MediaSource::Plain(ref url) => ThumbnailSource::Plain(url.clone()),
+        });
+
+        source.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<MediaSource>, D::Error>
+    where D: Deserializer<'de> {
+        let source: Option<ThumbnailSource> = Deserialize::deserialize(deserializer)?;
+
+        Ok(source.map(|source| match source {
+            ThumbnailSource::Encrypted(file) => MediaSource::Encrypted(file),
+            ThumbnailSource::Plain(url) => MediaSource::Plain(url),
+        }))
+    }
 }
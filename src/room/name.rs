@@ -1,5 +1,7 @@
 //! Types for the *m.room.name* event.
 
+use ContentBuildError;
+
 state_event! {
     /// A human-friendly room name designed to be displayed to the end-user.
     pub struct NameEvent(NameEventContent) {}
@@ -10,4 +12,46 @@ state_event! {
 pub struct NameEventContent {
     /// The name of the room. This MUST NOT exceed 255 bytes.
     pub name: String,
+    /// Fields that are not covered by the Matrix specification.
+    #[cfg(feature = "preserve-extra-fields")]
+    #[serde(flatten)]
+    pub extra: ::std::collections::BTreeMap<String, ::serde_json::Value>,
+}
+
+impl_event_content!(NameEventContent, RoomName);
+impl_static_state_event_content!(NameEventContent, ::EmptyStateKey);
+impl_room_event_content!(state, NameEventContent);
+
+impl NameEventContent {
+    /// Creates the content of a name event, checking that the name is at most 255 bytes long.
+    pub fn new(name: String) -> Result<Self, ContentBuildError> {
+        if name.len() > 255 {
+            return Err(ContentBuildError::RoomNameTooLong { length: name.len() });
+        }
+
+        Ok(NameEventContent {
+            name,
+            #[cfg(feature = "preserve-extra-fields")]
+            extra: Default::default(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ContentBuildError;
+    use super::NameEventContent;
+
+    #[test]
+    fn name_length_is_checked_in_bytes() {
+        assert_eq!(NameEventContent::new("a".repeat(255)).unwrap().name.len(), 255);
+        assert_eq!(
+            NameEventContent::new("a".repeat(256)).unwrap_err(),
+            ContentBuildError::RoomNameTooLong { length: 256 }
+        );
+        assert_eq!(
+            NameEventContent::new("é".repeat(128)).unwrap_err(),
+            ContentBuildError::RoomNameTooLong { length: 256 }
+        );
+    }
 }
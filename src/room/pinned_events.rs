@@ -0,0 +1,56 @@
+//! Types for the *m.room.pinned_events* event.
+
+use ruma_identifiers::EventId;
+
+state_event! {
+    /// Used to "pin" particular events in a room for other participants to review later.
+    pub struct PinnedEventsEvent(PinnedEventsEventContent) {}
+}
+
+/// The payload of a `PinnedEventsEvent`.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct PinnedEventsEventContent {
+    /// An ordered list of event IDs to pin.
+    pub pinned: Vec<EventId>,
+    /// Fields that are not covered by the Matrix specification.
+    #[cfg(feature = "preserve-extra-fields")]
+    #[serde(flatten)]
+    pub extra: ::std::collections::BTreeMap<String, ::serde_json::Value>,
+}
+
+impl_event_content!(PinnedEventsEventContent, RoomPinnedEvents);
+impl_static_state_event_content!(PinnedEventsEventContent, ::EmptyStateKey);
+impl_room_event_content!(state, PinnedEventsEventContent);
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{Value, from_str, to_value};
+
+    use collections::all::StateEvent;
+
+    #[test]
+    fn pinned_events_round_trip() {
+        let json = r#"{
+            "content": {"pinned": ["$a:example.com", "$b:example.com"]},
+            "event_id": "$h29iv0s8:example.com",
+            "origin_server_ts": 1,
+            "room_id": "!n8f893n9:example.com",
+            "sender": "@carl:example.com",
+            "state_key": "",
+            "type": "m.room.pinned_events"
+        }"#;
+
+        let event = from_str::<StateEvent>(json).unwrap();
+
+        match event {
+            StateEvent::RoomPinnedEvents(ref event) => {
+                let pinned = event.content.pinned.iter().map(ToString::to_string);
+
+                assert_eq!(pinned.collect::<Vec<_>>(), vec!["$a:example.com", "$b:example.com"]);
+            }
+            _ => unreachable!(),
+        }
+
+        assert_eq!(to_value(&event).unwrap(), from_str::<Value>(json).unwrap());
+    }
+}
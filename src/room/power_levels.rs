@@ -1,10 +1,11 @@
 //! Types for the *m.room.power_levels* event.
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 use ruma_identifiers::UserId;
 
-use EventType;
+use {ContentBuildError, EventType};
+use int::Int;
 
 state_event! {
     /// Defines the power levels (privileges) of users in the room.
@@ -16,43 +17,412 @@ state_event! {
 pub struct PowerLevelsEventContent {
     /// The level required to ban a user.
     #[serde(default="default_power_level")]
-    pub ban: u64,
+    #[cfg_attr(feature = "compat", serde(deserialize_with="compat::level"))]
+    pub ban: Int,
 
     /// The level required to send specific event types.
     ///
     /// This is a mapping from event type to power level required.
-    pub events: HashMap<EventType, u64>,
+    #[cfg_attr(feature = "compat", serde(deserialize_with="compat::levels"))]
+    pub events: BTreeMap<EventType, Int>,
 
     /// The default level required to send message events.
     #[serde(default)]
-    pub events_default: u64,
+    #[cfg_attr(feature = "compat", serde(deserialize_with="compat::level"))]
+    pub events_default: Int,
 
     /// The level required to invite a user.
     #[serde(default="default_power_level")]
-    pub invite: u64,
+    #[cfg_attr(feature = "compat", serde(deserialize_with="compat::level"))]
+    pub invite: Int,
 
     /// The level required to kick a user.
     #[serde(default="default_power_level")]
-    pub kick: u64,
+    #[cfg_attr(feature = "compat", serde(deserialize_with="compat::level"))]
+    pub kick: Int,
 
     /// The level required to redact an event.
     #[serde(default="default_power_level")]
-    pub redact: u64,
+    #[cfg_attr(feature = "compat", serde(deserialize_with="compat::level"))]
+    pub redact: Int,
 
     /// The default level required to send state events.
     #[serde(default="default_power_level")]
-    pub state_default: u64,
+    #[cfg_attr(feature = "compat", serde(deserialize_with="compat::level"))]
+    pub state_default: Int,
 
     /// The power levels for specific users.
     ///
     /// This is a mapping from `user_id` to power level for that user.
-    pub users: HashMap<UserId, u64>,
+    #[cfg_attr(feature = "compat", serde(deserialize_with="compat::levels"))]
+    pub users: HashMap<UserId, Int>,
 
     /// The default power level for every user in the room.
     #[serde(default)]
-    pub users_default: u64,
+    #[cfg_attr(feature = "compat", serde(deserialize_with="compat::level"))]
+    pub users_default: Int,
+
+    /// Fields that are not covered by the Matrix specification.
+    #[cfg(feature = "preserve-extra-fields")]
+    #[serde(flatten)]
+    pub extra: ::std::collections::BTreeMap<String, ::serde_json::Value>,
+}
+
+impl_event_content!(PowerLevelsEventContent, RoomPowerLevels);
+impl_static_state_event_content!(PowerLevelsEventContent, ::EmptyStateKey);
+impl_room_event_content!(state, PowerLevelsEventContent);
+
+impl Default for PowerLevelsEventContent {
+    /// The power levels that apply to a room without an *m.room.power_levels* event, as far as
+    /// they can be expressed without knowing the room's creator.
+    fn default() -> Self {
+        PowerLevelsEventContent {
+            ban: default_power_level(),
+            events: BTreeMap::new(),
+            events_default: Int::from(0),
+            invite: default_power_level(),
+            kick: default_power_level(),
+            redact: default_power_level(),
+            state_default: default_power_level(),
+            users: HashMap::new(),
+            users_default: Int::from(0),
+            #[cfg(feature = "preserve-extra-fields")]
+            extra: Default::default(),
+        }
+    }
+}
+
+impl PowerLevelsEventContent {
+    /// The power level of `user`.
+    pub fn user_level(&self, user: &UserId) -> Int {
+        self.users.get(user).cloned().unwrap_or(self.users_default)
+    }
+
+    /// The level required to send message-like events of the given type.
+    pub fn message_level(&self, event_type: &EventType) -> Int {
+        self.events.get(event_type).cloned().unwrap_or(self.events_default)
+    }
+
+    /// The level required to send state events of the given type.
+    pub fn state_level(&self, event_type: &EventType) -> Int {
+        self.events.get(event_type).cloned().unwrap_or(self.state_default)
+    }
+
+    /// Raises the power level of `user` to `level` on behalf of `actor`.
+    ///
+    /// Fails if `level` is below the current level of `user`, or if servers would reject the
+    /// change because `actor` cannot send power levels, `level` is above the level of `actor`, or
+    /// `user` is someone else whose level is not below the level of `actor`.
+    pub fn promote(&mut self, actor: &UserId, user: &UserId, level: Int)
+    -> Result<(), ContentBuildError> {
+        let current = self.user_level(user);
+
+        if level < current {
+            return Err(ContentBuildError::PromotionLowersLevel { current, level });
+        }
+
+        self.set_user_level(actor, user, level)
+    }
+
+    /// Lowers the power level of `user` to `level` on behalf of `actor`, with the same checks as
+    /// `promote`, except that `level` must not be above the current level of `user`.
+    ///
+    /// A user lowered to the default level is removed from `users`.
+    pub fn demote(&mut self, actor: &UserId, user: &UserId, level: Int)
+    -> Result<(), ContentBuildError> {
+        let current = self.user_level(user);
+
+        if level > current {
+            return Err(ContentBuildError::DemotionRaisesLevel { current, level });
+        }
+
+        self.set_user_level(actor, user, level)?;
+
+        if level == self.users_default {
+            self.users.remove(user);
+        }
+
+        Ok(())
+    }
+
+    /// Sets the level required to send events of the given type on behalf of `actor`.
+    ///
+    /// Fails if servers would reject the change because `actor` cannot send power levels, or the
+    /// current or new requirement is above the level of `actor`.
+    pub fn set_event_requirement(&mut self, actor: &UserId, event_type: EventType, level: Int)
+    -> Result<(), ContentBuildError> {
+        let actor_level = self.check_can_change(actor)?;
+
+        if let Some(&current) = self.events.get(&event_type) {
+            check_level(current, actor_level)?;
+        }
+
+        check_level(level, actor_level)?;
+        self.events.insert(event_type, level);
+
+        Ok(())
+    }
+
+    fn set_user_level(&mut self, actor: &UserId, user: &UserId, level: Int)
+    -> Result<(), ContentBuildError> {
+        let actor_level = self.check_can_change(actor)?;
+
+        let user_level = self.user_level(user);
+
+        if user != actor && user_level >= actor_level {
+            return Err(ContentBuildError::PowerLevelTooLow {
+                required: Int::new(i64::from(user_level) + 1).unwrap_or(Int::MAX),
+                actual: actor_level,
+            });
+        }
+
+        check_level(level, actor_level)?;
+        self.users.insert(user.clone(), level);
+
+        Ok(())
+    }
+
+    /// Checks that `actor` may send power levels at all, returning their level.
+    fn check_can_change(&self, actor: &UserId) -> Result<Int, ContentBuildError> {
+        let actor_level = self.user_level(actor);
+
+        check_level(self.state_level(&EventType::RoomPowerLevels), actor_level)?;
+
+        Ok(actor_level)
+    }
+}
+
+fn check_level(required: Int, actual: Int) -> Result<(), ContentBuildError> {
+    if actual >= required {
+        Ok(())
+    } else {
+        Err(ContentBuildError::PowerLevelTooLow { required, actual })
+    }
+}
+
+fn default_power_level() -> Int {
+    Int::from(50)
 }
 
-fn default_power_level() -> u64 {
-    50
+/// Deserialization of power levels given as strings, such as `"50"`, which older homeservers
+/// accepted.
+///
+/// A string is only accepted if it is the canonical decimal form of the level, so that no
+/// information is lost by converting it.
+#[cfg(feature = "compat")]
+mod compat {
+    use std::collections::HashMap;
+    use std::fmt::{Formatter, Result as FmtResult};
+    use std::hash::Hash;
+    use std::iter::FromIterator;
+
+    use serde::{Deserialize, Deserializer};
+    use serde::de::{Error, Visitor};
+
+    use int::Int;
+
+    pub fn level<'de, D>(deserializer: D) -> Result<Int, D::Error> where D: Deserializer<'de> {
+        deserializer.deserialize_any(LevelVisitor)
+    }
+
+    pub fn levels<'de, D, K, M>(deserializer: D) -> Result<M, D::Error>
+    where D: Deserializer<'de>, K: Deserialize<'de> + Eq + Hash, M: FromIterator<(K, Int)> {
+        let levels: HashMap<K, Level> = Deserialize::deserialize(deserializer)?;
+
+        Ok(levels.into_iter().map(|(key, Level(level))| (key, level)).collect())
+    }
+
+    struct Level(Int);
+
+    impl<'de> Deserialize<'de> for Level {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where D: Deserializer<'de> {
+            level(deserializer).map(Level)
+        }
+    }
+
+    struct LevelVisitor;
+
+    impl<'de> Visitor<'de> for LevelVisitor {
+        type Value = Int;
+
+        fn expecting(&self, formatter: &mut Formatter) -> FmtResult {
+            write!(formatter, "a power level as an integer or a string of an integer")
+        }
+
+        fn visit_u64<E>(self, v: u64) -> Result<Int, E> where E: Error {
+            if v > i64::MAX as u64 {
+                return Err(E::custom(format!("power level {} is out of range", v)));
+            }
+
+            self.visit_i64(v as i64)
+        }
+
+        fn visit_i64<E>(self, v: i64) -> Result<Int, E> where E: Error {
+            Int::new(v).ok_or_else(|| E::custom(format!("power level {} is out of range", v)))
+        }
+
+        fn visit_str<E>(self, v: &str) -> Result<Int, E> where E: Error {
+            match v.parse::<i64>().ok().and_then(Int::new) {
+                Some(level) if level.to_string() == v => Ok(level),
+                _ => Err(E::custom(format!("invalid power level {:?}", v))),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{from_str, to_value};
+
+    use {ContentBuildError, EventType, RoomEventContent};
+    use int::Int;
+    use room::member::MemberEventContent;
+    use room::redaction::RedactionEventContent;
+    use room::tombstone::TombstoneEventContent;
+    use super::PowerLevelsEventContent;
+
+    #[test]
+    fn power_level_changes_are_checked() {
+        let mut content = from_str::<PowerLevelsEventContent>(r#"{
+            "events": {"m.room.power_levels": 50},
+            "users": {"@admin:example.com": 100, "@mod:example.com": 50}
+        }"#).unwrap();
+        let admin = from_str(r#""@admin:example.com""#).unwrap();
+        let moderator = from_str(r#""@mod:example.com""#).unwrap();
+        let user = from_str(r#""@user:example.com""#).unwrap();
+
+        content.promote(&admin, &user, Int::from(50)).unwrap();
+        assert_eq!(content.user_level(&user), Int::from(50));
+
+        assert_eq!(
+            content.demote(&moderator, &user, Int::from(10)),
+            Err(ContentBuildError::PowerLevelTooLow {
+                required: Int::from(51),
+                actual: Int::from(50),
+            })
+        );
+
+        content.demote(&admin, &user, Int::from(0)).unwrap();
+        assert!(!content.users.contains_key(&user));
+
+        content.demote(&admin, &admin, Int::from(90)).unwrap();
+        assert_eq!(
+            content.set_event_requirement(&admin, EventType::RoomName, Int::from(95)),
+            Err(ContentBuildError::PowerLevelTooLow {
+                required: Int::from(95),
+                actual: Int::from(90),
+            })
+        );
+        assert_eq!(
+            content.promote(&user, &user, Int::from(100)),
+            Err(ContentBuildError::PowerLevelTooLow {
+                required: Int::from(50),
+                actual: Int::from(0),
+            })
+        );
+    }
+
+    #[test]
+    fn promote_and_demote_check_direction() {
+        let mut content = from_str::<PowerLevelsEventContent>(r#"{
+            "events": {},
+            "users": {"@admin:example.com": 100, "@mod:example.com": 50}
+        }"#).unwrap();
+        let admin = from_str(r#""@admin:example.com""#).unwrap();
+        let moderator = from_str(r#""@mod:example.com""#).unwrap();
+
+        assert_eq!(
+            content.promote(&admin, &moderator, Int::from(10)),
+            Err(ContentBuildError::PromotionLowersLevel {
+                current: Int::from(50),
+                level: Int::from(10),
+            })
+        );
+        assert_eq!(
+            content.demote(&admin, &moderator, Int::from(75)),
+            Err(ContentBuildError::DemotionRaisesLevel {
+                current: Int::from(50),
+                level: Int::from(75),
+            })
+        );
+        assert_eq!(content.user_level(&moderator), Int::from(50));
+
+        content.promote(&admin, &moderator, Int::from(50)).unwrap();
+        content.demote(&admin, &moderator, Int::from(50)).unwrap();
+        assert_eq!(content.user_level(&moderator), Int::from(50));
+    }
+
+    #[test]
+    fn events_are_keyed_by_event_type() {
+        let content = from_str::<PowerLevelsEventContent>(r#"{
+            "events": {"org.example.custom": 10, "m.room.topic": 25, "m.room.name": 75},
+            "users": {}
+        }"#).unwrap();
+
+        assert_eq!(content.events[&EventType::RoomName], Int::from(75));
+        assert_eq!(
+            content.events[&EventType::Custom("org.example.custom".into())],
+            Int::from(10)
+        );
+
+        let keys: Vec<&str> = content.events.keys().map(EventType::as_str).collect();
+        assert_eq!(keys, vec!["m.room.name", "m.room.topic", "org.example.custom"]);
+        assert_eq!(to_value(&content).unwrap()["events"]["m.room.topic"], 25);
+    }
+
+    #[test]
+    fn negative_levels() {
+        let content = from_str::<PowerLevelsEventContent>(r#"{
+            "events": {},
+            "events_default": -5,
+            "users": {"@admin:example.com": 100},
+            "users_default": -10
+        }"#).unwrap();
+        let guest = from_str(r#""@guest:example.com""#).unwrap();
+
+        assert_eq!(content.user_level(&guest), Int::from(-10));
+        assert_eq!(content.message_level(&EventType::RoomMessage), Int::from(-5));
+        assert_eq!(to_value(&content).unwrap()["users_default"], -10);
+    }
+
+    #[test]
+    fn required_power_levels() {
+        let content = from_str::<PowerLevelsEventContent>(r#"{
+            "ban": 75,
+            "events": {"m.room.tombstone": 100},
+            "kick": 60,
+            "events_default": 10,
+            "users": {}
+        }"#).unwrap();
+        let tombstone = from_str::<TombstoneEventContent>(
+            r#"{"body":"This room has been replaced","replacement_room":"!new:example.com"}"#
+        ).unwrap();
+
+        assert_eq!(tombstone.required_power_level(&content), Int::from(100));
+        assert_eq!(content.required_power_level(&content), Int::from(50));
+        assert_eq!(RedactionEventContent::new().required_power_level(&content), Int::from(10));
+        assert_eq!(MemberEventContent::ban(None).required_power_level(&content), Int::from(75));
+        assert_eq!(
+            MemberEventContent::leave_with_reason(None).required_power_level(&content),
+            Int::from(75)
+        );
+        assert_eq!(MemberEventContent::invite(None).required_power_level(&content), Int::from(50));
+    }
+
+    #[cfg(feature = "compat")]
+    #[test]
+    fn string_levels_are_coerced() {
+        let content = from_str::<PowerLevelsEventContent>(r#"{
+            "ban": "100",
+            "events": {"m.room.name": "75"},
+            "users": {"@admin:example.com": "100"}
+        }"#).unwrap();
+        let admin = from_str(r#""@admin:example.com""#).unwrap();
+
+        assert_eq!(content.ban, Int::from(100));
+        assert_eq!(content.events[&EventType::RoomName], Int::from(75));
+        assert_eq!(content.user_level(&admin), Int::from(100));
+        assert!(from_str::<PowerLevelsEventContent>(r#"{"ban":"050","events":{},"users":{}}"#)
+            .is_err());
+    }
 }
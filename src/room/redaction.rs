@@ -2,18 +2,170 @@
 
 use ruma_identifiers::EventId;
 
+use room_version::RoomVersionRules;
+
 room_event! {
     /// A redaction of an event.
     pub struct RedactionEvent(RedactionEventContent) {
         /// The ID of the event that was redacted.
-        pub redacts: EventId
+        ///
+        /// Since room version 11, this is part of the content instead.
+        #[serde(skip_serializing_if="Option::is_none")]
+        pub redacts: Option<EventId>
     }
 }
 
 /// The payload of a `RedactionEvent`.
-#[derive(Clone, Debug, Deserialize, Serialize)]
+///
+/// `required_power_level` is the level to redact one's own events. Redacting the events of other
+/// users also requires the `redact` level of the room.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
 pub struct RedactionEventContent {
     /// The reason for the redaction, if any.
     #[serde(skip_serializing_if="Option::is_none")]
     pub reason: Option<String>,
+
+    /// The ID of the event that was redacted, in room version 11 and later.
+    #[serde(skip_serializing_if="Option::is_none")]
+    pub redacts: Option<EventId>,
+
+    /// Fields that are not covered by the Matrix specification.
+    #[cfg(feature = "preserve-extra-fields")]
+    #[serde(flatten)]
+    pub extra: ::std::collections::BTreeMap<String, ::serde_json::Value>,
+}
+
+impl_event_content!(RedactionEventContent, RoomRedaction);
+impl_room_event_content!(message_like, RedactionEventContent);
+
+impl RedactionEventContent {
+    /// Creates the content of a redaction without a reason, for rooms before version 11, where
+    /// the redacted event is given next to the content.
+    pub fn new() -> Self {
+        RedactionEventContent::default()
+    }
+
+    /// Creates the content of a redaction with the given reason, for rooms before version 11.
+    pub fn with_reason(reason: String) -> Self {
+        RedactionEventContent {
+            reason: Some(reason),
+            redacts: None,
+            #[cfg(feature = "preserve-extra-fields")]
+            extra: Default::default(),
+        }
+    }
+}
+
+impl RedactionEvent {
+    /// The ID of the event that was redacted, wherever the room version puts it.
+    pub fn redacted_event_id(&self) -> Option<&EventId> {
+        self.content.redacts.as_ref().or(self.redacts.as_ref())
+    }
+
+    /// The ID of the event that was redacted, read only from where the given room version puts
+    /// it.
+    pub fn redacted_event_id_for(&self, rules: &RoomVersionRules) -> Option<&EventId> {
+        if rules.redacts_in_content {
+            self.content.redacts.as_ref()
+        } else {
+            self.redacts.as_ref()
+        }
+    }
+}
+
+/// Creates the content of a redaction of `event_id` with an optional reason, in the layout of room
+/// version 11 and later, where the redacted event is part of the content.
+pub fn redaction_for(event_id: EventId, reason: Option<String>) -> RedactionEventContent {
+    RedactionEventContent {
+        reason,
+        redacts: Some(event_id),
+        #[cfg(feature = "preserve-extra-fields")]
+        extra: Default::default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{from_str, to_string};
+
+    use room_version::{RoomVersionId, room_version_rules};
+    use super::{RedactionEvent, redaction_for};
+
+    #[test]
+    fn redacted_event_id_in_both_layouts() {
+        let event = from_str::<RedactionEvent>(r#"{
+            "content": {"reason": "spam"},
+            "event_id": "$h29iv0s8:example.com",
+            "origin_server_ts": 1,
+            "redacts": "$fukweghifu23:localhost",
+            "room_id": "!n8f893n9:example.com",
+            "sender": "@carl:example.com",
+            "type": "m.room.redaction"
+        }"#).unwrap();
+        assert_eq!(event.redacted_event_id().unwrap().to_string(), "$fukweghifu23:localhost");
+
+        let event = from_str::<RedactionEvent>(r#"{
+            "content": {"redacts": "$fukweghifu23:localhost"},
+            "event_id": "$h29iv0s8:example.com",
+            "origin_server_ts": 1,
+            "room_id": "!n8f893n9:example.com",
+            "sender": "@carl:example.com",
+            "type": "m.room.redaction"
+        }"#).unwrap();
+        assert_eq!(event.redacted_event_id().unwrap().to_string(), "$fukweghifu23:localhost");
+
+        let content = redaction_for(event.redacted_event_id().unwrap().clone(), None);
+        assert_eq!(to_string(&content).unwrap(), r#"{"redacts":"$fukweghifu23:localhost"}"#);
+    }
+
+    #[test]
+    fn redacted_event_id_for_room_version() {
+        let v1 = room_version_rules(&RoomVersionId::V1).unwrap();
+        let v11 = room_version_rules(&RoomVersionId::V11).unwrap();
+
+        let event = from_str::<RedactionEvent>(r#"{
+            "content": {"reason": "spam"},
+            "event_id": "$h29iv0s8:example.com",
+            "origin_server_ts": 1,
+            "redacts": "$fukweghifu23:localhost",
+            "room_id": "!n8f893n9:example.com",
+            "sender": "@carl:example.com",
+            "type": "m.room.redaction"
+        }"#).unwrap();
+        assert_eq!(
+            event.redacted_event_id_for(&v1).unwrap().to_string(),
+            "$fukweghifu23:localhost"
+        );
+        assert!(event.redacted_event_id_for(&v11).is_none());
+
+        let event = from_str::<RedactionEvent>(r#"{
+            "content": {"redacts": "$fukweghifu23:localhost"},
+            "event_id": "$h29iv0s8:example.com",
+            "origin_server_ts": 1,
+            "room_id": "!n8f893n9:example.com",
+            "sender": "@carl:example.com",
+            "type": "m.room.redaction"
+        }"#).unwrap();
+        assert!(event.redacted_event_id_for(&v1).is_none());
+        assert_eq!(
+            event.redacted_event_id_for(&v11).unwrap().to_string(),
+            "$fukweghifu23:localhost"
+        );
+    }
+
+    #[test]
+    fn redacted_event_id_for_non_redaction() {
+        let event = from_str::<RedactionEvent>(r#"{
+            "content": {"body": "hello", "msgtype": "m.text"},
+            "event_id": "$h29iv0s8:example.com",
+            "origin_server_ts": 1,
+            "room_id": "!n8f893n9:example.com",
+            "sender": "@carl:example.com",
+            "type": "m.room.message"
+        }"#).unwrap();
+
+        for version in &[RoomVersionId::V1, RoomVersionId::V11] {
+            assert!(event.redacted_event_id_for(&room_version_rules(version).unwrap()).is_none());
+        }
+    }
 }
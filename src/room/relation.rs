@@ -0,0 +1,505 @@
+//! Types for relations between room events, as expressed by the `m.relates_to` field of event
+//! content.
+
+use ruma_identifiers::{EventId, UserId};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde::de::Error;
+use serde_json::{Value, from_value};
+
+use timeline::AnySyncTimelineEvent;
+
+/// The type of a relation between two events.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum RelationType {
+    /// m.annotation
+    Annotation,
+    /// m.reference
+    Reference,
+    /// m.replace
+    Replacement,
+    /// m.thread
+    Thread,
+    /// Any relation type that is not part of the specification.
+    Custom(String),
+}
+
+impl_string_enum! {
+    RelationType {
+        Annotation => "m.annotation",
+        Reference => "m.reference",
+        Replacement => "m.replace",
+        Thread => "m.thread",
+    }
+}
+
+impl RelationType {
+    /// The type of the relation in the given event content, read from `m.relates_to.rel_type`.
+    ///
+    /// This works on content that has not been deserialized into a specific type, such as the
+    /// chunks returned by the */relations* API, and on cleartext relations of encrypted events.
+    pub fn of_content(content: &Value) -> Option<RelationType> {
+        content.get("m.relates_to")?.get("rel_type")?.as_str().map(RelationType::from)
+    }
+}
+
+/// An *m.annotation* relation, such as a reaction, to another event.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(tag="rel_type", rename="m.annotation")]
+pub struct Annotation {
+    /// The event being annotated.
+    pub event_id: EventId,
+    /// The annotation, e.g. the emoji of a reaction.
+    pub key: String,
+}
+
+impl<'de> Deserialize<'de> for Annotation {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where D: Deserializer<'de> {
+        #[derive(Deserialize)]
+        struct RawAnnotation {
+            event_id: EventId,
+            key: String,
+            rel_type: RelationType,
+        }
+
+        let raw = RawAnnotation::deserialize(deserializer)?;
+
+        if raw.rel_type != RelationType::Annotation {
+            return Err(D::Error::custom(
+                format!("expected rel_type m.annotation, found {}", raw.rel_type)
+            ));
+        }
+
+        Ok(Annotation {
+            event_id: raw.event_id,
+            key: raw.key,
+        })
+    }
+}
+
+/// An *m.reference* relation, which refers to another event without further semantics.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(tag="rel_type", rename="m.reference")]
+pub struct Reference {
+    /// The event being referenced.
+    pub event_id: EventId,
+}
+
+impl<'de> Deserialize<'de> for Reference {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where D: Deserializer<'de> {
+        #[derive(Deserialize)]
+        struct RawReference {
+            event_id: EventId,
+            rel_type: RelationType,
+        }
+
+        let raw = RawReference::deserialize(deserializer)?;
+
+        if raw.rel_type != RelationType::Reference {
+            return Err(D::Error::custom(
+                format!("expected rel_type m.reference, found {}", raw.rel_type)
+            ));
+        }
+
+        Ok(Reference {
+            event_id: raw.event_id,
+        })
+    }
+}
+
+/// An *m.replace* relation, replacing the content of another event.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(tag="rel_type", rename="m.replace")]
+pub struct Replacement {
+    /// The event being replaced.
+    pub event_id: EventId,
+}
+
+impl<'de> Deserialize<'de> for Replacement {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where D: Deserializer<'de> {
+        #[derive(Deserialize)]
+        struct RawReplacement {
+            event_id: EventId,
+            rel_type: RelationType,
+        }
+
+        let raw = RawReplacement::deserialize(deserializer)?;
+
+        if raw.rel_type != RelationType::Replacement {
+            return Err(D::Error::custom(
+                format!("expected rel_type m.replace, found {}", raw.rel_type)
+            ));
+        }
+
+        Ok(Replacement {
+            event_id: raw.event_id,
+        })
+    }
+}
+
+/// An *m.thread* relation, placing an event in the thread of a root event.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(tag="rel_type", rename="m.thread")]
+pub struct Thread {
+    /// The root event of the thread.
+    pub event_id: EventId,
+    /// Whether `in_reply_to` is only a fallback for clients that do not support threads, rather
+    /// than a real reply.
+    #[serde(default, skip_serializing_if="::std::ops::Not::not")]
+    pub is_falling_back: bool,
+    /// The event this one replies to, or, when falling back, the latest event in the thread.
+    #[serde(rename="m.in_reply_to", skip_serializing_if="Option::is_none")]
+    pub in_reply_to: Option<InReplyTo>,
+}
+
+/// The event that an event replies to.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct InReplyTo {
+    /// The event being replied to.
+    pub event_id: EventId,
+}
+
+impl Thread {
+    /// The thread relation in the given event content, read from `m.relates_to`.
+    ///
+    /// Returns `None` if the content does not relate to another event as part of a thread.
+    pub fn of_content(content: &Value) -> Option<Thread> {
+        from_value(content.get("m.relates_to")?.clone()).ok()
+    }
+
+    /// The event this one actually replies to, ignoring the fallback for clients that do not
+    /// support threads.
+    pub fn reply_target(&self) -> Option<&EventId> {
+        if self.is_falling_back {
+            return None;
+        }
+
+        self.in_reply_to.as_ref().map(|in_reply_to| &in_reply_to.event_id)
+    }
+}
+
+impl<'de> Deserialize<'de> for Thread {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where D: Deserializer<'de> {
+        #[derive(Deserialize)]
+        struct RawThread {
+            event_id: EventId,
+            #[serde(default)]
+            is_falling_back: bool,
+            #[serde(rename="m.in_reply_to")]
+            in_reply_to: Option<InReplyTo>,
+            rel_type: RelationType,
+        }
+
+        let raw = RawThread::deserialize(deserializer)?;
+
+        if raw.rel_type != RelationType::Thread {
+            return Err(D::Error::custom(
+                format!("expected rel_type m.thread, found {}", raw.rel_type)
+            ));
+        }
+
+        Ok(Thread {
+            event_id: raw.event_id,
+            is_falling_back: raw.is_falling_back,
+            in_reply_to: raw.in_reply_to,
+        })
+    }
+}
+
+/// The relation of an event to another event, as found in `m.relates_to`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Relation {
+    /// An *m.annotation* relation.
+    Annotation(Annotation),
+    /// An *m.reference* relation.
+    Reference(Reference),
+    /// An *m.replace* relation.
+    Replacement(Replacement),
+    /// An *m.thread* relation.
+    Thread(Thread),
+    /// A reply, which has no `rel_type` but only `m.in_reply_to`.
+    Reply(InReplyTo),
+    /// A relation with a type that is not part of the specification, kept as JSON.
+    Custom(Value),
+}
+
+impl Relation {
+    /// The type of the relation, or `None` for replies.
+    pub fn rel_type(&self) -> Option<RelationType> {
+        match *self {
+            Relation::Annotation(_) => Some(RelationType::Annotation),
+            Relation::Reference(_) => Some(RelationType::Reference),
+            Relation::Replacement(_) => Some(RelationType::Replacement),
+            Relation::Thread(_) => Some(RelationType::Thread),
+            Relation::Reply(_) => None,
+            Relation::Custom(ref relation) => {
+                relation.get("rel_type")?.as_str().map(RelationType::from)
+            }
+        }
+    }
+}
+
+impl Serialize for Relation {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
+        #[derive(Serialize)]
+        struct RawReply<'a> {
+            #[serde(rename="m.in_reply_to")]
+            in_reply_to: &'a InReplyTo,
+        }
+
+        match *self {
+            Relation::Annotation(ref relation) => relation.serialize(serializer),
+            Relation::Reference(ref relation) => relation.serialize(serializer),
+            Relation::Replacement(ref relation) => relation.serialize(serializer),
+            Relation::Thread(ref relation) => relation.serialize(serializer),
+            Relation::Reply(ref in_reply_to) => RawReply { in_reply_to }.serialize(serializer),
+            Relation::Custom(ref relation) => relation.serialize(serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Relation {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where D: Deserializer<'de> {
+        let value = Value::deserialize(deserializer)?;
+
+        let rel_type = match value.get("rel_type") {
+            Some(rel_type) => rel_type,
+            None => {
+                let in_reply_to = match value.get("m.in_reply_to") {
+                    Some(in_reply_to) => in_reply_to.clone(),
+                    None => return Err(D::Error::missing_field("rel_type")),
+                };
+
+                return match from_value(in_reply_to) {
+                    Ok(in_reply_to) => Ok(Relation::Reply(in_reply_to)),
+                    Err(error) => Err(D::Error::custom(error.to_string())),
+                };
+            }
+        };
+
+        let rel_type = match RelationType::deserialize(rel_type) {
+            Ok(rel_type) => rel_type,
+            Err(error) => return Err(D::Error::custom(error.to_string())),
+        };
+
+        let relation = match rel_type {
+            RelationType::Annotation => from_value(value).map(Relation::Annotation),
+            RelationType::Reference => from_value(value).map(Relation::Reference),
+            RelationType::Replacement => from_value(value).map(Relation::Replacement),
+            RelationType::Thread => from_value(value).map(Relation::Thread),
+            RelationType::Custom(_) => Ok(Relation::Custom(value)),
+        };
+
+        relation.map_err(|error| D::Error::custom(error.to_string()))
+    }
+}
+
+/// Keeps the event contents that relate to other events with the given relation type.
+pub fn filter_by_relation_type<'a, I>(contents: I, rel_type: &RelationType) -> Vec<&'a Value>
+where I: IntoIterator<Item = &'a Value> {
+    contents.into_iter()
+        .filter(|content| RelationType::of_content(content).as_ref() == Some(rel_type))
+        .collect()
+}
+
+/// The aggregation of a thread, as bundled by servers in `unsigned.m.relations.m.thread` of the
+/// thread root.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ThreadSummary {
+    /// The number of events in the thread, not counting the root.
+    pub count: u64,
+    /// The most recent event in the thread.
+    pub latest_event: AnySyncTimelineEvent,
+    /// Whether the user the summary was computed for sent the root or an event in the thread.
+    #[serde(rename="current_user_participated")]
+    pub participated: bool,
+}
+
+impl ThreadSummary {
+    /// Computes the summary of the thread of the given root event, from events of the room in
+    /// chronological order, for the given user.
+    ///
+    /// Events that are not in the thread are skipped. Returns `None` if the thread has no events.
+    pub fn compute<'a, I>(root: &AnySyncTimelineEvent, events: I, user_id: &UserId)
+    -> Option<ThreadSummary>
+    where I: IntoIterator<Item = &'a AnySyncTimelineEvent> {
+        let mut count = 0;
+        let mut latest_event: Option<&AnySyncTimelineEvent> = None;
+        let mut participated = root.sender() == user_id;
+
+        for event in events {
+            match event.relates_to() {
+                Some(Relation::Thread(thread)) if thread.event_id == *root.event_id() => {}
+                _ => continue,
+            }
+
+            count += 1;
+            participated |= event.sender() == user_id;
+
+            if latest_event.is_none_or(|latest| {
+                event.origin_server_ts() >= latest.origin_server_ts()
+            }) {
+                latest_event = Some(event);
+            }
+        }
+
+        Some(ThreadSummary {
+            count,
+            latest_event: latest_event?.clone(),
+            participated,
+        })
+    }
+
+    /// The summary bundled by the server with the given thread root, if any.
+    pub fn bundled(root: &AnySyncTimelineEvent) -> Option<ThreadSummary> {
+        let summary = root.unsigned()?.get("m.relations")?.get("m.thread")?;
+
+        from_value(summary.clone()).ok()
+    }
+
+    /// Whether this summary agrees with another one on the count, the latest event and
+    /// participation.
+    ///
+    /// Latest events are compared by their `event_id`, since servers may bundle them with
+    /// different `unsigned` data.
+    pub fn matches(&self, other: &ThreadSummary) -> bool {
+        self.count == other.count &&
+            self.participated == other.participated &&
+            self.latest_event.event_id() == other.latest_event.event_id()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ruma_identifiers::UserId;
+    use serde_json::{Value, from_str, to_string};
+
+    use timeline::AnySyncTimelineEvent;
+
+    use super::{Relation, RelationType, Thread, ThreadSummary, filter_by_relation_type};
+
+    #[test]
+    fn relation_types() {
+        assert_eq!(to_string(&RelationType::Replacement).unwrap(), r#""m.replace""#);
+        assert_eq!("m.thread".parse::<RelationType>().unwrap(), RelationType::Thread);
+        assert_eq!(
+            from_str::<RelationType>(r#""org.example.rel""#).unwrap().as_str(),
+            "org.example.rel"
+        );
+
+        let contents = from_str::<Vec<Value>>(r#"[
+            {"m.relates_to": {"event_id": "$a:example.com", "rel_type": "m.annotation"}},
+            {"m.relates_to": {"event_id": "$a:example.com", "rel_type": "m.replace"}},
+            {"body": "unrelated"}
+        ]"#).unwrap();
+
+        assert_eq!(filter_by_relation_type(&contents, &RelationType::Annotation).len(), 1);
+    }
+
+    #[test]
+    fn relations() {
+        let replacement = r#"{"rel_type":"m.replace","event_id":"$a:example.com"}"#;
+        let reply = r#"{"m.in_reply_to":{"event_id":"$a:example.com"}}"#;
+        let custom = r#"{"event_id":"$a:example.com","rel_type":"org.example.rel"}"#;
+
+        match from_str::<Relation>(replacement).unwrap() {
+            Relation::Replacement(ref relation) => {
+                assert_eq!(relation.event_id.to_string(), "$a:example.com");
+            }
+            _ => unreachable!(),
+        }
+
+        for json in &[replacement, reply, custom] {
+            assert_eq!(to_string(&from_str::<Relation>(json).unwrap()).unwrap(), *json);
+        }
+
+        assert_eq!(from_str::<Relation>(reply).unwrap().rel_type(), None);
+        assert_eq!(
+            from_str::<Relation>(custom).unwrap().rel_type(),
+            Some(RelationType::Custom("org.example.rel".to_string()))
+        );
+    }
+
+    fn thread_event(event_id: &str, root_id: &str, ts: u64, sender: &str)
+    -> AnySyncTimelineEvent {
+        from_str(&format!(
+            r#"{{"content":{{"body":"In a thread","msgtype":"m.text",
+            "m.relates_to":{{"event_id":"{}","rel_type":"m.thread"}}}},
+            "event_id":"{}","origin_server_ts":{},"sender":"{}","type":"m.room.message"}}"#,
+            root_id, event_id, ts, sender
+        )).unwrap()
+    }
+
+    #[test]
+    fn thread_summary() {
+        let root = from_str::<AnySyncTimelineEvent>(r#"{
+            "content": {"body": "Root", "msgtype": "m.text"},
+            "event_id": "$root:example.com",
+            "origin_server_ts": 1,
+            "sender": "@carl:example.com",
+            "type": "m.room.message",
+            "unsigned": {"m.relations": {"m.thread": {
+                "count": 2,
+                "current_user_participated": true,
+                "latest_event": {
+                    "content": {
+                        "body": "In a thread",
+                        "msgtype": "m.text",
+                        "m.relates_to": {"event_id": "$root:example.com", "rel_type": "m.thread"}
+                    },
+                    "event_id": "$b:example.com",
+                    "origin_server_ts": 4,
+                    "sender": "@erin:example.com",
+                    "type": "m.room.message",
+                    "unsigned": {"age": 1234}
+                }
+            }}}
+        }"#).unwrap();
+        let events = vec![
+            thread_event("$a:example.com", "$root:example.com", 2, "@dana:example.com"),
+            thread_event("$x:example.com", "$other:example.com", 3, "@dana:example.com"),
+            thread_event("$b:example.com", "$root:example.com", 4, "@erin:example.com"),
+        ];
+
+        let carl = from_str::<UserId>(r#""@carl:example.com""#).unwrap();
+        let computed = ThreadSummary::compute(&root, &events, &carl).unwrap();
+
+        assert_eq!(computed.count, 2);
+        assert!(computed.participated);
+        assert_eq!(computed.latest_event.event_id().to_string(), "$b:example.com");
+        assert!(computed.matches(&ThreadSummary::bundled(&root).unwrap()));
+
+        let frank = from_str::<UserId>(r#""@frank:example.com""#).unwrap();
+        assert!(!ThreadSummary::compute(&root, &events, &frank).unwrap().participated);
+        assert!(ThreadSummary::compute(&root, &events[1..2], &carl).is_none());
+    }
+
+    #[test]
+    fn thread_relation() {
+        let content = from_str::<Value>(r#"{
+            "body": "In a thread",
+            "m.relates_to": {
+                "event_id": "$root:example.com",
+                "is_falling_back": true,
+                "m.in_reply_to": {"event_id": "$latest:example.com"},
+                "rel_type": "m.thread"
+            }
+        }"#).unwrap();
+
+        let thread = Thread::of_content(&content).unwrap();
+
+        assert_eq!(thread.event_id.to_string(), "$root:example.com");
+        assert!(thread.reply_target().is_none());
+        assert_eq!(
+            to_string(&thread).unwrap(),
+            concat!(
+                r#"{"rel_type":"m.thread","event_id":"$root:example.com","is_falling_back":true,"#,
+                r#""m.in_reply_to":{"event_id":"$latest:example.com"}}"#
+            )
+        );
+
+        let reply = from_str::<Value>(
+            r#"{"m.relates_to":{"event_id":"$a:example.com","rel_type":"m.annotation"}}"#
+        ).unwrap();
+        assert!(Thread::of_content(&reply).is_none());
+    }
+}
@@ -0,0 +1,131 @@
+//! Types for the unstable *m.room.retention* event, as proposed in MSC1763.
+
+use std::time::Duration;
+
+use clock::{Clock, SystemClock};
+use {EmptyStateKey, EventContent, EventType, StaticStateEventContent};
+
+state_event! {
+    /// Describes how long the messages of a room should be kept.
+    ///
+    /// Servers and clients should purge messages once they are older than `max_lifetime`, and
+    /// should keep them for at least `min_lifetime`.
+    pub struct RetentionEvent(RetentionEventContent) {}
+}
+
+/// The payload of a `RetentionEvent`.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct RetentionEventContent {
+    /// The maximum duration for which a message should be kept, serialized in milliseconds.
+    #[serde(default, skip_serializing_if="Option::is_none", with="duration_ms")]
+    pub max_lifetime: Option<Duration>,
+
+    /// The minimum duration for which a message should be kept, serialized in milliseconds.
+    #[serde(default, skip_serializing_if="Option::is_none", with="duration_ms")]
+    pub min_lifetime: Option<Duration>,
+
+    /// Fields that are not covered by the Matrix specification.
+    #[cfg(feature = "preserve-extra-fields")]
+    #[serde(flatten)]
+    pub extra: ::std::collections::BTreeMap<String, ::serde_json::Value>,
+}
+
+impl EventContent for RetentionEventContent {
+    fn event_type() -> EventType {
+        EventType::Custom("m.room.retention".into())
+    }
+}
+
+impl StaticStateEventContent for RetentionEventContent {
+    type StateKey = EmptyStateKey;
+}
+
+impl RetentionEventContent {
+    /// Whether an event sent at `event_ts` has outlived `max_lifetime` by now.
+    ///
+    /// `event_ts` is in milliseconds since the Unix epoch, like an event's `origin_server_ts`.
+    /// Events never expire if the room has no `max_lifetime`.
+    pub fn is_expired(&self, event_ts: u64) -> bool {
+        self.is_expired_at(event_ts, SystemClock)
+    }
+
+    /// Like `is_expired`, but reads the current time from `clock`.
+    pub fn is_expired_at<C: Clock>(&self, event_ts: u64, clock: C) -> bool {
+        match self.max_lifetime {
+            Some(max_lifetime) => {
+                let age = Duration::from_millis(clock.now().saturating_sub(event_ts));
+
+                age > max_lifetime
+            }
+            None => false,
+        }
+    }
+}
+
+/// Serializes an optional `Duration` as an integer number of milliseconds, which must be a valid
+/// `UInt`.
+mod duration_ms {
+    use std::time::Duration;
+
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use serde::ser::Error;
+
+    use int::UInt;
+
+    pub fn serialize<S>(duration: &Option<Duration>, serializer: S) -> Result<S::Ok, S::Error>
+    where S: Serializer {
+        let millis = match *duration {
+            Some(duration) => match UInt::new(duration.as_millis() as u64) {
+                Some(millis) if duration.as_millis() <= u128::from(u64::from(UInt::MAX)) => {
+                    Some(millis)
+                }
+                _ => return Err(S::Error::custom("duration exceeds the maximum integer value")),
+            },
+            None => None,
+        };
+
+        millis.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<Duration>, D::Error>
+    where D: Deserializer<'de> {
+        let millis: Option<UInt> = Deserialize::deserialize(deserializer)?;
+
+        Ok(millis.map(|millis| Duration::from_millis(millis.into())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use serde_json::{from_str, to_string};
+
+    use clock::FixedClock;
+    use super::RetentionEventContent;
+
+    #[test]
+    fn retention_content() {
+        let content = from_str::<RetentionEventContent>(r#"{"max_lifetime":86400000}"#).unwrap();
+
+        assert_eq!(content.max_lifetime, Some(Duration::from_secs(86400)));
+        assert_eq!(content.min_lifetime, None);
+        assert_eq!(to_string(&content).unwrap(), r#"{"max_lifetime":86400000}"#);
+        assert!(!content.is_expired_at(1_000, FixedClock(1_000 + 86_400_000)));
+        assert!(content.is_expired_at(1_000, FixedClock(1_001 + 86_400_000)));
+    }
+
+    #[test]
+    fn lifetimes_are_bounded() {
+        assert!(from_str::<RetentionEventContent>(r#"{"max_lifetime":9007199254740992}"#).is_err());
+        assert!(from_str::<RetentionEventContent>(r#"{"min_lifetime":-1}"#).is_err());
+
+        let content = RetentionEventContent {
+            max_lifetime: Some(Duration::from_millis(9_007_199_254_740_992)),
+            min_lifetime: None,
+            #[cfg(feature = "preserve-extra-fields")]
+            extra: Default::default(),
+        };
+        assert!(to_string(&content).is_err());
+    }
+}
@@ -0,0 +1,124 @@
+//! Types for the *m.room.server_acl* event.
+
+use std::net::Ipv4Addr;
+
+use glob::glob_matches;
+
+state_event! {
+    /// An event to indicate which servers are permitted to participate in the room.
+    pub struct ServerAclEvent(ServerAclEventContent) {}
+}
+
+/// The payload of a `ServerAclEvent`.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ServerAclEventContent {
+    /// True to allow server names that are IP address literals. False to deny.
+    ///
+    /// This is strongly recommended to be set to false as servers running with IP literal names
+    /// are strongly discouraged in order to require legitimate homeservers to be backed by a valid
+    /// registered domain name.
+    #[serde(default = "default_allow_ip_literals")]
+    pub allow_ip_literals: bool,
+    /// The server names to allow in the room, excluding any port information. Wildcards may be
+    /// used to cover a wider range of hosts, where `*` matches zero or more characters and `?`
+    /// matches exactly one character.
+    ///
+    /// **This defaults to an empty list when not provided, effectively disallowing every
+    /// server.**
+    #[serde(default)]
+    pub allow: Vec<String>,
+    /// The server names to disallow in the room, excluding any port information. Wildcards may
+    /// be used as for `allow`.
+    ///
+    /// This defaults to an empty list when not provided.
+    #[serde(default)]
+    pub deny: Vec<String>,
+    /// Fields that are not covered by the Matrix specification.
+    #[cfg(feature = "preserve-extra-fields")]
+    #[serde(flatten)]
+    pub extra: ::std::collections::BTreeMap<String, ::serde_json::Value>,
+}
+
+impl_event_content!(ServerAclEventContent, RoomServerAcl);
+impl_static_state_event_content!(ServerAclEventContent, ::EmptyStateKey);
+impl_room_event_content!(state, ServerAclEventContent);
+
+impl ServerAclEventContent {
+    /// Whether the given server is allowed to participate in the room.
+    ///
+    /// Any port in `server_name` is ignored. IP literals are checked against
+    /// `allow_ip_literals` first, then the server is denied if it matches any `deny` pattern and
+    /// allowed only if it matches an `allow` pattern.
+    pub fn is_server_allowed(&self, server_name: &str) -> bool {
+        let host = strip_port(server_name);
+
+        if !self.allow_ip_literals && is_ip_literal(host) {
+            return false;
+        }
+
+        if self.deny.iter().any(|pattern| glob_matches(pattern, host)) {
+            return false;
+        }
+
+        self.allow.iter().any(|pattern| glob_matches(pattern, host))
+    }
+}
+
+/// Used to default the `allow_ip_literals` field to true during deserialization.
+fn default_allow_ip_literals() -> bool {
+    true
+}
+
+/// Removes the port, if any, from a server name.
+fn strip_port(server_name: &str) -> &str {
+    if server_name.starts_with('[') {
+        return match server_name.find(']') {
+            Some(end) => &server_name[..=end],
+            None => server_name,
+        };
+    }
+
+    match server_name.rfind(':') {
+        Some(colon) => &server_name[..colon],
+        None => server_name,
+    }
+}
+
+/// Whether a host is an IPv4 literal or a bracketed IPv6 literal.
+fn is_ip_literal(host: &str) -> bool {
+    host.starts_with('[') || host.parse::<Ipv4Addr>().is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::from_str;
+
+    use super::ServerAclEventContent;
+
+    #[test]
+    fn is_server_allowed() {
+        let content: ServerAclEventContent = from_str(
+            r#"{"allow":["*"],"deny":["*.evil.com","evil.com","bad?.org"]}"#
+        ).unwrap();
+
+        assert!(content.allow_ip_literals);
+        assert!(content.is_server_allowed("example.com:8448"));
+        assert!(content.is_server_allowed("1.2.3.4"));
+        assert!(!content.is_server_allowed("evil.com"));
+        assert!(!content.is_server_allowed("matrix.evil.com:443"));
+        assert!(!content.is_server_allowed("bad1.org"));
+        assert!(content.is_server_allowed("bad12.org"));
+
+        let content: ServerAclEventContent = from_str(
+            r#"{"allow":["*.example.com"],"allow_ip_literals":false}"#
+        ).unwrap();
+
+        assert!(content.is_server_allowed("matrix.example.com"));
+        assert!(!content.is_server_allowed("example.com"));
+        assert!(!content.is_server_allowed("[::1]:8448"));
+        assert!(!content.is_server_allowed("127.0.0.1"));
+
+        let content: ServerAclEventContent = from_str("{}").unwrap();
+        assert!(!content.is_server_allowed("example.com"));
+    }
+}
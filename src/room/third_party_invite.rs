@@ -24,8 +24,17 @@ pub struct ThirdPartyInviteEventContent {
     /// Keys with which the token may be signed.
     #[serde(skip_serializing_if="Option::is_none")]
     pub public_keys: Option<Vec<PublicKey>>,
+
+    /// Fields that are not covered by the Matrix specification.
+    #[cfg(feature = "preserve-extra-fields")]
+    #[serde(flatten)]
+    pub extra: ::std::collections::BTreeMap<String, ::serde_json::Value>,
 }
 
+impl_event_content!(ThirdPartyInviteEventContent, RoomThirdPartyInvite);
+impl_static_state_event_content!(ThirdPartyInviteEventContent, String);
+impl_room_event_content!(state, ThirdPartyInviteEventContent);
+
 /// A public key for signing a third party invite token.
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct PublicKey {
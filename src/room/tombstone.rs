@@ -0,0 +1,61 @@
+//! Types for the *m.room.tombstone* event.
+
+use ruma_identifiers::RoomId;
+
+state_event! {
+    /// A state event signifying that a room has been upgraded to a different room version, and
+    /// that clients should go there.
+    pub struct TombstoneEvent(TombstoneEventContent) {}
+}
+
+/// The payload of a `TombstoneEvent`.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct TombstoneEventContent {
+    /// A server-defined message.
+    pub body: String,
+    /// The new room the client should be visiting.
+    pub replacement_room: RoomId,
+    /// Fields that are not covered by the Matrix specification.
+    #[cfg(feature = "preserve-extra-fields")]
+    #[serde(flatten)]
+    pub extra: ::std::collections::BTreeMap<String, ::serde_json::Value>,
+}
+
+impl_event_content!(TombstoneEventContent, RoomTombstone);
+impl_static_state_event_content!(TombstoneEventContent, ::EmptyStateKey);
+impl_room_event_content!(state, TombstoneEventContent);
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{Value, from_str, to_value};
+
+    use collections::all::StateEvent;
+
+    #[test]
+    fn tombstone_round_trip() {
+        let json = r#"{
+            "content": {
+                "body": "This room has been replaced",
+                "replacement_room": "!newroom:example.com"
+            },
+            "event_id": "$h29iv0s8:example.com",
+            "origin_server_ts": 1,
+            "room_id": "!n8f893n9:example.com",
+            "sender": "@carl:example.com",
+            "state_key": "",
+            "type": "m.room.tombstone"
+        }"#;
+
+        let event = from_str::<StateEvent>(json).unwrap();
+
+        match event {
+            StateEvent::RoomTombstone(ref event) => {
+                assert_eq!(event.content.body, "This room has been replaced");
+                assert_eq!(event.content.replacement_room.to_string(), "!newroom:example.com");
+            }
+            _ => unreachable!(),
+        }
+
+        assert_eq!(to_value(&event).unwrap(), from_str::<Value>(json).unwrap());
+    }
+}
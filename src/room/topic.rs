@@ -10,4 +10,12 @@ state_event! {
 pub struct TopicEventContent {
     /// The topic text.
     pub topic: String,
+    /// Fields that are not covered by the Matrix specification.
+    #[cfg(feature = "preserve-extra-fields")]
+    #[serde(flatten)]
+    pub extra: ::std::collections::BTreeMap<String, ::serde_json::Value>,
 }
+
+impl_event_content!(TopicEventContent, RoomTopic);
+impl_static_state_event_content!(TopicEventContent, ::EmptyStateKey);
+impl_room_event_content!(state, TopicEventContent);
@@ -0,0 +1,60 @@
+//! Types for the *m.room_key* event.
+
+use std::fmt::{Debug, Formatter, Result as FmtResult};
+
+use ruma_identifiers::{RoomId, UserId};
+
+use redact::Redacted;
+use room::encryption::Algorithm;
+
+event! {
+    /// This event type is used to exchange keys for end-to-end encryption.
+    ///
+    /// Typically it is encrypted as an *m.room.encrypted* event, then sent as a to-device event.
+    pub struct RoomKeyEvent(RoomKeyEventContent) {
+        /// The user who sent the event.
+        pub sender: UserId
+    }
+}
+
+/// The payload of a `RoomKeyEvent`.
+///
+/// The `Debug` output redacts `session_key`.
+#[derive(Clone, Deserialize, PartialEq, Serialize)]
+pub struct RoomKeyEventContent {
+    /// The encryption algorithm the key in this event is to be used with.
+    ///
+    /// Must be *m.megolm.v1.aes-sha2*.
+    pub algorithm: Algorithm,
+
+    /// The room where the key is used.
+    pub room_id: RoomId,
+
+    /// The ID of the session that the key is for.
+    pub session_id: String,
+
+    /// The key to be exchanged.
+    pub session_key: String,
+
+    /// Fields that are not covered by the Matrix specification.
+    #[cfg(feature = "preserve-extra-fields")]
+    #[serde(flatten)]
+    pub extra: ::std::collections::BTreeMap<String, ::serde_json::Value>,
+}
+
+impl Debug for RoomKeyEventContent {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        let mut debug = f.debug_struct("RoomKeyEventContent");
+        debug
+            .field("algorithm", &self.algorithm)
+            .field("room_id", &self.room_id)
+            .field("session_id", &self.session_id)
+            .field("session_key", &Redacted);
+        #[cfg(feature = "preserve-extra-fields")]
+        debug.field("extra", &self.extra);
+
+        debug.finish()
+    }
+}
+
+impl_event_content!(RoomKeyEventContent, RoomKey);
@@ -0,0 +1,61 @@
+//! Types for the *m.room_key_request* event.
+
+use ruma_identifiers::{RoomId, UserId};
+
+use DeviceId;
+use room::encryption::Algorithm;
+use secret::request::RequestAction;
+
+event! {
+    /// This event type is used to request keys for end-to-end encryption.
+    ///
+    /// It is sent as an unencrypted to-device event.
+    pub struct RoomKeyRequestEvent(RoomKeyRequestEventContent) {
+        /// The user who sent the event.
+        pub sender: UserId
+    }
+}
+
+/// The payload of a `RoomKeyRequestEvent`.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct RoomKeyRequestEventContent {
+    /// Whether the key is requested or the request is cancelled.
+    pub action: RequestAction,
+
+    /// Information about the requested key. Required if `action` is `request`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub body: Option<RequestedKeyInfo>,
+
+    /// The ID of the device requesting the key.
+    pub requesting_device_id: DeviceId,
+
+    /// A random string uniquely identifying the request for a key.
+    ///
+    /// If the key is requested multiple times, it should be reused. It should also be reused in
+    /// order to cancel a request.
+    pub request_id: String,
+
+    /// Fields that are not covered by the Matrix specification.
+    #[cfg(feature = "preserve-extra-fields")]
+    #[serde(flatten)]
+    pub extra: ::std::collections::BTreeMap<String, ::serde_json::Value>,
+}
+
+impl_event_content!(RoomKeyRequestEventContent, RoomKeyRequest);
+
+/// Information about a requested key.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct RequestedKeyInfo {
+    /// The encryption algorithm the requested key in this event is to be used with.
+    pub algorithm: Algorithm,
+
+    /// The room where the key is used.
+    pub room_id: RoomId,
+
+    /// The Curve25519 key of the device which initiated the session originally.
+    pub sender_key: String,
+
+    /// The ID of the session that the key is for.
+    pub session_id: String,
+}
+
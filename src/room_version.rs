@@ -0,0 +1,168 @@
+//! Room versions and the rules that differ between them.
+//!
+//! The rules are exposed as data so that the version-aware code in this crate and servers built on
+//! it agree on what each room version allows.
+
+/// The version of a room, which determines the algorithms used for the room.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum RoomVersionId {
+    /// Room version 1.
+    V1,
+    /// Room version 2.
+    V2,
+    /// Room version 3.
+    V3,
+    /// Room version 4.
+    V4,
+    /// Room version 5.
+    V5,
+    /// Room version 6.
+    V6,
+    /// Room version 7.
+    V7,
+    /// Room version 8.
+    V8,
+    /// Room version 9.
+    V9,
+    /// Room version 10.
+    V10,
+    /// Room version 11.
+    V11,
+    /// Any room version that is not part of the specification.
+    Custom(String),
+}
+
+impl_string_enum! {
+    RoomVersionId {
+        V1 => "1",
+        V2 => "2",
+        V3 => "3",
+        V4 => "4",
+        V5 => "5",
+        V6 => "6",
+        V7 => "7",
+        V8 => "8",
+        V9 => "9",
+        V10 => "10",
+        V11 => "11",
+    }
+}
+
+impl Default for RoomVersionId {
+    /// Room version 1, which applies to rooms whose *m.room.create* event has no `room_version`.
+    fn default() -> Self {
+        RoomVersionId::V1
+    }
+}
+
+/// The behaviour of a room version that differs between versions.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct RoomVersionRules {
+    /// The format of event IDs.
+    pub event_id_format: EventIdFormat,
+
+    /// The rules for which fields survive a redaction.
+    pub redaction: RedactionRules,
+
+    /// Whether *m.room.redaction* events give the redacted event in their content, rather than
+    /// next to it.
+    pub redacts_in_content: bool,
+
+    /// Whether the *knock* join rule is allowed.
+    pub knocking: bool,
+
+    /// Whether the *restricted* join rule is allowed.
+    pub restricted_joins: bool,
+}
+
+/// The format of event IDs in a room version.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum EventIdFormat {
+    /// An opaque local part and the server name of the originating server, e.g.
+    /// `$3ib2ajfoe:example.com`.
+    Original,
+
+    /// The standard base64 encoded reference hash of the event, e.g.
+    /// `$acR1l0raoZnm60CBwAVgqbZqoO/mYU81xysh1u7XcJk`.
+    Base64Hash,
+
+    /// The URL-safe base64 encoded reference hash of the event, e.g.
+    /// `$acR1l0raoZnm60CBwAVgqbZqoO_mYU81xysh1u7XcJk`.
+    UrlSafeBase64Hash,
+}
+
+/// A version of the redaction algorithm, named after the room version that introduced it.
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub enum RedactionRules {
+    /// The original redaction algorithm.
+    V1,
+    /// Room version 6 stops preserving the `aliases` of *m.room.aliases* events.
+    V6,
+    /// Room version 8 preserves the `allow` field of *m.room.join_rules* events.
+    V8,
+    /// Room version 9 preserves `join_authorised_via_users_server` of *m.room.member* events.
+    V9,
+    /// Room version 11 preserves more fields, such as the whole content of *m.room.create* events
+    /// and `redacts` of *m.room.redaction* events.
+    V11,
+}
+
+/// The rules of the given room version, or `None` if the version is not part of the
+/// specification.
+pub fn room_version_rules(version: &RoomVersionId) -> Option<RoomVersionRules> {
+    let rules = |event_id_format, redaction, knocking, restricted_joins| RoomVersionRules {
+        event_id_format,
+        redaction,
+        redacts_in_content: redaction >= RedactionRules::V11,
+        knocking,
+        restricted_joins,
+    };
+
+    let rules = match *version {
+        RoomVersionId::V1 | RoomVersionId::V2 => {
+            rules(EventIdFormat::Original, RedactionRules::V1, false, false)
+        }
+        RoomVersionId::V3 => rules(EventIdFormat::Base64Hash, RedactionRules::V1, false, false),
+        RoomVersionId::V4 | RoomVersionId::V5 => {
+            rules(EventIdFormat::UrlSafeBase64Hash, RedactionRules::V1, false, false)
+        }
+        RoomVersionId::V6 => {
+            rules(EventIdFormat::UrlSafeBase64Hash, RedactionRules::V6, false, false)
+        }
+        RoomVersionId::V7 => {
+            rules(EventIdFormat::UrlSafeBase64Hash, RedactionRules::V6, true, false)
+        }
+        RoomVersionId::V8 => {
+            rules(EventIdFormat::UrlSafeBase64Hash, RedactionRules::V8, true, true)
+        }
+        RoomVersionId::V9 | RoomVersionId::V10 => {
+            rules(EventIdFormat::UrlSafeBase64Hash, RedactionRules::V9, true, true)
+        }
+        RoomVersionId::V11 => {
+            rules(EventIdFormat::UrlSafeBase64Hash, RedactionRules::V11, true, true)
+        }
+        RoomVersionId::Custom(_) => return None,
+    };
+
+    Some(rules)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::from_str;
+
+    use super::{EventIdFormat, RedactionRules, RoomVersionId, room_version_rules};
+
+    #[test]
+    fn rules_by_version() {
+        let v1 = room_version_rules(&RoomVersionId::V1).unwrap();
+        assert_eq!(v1.event_id_format, EventIdFormat::Original);
+        assert!(!v1.knocking && !v1.redacts_in_content);
+
+        let v11 = room_version_rules(&from_str::<RoomVersionId>(r#""11""#).unwrap()).unwrap();
+        assert_eq!(v11.redaction, RedactionRules::V11);
+        assert!(v11.knocking && v11.restricted_joins && v11.redacts_in_content);
+
+        assert_eq!(room_version_rules(&RoomVersionId::from("org.example.v12")), None);
+    }
+}
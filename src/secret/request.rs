@@ -0,0 +1,140 @@
+//! Types for the *m.secret.request* event.
+
+use ruma_identifiers::UserId;
+
+use DeviceId;
+
+event! {
+    /// Sent by a client to request a secret from another device or to cancel a previous request.
+    ///
+    /// It is sent as an unencrypted to-device event.
+    pub struct SecretRequestEvent(SecretRequestEventContent) {
+        /// The user who sent the event.
+        pub sender: UserId
+    }
+}
+
+/// The payload of a `SecretRequestEvent`.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct SecretRequestEventContent {
+    /// Whether the secret is requested or the request is cancelled.
+    pub action: RequestAction,
+
+    /// The name of the secret that is requested. Required if `action` is `request`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<SecretName>,
+
+    /// The ID of the device requesting the secret.
+    pub requesting_device_id: DeviceId,
+
+    /// A random string uniquely identifying the request for a secret.
+    ///
+    /// If the secret is requested from multiple devices at the same time, the same ID may be
+    /// used for every target. The same ID is also used in order to cancel a previous request.
+    pub request_id: String,
+
+    /// Fields that are not covered by the Matrix specification.
+    #[cfg(feature = "preserve-extra-fields")]
+    #[serde(flatten)]
+    pub extra: ::std::collections::BTreeMap<String, ::serde_json::Value>,
+}
+
+impl_event_content!(SecretRequestEventContent, SecretRequest);
+
+impl SecretRequestEventContent {
+    /// Creates the content of a request for the given secret.
+    pub fn request(name: SecretName, requesting_device_id: DeviceId, request_id: String) -> Self {
+        SecretRequestEventContent {
+            action: RequestAction::Request,
+            name: Some(name),
+            requesting_device_id,
+            request_id,
+            #[cfg(feature = "preserve-extra-fields")]
+            extra: Default::default(),
+        }
+    }
+
+    /// Creates the content that cancels the request with the given ID.
+    pub fn cancellation(requesting_device_id: DeviceId, request_id: String) -> Self {
+        SecretRequestEventContent {
+            action: RequestAction::RequestCancellation,
+            name: None,
+            requesting_device_id,
+            request_id,
+            #[cfg(feature = "preserve-extra-fields")]
+            extra: Default::default(),
+        }
+    }
+}
+
+/// The action of a secret request.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum RequestAction {
+    /// request
+    Request,
+    /// request_cancellation
+    RequestCancellation,
+    /// Any action that is not part of the specification.
+    Custom(String),
+}
+
+impl_string_enum! {
+    RequestAction {
+        Request => "request",
+        RequestCancellation => "request_cancellation",
+    }
+}
+
+/// The name of a secret.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum SecretName {
+    /// m.cross_signing.master
+    CrossSigningMasterKey,
+    /// m.cross_signing.self_signing
+    CrossSigningSelfSigningKey,
+    /// m.cross_signing.user_signing
+    CrossSigningUserSigningKey,
+    /// m.megolm_backup.v1
+    RecoveryKey,
+    /// Any secret that is not part of the specification.
+    Custom(String),
+}
+
+impl_string_enum! {
+    SecretName {
+        CrossSigningMasterKey => "m.cross_signing.master",
+        CrossSigningSelfSigningKey => "m.cross_signing.self_signing",
+        CrossSigningUserSigningKey => "m.cross_signing.user_signing",
+        RecoveryKey => "m.megolm_backup.v1",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{from_str, to_string};
+
+    use super::{RequestAction, SecretName, SecretRequestEventContent};
+
+    #[test]
+    fn request_content() {
+        let json = concat!(
+            r#"{"action":"request","name":"m.cross_signing.master","#,
+            r#""requesting_device_id":"ABCDEFG","request_id":"randomly_generated_id_9573"}"#
+        );
+        let content = SecretRequestEventContent::request(
+            SecretName::CrossSigningMasterKey,
+            "ABCDEFG".parse().unwrap(),
+            "randomly_generated_id_9573".to_string(),
+        );
+
+        assert_eq!(to_string(&content).unwrap(), json);
+
+        let cancellation = from_str::<SecretRequestEventContent>(concat!(
+            r#"{"action":"request_cancellation","#,
+            r#""requesting_device_id":"ABCDEFG","request_id":"randomly_generated_id_9573"}"#
+        )).unwrap();
+
+        assert_eq!(cancellation.action, RequestAction::RequestCancellation);
+        assert_eq!(cancellation.name, None);
+    }
+}
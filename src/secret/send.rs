@@ -0,0 +1,51 @@
+//! Types for the *m.secret.send* event.
+
+use std::fmt::{Debug, Formatter, Result as FmtResult};
+
+use ruma_identifiers::UserId;
+
+use redact::Redacted;
+
+event! {
+    /// Sent by a client to share a secret with another device, in response to an
+    /// *m.secret.request* event.
+    ///
+    /// It must be encrypted as an *m.room.encrypted* event using Olm, then sent as a to-device
+    /// event.
+    pub struct SecretSendEvent(SecretSendEventContent) {
+        /// The user who sent the event.
+        pub sender: UserId
+    }
+}
+
+/// The payload of a `SecretSendEvent`.
+///
+/// The `Debug` output redacts `secret`.
+#[derive(Clone, Deserialize, PartialEq, Serialize)]
+pub struct SecretSendEventContent {
+    /// The ID of the request that this is a response to.
+    pub request_id: String,
+
+    /// The contents of the secret.
+    pub secret: String,
+
+    /// Fields that are not covered by the Matrix specification.
+    #[cfg(feature = "preserve-extra-fields")]
+    #[serde(flatten)]
+    pub extra: ::std::collections::BTreeMap<String, ::serde_json::Value>,
+}
+
+impl Debug for SecretSendEventContent {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        let mut debug = f.debug_struct("SecretSendEventContent");
+        debug
+            .field("request_id", &self.request_id)
+            .field("secret", &Redacted);
+        #[cfg(feature = "preserve-extra-fields")]
+        debug.field("extra", &self.extra);
+
+        debug.finish()
+    }
+}
+
+impl_event_content!(SecretSendEventContent, SecretSend);
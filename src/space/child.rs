@@ -0,0 +1,105 @@
+//! Types for the *m.space.child* event.
+
+use ruma_identifiers::RoomId;
+
+use ContentBuildError;
+
+state_event! {
+    /// The admins of a space can advertise rooms and subspaces for their space by setting this
+    /// event. The state key is the ID of the child room.
+    pub struct ChildEvent(ChildEventContent) {}
+}
+
+/// The payload of a `ChildEvent`.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ChildEventContent {
+    /// Servers to try to join the child room through. The child is not part of the space unless
+    /// this list is present and non-empty.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub via: Option<Vec<String>>,
+    /// A string used to order the children of the space lexicographically.
+    ///
+    /// It must consist of at most 50 characters in the range `\x20` to `\x7E`. Clients ignore
+    /// invalid orders, see `valid_order`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub order: Option<String>,
+    /// Whether the space admins suggest that users join the child room.
+    #[serde(default, skip_serializing_if = "::std::ops::Not::not")]
+    pub suggested: bool,
+    /// Fields that are not covered by the Matrix specification.
+    #[cfg(feature = "preserve-extra-fields")]
+    #[serde(flatten)]
+    pub extra: ::std::collections::BTreeMap<String, ::serde_json::Value>,
+}
+
+impl_event_content!(ChildEventContent, SpaceChild);
+impl_static_state_event_content!(ChildEventContent, RoomId);
+impl_room_event_content!(state, ChildEventContent);
+
+impl ChildEventContent {
+    /// Creates the content of a child event with the servers to join the child room through.
+    pub fn new(via: Vec<String>) -> Self {
+        ChildEventContent {
+            via: Some(via),
+            order: None,
+            suggested: false,
+            #[cfg(feature = "preserve-extra-fields")]
+            extra: Default::default(),
+        }
+    }
+
+    /// Sets the order of the child, checking that it is valid.
+    pub fn with_order(mut self, order: String) -> Result<Self, ContentBuildError> {
+        if !is_valid_order(&order) {
+            return Err(ContentBuildError::InvalidSpaceOrder(order));
+        }
+
+        self.order = Some(order);
+
+        Ok(self)
+    }
+
+    /// The order of the child, if it is present and valid.
+    pub fn valid_order(&self) -> Option<&str> {
+        self.order.as_deref().filter(|order| is_valid_order(order))
+    }
+
+    /// Whether the child is part of the space, which requires a non-empty `via`.
+    pub fn is_active(&self) -> bool {
+        self.via.as_ref().is_some_and(|via| !via.is_empty())
+    }
+}
+
+/// Whether an order consists of at most 50 printable ASCII characters.
+fn is_valid_order(order: &str) -> bool {
+    order.len() <= 50 && order.bytes().all(|byte| (0x20..=0x7E).contains(&byte))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::from_str;
+
+    use ContentBuildError;
+    use super::ChildEventContent;
+
+    #[test]
+    fn order_validation() {
+        let content = ChildEventContent::new(vec!["example.com".to_string()]);
+
+        assert!(content.is_active());
+        assert_eq!(
+            content.clone().with_order("a".repeat(51)).unwrap_err(),
+            ContentBuildError::InvalidSpaceOrder("a".repeat(51))
+        );
+        assert_eq!(
+            content.with_order("lobby".to_string()).unwrap().valid_order(),
+            Some("lobby")
+        );
+
+        let content: ChildEventContent = from_str(r#"{"order":"\n","via":[]}"#).unwrap();
+
+        assert!(!content.is_active());
+        assert!(!content.suggested);
+        assert_eq!(content.valid_order(), None);
+    }
+}
@@ -0,0 +1,73 @@
+//! Types for the *m.space.parent* event.
+
+use ruma_identifiers::RoomId;
+
+state_event! {
+    /// Rooms can claim parents via this event. The state key is the ID of the parent space.
+    pub struct ParentEvent(ParentEventContent) {}
+}
+
+/// The payload of a `ParentEvent`.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ParentEventContent {
+    /// Servers to try to join the parent space through. The parent is not valid unless this list
+    /// is present and non-empty.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub via: Option<Vec<String>>,
+    /// Whether this is the main parent of the room.
+    #[serde(default, skip_serializing_if = "::std::ops::Not::not")]
+    pub canonical: bool,
+    /// Fields that are not covered by the Matrix specification.
+    #[cfg(feature = "preserve-extra-fields")]
+    #[serde(flatten)]
+    pub extra: ::std::collections::BTreeMap<String, ::serde_json::Value>,
+}
+
+impl_event_content!(ParentEventContent, SpaceParent);
+impl_static_state_event_content!(ParentEventContent, RoomId);
+impl_room_event_content!(state, ParentEventContent);
+
+impl ParentEventContent {
+    /// Creates the content of a parent event with the servers to join the parent space through.
+    pub fn new(via: Vec<String>) -> Self {
+        ParentEventContent {
+            via: Some(via),
+            canonical: false,
+            #[cfg(feature = "preserve-extra-fields")]
+            extra: Default::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{Value, from_str, to_value};
+
+    use collections::all::StateEvent;
+
+    #[test]
+    fn parent_round_trip() {
+        let json = r#"{
+            "content": {"canonical": true, "via": ["example.com"]},
+            "event_id": "$h29iv0s8:example.com",
+            "origin_server_ts": 1,
+            "room_id": "!n8f893n9:example.com",
+            "sender": "@carl:example.com",
+            "state_key": "!space:example.com",
+            "type": "m.space.parent"
+        }"#;
+
+        let event = from_str::<StateEvent>(json).unwrap();
+
+        match event {
+            StateEvent::SpaceParent(ref event) => {
+                assert!(event.content.canonical);
+                assert_eq!(event.content.via, Some(vec!["example.com".to_string()]));
+                assert_eq!(event.state_key.to_string(), "!space:example.com");
+            }
+            _ => unreachable!(),
+        }
+
+        assert_eq!(to_value(&event).unwrap(), from_str::<Value>(json).unwrap());
+    }
+}
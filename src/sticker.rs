@@ -0,0 +1,58 @@
+//! Types for the *m.sticker* event.
+
+use room::{ImageInfo, MediaSource};
+
+room_event! {
+    /// A sticker message, i.e. an image displayed at a fixed size without a caption.
+    pub struct StickerEvent(StickerEventContent) {}
+}
+
+/// The payload of a `StickerEvent`.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct StickerEventContent {
+    /// A textual representation or associated description of the sticker image.
+    pub body: String,
+    /// Metadata about the image referred to in `source`, including its thumbnail.
+    pub info: ImageInfo,
+    /// The location of the sticker image, serialized as either `url` or `file`.
+    #[serde(flatten)]
+    pub source: MediaSource,
+    /// Fields that are not covered by the Matrix specification.
+    #[cfg(feature = "preserve-extra-fields")]
+    #[serde(flatten)]
+    pub extra: ::std::collections::BTreeMap<String, ::serde_json::Value>,
+}
+
+impl_event_content!(StickerEventContent, Sticker);
+impl_room_event_content!(message_like, StickerEventContent);
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{Value, from_str, to_string};
+
+    use room::MediaSource;
+    use super::StickerEventContent;
+
+    #[test]
+    fn encrypted_sticker() {
+        let json = concat!(
+            r#"{"body":"Hello","file":{"#,
+            r#""hashes":{"sha256":"aWOHudBnDkJ9IwaR1Nd8XKoI7DOrqDTwt6xDPfVGN6Q"},"#,
+            r#""iv":"S22dq3NAX8wAAAAAAAAAAA","key":{"alg":"A256CTR","ext":true,"#,
+            r#""k":"aWF6-32KGYaC3A_FEUCk1Bt0JA37zP0wrStgmdCaW-0","key_ops":["encrypt","decrypt"],"#,
+            r#""kty":"oct"},"url":"mxc://example.org/sticker","v":"v2"},"info":{"h":200,"w":200}}"#
+        );
+        let content = from_str::<StickerEventContent>(json).unwrap();
+
+        match content.source {
+            MediaSource::Encrypted(ref file) => {
+                assert_eq!(file.url.as_str(), "mxc://example.org/sticker")
+            }
+            MediaSource::Plain(_) => panic!("expected an encrypted sticker"),
+        }
+        assert_eq!(
+            from_str::<Value>(&to_string(&content).unwrap()).unwrap(),
+            from_str::<Value>(json).unwrap()
+        );
+    }
+}
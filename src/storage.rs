@@ -0,0 +1,184 @@
+//! A versioned envelope for persisting events.
+//!
+//! Events stored with `StoredEvent` carry the schema version of the crate that stored them. When a
+//! future release changes how an event type is represented, it increments `SCHEMA_VERSION` and
+//! ships a `Migration` that upgrades events stored by the previous version, so that stored data
+//! keeps loading.
+
+use std::error::Error;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Error as JsonError, Value, from_value, to_value};
+
+/// The schema version written by this version of the crate.
+pub const SCHEMA_VERSION: u64 = 1;
+
+/// An event together with the schema version it was stored with.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct StoredEvent<E> {
+    /// The event.
+    pub event: E,
+    /// The schema version the event was stored with.
+    pub schema_version: u64,
+}
+
+/// A transformation of a stored event from one schema version to the next.
+pub trait Migration {
+    /// The schema version this migration upgrades from. It upgrades to the version after it.
+    fn source_version(&self) -> u64;
+
+    /// Transforms the JSON of an event stored with `source_version` into the JSON of the next
+    /// version.
+    fn migrate(&self, event: Value) -> Result<Value, StorageError>;
+}
+
+/// An error when loading a stored event.
+#[derive(Debug)]
+pub enum StorageError {
+    /// The stored data or the event in it could not be deserialized.
+    Json(JsonError),
+    /// No migration was given for upgrading events from this schema version.
+    MissingMigration(u64),
+    /// The event was stored by a newer version of the crate.
+    UnsupportedVersion(u64),
+}
+
+impl<E> StoredEvent<E> {
+    /// Wraps an event in an envelope with the current schema version.
+    pub fn new(event: E) -> Self {
+        StoredEvent {
+            event,
+            schema_version: SCHEMA_VERSION,
+        }
+    }
+}
+
+impl<E> StoredEvent<E> where E: Serialize {
+    /// Converts the envelope into JSON ready for storage.
+    pub fn to_value(&self) -> Result<Value, StorageError> {
+        to_value(self).map_err(StorageError::Json)
+    }
+}
+
+impl<E> StoredEvent<E> where E: for<'de> Deserialize<'de> {
+    /// Loads a stored envelope, upgrading the event to the current schema version with the given
+    /// migrations first if it was stored with an older one.
+    pub fn from_value(stored: Value, migrations: &[&dyn Migration])
+    -> Result<Self, StorageError> {
+        #[derive(Deserialize)]
+        struct RawStoredEvent {
+            event: Value,
+            schema_version: u64,
+        }
+
+        let RawStoredEvent { mut event, mut schema_version } =
+            from_value(stored).map_err(StorageError::Json)?;
+
+        if schema_version > SCHEMA_VERSION {
+            return Err(StorageError::UnsupportedVersion(schema_version));
+        }
+
+        while schema_version < SCHEMA_VERSION {
+            let migration = migrations.iter()
+                .find(|migration| migration.source_version() == schema_version)
+                .ok_or(StorageError::MissingMigration(schema_version))?;
+
+            event = migration.migrate(event)?;
+            schema_version += 1;
+        }
+
+        Ok(StoredEvent {
+            event: from_value(event).map_err(StorageError::Json)?,
+            schema_version,
+        })
+    }
+}
+
+impl Display for StorageError {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match *self {
+            StorageError::Json(ref error) => write!(f, "invalid stored event: {}", error),
+            StorageError::MissingMigration(version) => {
+                write!(f, "no migration from schema version {}", version)
+            }
+            StorageError::UnsupportedVersion(version) => {
+                write!(f, "unsupported schema version {}", version)
+            }
+        }
+    }
+}
+
+impl Error for StorageError {}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{Map, Value, from_str, json};
+
+    use collections::all::Event;
+    use super::{Migration, StorageError, StoredEvent};
+
+    /// Upgrades tag events from a schema version 0 that stored them as `{"tag_list": [...]}`.
+    struct TagListMigration;
+
+    impl Migration for TagListMigration {
+        fn source_version(&self) -> u64 {
+            0
+        }
+
+        fn migrate(&self, mut event: Value) -> Result<Value, StorageError> {
+            let tags: Map<String, Value> = event["content"]["tag_list"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(Value::as_str)
+                .map(|tag| (tag.to_string(), json!({})))
+                .collect();
+
+            event["content"] = json!({ "tags": tags });
+
+            Ok(event)
+        }
+    }
+
+    #[test]
+    fn stored_events_round_trip() {
+        let event = from_str::<Event>(r#"{"content":{"tags":{}},"type":"m.tag"}"#).unwrap();
+        let stored = StoredEvent::new(event).to_value().unwrap();
+
+        assert_eq!(stored["schema_version"], 1);
+        assert!(StoredEvent::<Event>::from_value(stored, &[]).is_ok());
+    }
+
+    #[test]
+    fn newer_schema_versions_are_rejected() {
+        let stored = from_str::<Value>(
+            r#"{"event":{"content":{"tags":{}},"type":"m.tag"},"schema_version":2}"#
+        ).unwrap();
+
+        match StoredEvent::<Event>::from_value(stored, &[]) {
+            Err(StorageError::UnsupportedVersion(2)) => {}
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn older_schema_versions_are_migrated() {
+        let stored = from_str::<Value>(
+            r#"{"event":{"content":{"tag_list":["u.work"]},"type":"m.tag"},"schema_version":0}"#
+        ).unwrap();
+
+        match StoredEvent::<Event>::from_value(stored.clone(), &[]) {
+            Err(StorageError::MissingMigration(0)) => {}
+            _ => unreachable!(),
+        }
+
+        let loaded = StoredEvent::<Event>::from_value(stored, &[&TagListMigration]).unwrap();
+        assert_eq!(loaded.schema_version, 1);
+
+        match loaded.event {
+            Event::Tag(ref event) => assert!(event.content.tags.contains_key("u.work")),
+            _ => unreachable!(),
+        }
+    }
+}
@@ -7,17 +7,21 @@
 
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use serde::de::Error;
-use serde_json::{Value, from_value};
+use ruma_identifiers::{RoomAliasId, UserId};
+use serde_json::{Error as JsonError, Value, from_value};
 
-use EventType;
+use {EventType, StaticStateEventContent};
+use collections::deserialize_limited_value;
+use mxc_uri::MxcUri;
 use room::aliases::AliasesEventContent;
 use room::avatar::AvatarEventContent;
 use room::canonical_alias::CanonicalAliasEventContent;
 use room::create::CreateEventContent;
+use room::encryption::{Algorithm, EncryptionEventContent};
 use room::guest_access::GuestAccessEventContent;
 use room::history_visibility::HistoryVisibilityEventContent;
-use room::join_rules::JoinRulesEventContent;
-use room::member::MemberEventContent;
+use room::join_rules::{JoinRule, JoinRulesEventContent};
+use room::member::{MemberEventContent, MembershipState};
 use room::name::NameEventContent;
 use room::power_levels::PowerLevelsEventContent;
 use room::third_party_invite::ThirdPartyInviteEventContent;
@@ -38,6 +42,9 @@ pub enum StrippedState {
     /// A striped-down version of the *m.room.create* event.
     RoomCreate(StrippedRoomCreate),
 
+    /// A stripped-down version of the *m.room.encryption* event.
+    RoomEncryption(StrippedRoomEncryption),
+
     /// A stripped-down version of the *m.room.guest_access* event.
     RoomGuestAccess(StrippedRoomGuestAccess),
 
@@ -64,6 +71,9 @@ pub enum StrippedState {
 }
 
 /// A "stripped-down" version of a core state event.
+///
+/// With `Value` as the content, this can hold state events of types that `StrippedState` does not
+/// cover, and lets a few event types be inspected without matching on `StrippedState`.
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct StrippedStateContent<C> {
     /// Data specific to the event type.
@@ -71,10 +81,124 @@ pub struct StrippedStateContent<C> {
     /// The type of the event.
     #[serde(rename="type")]
     pub event_type: EventType,
+    /// The user who sent the event.
+    ///
+    /// Servers include this since version 1.1 of the specification, older servers may omit it.
+    #[serde(skip_serializing_if="Option::is_none")]
+    pub sender: Option<UserId>,
     /// A key that determines which piece of room state the event represents.
     pub state_key: String,
 }
 
+impl<C> StrippedStateContent<C> where C: StaticStateEventContent {
+    /// Creates a stripped-down state event from its content and a state key of the type that
+    /// events with this content require, without a sender.
+    pub fn new(content: C, state_key: &C::StateKey) -> Self {
+        StrippedStateContent {
+            content,
+            event_type: C::event_type(),
+            sender: None,
+            state_key: state_key.to_string(),
+        }
+    }
+}
+
+impl StrippedState {
+    /// Whether `StrippedState` has a variant for events of the given type.
+    pub fn covers(event_type: &EventType) -> bool {
+        matches!(
+            *event_type,
+            EventType::RoomAliases
+                | EventType::RoomAvatar
+                | EventType::RoomCanonicalAlias
+                | EventType::RoomCreate
+                | EventType::RoomEncryption
+                | EventType::RoomGuestAccess
+                | EventType::RoomHistoryVisibility
+                | EventType::RoomJoinRules
+                | EventType::RoomMember
+                | EventType::RoomName
+                | EventType::RoomPowerLevels
+                | EventType::RoomThirdPartyInvite
+                | EventType::RoomTopic
+        )
+    }
+}
+
+impl StrippedStateContent<Value> {
+    /// Deserializes the content of the event into the content type of a specific event type.
+    ///
+    /// This does not check that the event is of the type the content belongs to.
+    pub fn deserialize_content<C>(&self) -> Result<C, JsonError>
+    where C: for<'de> Deserialize<'de> {
+        C::deserialize(&self.content)
+    }
+}
+
+/// A summary of a room assembled from stripped state, as shown to users invited to or knocking on
+/// a room they cannot see yet.
+#[derive(Clone, Debug, Default)]
+pub struct RoomPreview {
+    /// The canonical alias of the room.
+    pub alias: Option<RoomAliasId>,
+    /// The URL of the room's avatar image.
+    pub avatar_url: Option<MxcUri>,
+    /// The encryption algorithm of the room, if encryption is enabled.
+    pub encryption: Option<Algorithm>,
+    /// The join rule of the room.
+    pub join_rule: Option<JoinRule>,
+    /// The number of joined members among the stripped member events.
+    ///
+    /// Servers usually only include a few member events, so this is a lower bound rather than the
+    /// actual member count of the room.
+    pub joined_member_count_hint: u64,
+    /// The name of the room.
+    pub name: Option<String>,
+    /// The topic of the room.
+    pub topic: Option<String>,
+}
+
+impl RoomPreview {
+    /// Assembles a preview from the stripped state of a room.
+    ///
+    /// If the same piece of state appears more than once, the last occurrence wins.
+    pub fn from_stripped_state(state: &[StrippedState]) -> Self {
+        let mut preview = RoomPreview::default();
+
+        for event in state {
+            match *event {
+                StrippedState::RoomAvatar(ref event) => {
+                    preview.avatar_url = event.content.source
+                        .as_ref()
+                        .map(|source| source.url().clone());
+                }
+                StrippedState::RoomCanonicalAlias(ref event) => {
+                    preview.alias = Some(event.content.alias.clone());
+                }
+                StrippedState::RoomEncryption(ref event) => {
+                    preview.encryption = Some(event.content.algorithm.clone());
+                }
+                StrippedState::RoomJoinRules(ref event) => {
+                    preview.join_rule = Some(event.content.join_rule);
+                }
+                StrippedState::RoomMember(ref event)
+                    if event.content.membership == MembershipState::Join => {
+                    preview.joined_member_count_hint += 1;
+                }
+                StrippedState::RoomName(ref event) => {
+                    preview.name = Some(event.content.name.clone());
+                }
+                StrippedState::RoomTopic(ref event) => {
+                    preview.topic = Some(event.content.topic.clone());
+                }
+                _ => {}
+            }
+        }
+
+        preview
+    }
+}
+
 impl Serialize for StrippedState {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
         match *self {
@@ -82,6 +206,7 @@ impl Serialize for StrippedState {
             StrippedState::RoomAvatar(ref event) => event.serialize(serializer),
             StrippedState::RoomCanonicalAlias(ref event) => event.serialize(serializer),
             StrippedState::RoomCreate(ref event) => event.serialize(serializer),
+            StrippedState::RoomEncryption(ref event) => event.serialize(serializer),
             StrippedState::RoomGuestAccess(ref event) => event.serialize(serializer),
             StrippedState::RoomHistoryVisibility(ref event) => event.serialize(serializer),
             StrippedState::RoomJoinRules(ref event) => event.serialize(serializer),
@@ -95,15 +220,16 @@ impl Serialize for StrippedState {
 }
 
 impl<'de> Deserialize<'de> for StrippedState {
+    #[allow(clippy::needless_return)]
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where D: Deserializer<'de> {
-        let value: Value = Deserialize::deserialize(deserializer)?;
+        let value = deserialize_limited_value(deserializer)?;
 
         let event_type_value = match value.get("type") {
-            Some(value) => value.clone(),
+            Some(value) => value,
             None => return Err(D::Error::missing_field("type")),
         };
 
-        let event_type = match from_value::<EventType>(event_type_value.clone()) {
+        let event_type = match EventType::deserialize(event_type_value) {
             Ok(event_type) => event_type,
             Err(error) => return Err(D::Error::custom(error.to_string())),
         };
@@ -141,6 +267,14 @@ impl<'de> Deserialize<'de> for StrippedState {
 
                 Ok(StrippedState::RoomCreate(event))
             },
+            EventType::RoomEncryption => {
+                let event = match from_value::<StrippedRoomEncryption>(value) {
+                    Ok(event) => event,
+                    Err(error) => return Err(D::Error::custom(error.to_string())),
+                };
+
+                Ok(StrippedState::RoomEncryption(event))
+            },
             EventType::RoomGuestAccess => {
                 let event = match from_value::<StrippedRoomGuestAccess>(value) {
                     Ok(event) => event,
@@ -224,6 +358,9 @@ pub type StrippedRoomCanonicalAlias = StrippedStateContent<CanonicalAliasEventCo
 /// A stripped-down version of the *m.room.create* event.
 pub type StrippedRoomCreate = StrippedStateContent<CreateEventContent>;
 
+/// A stripped-down version of the *m.room.encryption* event.
+pub type StrippedRoomEncryption = StrippedStateContent<EncryptionEventContent>;
+
 /// A stripped-down version of the *m.room.guest_access* event.
 pub type StrippedRoomGuestAccess = StrippedStateContent<GuestAccessEventContent>;
 
@@ -250,18 +387,26 @@ pub type StrippedRoomTopic = StrippedStateContent<TopicEventContent>;
 
 #[cfg(test)]
 mod tests {
-    use EventType;
+    use {EmptyStateKey, EventType};
+    use int::UInt;
+    use room::encryption::Algorithm;
     use room::join_rules::JoinRule;
     use room::topic::TopicEventContent;
-    use serde_json::{from_str, to_string};
-    use super::{StrippedRoomTopic, StrippedState};
+    use serde_json::{Value, from_str, to_string};
+    use room::name::NameEventContent;
+    use super::{RoomPreview, StrippedRoomTopic, StrippedState, StrippedStateContent};
 
     #[test]
     fn serialize_stripped_state_event() {
         let content = StrippedRoomTopic {
-            content: TopicEventContent { topic: "Testing room".to_string() },
+            content: TopicEventContent {
+                topic: "Testing room".to_string(),
+                #[cfg(feature = "preserve-extra-fields")]
+                extra: Default::default(),
+            },
             state_key: "".to_string(),
-            event_type: EventType::RoomTopic
+            event_type: EventType::RoomTopic,
+            sender: None,
         };
 
         let event = StrippedState::RoomTopic(content);
@@ -273,9 +418,62 @@ mod tests {
     }
 
     #[test]
+    fn serialize_stripped_state_event_with_sender() {
+        let mut event = StrippedRoomTopic::new(
+            TopicEventContent {
+                topic: "Testing room".to_string(),
+                #[cfg(feature = "preserve-extra-fields")]
+                extra: Default::default(),
+            },
+            &EmptyStateKey,
+        );
+        event.sender = Some(from_str(r#""@carl:example.com""#).unwrap());
+
+        let json = concat!(
+            r#"{"content":{"topic":"Testing room"},"type":"m.room.topic","#,
+            r#""sender":"@carl:example.com","state_key":""}"#
+        );
+
+        assert_eq!(to_string(&event).unwrap(), json);
+        assert_eq!(
+            from_str::<StrippedRoomTopic>(json).unwrap().sender.unwrap().to_string(),
+            "@carl:example.com"
+        );
+    }
+
+    #[test]
+    fn deserialize_stripped_state_event_without_sender() {
+        let event = from_str::<StrippedRoomTopic>(
+            r#"{"content":{"topic":"Testing room"},"state_key":"","type":"m.room.topic"}"#
+        ).unwrap();
+
+        assert!(event.sender.is_none());
+        assert!(from_str::<StrippedRoomTopic>(concat!(
+            r#"{"content":{"topic":"Testing room"},"sender":"carl","#,
+            r#""state_key":"","type":"m.room.topic"}"#
+        )).is_err());
+    }
+
+    #[test]
+    fn new_stripped_state_event() {
+        let content = TopicEventContent {
+            topic: "Testing room".to_string(),
+            #[cfg(feature = "preserve-extra-fields")]
+            extra: Default::default(),
+        };
+
+        let event = StrippedRoomTopic::new(content, &EmptyStateKey);
+
+        assert_eq!(event.event_type, EventType::RoomTopic);
+        assert_eq!(event.state_key, "");
+    }
+
+    #[test]
+    #[allow(clippy::assertions_on_constants)]
     fn deserialize_stripped_state_events() {
         let name_event = r#"{
             "type": "m.room.name",
+            "sender": "@carl:example.com",
             "state_key": "",
             "content": {"name": "Ruma"}
         }"#;
@@ -291,25 +489,26 @@ mod tests {
             "state_key": "",
             "content": {
                 "info": {
-                    "height": 128,
-                    "width": 128,
+                    "h": 128,
+                    "w": 128,
                     "mimetype": "image/jpeg",
-                    "size": 1024
+                    "size": 1024,
+                    "thumbnail_info": {
+                        "h": 16,
+                        "w": 16,
+                        "mimetype": "image/jpeg",
+                        "size": 32
+                    },
+                    "thumbnail_url": "mxc://domain.com/image-thumbnail"
                 },
-                "thumbnail_info": {
-                    "height": 16,
-                    "width": 16,
-                    "mimetype": "image/jpeg",
-                    "size": 32
-                },
-                "thumbnail_url": "https://domain.com/image-thumbnail.jpg",
-                "url": "https://domain.com/image.jpg"
+                "url": "mxc://domain.com/image"
             }
         }"#;
 
         match from_str::<StrippedState>(name_event).unwrap() {
             StrippedState::RoomName(event) => {
                 assert_eq!(event.content.name, "Ruma");
+                assert_eq!(event.sender.unwrap().to_string(), "@carl:example.com");
                 assert_eq!(event.event_type, EventType::RoomName);
                 assert_eq!(event.state_key, "");
             },
@@ -331,12 +530,13 @@ mod tests {
 
         match from_str::<StrippedState>(avatar_event).unwrap() {
             StrippedState::RoomAvatar(event) => {
-                assert_eq!(event.content.info.height, 128);
-                assert_eq!(event.content.info.width, 128);
-                assert_eq!(event.content.info.mimetype, "image/jpeg");
-                assert_eq!(event.content.info.size, 1024);
-                assert_eq!(event.content.thumbnail_info.size, 32);
-                assert_eq!(event.content.url, "https://domain.com/image.jpg");
+                let info = event.content.info.unwrap();
+                assert_eq!(info.h, Some(UInt::from(128u32)));
+                assert_eq!(info.w, Some(UInt::from(128u32)));
+                assert_eq!(info.mimetype.as_deref(), Some("image/jpeg"));
+                assert_eq!(info.size, Some(UInt::from(1024u32)));
+                assert_eq!(info.thumbnail_info.and_then(|info| info.size), Some(UInt::from(32u32)));
+                assert_eq!(event.content.source.unwrap().url().as_str(), "mxc://domain.com/image");
                 assert_eq!(event.event_type, EventType::RoomAvatar);
                 assert_eq!(event.state_key, "");
             },
@@ -345,4 +545,39 @@ mod tests {
             }
         };
     }
+
+    #[test]
+    fn stripped_state_event_content_probing() {
+        let event = from_str::<StrippedStateContent<Value>>(
+            r#"{"content":{"name":"Ruma"},"state_key":"","type":"m.room.name"}"#
+        ).unwrap();
+
+        assert_eq!(event.deserialize_content::<NameEventContent>().unwrap().name, "Ruma");
+        assert!(event.deserialize_content::<TopicEventContent>().is_err());
+    }
+
+    #[test]
+    fn room_preview_from_stripped_state() {
+        let state = from_str::<Vec<StrippedState>>(r#"[
+            {"content":{"name":"Old"},"state_key":"","type":"m.room.name"},
+            {"content":{"name":"Ruma"},"state_key":"","type":"m.room.name"},
+            {"content":{"join_rule":"invite"},"state_key":"","type":"m.room.join_rules"},
+            {
+                "content":{"algorithm":"m.megolm.v1.aes-sha2"},
+                "state_key":"",
+                "type":"m.room.encryption"
+            },
+            {"content":{"membership":"join"},"state_key":"@a:example.com","type":"m.room.member"},
+            {"content":{"membership":"invite"},"state_key":"@b:example.com","type":"m.room.member"}
+        ]"#).unwrap();
+
+        let preview = RoomPreview::from_stripped_state(&state);
+
+        assert_eq!(preview.name, Some("Ruma".to_string()));
+        assert_eq!(preview.join_rule, Some(JoinRule::Invite));
+        assert_eq!(preview.encryption, Some(Algorithm::MegolmV1AesSha2));
+        assert_eq!(preview.joined_member_count_hint, 1);
+        assert!(preview.alias.is_none());
+        assert!(preview.topic.is_none());
+    }
 }
@@ -1,23 +1,336 @@
 //! Types for the *m.tag* event.
 
-use std::collections::HashMap;
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BTreeMap, HashMap};
+
+use ruma_identifiers::RoomId;
+use serde::{Deserialize, Deserializer};
+use serde::de::Error as DeError;
+use serde::ser::Error as SerError;
+use serde_json::{Error as JsonError, Value, to_value};
+
+use ContentBuildError;
+use int::UInt;
 
 event! {
     /// Informs the client of tags on a room.
-    pub struct TagEvent(TagEventContent) {}
+    ///
+    /// This event appears in the user's room account data for the room the tags apply to.
+    pub struct TagEvent(TagEventContent) {
+        /// The unique identifier for the room associated with this event.
+        ///
+        /// Absent when the event is part of the account data of a room in a sync response.
+        #[serde(skip_serializing_if="Option::is_none")]
+        pub room_id: Option<RoomId>
+    }
+}
+
+impl TagEvent {
+    /// Attaches the ID of the room the event belongs to, such as the room whose account data in
+    /// a sync response contains the event.
+    pub fn into_full_event(self, room_id: RoomId) -> TagEvent {
+        TagEvent {
+            room_id: Some(room_id),
+            ..self
+        }
+    }
 }
 
 /// The payload of a `TagEvent`.
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
 pub struct TagEventContent {
     /// A map of tag names to tag info.
     pub tags: HashMap<String, TagInfo>,
+    /// Fields that are not covered by the Matrix specification.
+    #[cfg(feature = "preserve-extra-fields")]
+    #[serde(flatten)]
+    pub extra: ::std::collections::BTreeMap<String, ::serde_json::Value>,
 }
 
+impl_event_content!(TagEventContent, Tag);
+
 /// Information about a tag.
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
 pub struct TagInfo {
-    /// Value to use for lexicographically ordering rooms with this tag.
-    #[serde(skip_serializing_if="Option::is_none")]
-    pub order: Option<String>,
+    /// Value between 0 and 1 to use for ordering rooms with this tag.
+    ///
+    /// Some clients send the order as a string, which is accepted when deserializing.
+    #[serde(default, deserialize_with="deserialize_order", skip_serializing_if="Option::is_none")]
+    pub order: Option<f64>,
+}
+
+impl TagInfo {
+    /// Creates tag information with the given order, checking that it is between 0 and 1.
+    pub fn with_order(order: f64) -> Result<Self, ContentBuildError> {
+        if (0.0..=1.0).contains(&order) {
+            Ok(TagInfo { order: Some(order) })
+        } else {
+            Err(ContentBuildError::TagOrderOutOfRange(order))
+        }
+    }
+}
+
+/// The flavour of JSON to produce when serializing tags.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum JsonFormat {
+    /// The JSON used in the client-server API, where tag orders are serialized as floats.
+    #[default]
+    Client,
+
+    /// Canonical JSON, as used for signing and federation.
+    ///
+    /// Canonical JSON does not allow floats, so serializing a tag whose order is not an integer
+    /// fails.
+    Canonical,
+}
+
+/// The differences between two versions of a room's tags.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TagChanges {
+    /// Tags that are only present in the newer version, in lexicographical order.
+    pub added: Vec<String>,
+    /// Tags that are present in both versions but whose info differs, in lexicographical order.
+    pub changed: Vec<String>,
+    /// Tags that are only present in the older version, in lexicographical order.
+    pub removed: Vec<String>,
+}
+
+impl TagEventContent {
+    /// Computes which tags were added, changed, or removed since `previous`.
+    pub fn changes_since(&self, previous: &TagEventContent) -> TagChanges {
+        let mut changes = TagChanges::default();
+
+        for (tag, info) in &self.tags {
+            match previous.tags.get(tag) {
+                Some(previous_info) if previous_info == info => {}
+                Some(_) => changes.changed.push(tag.clone()),
+                None => changes.added.push(tag.clone()),
+            }
+        }
+
+        for tag in previous.tags.keys() {
+            if !self.tags.contains_key(tag) {
+                changes.removed.push(tag.clone());
+            }
+        }
+
+        changes.added.sort();
+        changes.changed.sort();
+        changes.removed.sort();
+
+        changes
+    }
+
+    /// Serializes the content into a JSON value of the given format.
+    ///
+    /// Serializing with `JsonFormat::Client` is the same as using the `Serialize` implementation.
+    pub fn to_value_with_format(&self, format: JsonFormat) -> Result<Value, JsonError> {
+        let mut value = to_value(self)?;
+
+        if format == JsonFormat::Canonical {
+            for (tag, info) in &self.tags {
+                let order = match info.order {
+                    Some(order) => order,
+                    None => continue,
+                };
+
+                if order.fract() != 0.0 || order.abs() > MAX_CANONICAL_INTEGER {
+                    return Err(SerError::custom(
+                        format!("order {} of tag {} is not allowed in canonical JSON", order, tag)
+                    ));
+                }
+
+                value["tags"][tag]["order"] = Value::from(order as i64);
+            }
+        }
+
+        Ok(value)
+    }
+}
+
+/// A room with its tags, to be placed in a room list by `sort_rooms_by_tag`.
+#[derive(Clone, Copy, Debug)]
+pub struct TaggedRoom<'a> {
+    /// The ID of the room.
+    pub room_id: &'a RoomId,
+    /// The content of the room's *m.tag* event.
+    pub tags: &'a TagEventContent,
+    /// The timestamp in milliseconds of the latest activity in the room, such as the
+    /// `origin_server_ts` of its latest event.
+    pub last_activity: UInt,
+}
+
+/// Groups rooms by tag and sorts the rooms of each tag for display in a room list.
+///
+/// Rooms with an `order` come first, in ascending order. Rooms without one, and rooms with the same
+/// order, follow with the most recently active first. Remaining ties are broken by room ID, so the
+/// result is stable regardless of the order of `rooms`.
+pub fn sort_rooms_by_tag<'a, I>(rooms: I) -> BTreeMap<String, Vec<RoomId>>
+where I: IntoIterator<Item = TaggedRoom<'a>> {
+    let mut by_tag = BTreeMap::<String, Vec<(Option<f64>, TaggedRoom<'a>)>>::new();
+
+    for room in rooms {
+        for (tag, info) in &room.tags.tags {
+            by_tag.entry(tag.clone()).or_default().push((info.order, room));
+        }
+    }
+
+    by_tag
+        .into_iter()
+        .map(|(tag, mut rooms)| {
+            rooms.sort_by(|(a_order, a), (b_order, b)| {
+                compare_orders(*a_order, *b_order)
+                    .then_with(|| Reverse(a.last_activity).cmp(&Reverse(b.last_activity)))
+                    .then_with(|| a.room_id.to_string().cmp(&b.room_id.to_string()))
+            });
+
+            (tag, rooms.into_iter().map(|(_, room)| room.room_id.clone()).collect())
+        })
+        .collect()
+}
+
+/// Compares tag orders, placing rooms without an order last.
+fn compare_orders(a: Option<f64>, b: Option<f64>) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) => a.total_cmp(&b),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+/// The largest integer that canonical JSON can represent.
+const MAX_CANONICAL_INTEGER: f64 = 9_007_199_254_740_991.0;
+
+/// Deserializes a tag order given either as a number or as a string containing a finite number.
+fn deserialize_order<'de, D>(deserializer: D) -> Result<Option<f64>, D::Error>
+where D: Deserializer<'de> {
+    match Option::<Value>::deserialize(deserializer)? {
+        None | Some(Value::Null) => Ok(None),
+        Some(Value::Number(number)) => Ok(number.as_f64()),
+        Some(Value::String(string)) => match string.parse::<f64>() {
+            Ok(order) if order.is_finite() => Ok(Some(order)),
+            Ok(order) => Err(DeError::custom(format!("invalid tag order: {}", order))),
+            Err(error) => Err(DeError::custom(error)),
+        },
+        Some(other) => Err(DeError::custom(format!("invalid tag order: {}", other))),
+    }
+}
+
+impl TagChanges {
+    /// Whether there are no differences at all.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.changed.is_empty() && self.removed.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use ruma_identifiers::RoomId;
+    use serde_json::{from_str, to_string};
+
+    use ContentBuildError;
+    use int::UInt;
+    use super::{JsonFormat, TagEvent, TagEventContent, TagInfo, TaggedRoom, sort_rooms_by_tag};
+
+    #[test]
+    fn tag_changes() {
+        let previous = from_str::<TagEventContent>(
+            r#"{"tags":{"m.favourite":{"order":"0.1"},"u.work":{},"u.old":{}}}"#
+        ).unwrap();
+        let current = from_str::<TagEventContent>(
+            r#"{"tags":{"m.favourite":{"order":"0.2"},"u.work":{},"m.lowpriority":{}}}"#
+        ).unwrap();
+
+        let changes = current.changes_since(&previous);
+
+        assert_eq!(changes.added, vec!["m.lowpriority".to_string()]);
+        assert_eq!(changes.changed, vec!["m.favourite".to_string()]);
+        assert_eq!(changes.removed, vec!["u.old".to_string()]);
+        assert!(current.changes_since(&current).is_empty());
+    }
+
+    #[test]
+    fn tag_order_formats() {
+        let content = from_str::<TagEventContent>(
+            r#"{"tags":{"m.favourite":{"order":0.5}}}"#
+        ).unwrap();
+        let client = content.to_value_with_format(JsonFormat::Client).unwrap();
+
+        assert_eq!(to_string(&client).unwrap(), r#"{"tags":{"m.favourite":{"order":0.5}}}"#);
+        assert!(content.to_value_with_format(JsonFormat::Canonical).is_err());
+
+        let content = from_str::<TagEventContent>(r#"{"tags":{"u.work":{"order":"1"}}}"#).unwrap();
+        let canonical = content.to_value_with_format(JsonFormat::Canonical).unwrap();
+
+        assert_eq!(to_string(&canonical).unwrap(), r#"{"tags":{"u.work":{"order":1}}}"#);
+    }
+
+    #[test]
+    fn non_finite_tag_order() {
+        for order in &["NaN", "inf", "-inf", "infinity"] {
+            let json = format!(r#"{{"tags":{{"u.work":{{"order":"{}"}}}}}}"#, order);
+
+            assert!(from_str::<TagEventContent>(&json).is_err());
+        }
+    }
+
+    #[test]
+    fn tag_event_without_room_id() {
+        let event = from_str::<TagEvent>(
+            r#"{"content":{"tags":{"u.work":{"order":0.9}}},"type":"m.tag"}"#
+        ).unwrap();
+
+        assert_eq!(event.content.tags["u.work"].order, Some(0.9));
+        assert_eq!(event.room_id, None);
+
+        let event = event.into_full_event(RoomId::try_from("!a:example.com").unwrap());
+
+        assert_eq!(
+            to_string(&event).unwrap(),
+            concat!(
+                r#"{"content":{"tags":{"u.work":{"order":0.9}}},"type":"m.tag","#,
+                r#""room_id":"!a:example.com"}"#
+            )
+        );
+    }
+
+    #[test]
+    fn tag_order_range() {
+        assert_eq!(TagInfo::with_order(0.5).unwrap().order, Some(0.5));
+        assert_eq!(TagInfo::with_order(1.5), Err(ContentBuildError::TagOrderOutOfRange(1.5)));
+    }
+
+    #[test]
+    fn rooms_sorted_by_tag() {
+        let rooms = ["!a:example.com", "!b:example.com", "!c:example.com", "!d:example.com"]
+            .iter()
+            .map(|id| RoomId::try_from(*id).unwrap())
+            .collect::<Vec<_>>();
+        let tags = [
+            r#"{"tags":{"m.favourite":{"order":0.5},"u.work":{}}}"#,
+            r#"{"tags":{"m.favourite":{"order":0.2}}}"#,
+            r#"{"tags":{"m.favourite":{},"u.work":{}}}"#,
+            r#"{"tags":{"m.favourite":{}}}"#,
+        ]
+            .iter()
+            .map(|json| from_str::<TagEventContent>(json).unwrap())
+            .collect::<Vec<_>>();
+        let last_activity = [4u32, 3, 1, 2];
+
+        let sorted = sort_rooms_by_tag((0..4).map(|index| TaggedRoom {
+            room_id: &rooms[index],
+            tags: &tags[index],
+            last_activity: UInt::from(last_activity[index]),
+        }));
+
+        assert_eq!(sorted.len(), 2);
+        assert_eq!(
+            sorted["m.favourite"],
+            vec![rooms[1].clone(), rooms[0].clone(), rooms[3].clone(), rooms[2].clone()]
+        );
+        assert_eq!(sorted["u.work"], vec![rooms[0].clone(), rooms[2].clone()]);
+    }
 }
@@ -0,0 +1,523 @@
+//! Types for slices of a room's timeline, as returned by sync and pagination.
+
+use ruma_identifiers::{EventId, UserId};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde::de::Error as DeError;
+use serde::ser::Error as SerError;
+use serde_json::{Error as JsonError, Value, to_value};
+
+use EventType;
+use collections::deserialize_limited_value;
+use content::{AnyMessageLikeEventContent, AnyStateEventContent, deserialize_content};
+use int::UInt;
+#[cfg(feature = "events-encryption")]
+use room::encrypted::EncryptedEventContent;
+use room::relation::Relation;
+
+/// A contiguous slice of a room's timeline.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct TimelineChunk {
+    /// The events of the slice, in chronological order.
+    ///
+    /// Like in sync responses, the events leave out the `room_id` of the room they belong to.
+    pub events: Vec<AnySyncTimelineEvent>,
+    /// Whether the server left out events between this slice and the previous one.
+    #[serde(default)]
+    pub limited: bool,
+    /// A token for paginating backwards from the start of this slice.
+    #[serde(skip_serializing_if="Option::is_none")]
+    pub prev_batch: Option<String>,
+}
+
+impl TimelineChunk {
+    /// Whether there may be a gap before this slice that has to be filled by paginating backwards
+    /// from `prev_batch`.
+    pub fn has_gap(&self) -> bool {
+        self.limited && self.prev_batch.is_some()
+    }
+
+    /// Whether the slice contains no events.
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+}
+
+/// Any event that can appear in the timeline of a room in a sync response.
+///
+/// Events in a sync response leave out the `room_id`, since it is given by the room they are
+/// listed under.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub enum AnySyncTimelineEvent {
+    /// An event that is neither a state event nor encrypted.
+    MessageLike(SyncMessageLikeEvent<AnyMessageLikeEventContent>),
+    /// A state event.
+    State(SyncStateEvent),
+    /// An encrypted event that has not been decrypted.
+    #[cfg(feature = "events-encryption")]
+    Encrypted(SyncMessageLikeEvent<EncryptedEventContent>),
+    /// An event whose content was removed by a redaction.
+    Redacted(RedactedSyncEvent),
+}
+
+/// A room event that is not a state event, in the format used in sync responses.
+#[derive(Clone, Debug)]
+pub struct SyncMessageLikeEvent<C> {
+    /// The event's content.
+    pub content: C,
+    /// The unique identifier for the event.
+    pub event_id: EventId,
+    /// Timestamp in milliseconds on originating homeserver when this event was sent.
+    pub origin_server_ts: UInt,
+    /// The relation of the event to another event, sent as `m.relates_to` in the content.
+    pub relates_to: Option<Relation>,
+    /// The unique identifier for the user who sent this event.
+    pub sender: UserId,
+    /// Additional key-value pairs not signed by the homeserver.
+    pub unsigned: Option<Value>,
+}
+
+/// A state event, in the format used in sync responses.
+#[derive(Clone, Debug)]
+pub struct SyncStateEvent {
+    /// The event's content.
+    pub content: AnyStateEventContent,
+    /// The unique identifier for the event.
+    pub event_id: EventId,
+    /// Timestamp in milliseconds on originating homeserver when this event was sent.
+    pub origin_server_ts: UInt,
+    /// The unique identifier for the user who sent this event.
+    pub sender: UserId,
+    /// A key that determines which piece of room state the event represents.
+    pub state_key: String,
+    /// Additional key-value pairs not signed by the homeserver.
+    pub unsigned: Option<Value>,
+}
+
+/// An event whose content was removed by a redaction, in the format used in sync responses.
+#[derive(Clone, Debug)]
+pub struct RedactedSyncEvent {
+    /// The content left over after redaction, which keeps only the fields the redaction algorithm
+    /// preserves for the event type.
+    pub content: Value,
+    /// The unique identifier for the event.
+    pub event_id: EventId,
+    /// The type of the event.
+    pub event_type: EventType,
+    /// Timestamp in milliseconds on originating homeserver when this event was sent.
+    pub origin_server_ts: UInt,
+    /// The unique identifier for the user who sent this event.
+    pub sender: UserId,
+    /// The state key, if the event is a state event.
+    pub state_key: Option<String>,
+    /// Additional key-value pairs not signed by the homeserver, including `redacted_because`.
+    pub unsigned: Value,
+}
+
+impl AnySyncTimelineEvent {
+    /// The unique identifier for the event.
+    pub fn event_id(&self) -> &EventId {
+        match *self {
+            AnySyncTimelineEvent::MessageLike(ref event) => &event.event_id,
+            AnySyncTimelineEvent::State(ref event) => &event.event_id,
+            #[cfg(feature = "events-encryption")]
+            AnySyncTimelineEvent::Encrypted(ref event) => &event.event_id,
+            AnySyncTimelineEvent::Redacted(ref event) => &event.event_id,
+        }
+    }
+
+    /// The type of the event.
+    pub fn event_type(&self) -> EventType {
+        match *self {
+            AnySyncTimelineEvent::MessageLike(ref event) => event.content.event_type(),
+            AnySyncTimelineEvent::State(ref event) => event.content.event_type(),
+            #[cfg(feature = "events-encryption")]
+            AnySyncTimelineEvent::Encrypted(_) => EventType::RoomEncrypted,
+            AnySyncTimelineEvent::Redacted(ref event) => event.event_type.clone(),
+        }
+    }
+
+    /// Timestamp in milliseconds on originating homeserver when this event was sent.
+    pub fn origin_server_ts(&self) -> UInt {
+        match *self {
+            AnySyncTimelineEvent::MessageLike(ref event) => event.origin_server_ts,
+            AnySyncTimelineEvent::State(ref event) => event.origin_server_ts,
+            #[cfg(feature = "events-encryption")]
+            AnySyncTimelineEvent::Encrypted(ref event) => event.origin_server_ts,
+            AnySyncTimelineEvent::Redacted(ref event) => event.origin_server_ts,
+        }
+    }
+
+    /// The unique identifier for the user who sent this event.
+    pub fn sender(&self) -> &UserId {
+        match *self {
+            AnySyncTimelineEvent::MessageLike(ref event) => &event.sender,
+            AnySyncTimelineEvent::State(ref event) => &event.sender,
+            #[cfg(feature = "events-encryption")]
+            AnySyncTimelineEvent::Encrypted(ref event) => &event.sender,
+            AnySyncTimelineEvent::Redacted(ref event) => &event.sender,
+        }
+    }
+
+    /// The state key, if the event is a state event.
+    pub fn state_key(&self) -> Option<&str> {
+        match *self {
+            AnySyncTimelineEvent::State(ref event) => Some(&event.state_key),
+            AnySyncTimelineEvent::Redacted(ref event) => event.state_key.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// The relation of the event to another event, if it is a message-like event with one.
+    pub fn relates_to(&self) -> Option<&Relation> {
+        match *self {
+            AnySyncTimelineEvent::MessageLike(ref event) => event.relates_to.as_ref(),
+            #[cfg(feature = "events-encryption")]
+            AnySyncTimelineEvent::Encrypted(ref event) => event.relates_to.as_ref(),
+            _ => None,
+        }
+    }
+
+    /// Additional key-value pairs not signed by the homeserver.
+    pub fn unsigned(&self) -> Option<&Value> {
+        match *self {
+            AnySyncTimelineEvent::MessageLike(ref event) => event.unsigned.as_ref(),
+            AnySyncTimelineEvent::State(ref event) => event.unsigned.as_ref(),
+            #[cfg(feature = "events-encryption")]
+            AnySyncTimelineEvent::Encrypted(ref event) => event.unsigned.as_ref(),
+            AnySyncTimelineEvent::Redacted(ref event) => Some(&event.unsigned),
+        }
+    }
+}
+
+impl RedactedSyncEvent {
+    /// The redaction event that redacted this event, if the server included it.
+    pub fn redacted_because(&self) -> Option<&Value> {
+        self.unsigned.get("redacted_because")
+    }
+}
+
+/// The fields shared by all events in a sync timeline, with the content left as JSON.
+#[derive(Deserialize, Serialize)]
+struct RawSyncEvent {
+    content: Value,
+    event_id: EventId,
+    origin_server_ts: UInt,
+    sender: UserId,
+    #[serde(skip_serializing_if="Option::is_none")]
+    state_key: Option<String>,
+    #[serde(rename="type")]
+    event_type: EventType,
+    #[serde(default, skip_serializing_if="Option::is_none")]
+    unsigned: Option<Value>,
+}
+
+impl AnySyncTimelineEvent {
+    fn from_raw(raw: RawSyncEvent) -> Result<Self, JsonError> {
+        let redacted = raw.unsigned.as_ref()
+            .and_then(|unsigned| unsigned.get("redacted_because"))
+            .is_some();
+
+        if redacted {
+            return Ok(AnySyncTimelineEvent::Redacted(RedactedSyncEvent {
+                content: raw.content,
+                event_id: raw.event_id,
+                event_type: raw.event_type,
+                origin_server_ts: raw.origin_server_ts,
+                sender: raw.sender,
+                state_key: raw.state_key,
+                unsigned: raw.unsigned.unwrap_or(Value::Null),
+            }));
+        }
+
+        let event_type = raw.event_type;
+        let mut content = raw.content;
+
+        // The relation is kept next to the content of message-like events, since most content
+        // types have no field for it. Encrypted content keeps its own copy.
+        let relates_to = match raw.state_key {
+            Some(_) => None,
+            #[cfg(feature = "events-encryption")]
+            None if event_type == EventType::RoomEncrypted => content.get("m.relates_to")
+                .map(Relation::deserialize)
+                .transpose()?,
+            None => content.as_object_mut()
+                .and_then(|content| content.remove("m.relates_to"))
+                .map(Relation::deserialize)
+                .transpose()?,
+        };
+
+        let content = deserialize_content(&event_type, content)?;
+
+        if let Some(state_key) = raw.state_key {
+            let content = AnyStateEventContent::from_any_content(content).ok_or_else(|| {
+                <JsonError as DeError>::custom(
+                    format!("{} is not a state event type", event_type)
+                )
+            })?;
+
+            return Ok(AnySyncTimelineEvent::State(SyncStateEvent {
+                content,
+                event_id: raw.event_id,
+                origin_server_ts: raw.origin_server_ts,
+                sender: raw.sender,
+                state_key,
+                unsigned: raw.unsigned,
+            }));
+        }
+
+        let is_state = AnyStateEventContent::can_convert_from(&content);
+
+        match AnyMessageLikeEventContent::from_any_content(content) {
+            #[cfg(feature = "events-encryption")]
+            Some(AnyMessageLikeEventContent::RoomEncrypted(content)) => {
+                Ok(AnySyncTimelineEvent::Encrypted(SyncMessageLikeEvent {
+                    content,
+                    event_id: raw.event_id,
+                    origin_server_ts: raw.origin_server_ts,
+                    relates_to,
+                    sender: raw.sender,
+                    unsigned: raw.unsigned,
+                }))
+            }
+            Some(content) => Ok(AnySyncTimelineEvent::MessageLike(SyncMessageLikeEvent {
+                content,
+                event_id: raw.event_id,
+                origin_server_ts: raw.origin_server_ts,
+                relates_to,
+                sender: raw.sender,
+                unsigned: raw.unsigned,
+            })),
+            None if is_state => Err(<JsonError as DeError>::custom(
+                format!("{} is a state event type but has no state_key", event_type)
+            )),
+            None => Err(<JsonError as DeError>::custom(
+                format!("{} is not a timeline event type", event_type)
+            )),
+        }
+    }
+
+    fn to_raw(&self) -> Result<RawSyncEvent, JsonError> {
+        let raw = match *self {
+            AnySyncTimelineEvent::MessageLike(ref event) => RawSyncEvent {
+                content: content_with_relation(&event.content, event.relates_to.as_ref())?,
+                event_id: event.event_id.clone(),
+                origin_server_ts: event.origin_server_ts,
+                sender: event.sender.clone(),
+                state_key: None,
+                event_type: event.content.event_type(),
+                unsigned: event.unsigned.clone(),
+            },
+            AnySyncTimelineEvent::State(ref event) => RawSyncEvent {
+                content: to_value(&event.content)?,
+                event_id: event.event_id.clone(),
+                origin_server_ts: event.origin_server_ts,
+                sender: event.sender.clone(),
+                state_key: Some(event.state_key.clone()),
+                event_type: event.content.event_type(),
+                unsigned: event.unsigned.clone(),
+            },
+            #[cfg(feature = "events-encryption")]
+            AnySyncTimelineEvent::Encrypted(ref event) => RawSyncEvent {
+                content: content_with_relation(&event.content, event.relates_to.as_ref())?,
+                event_id: event.event_id.clone(),
+                origin_server_ts: event.origin_server_ts,
+                sender: event.sender.clone(),
+                state_key: None,
+                event_type: EventType::RoomEncrypted,
+                unsigned: event.unsigned.clone(),
+            },
+            AnySyncTimelineEvent::Redacted(ref event) => RawSyncEvent {
+                content: event.content.clone(),
+                event_id: event.event_id.clone(),
+                origin_server_ts: event.origin_server_ts,
+                sender: event.sender.clone(),
+                state_key: event.state_key.clone(),
+                event_type: event.event_type.clone(),
+                unsigned: Some(event.unsigned.clone()),
+            },
+        };
+
+        Ok(raw)
+    }
+}
+
+/// Serializes the content of a message-like event, adding the relation as `m.relates_to` unless
+/// the content already has it.
+fn content_with_relation<C>(content: &C, relates_to: Option<&Relation>) -> Result<Value, JsonError>
+where C: Serialize {
+    let mut content = to_value(content)?;
+
+    if let (Some(relates_to), Some(object)) = (relates_to, content.as_object_mut()) {
+        if !object.contains_key("m.relates_to") {
+            object.insert("m.relates_to".to_string(), to_value(relates_to)?);
+        }
+    }
+
+    Ok(content)
+}
+
+impl Serialize for AnySyncTimelineEvent {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
+        self.to_raw().map_err(S::Error::custom)?.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for AnySyncTimelineEvent {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where D: Deserializer<'de> {
+        let value = deserialize_limited_value(deserializer)?;
+        let raw = RawSyncEvent::deserialize(value).map_err(D::Error::custom)?;
+
+        AnySyncTimelineEvent::from_raw(raw).map_err(D::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{Value, from_str, to_value};
+
+    use EventType;
+    use int::UInt;
+    use room::relation::Relation;
+    use super::{AnySyncTimelineEvent, TimelineChunk};
+
+    #[test]
+    fn deserialize_sync_timeline() {
+        let chunk = from_str::<TimelineChunk>(r#"{
+            "events": [{
+                "content": {"body": "Hello", "msgtype": "m.text"},
+                "event_id": "$h29iv0s8:example.com",
+                "origin_server_ts": 1,
+                "sender": "@carl:example.com",
+                "type": "m.room.message"
+            }],
+            "limited": true,
+            "prev_batch": "t34-23535_0_0"
+        }"#).unwrap();
+
+        assert!(chunk.has_gap());
+
+        match chunk.events[0] {
+            AnySyncTimelineEvent::MessageLike(ref event) => {
+                assert_eq!(event.content.event_type(), EventType::RoomMessage);
+            }
+            _ => unreachable!(),
+        }
+
+        let json = to_value(&chunk).unwrap();
+        assert!(json["events"][0].get("room_id").is_none());
+        assert_eq!(json["prev_batch"], Value::from("t34-23535_0_0"));
+    }
+
+    #[test]
+    fn sync_timeline_events() {
+        let events = from_str::<Vec<AnySyncTimelineEvent>>(r#"[
+            {
+                "content": {"body": "Hello", "msgtype": "m.text"},
+                "event_id": "$h29iv0s8:example.com",
+                "origin_server_ts": 1,
+                "sender": "@carl:example.com",
+                "type": "m.room.message"
+            },
+            {
+                "content": {"name": "Room"},
+                "event_id": "$h29iv0s9:example.com",
+                "origin_server_ts": 2,
+                "sender": "@carl:example.com",
+                "state_key": "",
+                "type": "m.room.name"
+            },
+            {
+                "content": {},
+                "event_id": "$h29iv0sa:example.com",
+                "origin_server_ts": 3,
+                "sender": "@carl:example.com",
+                "type": "m.room.message",
+                "unsigned": {"redacted_because": {"type": "m.room.redaction"}}
+            }
+        ]"#).unwrap();
+
+        match events[0] {
+            AnySyncTimelineEvent::MessageLike(ref event) => {
+                assert_eq!(event.content.event_type(), EventType::RoomMessage);
+            }
+            _ => unreachable!(),
+        }
+        assert_eq!(events[1].state_key(), Some(""));
+        assert_eq!(events[1].origin_server_ts(), UInt::from(2u32));
+        match events[2] {
+            AnySyncTimelineEvent::Redacted(ref event) => {
+                assert!(event.redacted_because().is_some());
+            }
+            _ => unreachable!(),
+        }
+
+        let json = to_value(&events[1]).unwrap();
+        assert_eq!(json["type"], Value::from("m.room.name"));
+        assert!(json.get("room_id").is_none());
+    }
+
+    #[test]
+    fn relations_of_message_like_events() {
+        let json = from_str::<Value>(r#"{
+            "content": {
+                "body": "In a thread",
+                "m.relates_to": {"event_id": "$root:example.com", "rel_type": "m.thread"},
+                "msgtype": "m.text"
+            },
+            "event_id": "$h29iv0s8:example.com",
+            "origin_server_ts": 1,
+            "sender": "@carl:example.com",
+            "type": "m.room.message"
+        }"#).unwrap();
+
+        let event = from_str::<AnySyncTimelineEvent>(&json.to_string()).unwrap();
+
+        match event.relates_to() {
+            Some(Relation::Thread(thread)) => {
+                assert_eq!(thread.event_id.to_string(), "$root:example.com");
+            }
+            _ => unreachable!(),
+        }
+        assert_eq!(to_value(&event).unwrap(), json);
+
+        let mut malformed = json.clone();
+        malformed["content"]["m.relates_to"] = Value::from("$root:example.com");
+        assert!(from_str::<AnySyncTimelineEvent>(&malformed.to_string()).is_err());
+    }
+
+    #[test]
+    fn events_without_state_key_are_checked() {
+        let event = |event_type: &str, content: &str| format!(r#"{{
+            "content": {},
+            "event_id": "$h29iv0s8:example.com",
+            "origin_server_ts": 1,
+            "sender": "@carl:example.com",
+            "type": "{}"
+        }}"#, content, event_type);
+
+        let error = from_str::<AnySyncTimelineEvent>(&event("m.room.name", r#"{"name": "Room"}"#))
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("m.room.name is a state event type but has no state_key"));
+
+        let error = from_str::<AnySyncTimelineEvent>(&event("m.tag", r#"{"tags": {}}"#))
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("m.tag is not a timeline event type"));
+    }
+
+    #[test]
+    fn input_limits_apply_to_sync_timeline_events() {
+        let nested = format!(r#"{{
+            "content": {{"body": "Hello", "msgtype": "m.text", "value": {}{}}},
+            "event_id": "$h29iv0s8:example.com",
+            "origin_server_ts": 1,
+            "sender": "@carl:example.com",
+            "type": "m.room.message"
+        }}"#, "[".repeat(40), "]".repeat(40));
+
+        let error = from_str::<AnySyncTimelineEvent>(&nested).unwrap_err().to_string();
+        assert!(error.contains("maximum depth"));
+    }
+}
@@ -1,6 +1,6 @@
 //! Types for the *m.typing* event.
 
-use ruma_identifiers::{EventId, RoomId};
+use ruma_identifiers::{RoomId, UserId};
 
 event! {
     /// Informs the client of the list of users currently typing.
@@ -11,8 +11,14 @@ event! {
 }
 
 /// The payload of a `TypingEvent`.
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
 pub struct TypingEventContent {
     /// The list of user IDs typing in this room, if any.
-    pub user_ids: Vec<EventId>,
+    pub user_ids: Vec<UserId>,
+    /// Fields that are not covered by the Matrix specification.
+    #[cfg(feature = "preserve-extra-fields")]
+    #[serde(flatten)]
+    pub extra: ::std::collections::BTreeMap<String, ::serde_json::Value>,
 }
+
+impl_event_content!(TypingEventContent, Typing);
@@ -0,0 +1,282 @@
+//! Validation of batches of events, such as the events of an application service transaction.
+//!
+//! Instead of failing on the first problem, validation collects every issue of every event into a
+//! `ValidationReport`, so that a whole payload can be checked at once, e.g. in CI.
+
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+use serde_json::{Map, Value, from_value, to_value, to_vec};
+
+use ContentBuildError;
+use clock::{Clock, SystemClock};
+use collections::all::Event;
+
+/// The maximum size of an event in bytes, as serialized JSON.
+const MAX_EVENT_SIZE: usize = 65_536;
+
+/// How serious an issue is.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Severity {
+    /// The event is valid, but likely not what the sender intended.
+    Warning,
+    /// The event would be rejected by servers or clients.
+    Error,
+}
+
+/// A problem with an event.
+#[derive(Clone, Debug, PartialEq)]
+pub enum IssueKind {
+    /// The event breaks a rule of its content, such as a sender or alias that belongs to the wrong
+    /// server.
+    Content(ContentBuildError),
+    /// The lifetime of the event, such as that of a call invite, has passed.
+    Expired,
+    /// The event is not valid JSON of a known structure, e.g. because of an invalid ID.
+    Malformed(String),
+    /// The event is larger than 65536 bytes.
+    TooLarge(usize),
+    /// The event has a field, given as a path such as `content.foo`, that is not part of its
+    /// type. Only reported in strict mode.
+    UnknownField(String),
+}
+
+/// A problem with one event of a batch.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Issue {
+    /// The index of the event within the batch.
+    pub index: usize,
+    /// How serious the issue is.
+    pub severity: Severity,
+    /// The kind of issue.
+    pub kind: IssueKind,
+}
+
+/// The issues found in a batch of events.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ValidationReport {
+    /// All issues, ordered by the index of their event.
+    pub issues: Vec<Issue>,
+}
+
+/// Options for validating events.
+#[derive(Clone, Debug, Default)]
+pub struct ValidationOptions<C = SystemClock> {
+    /// Whether fields that are not part of an event's type are reported as errors.
+    pub strict: bool,
+    /// The clock used to check whether events have expired.
+    pub clock: C,
+}
+
+impl ValidationReport {
+    /// Whether no event has an issue with `Severity::Error`.
+    pub fn is_valid(&self) -> bool {
+        self.errors().next().is_none()
+    }
+
+    /// The issues with `Severity::Error`.
+    pub fn errors(&self) -> impl Iterator<Item = &Issue> {
+        self.issues.iter().filter(|issue| issue.severity == Severity::Error)
+    }
+
+    /// The issues with `Severity::Warning`.
+    pub fn warnings(&self) -> impl Iterator<Item = &Issue> {
+        self.issues.iter().filter(|issue| issue.severity == Severity::Warning)
+    }
+
+    fn push(&mut self, index: usize, severity: Severity, kind: IssueKind) {
+        self.issues.push(Issue { index, severity, kind });
+    }
+}
+
+impl Display for IssueKind {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match *self {
+            IssueKind::Content(ref error) => write!(f, "{}", error),
+            IssueKind::Expired => write!(f, "event has expired"),
+            IssueKind::Malformed(ref error) => write!(f, "event is malformed: {}", error),
+            IssueKind::TooLarge(size) => {
+                write!(f, "event is {} bytes long, but at most {} bytes are allowed", size,
+                    MAX_EVENT_SIZE)
+            }
+            IssueKind::UnknownField(ref path) => write!(f, "unknown field {}", path),
+        }
+    }
+}
+
+/// Validates typed events with the default options: not strict, and using the system clock.
+pub fn validate_events(events: &[Event]) -> ValidationReport {
+    validate_events_with(events, &ValidationOptions::<SystemClock>::default())
+}
+
+/// Validates typed events.
+///
+/// Unknown fields were already dropped when the events were deserialized, so they are not
+/// reported even in strict mode. Use `validate_json` to check for them.
+pub fn validate_events_with<C: Clock>(
+    events: &[Event],
+    options: &ValidationOptions<C>,
+) -> ValidationReport {
+    let mut report = ValidationReport::default();
+
+    for (index, event) in events.iter().enumerate() {
+        if let Ok(json) = to_vec(event) {
+            check_size(&mut report, index, json.len());
+        }
+
+        check_event(&mut report, index, event, options);
+    }
+
+    report
+}
+
+/// Validates the JSON of events, such as the `events` of an application service transaction.
+///
+/// In addition to the checks of `validate_events_with`, events that fail to deserialize are
+/// reported as malformed, and in strict mode every field that does not survive deserialization
+/// is reported as unknown. With the `preserve-extra-fields` feature, unknown fields of event
+/// content are kept and therefore not reported.
+pub fn validate_json<C: Clock>(events: &[Value], options: &ValidationOptions<C>)
+-> ValidationReport {
+    let mut report = ValidationReport::default();
+
+    for (index, json) in events.iter().enumerate() {
+        if let Ok(bytes) = to_vec(json) {
+            check_size(&mut report, index, bytes.len());
+        }
+
+        let event = match from_value::<Event>(json.clone()) {
+            Ok(event) => event,
+            Err(error) => {
+                report.push(index, Severity::Error, IssueKind::Malformed(error.to_string()));
+                continue;
+            }
+        };
+
+        if options.strict {
+            if let (Value::Object(original), Ok(Value::Object(typed))) = (json, &to_value(&event)) {
+                let mut unknown = Vec::new();
+                unknown_fields("", original, typed, &mut unknown);
+
+                for path in unknown {
+                    report.push(index, Severity::Error, IssueKind::UnknownField(path));
+                }
+            }
+        }
+
+        check_event(&mut report, index, &event, options);
+    }
+
+    report
+}
+
+fn check_size(report: &mut ValidationReport, index: usize, size: usize) {
+    if size > MAX_EVENT_SIZE {
+        report.push(index, Severity::Error, IssueKind::TooLarge(size));
+    }
+}
+
+#[cfg_attr(not(feature = "events-call"), allow(unused_variables))]
+fn check_event<C: Clock>(
+    report: &mut ValidationReport,
+    index: usize,
+    event: &Event,
+    options: &ValidationOptions<C>,
+) {
+    match *event {
+        Event::RoomAliases(ref event) => {
+            if let Err(error) = event.validate_sender_domain() {
+                report.push(index, Severity::Error, IssueKind::Content(error));
+            }
+
+            for alias in event.invalid_aliases() {
+                let error = ContentBuildError::ForeignAlias {
+                    alias: alias.clone(),
+                    server_name: event.state_key.clone(),
+                };
+
+                report.push(index, Severity::Warning, IssueKind::Content(error));
+            }
+        }
+        Event::RoomCreate(ref event) => {
+            if let Err(error) = event.validate_sender_domain() {
+                report.push(index, Severity::Error, IssueKind::Content(error));
+            }
+        }
+        #[cfg(feature = "events-call")]
+        Event::CallInvite(ref event) if event.is_expired_at(&options.clock) => {
+            report.push(index, Severity::Warning, IssueKind::Expired);
+        }
+        #[cfg(feature = "events-call")]
+        Event::CallNegotiate(ref event) if event.is_expired_at(&options.clock) => {
+            report.push(index, Severity::Warning, IssueKind::Expired);
+        }
+        _ => {}
+    }
+}
+
+/// Collects the paths of the non-null fields of `original` that are missing from `typed`.
+fn unknown_fields(
+    prefix: &str,
+    original: &Map<String, Value>,
+    typed: &Map<String, Value>,
+    unknown: &mut Vec<String>,
+) {
+    for (key, value) in original {
+        let path = format!("{}{}", prefix, key);
+
+        match (value, typed.get(key)) {
+            (Value::Null, _) => {}
+            (_, None) => unknown.push(path),
+            (Value::Object(original), Some(Value::Object(typed))) => {
+                unknown_fields(&format!("{}.", path), original, typed, unknown);
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{Value, from_str};
+
+    use clock::FixedClock;
+    use super::{IssueKind, Severity, ValidationOptions, validate_json};
+
+    #[test]
+    fn report_collects_issues() {
+        let events = from_str::<Vec<Value>>(r#"[
+            {
+                "content": {"creator": "@carl:other.com"},
+                "event_id": "$h29iv0s8:example.com",
+                "org.example.flag": true,
+                "origin_server_ts": 1,
+                "room_id": "!n8f893n9:example.com",
+                "sender": "@carl:other.com",
+                "state_key": "",
+                "type": "m.room.create"
+            },
+            {"content": {"tags": {}}, "type": "m.tag"},
+            {"content": {"name": "Ruma"}, "type": "m.room.name"}
+        ]"#).unwrap();
+        let options = ValidationOptions { strict: true, clock: FixedClock(0) };
+
+        let report = validate_json(&events, &options);
+
+        assert!(!report.is_valid());
+        assert_eq!(report.warnings().count(), 0);
+        assert_eq!(report.issues.len(), 3);
+        assert_eq!(report.issues[0].index, 0);
+        assert_eq!(
+            report.issues[0].kind,
+            IssueKind::UnknownField("org.example.flag".to_string())
+        );
+        match report.issues[1].kind {
+            IssueKind::Content(_) => assert_eq!(report.issues[1].severity, Severity::Error),
+            ref kind => panic!("unexpected issue {:?}", kind),
+        }
+        match report.issues[2].kind {
+            IssueKind::Malformed(_) => assert_eq!(report.issues[2].index, 2),
+            ref kind => panic!("unexpected issue {:?}", kind),
+        }
+    }
+}